@@ -7,7 +7,7 @@ use yew_agent::{HandlerId, Private, WorkerLink};
 use cfg::CfgOptions;
 use ide::{
     AnalysisHost, Change, CompletionConfig, CrateGraph, CrateId, DiagnosticsConfig, Edition,
-    FileId, LineIndex, SourceRoot, TextSize,
+    FileId, FileRange, HoverConfig, HoverDocFormat, LineIndex, SourceRoot, TextRange, TextSize,
 };
 use ide_db::base_db::{
     CrateDisplayName, CrateName, CrateOrigin, Dependency, Env, FileSet, LangCrateOrigin, VfsPath,
@@ -187,6 +187,7 @@ impl yew_agent::Worker for AnalyzerAgent {
         let response = match request {
             Request::Diagnostics(text) => self.diagnostics(text),
             Request::Completion(line, col) => self.completion(line, col),
+            Request::Hover(id, line, col) => self.hover(id, line, col),
         };
         if let Some(msg) = response {
             self.link.respond(who, msg);
@@ -251,17 +252,7 @@ impl AnalyzerAgent {
         };
 
         let analysis = self.analysis_host.analysis();
-        let line_index = analysis.file_line_index(file_id).unwrap();
-
-        let line_col = ide::LineCol {
-            line: line - 1,
-            col: col - 1,
-        };
-        let file_length = analysis.file_text(file_id).unwrap().len();
-        let offset = line_index
-            .offset(line_col)
-            .unwrap_or_default()
-            .min(TextSize::from(file_length as u32));
+        let offset = offset(&analysis, file_id, line, col);
         let pos = ide::FilePosition { file_id, offset };
 
         let items = match analysis.completions(&COMPLETION_CONFIG, pos, None).unwrap() {
@@ -285,12 +276,58 @@ impl AnalyzerAgent {
                     insertText: text,
                     insertTextRules: if item.is_snippet { 4 } else { 0 },
                     filterText: item.lookup().to_string(),
+                    documentation: item
+                        .documentation
+                        .as_ref()
+                        .map(|it| it.as_str().to_string())
+                        .unwrap_or_default(),
                 }
             })
             .collect();
 
         Some(Response::Completion(results))
     }
+
+    fn hover(&mut self, id: u64, line: u32, col: u32) -> Option<Response> {
+        let file_id = ide::FileId(0);
+
+        const HOVER_CONFIG: HoverConfig = HoverConfig {
+            links_in_hover: false,
+            memory_layout: None,
+            documentation: true,
+            keywords: false,
+            format: HoverDocFormat::Markdown,
+        };
+
+        let analysis = self.analysis_host.analysis();
+        let offset = offset(&analysis, file_id, line, col);
+        let range = FileRange {
+            file_id,
+            range: TextRange::empty(offset),
+        };
+        let markup = match analysis.hover(&HOVER_CONFIG, range) {
+            Ok(result) => result.map(|it| it.info.markup.to_string()),
+            Err(e) => {
+                log::error!("Error getting hover: {:?}", e);
+                None
+            }
+        };
+        Some(Response::Hover(id, markup))
+    }
+}
+
+/// Converts a 1-based Monaco position to an offset in the file.
+fn offset(analysis: &ide::Analysis, file_id: FileId, line: u32, col: u32) -> TextSize {
+    let line_index = analysis.file_line_index(file_id).unwrap();
+    let line_col = ide::LineCol {
+        line: line - 1,
+        col: col - 1,
+    };
+    let file_length = analysis.file_text(file_id).unwrap().len();
+    line_index
+        .offset(line_col)
+        .unwrap_or_default()
+        .min(TextSize::from(file_length as u32))
 }
 
 fn translate_diagnostic(diag: &ide::Diagnostic, line_index: &LineIndex) -> Diagnostic {
@@ -5,4 +5,9 @@ export function init() {
       return model.completer.complete(position);
     },
   });
+  monaco.languages.registerHoverProvider("rust", {
+    provideHover: function (model, position) {
+      return model.completer.hover(position);
+    },
+  });
 }
@@ -48,6 +48,7 @@ impl Component for Benchmark {
             seed,
             codes,
             nonce,
+            practice: false,
         });
         sim_agent.send(oort_simulation_worker::Request::Snapshot {
             ticks: BATCH_SIZE as u32,
@@ -0,0 +1,150 @@
+use crate::share::MAX_COMPRESSED_SIZE;
+use base64::Engine as _;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+
+/// Upper bound on decompressed size, to guard against malicious links.
+const MAX_DECOMPRESSED_SIZE: u64 = 1 << 20;
+
+/// A versus challenge: the challenger's code runs as team 0 against the
+/// recipient's code as team 1, on the same seed, so both players see the
+/// same battle.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeBundle {
+    pub scenario_name: String,
+    pub seed: u32,
+    pub code: String,
+}
+
+fn hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    // A truncated hash is enough to catch links mangled in transit.
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+/// Encodes a bundle into a URL fragment of the form `challenge=...&hash=...`.
+pub fn encode(bundle: &ChallengeBundle) -> Result<String, String> {
+    let json = serde_json::to_vec(bundle).expect("serialization failed");
+    let mut e = DeflateEncoder::new(Vec::new(), Compression::best());
+    e.write_all(&json).expect("compression failed");
+    let compressed = e.finish().expect("compression failed");
+    if compressed.len() > MAX_COMPRESSED_SIZE {
+        return Err(format!(
+            "Code is too large to share ({} bytes compressed, limit is {} bytes)",
+            compressed.len(),
+            MAX_COMPRESSED_SIZE
+        ));
+    }
+    let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(compressed);
+    Ok(format!("challenge={encoded}&hash={}", hash(&json)))
+}
+
+/// Decodes a URL fragment created by `encode`, rejecting it if the hash
+/// doesn't match. A leading '#' is ignored.
+pub fn decode(fragment: &str) -> Option<ChallengeBundle> {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    let mut encoded = None;
+    let mut expected_hash = None;
+    for param in fragment.split('&') {
+        match param.split_once('=') {
+            Some(("challenge", v)) => encoded = Some(v),
+            Some(("hash", v)) => expected_hash = Some(v),
+            _ => {}
+        }
+    }
+
+    let encoded = encoded?;
+    if encoded.len() > base64::encoded_len(MAX_COMPRESSED_SIZE, false)? {
+        return None;
+    }
+    let compressed = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(encoded)
+        .ok()?;
+    let mut json = Vec::new();
+    DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_DECOMPRESSED_SIZE)
+        .read_to_end(&mut json)
+        .ok()?;
+    if hash(&json) != expected_hash? {
+        log::warn!("Ignoring challenge with mismatched hash");
+        return None;
+    }
+    serde_json::from_slice(&json).ok()
+}
+
+/// Returns a full link to the bundle's scenario with the challenge in the
+/// fragment.
+pub fn make_link(bundle: &ChallengeBundle) -> Result<String, String> {
+    let fragment = encode(bundle)?;
+    let origin = gloo_utils::window()
+        .location()
+        .origin()
+        .map_err(|e| format!("Failed to get origin: {e:?}"))?;
+    Ok(format!(
+        "{origin}/scenario/{}#{fragment}",
+        bundle.scenario_name
+    ))
+}
+
+/// Decodes a challenge from the current URL and removes it from the address
+/// bar so that reloading the page doesn't prompt again.
+pub fn take_from_location() -> Option<ChallengeBundle> {
+    let window = gloo_utils::window();
+    let location = window.location();
+    let bundle = decode(&location.hash().ok()?)?;
+    let url = format!(
+        "{}{}",
+        location.pathname().unwrap_or_default(),
+        location.search().unwrap_or_default()
+    );
+    if let Ok(history) = window.history() {
+        let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url));
+    }
+    Some(bundle)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bundle() -> ChallengeBundle {
+        ChallengeBundle {
+            scenario_name: "versus01".to_string(),
+            seed: 1234,
+            code: "use oort_api::prelude::*;\n\npub struct Ship {}\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let fragment = encode(&bundle()).unwrap();
+        assert!(fragment.starts_with("challenge="));
+        assert_eq!(decode(&format!("#{fragment}")), Some(bundle()));
+    }
+
+    #[test]
+    fn test_hash_mismatch() {
+        let fragment = encode(&bundle()).unwrap();
+        let (challenge, _) = fragment.split_once('&').unwrap();
+        assert_eq!(decode(challenge), None);
+        assert_eq!(decode(&format!("{challenge}&hash=0000000000000000")), None);
+
+        let mut other = bundle();
+        other.seed = 1235;
+        let other_fragment = encode(&other).unwrap();
+        let (_, other_hash) = other_fragment.split_once('&').unwrap();
+        assert_eq!(decode(&format!("{challenge}&{other_hash}")), None);
+    }
+
+    #[test]
+    fn test_not_a_challenge() {
+        let shared = crate::share::encode("versus01", "foo").unwrap();
+        assert_eq!(decode(&shared), None);
+        assert_eq!(decode(""), None);
+    }
+}
@@ -1,14 +1,51 @@
 use super::game::{code_to_string, str_to_code};
+use crate::ui::ghost;
+use crate::ui::setting::{self, LocalStorage, Store};
 use log::{error, info};
 use oort_simulator::scenario;
 use oort_simulator::simulation::Code;
 
-pub fn load(scenario_name: &str) -> Vec<Code> {
-    let window = web_sys::window().expect("no global `window` exists");
-    let storage = window
-        .local_storage()
-        .expect("failed to get local storage")
-        .unwrap();
+/// Saved source code, by scenario and team slot. Slot 0 is the player's code.
+pub trait CodeStore {
+    fn load(&self, scenario_name: &str, slot: usize) -> Option<String>;
+    fn save(&self, scenario_name: &str, slot: usize, code: &str) -> Option<()>;
+    /// The API version the player's code was last saved against.
+    fn load_api_version(&self, scenario_name: &str) -> Option<u32>;
+    fn save_api_version(&self, scenario_name: &str, version: u32) -> Option<()>;
+}
+
+fn code_key(scenario_name: &str, slot: usize) -> String {
+    if slot == 0 {
+        format!("/code/{scenario_name}")
+    } else {
+        format!("/code/{scenario_name}/{slot}")
+    }
+}
+
+impl<S: Store + ?Sized> CodeStore for S {
+    fn load(&self, scenario_name: &str, slot: usize) -> Option<String> {
+        self.get_item(&code_key(scenario_name, slot))
+    }
+
+    fn save(&self, scenario_name: &str, slot: usize, code: &str) -> Option<()> {
+        self.set_item(&code_key(scenario_name, slot), code)
+    }
+
+    fn load_api_version(&self, scenario_name: &str) -> Option<u32> {
+        self.get_item(&format!("/code_api_version/{scenario_name}"))?
+            .parse()
+            .ok()
+    }
+
+    fn save_api_version(&self, scenario_name: &str, version: u32) -> Option<()> {
+        self.set_item(
+            &format!("/code_api_version/{scenario_name}"),
+            &version.to_string(),
+        )
+    }
+}
+
+pub fn load_from(store: &dyn CodeStore, scenario_name: &str) -> Vec<Code> {
     let scenario = scenario::load(scenario_name);
     let mut result = scenario.initial_code();
     let mut names = vec![];
@@ -16,21 +53,223 @@ pub fn load(scenario_name: &str) -> Vec<Code> {
     names.append(&mut scenario.previous_names());
     let player_code = names
         .iter()
-        .find_map(|name| storage.get_item(&format!("/code/{name}")).unwrap());
+        .find_map(|name| store.load(name, 0).map(|code| (name, code)));
     match player_code {
-        Some(code) => result[0] = str_to_code(&code),
+        Some((name, code)) => result[0] = pin_api_version(store, name, str_to_code(&code)),
         None => info!("No saved code, using starter code"),
     }
     result
 }
 
-pub fn save(scenario_name: &str, code: &Code) {
-    let window = web_sys::window().expect("no global `window` exists");
-    let storage = window
-        .local_storage()
-        .expect("failed to get local storage")
-        .unwrap();
-    if let Err(msg) = storage.set_item(&format!("/code/{scenario_name}"), &code_to_string(code)) {
-        error!("Failed to save code: {:?}", msg);
+/// Marks code saved against an older API version so the compiler keeps
+/// accepting it after the API moves on.
+fn pin_api_version(store: &dyn CodeStore, scenario_name: &str, code: Code) -> Code {
+    let version = store
+        .load_api_version(scenario_name)
+        .unwrap_or(oort_api::API_VERSION);
+    match code {
+        Code::Rust(text)
+            if version < oort_api::API_VERSION
+                && oort_api::declared_api_version(&text).is_none() =>
+        {
+            Code::Rust(format!("// oort-api: {version}\n{text}"))
+        }
+        code => code,
+    }
+}
+
+pub fn save_to(store: &dyn CodeStore, scenario_name: &str, code: &Code) {
+    let text = code_to_string(code);
+    if store.save(scenario_name, 0, &text).is_none() {
+        error!("Failed to save code");
+    }
+    if let Code::Rust(text) = code {
+        let version = oort_api::declared_api_version(text).unwrap_or(oort_api::API_VERSION);
+        if store.save_api_version(scenario_name, version).is_none() {
+            error!("Failed to save code API version");
+        }
+    }
+}
+
+/// Saves the editor's cursor and scroll position for a scenario, as JSON from
+/// Monaco's `saveViewState`.
+pub fn save_view_state(scenario_name: &str, view_state: &str) {
+    if LocalStorage
+        .set_item(&format!("/view_state/{scenario_name}"), view_state)
+        .is_none()
+    {
+        error!("Failed to save editor view state");
+    }
+}
+
+pub fn load_view_state(scenario_name: &str) -> Option<String> {
+    LocalStorage.get_item(&format!("/view_state/{scenario_name}"))
+}
+
+pub fn mark_completed(scenario_name: &str) {
+    crate::ui::setting::write(&format!("/completed/{scenario_name}"), &true);
+}
+
+pub fn is_completed(scenario_name: &str) -> bool {
+    let mut names = vec![scenario_name.to_string()];
+    if let Ok(scenario) = scenario::load_safe(scenario_name) {
+        names.append(&mut scenario.previous_names());
+    }
+    names
+        .iter()
+        .any(|name| crate::ui::setting::read(&format!("/completed/{name}"), false))
+}
+
+const COMPLETED_MIGRATION_KEY: &str = "/completed_migrated";
+
+/// Profiles from before scenario locks have no "/completed" keys. This
+/// marks every scenario the player has won as completed, along with its
+/// chain of prerequisites, so those players keep access to what they've
+/// already unlocked. Stars and the best-run ghost are only recorded on
+/// victory; saved code isn't enough, since it's written as soon as a
+/// scenario is opened. It only runs once per profile.
+pub fn migrate_completed(store: &dyn Store) {
+    if setting::read_from(store, COMPLETED_MIGRATION_KEY, false) {
+        return;
+    }
+    for name in scenario::list().into_iter().flat_map(|(_, names)| names) {
+        let Ok(scenario) = scenario::load_safe(&name) else {
+            continue;
+        };
+        let mut names = vec![name.clone()];
+        names.append(&mut scenario.previous_names());
+        let won = names.iter().any(|name| {
+            setting::read_from(store, &format!("/stars/{name}"), 0u32) > 0
+                || ghost::load_from(store, name).is_some()
+        });
+        let mut next = won.then_some(name);
+        while let Some(name) = next {
+            setting::write_to(store, &format!("/completed/{name}"), &true);
+            next = scenario::load_safe(&name)
+                .ok()
+                .and_then(|scenario| scenario.prerequisite());
+        }
+    }
+    setting::write_to(store, COMPLETED_MIGRATION_KEY, &true);
+}
+
+/// Records stars earned on a scenario, keeping the best result.
+pub fn record_stars(scenario_name: &str, stars: u32) {
+    if stars > best_stars(scenario_name) {
+        crate::ui::setting::write(&format!("/stars/{scenario_name}"), &stars);
+    }
+}
+
+pub fn best_stars(scenario_name: &str) -> u32 {
+    crate::ui::setting::read(&format!("/stars/{scenario_name}"), 0)
+}
+
+pub fn format_stars(stars: u32) -> String {
+    (0..3)
+        .map(|i| if i < stars { '\u{2605}' } else { '\u{2606}' })
+        .collect()
+}
+
+pub fn set_unlock_all(unlock_all: bool) {
+    crate::ui::setting::write("unlock_all", &unlock_all);
+}
+
+/// Returns the prerequisite that must be completed first, if the scenario is locked.
+pub fn locked_by(scenario_name: &str) -> Option<String> {
+    if crate::ui::setting::read("unlock_all", false) {
+        return None;
+    }
+    scenario::load_safe(scenario_name)
+        .ok()?
+        .prerequisite()
+        .filter(|prerequisite| !is_completed(prerequisite))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ui::setting::MemoryStore;
+
+    #[test]
+    fn test_save_and_load() {
+        let store = MemoryStore::default();
+        let starter = load_from(&store, "tutorial_guns");
+        assert_ne!(starter[0], Code::None);
+
+        let code = Code::Rust("// saved".to_string());
+        save_to(&store, "tutorial_guns", &code);
+        assert_eq!(load_from(&store, "tutorial_guns")[0], code);
+        assert_eq!(
+            CodeStore::load(&store, "tutorial_guns", 0).as_deref(),
+            Some("// saved")
+        );
+        assert_eq!(CodeStore::load(&store, "tutorial_guns", 1), None);
+        assert_eq!(
+            store.load_api_version("tutorial_guns"),
+            Some(oort_api::API_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_previous_name() {
+        let store = MemoryStore::default();
+        CodeStore::save(&store, "tutorial02", 0, "// old name");
+        assert_eq!(
+            load_from(&store, "tutorial_acceleration")[0],
+            Code::Rust("// old name".to_string())
+        );
+    }
+
+    #[test]
+    fn test_migrate_completed() {
+        let completed = |store: &MemoryStore, name: &str| {
+            setting::read_from(store, &format!("/completed/{name}"), false)
+        };
+
+        // Stars saved under an old name unlock the scenario and its
+        // prerequisites.
+        let store = MemoryStore::default();
+        setting::write_to(&store, "/stars/tutorial03", &1u32);
+        migrate_completed(&store);
+        assert!(completed(&store, "tutorial_acceleration2"));
+        assert!(completed(&store, "tutorial_acceleration"));
+        assert!(completed(&store, "tutorial_guns"));
+        assert!(!completed(&store, "tutorial_rotation"));
+
+        // Opening a scenario saves its code, which doesn't mean it was won.
+        let store = MemoryStore::default();
+        CodeStore::save(&store, "tutorial_rotation", 0, "// attempt");
+        migrate_completed(&store);
+        assert!(!completed(&store, "tutorial_rotation"));
+
+        // A ghost is only saved on victory.
+        let store = MemoryStore::default();
+        let track = ghost::Track {
+            class: oort_simulator::ship::ShipClass::Fighter,
+            ticks: 1,
+            interval: 1,
+            positions: vec![[0.0, 0.0, 0.0]],
+        };
+        ghost::save_if_best(&store, "tutorial_rotation", &track);
+        migrate_completed(&store);
+        assert!(completed(&store, "tutorial_rotation"));
+
+        // Only the first run migrates.
+        let store = MemoryStore::default();
+        migrate_completed(&store);
+        setting::write_to(&store, "/stars/tutorial_guns", &3u32);
+        migrate_completed(&store);
+        assert!(!completed(&store, "tutorial_guns"));
+    }
+
+    #[test]
+    fn test_pin_api_version() {
+        let store = MemoryStore::default();
+        CodeStore::save(&store, "tutorial_guns", 0, "// old api");
+        setting::write_to(&store, "/code_api_version/tutorial_guns", &0u32);
+        assert_eq!(
+            load_from(&store, "tutorial_guns")[0],
+            Code::Rust("// oort-api: 0\n// old api".to_string())
+        );
     }
 }
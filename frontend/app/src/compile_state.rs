@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+// Ctrl-Enter pressed again this soon after starting a compile is ignored.
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+pub const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Progress of the most recent compile, shown as a pill in the toolbar.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CompileState {
+    Idle,
+    Compiling { started_at: instant::Instant },
+    Failed { errors: String },
+    Succeeded,
+}
+
+impl CompileState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CompileState::Idle => "Idle",
+            CompileState::Compiling { .. } => "Compiling",
+            CompileState::Failed { .. } => "Failed",
+            CompileState::Succeeded => "Compiled",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            CompileState::Idle => "compile-idle",
+            CompileState::Compiling { .. } => "compile-compiling",
+            CompileState::Failed { .. } => "compile-failed",
+            CompileState::Succeeded => "compile-succeeded",
+        }
+    }
+
+    pub fn tooltip(&self) -> String {
+        match self {
+            CompileState::Idle => "No compile yet".to_string(),
+            CompileState::Compiling { .. } => "Compiling your code".to_string(),
+            CompileState::Failed { errors } => errors.clone(),
+            CompileState::Succeeded => "The last compile succeeded".to_string(),
+        }
+    }
+
+    /// Whether a compile requested at `now` repeats one that just started.
+    pub fn is_repeat(&self, now: instant::Instant) -> bool {
+        match self {
+            CompileState::Compiling { started_at } => now - *started_at < DEBOUNCE,
+            _ => false,
+        }
+    }
+
+    /// Whether this is the compile that started at `started_at` and it has
+    /// run for longer than `TIMEOUT`.
+    pub fn timed_out(&self, started_at: instant::Instant, now: instant::Instant) -> bool {
+        match self {
+            CompileState::Compiling { started_at: x } => *x == started_at && now - *x >= TIMEOUT,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_debounce() {
+        let started_at = instant::Instant::now();
+        let state = CompileState::Compiling { started_at };
+        assert!(state.is_repeat(started_at + Duration::from_millis(100)));
+        assert!(!state.is_repeat(started_at + DEBOUNCE));
+        assert!(!CompileState::Idle.is_repeat(started_at));
+        assert!(!CompileState::Succeeded.is_repeat(started_at));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let started_at = instant::Instant::now();
+        let state = CompileState::Compiling { started_at };
+        assert!(!state.timed_out(started_at, started_at + Duration::from_secs(1)));
+        assert!(state.timed_out(started_at, started_at + TIMEOUT));
+        // A timer left over from an earlier compile doesn't fail this one.
+        let earlier = started_at - Duration::from_secs(1);
+        assert!(!state.timed_out(earlier, started_at + TIMEOUT));
+        assert!(!CompileState::Succeeded.timed_out(started_at, started_at + TIMEOUT));
+    }
+}
@@ -7,6 +7,7 @@ pub enum Msg {}
 pub struct CompilerOutputWindowProps {
     pub host: web_sys::Element,
     pub compiler_errors: Option<String>,
+    pub live_code: Option<String>,
 }
 
 pub struct CompilerOutputWindow {}
@@ -30,6 +31,7 @@ impl Component for CompilerOutputWindow {
                 <>
                     <div class="compiler-output">
                         <h1>{ "Compiler Output" }</h1>
+                        <p class="live-code">{ context.props().live_code.clone().unwrap_or_default() }</p>
                         <pre>
                             { compile_errors.unwrap_or_default() }
                         </pre>
@@ -0,0 +1,132 @@
+use oort_proto::CompilerStatus;
+
+/// How often the toolbar polls the compiler service.
+pub const POLL_INTERVAL_MS: u32 = 30_000;
+/// Average compile times above this show the service as busy.
+pub const SLOW_COMPILE_MS: f64 = 5_000.0;
+/// A /status response slower than this probably waited for a new instance.
+pub const COLD_START_LATENCY_MS: f64 = 2_000.0;
+/// Cloud Run may scale the service to zero after it's idle this long.
+pub const IDLE_TIMEOUT_MS: f64 = 15.0 * 60.0 * 1000.0;
+
+/// Health of the compiler service, shown as a dot in the toolbar.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CompilerHealth {
+    Unknown,
+    Healthy(CompilerStatus),
+    Busy(CompilerStatus),
+    Unreachable,
+}
+
+impl CompilerHealth {
+    pub fn from_result(result: anyhow::Result<CompilerStatus>) -> Self {
+        match result {
+            Ok(status)
+                if !status.healthy
+                    || status.avg_compile_ms.map_or(false, |x| x > SLOW_COMPILE_MS) =>
+            {
+                CompilerHealth::Busy(status)
+            }
+            Ok(status) => CompilerHealth::Healthy(status),
+            Err(_) => CompilerHealth::Unreachable,
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            CompilerHealth::Unknown => "compiler-unknown",
+            CompilerHealth::Healthy(_) => "compiler-healthy",
+            CompilerHealth::Busy(_) => "compiler-busy",
+            CompilerHealth::Unreachable => "compiler-unreachable",
+        }
+    }
+
+    pub fn tooltip(&self) -> String {
+        let describe = |status: &CompilerStatus| {
+            let avg = match status.avg_compile_ms {
+                Some(ms) => format!("{:.1}s", ms / 1e3),
+                None => "n/a".to_string(),
+            };
+            format!(
+                "{} queued, average compile {}, version {}",
+                status.queue_depth, avg, status.version
+            )
+        };
+        match self {
+            CompilerHealth::Unknown => "Checking compiler status".to_string(),
+            CompilerHealth::Healthy(status) => format!("Compiler healthy: {}", describe(status)),
+            CompilerHealth::Busy(status) => format!("Compiler busy: {}", describe(status)),
+            CompilerHealth::Unreachable => "Compiler unreachable".to_string(),
+        }
+    }
+}
+
+/// Whether a compile is likely to wait for the service to start an instance.
+/// Cloud Run doesn't report cold starts, so this goes by the last /status
+/// request: it failed, it was slow enough to have started an instance
+/// itself, or it was long enough ago that the service may have scaled to
+/// zero since. Polls from a hidden tab are throttled, so that happens.
+pub fn may_be_cold_starting(health: &CompilerHealth, latency_ms: f64, age_ms: f64) -> bool {
+    *health == CompilerHealth::Unreachable
+        || latency_ms > COLD_START_LATENCY_MS
+        || age_ms > IDLE_TIMEOUT_MS
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn status(healthy: bool, avg_compile_ms: Option<f64>) -> CompilerStatus {
+        CompilerStatus {
+            healthy,
+            queue_depth: 2,
+            version: "0.1.0".to_string(),
+            avg_compile_ms,
+        }
+    }
+
+    #[test]
+    fn test_from_result() {
+        assert_eq!(
+            CompilerHealth::from_result(Ok(status(true, None))).css_class(),
+            "compiler-healthy"
+        );
+        assert_eq!(
+            CompilerHealth::from_result(Ok(status(true, Some(1000.0)))).css_class(),
+            "compiler-healthy"
+        );
+        assert_eq!(
+            CompilerHealth::from_result(Ok(status(true, Some(8000.0)))).css_class(),
+            "compiler-busy"
+        );
+        assert_eq!(
+            CompilerHealth::from_result(Ok(status(false, None))).css_class(),
+            "compiler-busy"
+        );
+        assert_eq!(
+            CompilerHealth::from_result(Err(anyhow::anyhow!("timeout"))),
+            CompilerHealth::Unreachable
+        );
+    }
+
+    #[test]
+    fn test_may_be_cold_starting() {
+        let healthy = CompilerHealth::Healthy(status(true, None));
+        assert!(!may_be_cold_starting(&healthy, 100.0, 1_000.0));
+        assert!(may_be_cold_starting(&healthy, 8_000.0, 1_000.0));
+        assert!(may_be_cold_starting(&healthy, 100.0, 20.0 * 60e3));
+        assert!(may_be_cold_starting(
+            &CompilerHealth::Unreachable,
+            100.0,
+            1_000.0
+        ));
+    }
+
+    #[test]
+    fn test_tooltip() {
+        assert_eq!(
+            CompilerHealth::Healthy(status(true, Some(1500.0))).tooltip(),
+            "Compiler healthy: 2 queued, average compile 1.5s, version 0.1.0"
+        );
+    }
+}
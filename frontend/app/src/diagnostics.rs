@@ -0,0 +1,311 @@
+//! Recovery screen shown when the app crashes, with a prefilled issue report.
+//!
+//! A panic leaves the wasm instance unusable, so the screen is plain DOM with
+//! inline handlers rather than a yew component.
+
+use oort_proto::Telemetry;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+const MAX_EVENTS: usize = 50;
+const MAX_ERROR_LEN: usize = 1000;
+const MAX_DISPLAYED_ERROR_LEN: usize = 300;
+// GitHub rejects very long URLs, so the issue only gets the start of the code.
+// The copied diagnostics have all of it.
+const MAX_ISSUE_CODE_LEN: usize = 4000;
+const NEW_ISSUE_URL: &str = "https://github.com/rlane/oort3/issues/new";
+
+#[derive(Default)]
+struct State {
+    events: VecDeque<String>,
+    scenario: Option<String>,
+    code: Option<String>,
+    shown: bool,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(Default::default());
+}
+
+/// Adds an event to the ring buffer included in crash reports.
+pub fn record(event: String) {
+    STATE.with(|state| {
+        if let Ok(mut state) = state.try_borrow_mut() {
+            if state.events.len() == MAX_EVENTS {
+                state.events.pop_front();
+            }
+            state.events.push_back(event);
+        }
+    });
+}
+
+pub fn set_scenario(scenario_name: &str) {
+    STATE.with(|state| state.borrow_mut().scenario = Some(scenario_name.to_string()));
+}
+
+/// Remembers the player's code, which is only reported if they opt in.
+pub fn set_code(code: &str) {
+    STATE.with(|state| state.borrow_mut().code = Some(code.to_string()));
+}
+
+/// Summarizes a telemetry message for the event log, leaving out code and
+/// free text.
+pub fn describe(payload: &Telemetry) -> String {
+    match payload {
+        Telemetry::StartScenario { scenario_name, .. } => {
+            format!("StartScenario {scenario_name}")
+        }
+        Telemetry::FinishScenario {
+            scenario_name,
+            ticks,
+            success,
+            ..
+        } => format!("FinishScenario {scenario_name} ticks={ticks} success={success}"),
+        Telemetry::Crash { msg } => format!("Crash {}", truncate(msg, 100)),
+        Telemetry::SubmitToTournament { scenario_name, .. } => {
+            format!("SubmitToTournament {scenario_name}")
+        }
+        Telemetry::Feedback { .. } => "Feedback".to_string(),
+        Telemetry::ScenarioComplete {
+            scenario_name,
+            ticks,
+            seed,
+        } => format!("ScenarioComplete {scenario_name} ticks={ticks} seed={seed}"),
+        Telemetry::Compile { success } => format!("Compile success={success}"),
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Report {
+    pub version: String,
+    pub scenario: Option<String>,
+    pub error: String,
+    pub events: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl Report {
+    /// Builds a report from the recorded state. Code is left out.
+    fn current(error: &str) -> Self {
+        let (scenario, events) = STATE.with(|state| match state.try_borrow() {
+            Ok(state) => (
+                state.scenario.clone(),
+                state.events.iter().cloned().collect(),
+            ),
+            Err(_) => (None, vec![]),
+        });
+        Report {
+            version: crate::version(),
+            scenario,
+            error: truncate(error, MAX_ERROR_LEN),
+            events,
+            code: None,
+        }
+    }
+
+    pub fn with_code(&self, code: Option<String>) -> Self {
+        Report {
+            code,
+            ..self.clone()
+        }
+    }
+
+    /// JSON for the "Copy diagnostics" button.
+    pub fn blob(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn issue_url(&self) -> String {
+        let title = format!("Crash: {}", truncate(first_line(&self.error), 80));
+        let mut body = format!(
+            "**Version:** {}\n**Scenario:** {}\n\n**Error:**\n```\n{}\n```\n\n**Recent events:**\n```\n{}\n```\n",
+            self.version,
+            self.scenario.as_deref().unwrap_or("unknown"),
+            self.error,
+            self.events.join("\n"),
+        );
+        if let Some(code) = self.code.as_ref() {
+            body.push_str(&format!(
+                "\n**Code:**\n```rust\n{}\n```\n",
+                truncate(code, MAX_ISSUE_CODE_LEN)
+            ));
+        }
+        format!(
+            "{NEW_ISSUE_URL}?title={}&body={}",
+            percent_encode(&title),
+            percent_encode(&body)
+        )
+    }
+}
+
+fn first_line(s: &str) -> &str {
+    s.lines().next().unwrap_or_default()
+}
+
+fn truncate(s: &str, max_len: usize) -> String {
+    if s.len() <= max_len {
+        return s.to_string();
+    }
+    let mut end = max_len;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &s[..end])
+}
+
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Shows the recovery screen on panics and on wasm traps that surface as JS
+/// errors. Other uncaught JS errors are only recorded.
+pub fn install() {
+    std::panic::set_hook(Box::new(|info| {
+        console_error_panic_hook::hook(info);
+        show(&info.to_string());
+    }));
+
+    let closure = Closure::wrap(Box::new(move |e: web_sys::ErrorEvent| {
+        record(format!("JS error: {}", truncate(&e.message(), 200)));
+        if e.error()
+            .is_instance_of::<js_sys::WebAssembly::RuntimeError>()
+        {
+            show(&e.message());
+        }
+    }) as Box<dyn FnMut(_)>);
+    gloo_utils::window()
+        .add_event_listener_with_callback("error", closure.as_ref().unchecked_ref())
+        .unwrap();
+    closure.forget();
+}
+
+fn show(error: &str) {
+    let already_shown = STATE.with(|state| match state.try_borrow_mut() {
+        Ok(mut state) => std::mem::replace(&mut state.shown, true),
+        Err(_) => false,
+    });
+    if already_shown {
+        return;
+    }
+
+    let report = Report::current(error);
+    let code = STATE.with(|state| state.try_borrow().ok().and_then(|x| x.code.clone()));
+    let report_with_code = report.with_code(code);
+
+    let html = format!(
+        r#"<div class="crash-screen-box">
+  <h2>Oort crashed</h2>
+  <p>Version {version}, scenario {scenario}</p>
+  <pre>{error}</pre>
+  <label><input type="checkbox" id="crash-include-code"
+    onchange="var a = document.getElementById('crash-report-issue'); a.href = this.checked ? a.dataset.hrefCode : a.dataset.hrefPlain;">
+    Include my code in the report</label>
+  <textarea id="crash-blob" hidden>{blob}</textarea>
+  <textarea id="crash-blob-code" hidden>{blob_code}</textarea>
+  <div class="crash-screen-buttons">
+    <button onclick="location.reload()">Reload</button>
+    <button onclick="navigator.clipboard.writeText(document.getElementById(document.getElementById('crash-include-code').checked ? 'crash-blob-code' : 'crash-blob').value)">Copy diagnostics</button>
+    <a id="crash-report-issue" target="_blank" href="{issue_url}" data-href-plain="{issue_url}" data-href-code="{issue_url_code}">Report issue</a>
+  </div>
+</div>"#,
+        version = escape_html(&report.version),
+        scenario = escape_html(report.scenario.as_deref().unwrap_or("unknown")),
+        error = escape_html(&truncate(error, MAX_DISPLAYED_ERROR_LEN)),
+        blob = escape_html(&report.blob()),
+        blob_code = escape_html(&report_with_code.blob()),
+        issue_url = escape_html(&report.issue_url()),
+        issue_url_code = escape_html(&report_with_code.issue_url()),
+    );
+
+    let document = gloo_utils::document();
+    if let Ok(div) = document.create_element("div") {
+        div.set_class_name("crash-screen");
+        div.set_inner_html(&html);
+        if let Some(body) = document.body() {
+            let _ = body.append_child(&div);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn report() -> Report {
+        Report {
+            version: "v0.73.0".to_string(),
+            scenario: Some("tutorial_guns".to_string()),
+            error: "panicked at 'oops'\nsecond line".to_string(),
+            events: vec!["Compile success=true".to_string()],
+            code: None,
+        }
+    }
+
+    #[test]
+    fn test_record_keeps_last_events() {
+        for i in 0..(MAX_EVENTS + 10) {
+            record(format!("event {i}"));
+        }
+        let events = STATE.with(|state| state.borrow().events.clone());
+        assert_eq!(events.len(), MAX_EVENTS);
+        assert_eq!(events.front().unwrap(), "event 10");
+        assert_eq!(events.back().unwrap(), &format!("event {}", MAX_EVENTS + 9));
+    }
+
+    #[test]
+    fn test_code_is_opt_in() {
+        let report = report();
+        assert!(!report.blob().contains("\"code\""));
+        assert!(!report.issue_url().contains("fn%20tick"));
+
+        let with_code = report.with_code(Some("fn tick(&mut self) {}".to_string()));
+        assert!(with_code.blob().contains("fn tick"));
+        assert!(with_code.issue_url().contains("fn%20tick"));
+    }
+
+    #[test]
+    fn test_issue_url() {
+        let url = report().issue_url();
+        assert!(url.starts_with("https://github.com/rlane/oort3/issues/new?title=Crash%3A%20panicked%20at%20%27oops%27&body="));
+        assert!(!url.contains(' '));
+        assert!(!url.contains('\n'));
+        assert!(url.contains("tutorial_guns"));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("hello", 10), "hello");
+        assert_eq!(truncate("hello world", 5), "hello...");
+        assert_eq!(truncate("héllo", 2), "h...");
+    }
+
+    #[test]
+    fn test_describe_leaves_out_code() {
+        let event = describe(&Telemetry::StartScenario {
+            scenario_name: "tutorial_guns".to_string(),
+            code: "secret".to_string(),
+        });
+        assert_eq!(event, "StartScenario tutorial_guns");
+    }
+}
@@ -1,13 +1,27 @@
+use crate::ui::KEYBINDINGS;
+use oort_api::reference::{Section, SECTIONS};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct DocumentationProps {
     pub host: web_sys::Element,
+    pub scenario_name: String,
     pub show_feedback_cb: Callback<MouseEvent>,
 }
 
 #[function_component(Documentation)]
 pub fn documentation(props: &DocumentationProps) -> Html {
+    let query = use_state(String::new);
+    let oninput = {
+        let query = query.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target().unwrap().dyn_into().unwrap();
+            query.set(input.value());
+        })
+    };
+
     let htm = html! {
         <div class="documentation">
             <h1>{ "Quick Reference" }</h1>
@@ -22,18 +36,7 @@ pub fn documentation(props: &DocumentationProps) -> Html {
             { "Click the run button in the editor to start the scenario with a new version of your code." }<br/>
 
             <h2>{ "Controls" }</h2>
-            <ul>
-                <li>{ "W/A/S/D: Pan the camera." }</li>
-                <li>{ "Space: Pause/resume." }</li>
-                <li>{ "N: Single-step (advance time by one tick and then pause)." }</li>
-                <li>{ "F: Fast-forward." }</li>
-                <li>{ "M: Slow motion." }</li>
-                <li>{ "G: Show debug lines for all ships." }</li>
-                <li>{ "V: Toggle NLIPS, which makes smaller ships more visible when zoomed out." }</li>
-                <li>{ "B: Toggle postprocessing (blur)." }</li>
-                <li>{ "Mouse wheel: Zoom." }</li>
-                <li>{ "Mouse click: Select a ship to show debugging info." }</li>
-            </ul>
+            <Keybindings />
 
             <h2>{ "Language" }</h2>
             <p>
@@ -62,128 +65,18 @@ pub fn documentation(props: &DocumentationProps) -> Html {
                 { "The API uses units of meters, radians, and seconds." }
             </p>
 
-            <h2>{ "Ship Status and Control" }</h2>
-            <ul>
-              <li><code>{ "class() → Class" }</code>{ ": Returns the ship class." }</li>
-              <li><code>{ "position() → Vec2" }</code>{ ": Get the current position in meters." }</li>
-              <li><code>{ "velocity() → Vec2" }</code>{ ": Get the current velocity in m/s." }</li>
-              <li><code>{ "heading() → f64" }</code>{ ": Get the current heading in radians." }</li>
-              <li><code>{ "angular_velocity() → f64" }</code>{ ": Get the current angular velocity in radians/s." }</li>
-              <li><code>{ "health() → f64" }</code>{ ": Current health." }</li>
-              <li><code>{ "fuel() → f64" }</code>{ ": Current fuel (delta-v)." }</li>
-              <li><code>{ "accelerate(acceleration: Vec2)" }</code>{ ": Accelerate the ship. Units are m/s²." }</li>
-              <li><code>{ "turn(speed: f64)" }</code>{ ": Rotate the ship. Unit is radians/s." }</li>
-              <li><code>{ "torque(acceleration: f64)" }</code>{ ": Angular acceleration. Unit is radians/s²." }</li>
-              <li><code>{ "max_forward_acceleration() -> f64" }</code>{ ": Maximum forward acceleration." }</li>
-              <li><code>{ "max_backward_acceleration() -> f64" }</code>{ ": Maximum backward acceleration." }</li>
-              <li><code>{ "max_lateral_acceleration() -> f64" }</code>{ ": Maximum lateral acceleration." }</li>
-              <li><code>{ "max_angular_acceleration() -> f64" }</code>{ ": Maximum angular acceleration." }</li>
-            </ul>
-
-            <h2>{ "Weapons" }</h2>
-            <ul>
-              <li><code>{ "fire(index: usize)" }</code>{ ": Fire a weapon (gun or missile launcher)." }</li>
-              <li><code>{ "aim(index: usize, angle: f64)" }</code>{ ": Aim a weapon (for weapons on a turret)." }</li>
-              <li><code>{ "explode()" }</code>{ ": Self-destruct." }</li>
-            </ul>
-
-            <h2>{ "Radar" }</h2>
-            <ul>
-              <li><code>{ "set_radar_heading(angle: f64)" }</code>{ ": Point the radar at the given heading." }</li>
-              <li><code>{ "radar_heading() -> f64" }</code>{ ": Get current radar heading." }</li>
-              <li><code>{ "set_radar_width(width: f64)" }</code>{ ": Adjust the width of the radar beam (in radians)." }</li>
-              <li><code>{ "radar_width() -> f64" }</code>{ ": Get current radar width." }</li>
-              <li><code>{ "scan() → Option<ScanResult>" }</code>{ ": Find an enemy ship illuminated by the radar." }</li>
-              <li><code>{ "struct ScanResult { position: Vec2, velocity: Vec2 }" }</code></li>
-            </ul>
-
-            <h2>{ "Advanced Radar" }</h2>
-            <ul>
-              <li><code>{ "set_radar_min_distance(dist: f64)" }</code>{ ": Set the minimum distance filter." }</li>
-              <li><code>{ "radar_min_distance() -> f64" }</code>{ ": Get current minimum distance filter." }</li>
-              <li><code>{ "set_radar_max_distance(dist: f64)" }</code>{ ": Set the maximum distance filter." }</li>
-              <li><code>{ "radar_max_distance() -> f64" }</code>{ ": Get current maximum distance filter." }</li>
-              <li><code>{ "set_radar_ecm_mode(mode: EcmMode)" }</code>{ ": Set the Electronic Counter Measures (ECM) mode." }</li>
-              <li><code>{ "EcmMode::None" }</code>{ ": No ECM, radar will operate normally." }</li>
-              <li><code>{ "EcmMode::Noise" }</code>{ ": Decrease the enemy radar's signal to noise ratio, making it more difficult to detect targets and reducing accuracy of returned contacts." }</li>
-            </ul>
-
-            <h2>{ "Radio" }</h2>
-            <ul>
-              <li><code>{ "set_radio_channel(channel: usize)" }</code>{ ": Change the radio channel (0 to 9). Takes effect next tick." }</li>
-              <li><code>{ "get_radio_channel() -> usize" }</code>{ ": Get the radio channel." }</li>
-              <li><code>{ "send(data: [f64; 4])" }</code>{ ": Send a message on a channel." }</li>
-              <li><code>{ "receive() -> Option<[f64; 4]>" }</code>{ ": Receive a message from the channel. The message with the strongest signal is returned." }</li>
-              <li><code>{ "send_bytes(data: &[u8])" }</code>{ ": Send a message on a channel as bytes, the data will be zero-filled or truncated to a length of 32 bytes." }</li>
-              <li><code>{ "receive_bytes() -> Option<[u8; 32]>" }</code>{ ": Just like receive, but instead the message will be returned as a byte array." }</li>
-              <li><code>{ "select_radio(index: usize)" }</code>{ ": Select the radio to control with subsequent API calls. Frigates have 4 radios and cruisers have 8." }</li>
-            </ul>
-
-            <h2>{ "Special Abilities" }</h2>
-            <ul>
-              <li><code>{ "activate_ability(ability: Ability)" }</code>{ ": Activates a ship's special ability." }</li>
-              <li><code>{ "deactivate_ability(ability: Ability)" }</code>{ ": Deactivates a ship's special ability." }</li>
-              <li><code>{ "active_abilities() → ActiveAbilities" }</code>{ ": Returns the ship's active abilities." }</li>
-              <li>{ "Available abilities:" }
-                <ul>
-                  <li><code>{ "Ability::Boost" }</code>{ ": Fighter and missile only. Applies a 100 m/s² forward acceleration for 2s. Reloads in 10s." }</li>
-                  <li><code>{ "Ability::Decoy" }</code>{ ": Torpedo only. Mimics the radar signature of a Cruiser for 0.5s. Reloads in 10s." }</li>
-                  <li><code>{ "Ability::Shield" }</code>{ ": Cruiser only. Deflects damage for 1s. Reloads in 5s." }</li>
-                </ul>
-              </li>
-            </ul>
-
-            <h2>{ "Scalar Math" }</h2>
-            <ul>
-              <li><code>{ "PI, TAU" }</code>{ ": Constants."}</li>
-              <li><code>{ "x.abs()" }</code>{ ": Absolute value."}</li>
-              <li><code>{ "x.sqrt()" }</code>{ ": Square root."}</li>
-              <li><code>{ "x.sin(), x.cos(), x.tan()" }</code>{": Trignometry."}</li>
-              <li>{ "See the " }<a href="https://doc.rust-lang.org/std/primitive.f64.html" target="_blank">{ "Rust documentation" }</a>{ " for the full list of f64 methods." }</li>
-            </ul>
-
-            <h2>{ "Vector Math" }</h2>
-            <span>
+            <h2>{ "API" }</h2>
+            <input type="search" class="api-search" placeholder="Search functions" oninput={oninput} value={(*query).clone()} />
+            <ApiReference query={(*query).clone()} />
+            <p>
+                { "See the " }<a href="https://doc.rust-lang.org/std/primitive.f64.html" target="_blank">{ "Rust documentation" }</a>{ " for the full list of f64 methods. " }
                 { "For a refresher on vectors check out this " }
                 <a href="https://phys.libretexts.org/Bookshelves/University_Physics/Radically_Modern_Introductory_Physics_Text_I_(Raymond)/02%3A_Waves_in_Two_and_Three_Dimensions/2.01%3A_Math_Tutorial__Vectors"
                    target="_blank">{ "tutorial" }</a>
                 { "." }
-            </span>
-            <ul>
-              <li><code>{ "vec2(x, y) → Vec2" }</code>{ ": Create a vector." }</li>
-              <li><code>{ "v.x, v.y → f64" }</code>{ ": Get a component of a vector." }</li>
-              <li><code>{ "v1 +- v2 → Vec2" }</code>{ ": Basic arithmetic between vectors." }</li>
-              <li><code>{ "v */ f64 → Vec2" }</code>{ ": Basic arithmetic between vectors and scalars." }</li>
-              <li><code>{ "-v → Vec2" }</code>{ ": Negate a vector." }</li>
-              <li><code>{ "v.length() → f64" }</code>{ ": Length." }</li>
-              <li><code>{ "v.normalize() → Vec2" }</code>{ ": Normalize to a unit vector." }</li>
-              <li><code>{ "v.rotate(f64) → Vec2" }</code>{ ": Rotate counter-clockwise." }</li>
-              <li><code>{ "v.angle() → f64" }</code>{ ": Angle of a vector." }</li>
-              <li><code>{ "v1.dot(v2: Vec2) → f64" }</code>{ ": Dot product." }</li>
-              <li><code>{ "v1.distance(v2: Vec2) → f64" }</code>{ ": Distance between two points." }</li>
-            </ul>
-
-            <h2>{ "Debugging" }</h2>
-            <ul>
-              <li><code>{ "debug!(...)" }</code>{ ": Add text to be displayed when the ship is selected by clicking on it. Works just like " }<code>{ "println!" }</code>{ "." }</li>
-              <li><code>{ "draw_line(v0: Vec2, v1: Vec2, color: u32)" }</code>{ ": Draw a line visible when the ship is selected. Color is 24-bit RGB." }</li>
-              <li><code>{ "draw_triangle(center: Vec2, radius: f64, color: u32)" }</code>{ ": Draw a triangle visible when the ship is selected." }</li>
-              <li><code>{ "draw_square(center: Vec2, radius: f64, color: u32)" }</code>{ ": Draw a square visible when the ship is selected." }</li>
-              <li><code>{ "draw_diamond(center: Vec2, radius: f64, color: u32)" }</code>{ ": Draw a diamond visible when the ship is selected." }</li>
-              <li><code>{ "draw_polygon(center: Vec2, radius: f64, sides: i32, angle: f64, color: u32)" }</code>{ ": Draw a regular polygon visible when the ship is selected." }</li>
-              <li><code>{ "draw_text!(topleft: Vec2, color: u32, ...)" }</code>{ ": Draw text. Works like " }<code>{ "println!" }</code>{ "." }</li>
-            </ul>
+            </p>
 
-            <h2>{ "Miscellaneous" }</h2>
-            <ul>
-              <li><code>{ "current_tick() → u32" }</code>{ ": Returns the number of ticks elapsed since the simulation started." }</li>
-              <li><code>{ "current_time() → f64" }</code>{ ": Returns the number of seconds elapsed since the simulation started." }</li>
-              <li><code>{ "angle_diff(a: f64, b: f64) → f64" }</code>{ ": Returns the shortest (possibly negative) distance between two angles." }</li>
-              <li><code>{ "rand(low: f64, high: f64) → f64" }</code>{ ": Get a random number." }</li>
-              <li><code>{ "target() → Vec2" }</code>{ ": Used in some scenarios, returns the position of the target." }</li>
-              <li><code>{ "target_velocity() → Vec2" }</code>{ ": Used in some scenarios, returns the velocity of the target." }</li>
-              <li><code>{ "seed() → u128" }</code>{ ": Returns a seed useful for initializing a random number generator." }</li>
-            </ul>
+            <ScenarioVariables scenario_name={props.scenario_name.clone()} />
 
             <h2>{ "Extra Crates" }</h2>
             <p>{ "The following crates are available for use in your code:" }</p>
@@ -195,7 +88,7 @@ pub fn documentation(props: &DocumentationProps) -> Html {
 
             <h2>{ "Ship Classes" }</h2>
             <ul>
-              <li>{ "Fighter: Small, fast, and lightly armored. One forward-facing gun and one missile launcher. "}</li>
+              <li>{ "Fighter: Small, fast, and lightly armored. One forward-facing gun, one missile launcher, and one forward-facing laser. "}</li>
               <li>{ "Frigate: Medium size with heavy armor. One forward-facing high-velocity gun, two turreted guns, and one missile launcher. "}</li>
               <li>{ "Cruiser: Large, slow, and heavily armored. One turreted flak gun, two missile launchers, and one torpedo launcher. "}</li>
               <li>{ "Missile: Highly maneuverable but unarmored. Explodes on contact or after an " }<code>{ "explode()" }</code>{ " call." }</li>
@@ -206,3 +99,131 @@ pub fn documentation(props: &DocumentationProps) -> Html {
 
     create_portal(htm, props.host.clone())
 }
+
+#[function_component(Keybindings)]
+pub fn keybindings() -> Html {
+    html! {
+        <ul>
+            { for KEYBINDINGS.iter().map(|(key, description)| html! {
+                <li>{ format!("{key}: {description}") }</li>
+            }) }
+        </ul>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ScenarioVariablesProps {
+    pub scenario_name: String,
+}
+
+#[function_component(ScenarioVariables)]
+pub fn scenario_variables(props: &ScenarioVariablesProps) -> Html {
+    let variables = oort_simulator::scenario::load_safe(&props.scenario_name)
+        .map(|scenario| scenario.variables())
+        .unwrap_or_default();
+    if variables.is_empty() {
+        return html! {};
+    }
+    html! {
+        <>
+            <h2>{ "Scenario Variables" }</h2>
+            <p>{ "This scenario passes these values to your code. Read them with " }<code>{ "scenario_var" }</code>{ "." }</p>
+            <ul>
+                { for variables.iter().map(|(name, description)| html! {
+                    <li><code>{ name.clone() }</code>{ format!(": {description}") }</li>
+                }) }
+            </ul>
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ApiReferenceProps {
+    /// Only entries with signatures containing this are shown.
+    pub query: String,
+}
+
+#[function_component(ApiReference)]
+pub fn api_reference(props: &ApiReferenceProps) -> Html {
+    html! {
+        { for SECTIONS.iter().map(|section| render_section(section, &props.query)) }
+    }
+}
+
+fn render_section(section: &Section, query: &str) -> Html {
+    let entries: Vec<_> = section
+        .entries
+        .iter()
+        .filter(|entry| entry.matches(query))
+        .collect();
+    if entries.is_empty() {
+        return html! {};
+    }
+    html! {
+        <>
+            <h3>{ section.name }</h3>
+            <ul>
+                { for entries.iter().map(|entry| html! {
+                    <li>
+                        <code>{ entry.signature }</code>
+                        if !entry.description.is_empty() {
+                            { ": " }{ entry.description }
+                        }
+                    </li>
+                }) }
+            </ul>
+        </>
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use yew::ServerRenderer;
+
+    #[tokio::test]
+    async fn test_api_reference_search() {
+        let all = ServerRenderer::<ApiReference>::with_props(|| ApiReferenceProps {
+            query: String::new(),
+        })
+        .render()
+        .await;
+        for section in SECTIONS {
+            assert!(all.contains(section.name), "missing {}", section.name);
+        }
+
+        let filtered = ServerRenderer::<ApiReference>::with_props(|| ApiReferenceProps {
+            query: "SCAN".to_string(),
+        })
+        .render()
+        .await;
+        assert!(filtered.contains("scan()"));
+        assert!(!filtered.contains("fire("));
+    }
+
+    #[tokio::test]
+    async fn test_keybindings() {
+        let html = ServerRenderer::<Keybindings>::new().render().await;
+        for (key, _) in KEYBINDINGS {
+            assert!(html.contains(&format!("{key}:")), "missing {key}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scenario_variables() {
+        let html = ServerRenderer::<ScenarioVariables>::with_props(|| ScenarioVariablesProps {
+            scenario_name: "tutorial_rotation".to_string(),
+        })
+        .render()
+        .await;
+        assert!(html.contains("Scenario Variables"));
+        assert!(html.contains("target"));
+
+        let html = ServerRenderer::<ScenarioVariables>::with_props(|| ScenarioVariablesProps {
+            scenario_name: "gunnery".to_string(),
+        })
+        .render()
+        .await;
+        assert!(!html.contains("Scenario Variables"));
+    }
+}
@@ -11,6 +11,7 @@ use monaco::{
 };
 use oort_simulator::simulation::Code;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
@@ -42,6 +43,12 @@ pub enum Msg {
         resolve: Function,
         reject: Function,
     },
+    RequestHover {
+        line: u32,
+        col: u32,
+        resolve: Function,
+        reject: Function,
+    },
     LoadedCodeFromDisk(String),
     OpenedFile(FileHandle),
     LinkedFile(FileHandle),
@@ -67,6 +74,10 @@ pub struct EditorWindow {
     #[allow(dead_code)]
     analyzer_interval: Interval,
     current_completion: Option<(Function, Function)>,
+    /// Hover promises waiting on the analyzer, by request id. Monaco can ask
+    /// again before the previous answer arrives.
+    pending_hovers: HashMap<u64, (Function, Function)>,
+    next_hover_id: u64,
     folded: bool,
     file_handle: Option<FileHandle>,
     linked: bool,
@@ -94,6 +105,8 @@ impl Component for EditorWindow {
             analyzer_agent,
             analyzer_interval,
             current_completion: None,
+            pending_hovers: HashMap::new(),
+            next_hover_id: 0,
             folded: false,
             file_handle: None,
             linked: false,
@@ -216,6 +229,33 @@ impl Component for EditorWindow {
                 }
                 false
             }
+            Msg::RequestHover {
+                line,
+                col,
+                resolve,
+                reject,
+            } => {
+                let id = self.next_hover_id;
+                self.next_hover_id += 1;
+                self.pending_hovers.insert(id, (resolve, reject));
+                self.analyzer_agent
+                    .send(analyzer_stub::Request::Hover(id, line, col));
+                false
+            }
+            Msg::AnalyzerResponse(analyzer_stub::Response::Hover(id, markup)) => {
+                if let Some((resolve, _)) = self.pending_hovers.remove(&id) {
+                    let this = JsValue::null();
+                    let result = match markup {
+                        Some(value) => serde_wasm_bindgen::to_value(&Hover {
+                            contents: vec![MarkdownString { value }],
+                        })
+                        .unwrap(),
+                        None => JsValue::null(),
+                    };
+                    resolve.call1(&this, &result).unwrap();
+                }
+                false
+            }
             Msg::LoadedCodeFromDisk(text) => {
                 let editor_link = context.props().editor_link.clone();
                 editor_link.with_editor(|editor| {
@@ -427,6 +467,24 @@ impl Component for EditorWindow {
 
                 add_action("oort-load-solution", "Load solution", None);
 
+                add_action(
+                    "oort-toggle-strict-compile",
+                    "Toggle stopping the simulation on compile errors",
+                    None,
+                );
+
+                add_action(
+                    "oort-toggle-keep-running-in-background",
+                    "Toggle running the simulation while the tab is hidden",
+                    None,
+                );
+
+                add_action(
+                    "oort-copy-challenge-link",
+                    "Copy a versus challenge link for this code and seed",
+                    None,
+                );
+
                 add_action("oort-load-file", "Load from a file", None);
 
                 add_action(
@@ -587,6 +645,16 @@ struct CompletionList {
     pub suggestions: Vec<CompletionItem>,
 }
 
+#[derive(Serialize, Deserialize)]
+struct MarkdownString {
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Hover {
+    pub contents: Vec<MarkdownString>,
+}
+
 #[wasm_bindgen]
 #[derive(Clone)]
 pub struct Completer {
@@ -609,6 +677,17 @@ impl Completer {
             })
         })
     }
+
+    pub fn hover(&mut self, position: Position) -> js_sys::Promise {
+        js_sys::Promise::new(&mut |resolve, reject| {
+            self.link.send_message(Msg::RequestHover {
+                line: position.line_number() as u32,
+                col: position.column() as u32,
+                resolve,
+                reject,
+            })
+        })
+    }
 }
 
 fn is_mac() -> bool {
@@ -1,5 +1,8 @@
-use crate::codestorage;
+use crate::challenge::{self, ChallengeBundle};
+use crate::codestorage::{self, CodeStore};
+use crate::compile_state::{self, CompileState};
 use crate::compiler_output_window::CompilerOutputWindow;
+use crate::compiler_status::{self, CompilerHealth};
 use crate::documentation::Documentation;
 use crate::editor_window::EditorWindow;
 use crate::gtag;
@@ -11,11 +14,12 @@ use crate::seed_window::SeedWindow;
 use crate::services;
 use crate::simulation_window::SimulationWindow;
 use crate::toolbar::Toolbar;
+use crate::ui::setting::{LocalStorage, Settings};
 use crate::userid;
 use crate::versions_window::VersionsWindow;
 use crate::welcome::Welcome;
 use monaco::yew::CodeEditorLink;
-use oort_proto::{LeaderboardSubmission, Telemetry};
+use oort_proto::{CompileFailure, CompileStatus, LeaderboardSubmission, Telemetry};
 use oort_simulation_worker::SimAgent;
 use oort_simulator::scenario::{self, Status, MAX_TICKS};
 use oort_simulator::simulation;
@@ -23,10 +27,11 @@ use oort_simulator::simulation::Code;
 use oort_simulator::snapshot::Snapshot;
 use rand::Rng;
 use regex::Regex;
-use reqwasm::http::Request;
 use simulation::PHYSICS_TICK_LENGTH;
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::time::Duration;
 use wasm_bindgen::prelude::Closure;
 use wasm_bindgen::JsCast;
 use wasm_bindgen::JsValue;
@@ -38,6 +43,7 @@ use yew_agent::{Bridge, Bridged};
 use yew_router::prelude::*;
 
 const NUM_BACKGROUND_SIMULATIONS: u32 = 10;
+const COMPILE_POLL_INTERVAL_MS: u32 = 250;
 
 fn empty() -> JsValue {
     js_sys::Object::new().into()
@@ -51,10 +57,17 @@ pub enum Msg {
     ReceivedBackgroundSimAgentResponse(oort_simulation_worker::Response, u32),
     EditorAction { team: usize, action: String },
     ShowFeedback,
+    ShowSettings,
+    SettingsChanged(Settings),
     DismissOverlay,
     CompileFinished(Vec<Result<Code, String>>, ExecutionMode),
+    CompileProgress(String),
+    CompileTimedOut(instant::Instant),
+    CancelCompile,
     SubmitToTournament,
     UploadShortcode,
+    ShareCode,
+    AcceptChallenge,
     FormattedCode { team: usize, text: String },
     ReplaceCode { team: usize, text: String },
     ShowError(String),
@@ -62,6 +75,12 @@ pub enum Msg {
     LoadVersion(String),
     SaveVersion(String),
     RefreshVersions,
+    ReceivedLeaderboardToken(String),
+    LeaderboardTokenFailed(String),
+    SetPractice(bool),
+    CheckCompilerStatus,
+    // The health and how long the request took.
+    ReceivedCompilerStatus(CompilerHealth, Duration),
     Nop,
 }
 
@@ -70,7 +89,23 @@ enum Overlay {
     MissionComplete,
     Compiling,
     Feedback,
+    Settings,
     Error(String),
+    SharedCode(String),
+    Challenge(ChallengeBundle),
+    ChallengeResult {
+        status: Status,
+        seed: u32,
+    },
+}
+
+/// The token that lets the backend accept a leaderboard submission for the
+/// current run.
+enum LeaderboardToken {
+    NotRequested,
+    Pending,
+    Received(String),
+    Failed(String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -97,6 +132,24 @@ pub struct Game {
     previous_seed: Option<u32>,
     versions_update_timestamp: chrono::DateTime<chrono::Utc>,
     execution_mode: ExecutionMode,
+    leaderboard_token: LeaderboardToken,
+    compile_status: Option<String>,
+    compile_state: CompileState,
+    compile_cancelled: Rc<Cell<bool>>,
+    compile_abort: Option<web_sys::AbortController>,
+    compiler_health: CompilerHealth,
+    // When the last /status response arrived and how long it took.
+    compiler_status_checked: Option<(instant::Instant, Duration)>,
+    _compiler_status_interval: gloo_timers::callback::Interval,
+    practice: bool,
+    practice_run: bool,
+    strict_compile: bool,
+    live_codes: Vec<(Code, Code)>,
+    live_code_time: Option<String>,
+    // The accepted versus challenge, until the scenario changes.
+    challenge: Option<ChallengeBundle>,
+    // Where the player's code is saved between visits.
+    code_store: Box<dyn CodeStore>,
 }
 
 pub struct Team {
@@ -117,6 +170,10 @@ pub struct Props {
     pub seed: Option<u32>,
     pub player0: Option<String>,
     pub player1: Option<String>,
+    /// Shown in the compiler output window, e.g. when a link named a
+    /// scenario that doesn't exist.
+    #[prop_or_default]
+    pub error: Option<String>,
 }
 
 impl Component for Game {
@@ -133,8 +190,19 @@ impl Component for Game {
             closure.forget();
         }
 
+        apply_ui_scale(Settings::load().ui_scale);
+        codestorage::migrate_completed(&LocalStorage);
+
         let compilation_cache = HashMap::new();
 
+        context.link().send_message(Msg::CheckCompilerStatus);
+        let compiler_status_interval = {
+            let link = context.link().clone();
+            gloo_timers::callback::Interval::new(compiler_status::POLL_INTERVAL_MS, move || {
+                link.send_message(Msg::CheckCompilerStatus)
+            })
+        };
+
         Self {
             background_agents: Vec::new(),
             background_snapshots: Vec::new(),
@@ -152,6 +220,21 @@ impl Component for Game {
             previous_seed: None,
             versions_update_timestamp: chrono::Utc::now(),
             execution_mode: ExecutionMode::Initial,
+            leaderboard_token: LeaderboardToken::NotRequested,
+            compile_status: None,
+            compile_state: CompileState::Idle,
+            compile_cancelled: Rc::new(Cell::new(false)),
+            compile_abort: None,
+            compiler_health: CompilerHealth::Unknown,
+            compiler_status_checked: None,
+            _compiler_status_interval: compiler_status_interval,
+            practice: false,
+            practice_run: false,
+            strict_compile: crate::ui::setting::read("strict_compile", false),
+            live_codes: Vec::new(),
+            live_code_time: None,
+            challenge: None,
+            code_store: Box::new(LocalStorage),
         }
     }
 
@@ -169,6 +252,9 @@ impl Component for Game {
                     context.props().player1.clone(),
                 ];
                 let has_shortcodes = !shortcodes.iter().all(Option::is_none);
+                if self.redirect_if_locked(context, &context.props().scenario) {
+                    return false;
+                }
                 self.change_scenario(context, &context.props().scenario, !has_shortcodes);
                 if has_shortcodes {
                     context.link().send_future_batch(async move {
@@ -192,6 +278,24 @@ impl Component for Game {
                         }); // TODO
                         msgs
                     });
+                } else if let Some(bundle) = challenge::take_from_location() {
+                    if bundle.scenario_name == context.props().scenario {
+                        self.overlay = Some(Overlay::Challenge(bundle));
+                    } else {
+                        self.overlay = Some(Overlay::Error(format!(
+                            "The challenge is for a different scenario ({}).",
+                            bundle.scenario_name
+                        )));
+                    }
+                } else if let Some(shared) = crate::share::take_from_location() {
+                    if shared.scenario_name == context.props().scenario {
+                        self.overlay = Some(Overlay::SharedCode(shared.code));
+                    } else {
+                        self.overlay = Some(Overlay::Error(format!(
+                            "The shared code is for a different scenario ({}).",
+                            shared.scenario_name
+                        )));
+                    }
                 }
                 true
             }
@@ -249,6 +353,55 @@ impl Component for Game {
                 self.team(team).set_editor_text(&code_to_string(&code));
                 false
             }
+            Msg::EditorAction {
+                team: _,
+                ref action,
+            } if action == "oort-toggle-strict-compile" => {
+                self.strict_compile = !self.strict_compile;
+                crate::ui::setting::write("strict_compile", &self.strict_compile);
+                true
+            }
+            Msg::EditorAction {
+                team: _,
+                ref action,
+            } if action == "oort-toggle-keep-running-in-background" => {
+                let mut settings = Settings::load();
+                settings.keep_running_in_background = !settings.keep_running_in_background;
+                log::info!(
+                    "keep_running_in_background = {}",
+                    settings.keep_running_in_background
+                );
+                context.link().send_message(Msg::SettingsChanged(settings));
+                false
+            }
+            Msg::EditorAction {
+                team: _,
+                ref action,
+            } if action == "oort-copy-challenge-link" => {
+                let code = self.player_team().get_editor_code();
+                if is_encrypted(&code) {
+                    self.overlay = Some(Overlay::Error(
+                        "Encrypted code can't be shared.".to_string(),
+                    ));
+                    return true;
+                }
+                let bundle = ChallengeBundle {
+                    scenario_name: context.props().scenario.clone(),
+                    seed: self.previous_seed.unwrap_or_default(),
+                    code: code_to_string(&code),
+                };
+                match challenge::make_link(&bundle) {
+                    Ok(link) => {
+                        log::info!("Copied challenge link to clipboard");
+                        crate::js::clipboard::write(&link);
+                        false
+                    }
+                    Err(e) => {
+                        self.overlay = Some(Overlay::Error(e));
+                        true
+                    }
+                }
+            }
             Msg::EditorAction { team, ref action } if action == "oort-format" => {
                 let text = self.team(team).get_editor_text();
                 let cb = context
@@ -333,6 +486,20 @@ impl Component for Game {
                 self.overlay = Some(Overlay::Feedback);
                 true
             }
+            Msg::ShowSettings => {
+                self.overlay = Some(Overlay::Settings);
+                true
+            }
+            Msg::SettingsChanged(settings) => {
+                settings.save();
+                apply_ui_scale(settings.ui_scale);
+                if let Some(link) = self.simulation_window_link.as_ref() {
+                    link.send_message(crate::simulation_window::Msg::SettingsChanged(
+                        settings.clone(),
+                    ));
+                }
+                matches!(self.overlay, Some(Overlay::Settings))
+            }
             Msg::ShowError(e) => {
                 self.overlay = Some(Overlay::Error(e));
                 true
@@ -349,6 +516,8 @@ impl Component for Game {
                 if matches!(self.overlay, Some(Overlay::Compiling)) {
                     self.overlay = None;
                 }
+                self.compile_status = None;
+                self.compile_abort = None;
                 if self.compilation_cache.len() > 10 {
                     self.compilation_cache.clear();
                 }
@@ -374,20 +543,113 @@ impl Component for Game {
                     .filter_map(|x| x.as_ref().err())
                     .cloned()
                     .collect();
+                services::send_telemetry(Telemetry::Compile {
+                    success: errors.is_empty(),
+                });
+                self.compile_state = if errors.is_empty() {
+                    CompileState::Succeeded
+                } else {
+                    CompileState::Failed {
+                        errors: errors.join("\n"),
+                    }
+                };
                 if errors.is_empty() {
                     services::send_telemetry(Telemetry::StartScenario {
                         scenario_name: context.props().scenario.clone(),
                         code: code_to_string(&self.player_team().running_source_code),
                     });
+                    self.leaderboard_token = LeaderboardToken::NotRequested;
                     self.run(context, execution_mode);
+                    if execution_mode == ExecutionMode::Run && self.leaderboard_eligible() {
+                        let callback = context.link().callback(|result: anyhow::Result<String>| {
+                            result.map_or_else(
+                                |e| Msg::LeaderboardTokenFailed(e.to_string()),
+                                Msg::ReceivedLeaderboardToken,
+                            )
+                        });
+                        self.leaderboard_token = LeaderboardToken::Pending;
+                        services::get_leaderboard_token(&context.props().scenario, callback);
+                    }
                     self.focus_simulation();
                 } else {
-                    self.compiler_errors = Some(errors.join("\n"));
+                    let mut output = errors.join("\n");
+                    if self.strict_compile {
+                        if let Some(link) = self.simulation_window_link.as_ref() {
+                            link.send_message(crate::simulation_window::Msg::Halt(
+                                "COMPILE FAILED".to_string(),
+                            ));
+                        }
+                        self.live_code_time = None;
+                    } else if let Some(time) = self.live_code_time.as_ref() {
+                        // Roll back so the running code fields describe what the
+                        // simulation is still executing.
+                        for (team, (source_code, compiled_code)) in
+                            self.teams.iter_mut().zip(self.live_codes.iter())
+                        {
+                            team.running_source_code = source_code.clone();
+                            team.running_compiled_code = compiled_code.clone();
+                        }
+                        output.push_str(&format!(
+                            "\n\nThe simulation is still running the code from {time}."
+                        ));
+                    }
+                    self.compiler_errors = Some(output);
                     self.focus_editor(teams_with_errors[0]);
                     js::golden_layout::select_tab("compiler_output");
                 }
                 true
             }
+            Msg::CompileProgress(status) => {
+                self.compile_status = Some(status);
+                matches!(self.overlay, Some(Overlay::Compiling))
+            }
+            Msg::CompileTimedOut(started_at) => {
+                if !self
+                    .compile_state
+                    .timed_out(started_at, instant::Instant::now())
+                {
+                    return false;
+                }
+                self.abort_compile();
+                let errors = format!(
+                    "Compile timed out after {} seconds. The compiler may be busy, try again.",
+                    compile_state::TIMEOUT.as_secs()
+                );
+                self.compile_state = CompileState::Failed {
+                    errors: errors.clone(),
+                };
+                self.compiler_errors = Some(errors);
+                if matches!(self.overlay, Some(Overlay::Compiling)) {
+                    self.overlay = None;
+                }
+                js::golden_layout::select_tab("compiler_output");
+                true
+            }
+            Msg::CheckCompilerStatus => {
+                let start_time = instant::Instant::now();
+                services::get_compiler_status(context.link().callback(move |result| {
+                    Msg::ReceivedCompilerStatus(
+                        CompilerHealth::from_result(result),
+                        instant::Instant::now() - start_time,
+                    )
+                }));
+                false
+            }
+            Msg::ReceivedCompilerStatus(health, latency) => {
+                self.compiler_status_checked = Some((instant::Instant::now(), latency));
+                let changed = self.compiler_health != health;
+                self.compiler_health = health;
+                changed
+            }
+            Msg::CancelCompile => {
+                self.abort_compile();
+                self.compile_state = CompileState::Idle;
+                if matches!(self.overlay, Some(Overlay::Compiling)) {
+                    self.overlay = None;
+                }
+                self.focus_editor(0);
+                true
+            }
             Msg::FormattedCode { team, text } => {
                 self.team(team).set_editor_text_preserving_cursor(&text);
                 false
@@ -447,6 +709,42 @@ impl Component for Game {
                 });
                 false
             }
+            Msg::ShareCode => {
+                let code = self.player_team().get_editor_code();
+                if is_encrypted(&code) {
+                    self.overlay = Some(Overlay::Error(
+                        "Encrypted code can't be shared.".to_string(),
+                    ));
+                    return true;
+                }
+                match crate::share::make_link(&context.props().scenario, &code_to_string(&code)) {
+                    Ok(link) => {
+                        log::info!("Copied share link to clipboard");
+                        crate::js::clipboard::write(&link);
+                        false
+                    }
+                    Err(e) => {
+                        self.overlay = Some(Overlay::Error(e));
+                        true
+                    }
+                }
+            }
+            Msg::AcceptChallenge => {
+                if let Some(Overlay::Challenge(bundle)) = self.overlay.take() {
+                    // The recipient's code moves to team 1. It's saved first
+                    // because saving is skipped while a challenge is active.
+                    self.save_current_code(context, &context.props().scenario, None);
+                    let own_code = self.player_team().get_editor_text();
+                    self.team(1).set_editor_text(&own_code);
+                    self.team(0).set_editor_text(&bundle.code);
+                    self.challenge = Some(bundle);
+                    context.link().send_message(Msg::EditorAction {
+                        team: 0,
+                        action: "oort-execute".to_string(),
+                    });
+                }
+                true
+            }
             Msg::Resized => {
                 let root = gloo_utils::document().document_element().unwrap();
                 let new_size = (root.client_width(), root.client_height());
@@ -462,6 +760,20 @@ impl Component for Game {
                 }
                 false
             }
+            // Re-render so a results overlay that was waiting can submit.
+            Msg::ReceivedLeaderboardToken(token) => {
+                self.leaderboard_token = LeaderboardToken::Received(token);
+                true
+            }
+            Msg::LeaderboardTokenFailed(e) => {
+                self.leaderboard_token = LeaderboardToken::Failed(e);
+                true
+            }
+            Msg::SetPractice(practice) => {
+                // Takes effect on the next run.
+                self.practice = practice;
+                true
+            }
             Msg::Nop => false,
         }
     }
@@ -474,12 +786,32 @@ impl Component for Game {
                 .target()
                 .expect("Event should have a target when dispatched");
             let data = target.unchecked_into::<HtmlInputElement>().value();
+            if let Some(prerequisite) = codestorage::locked_by(&data) {
+                return Msg::ShowError(format!(
+                    "Complete {} to unlock this scenario.",
+                    scenario::load(&prerequisite).human_name()
+                ));
+            }
             navigator.push(&crate::Route::Scenario {
                 scenario: data.clone(),
             });
             Msg::Nop
         });
+        let toggle_practice_cb = context.link().callback(|e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            Msg::SetPractice(target.unchecked_into::<HtmlInputElement>().checked())
+        });
         let show_feedback_cb = context.link().callback(|_| Msg::ShowFeedback);
+        let show_settings_cb = context.link().callback(|e: web_sys::MouseEvent| {
+            e.prevent_default();
+            Msg::ShowSettings
+        });
+        let share_cb = context.link().callback(|e: web_sys::MouseEvent| {
+            e.prevent_default();
+            Msg::ShareCode
+        });
 
         // For EditorWindow 0
         let editor_window0_host = gloo_utils::document()
@@ -504,6 +836,16 @@ impl Component for Game {
             .get_element_by_id("simulation-window")
             .expect("a #simulation-window element");
         let on_simulation_finished = context.link().callback(Msg::SimulationFinished);
+        let on_code_dropped = context.link().batch_callback(|text| {
+            vec![
+                Msg::ReplaceCode { team: 0, text },
+                Msg::EditorAction {
+                    team: 0,
+                    action: "oort-execute".to_string(),
+                },
+            ]
+        });
+        let on_show_settings = context.link().callback(|_| Msg::ShowSettings);
         let register_link = context.link().callback(Msg::RegisterSimulationWindowLink);
         let version = context.props().version.clone();
 
@@ -528,7 +870,21 @@ impl Component for Game {
         let compiler_output_window_host = gloo_utils::document()
             .get_element_by_id("compiler-output-window")
             .expect("a #compiler-output-window element");
-        let compiler_errors = self.compiler_errors.clone();
+        let compiler_errors = self
+            .compiler_errors
+            .clone()
+            .or_else(|| context.props().error.clone());
+        let live_code = Some(format!(
+            "Live code: {}. Compile errors {} the simulation.",
+            self.live_code_time
+                .as_ref()
+                .map_or("none".to_string(), |time| format!("from {time}")),
+            if self.strict_compile {
+                "stop"
+            } else {
+                "don't stop"
+            },
+        ));
 
         // For LeaderboardWindow.
         let leaderboard_window_host = gloo_utils::document()
@@ -595,13 +951,13 @@ impl Component for Game {
 
         html! {
         <>
-            <Toolbar scenario_name={context.props().scenario.clone()} {select_scenario_cb} show_feedback_cb={show_feedback_cb.clone()} />
+            <Toolbar scenario_name={context.props().scenario.clone()} practice={self.practice} compile_state={self.compile_state.clone()} compiler_health={self.compiler_health.clone()} {select_scenario_cb} {toggle_practice_cb} show_feedback_cb={show_feedback_cb.clone()} {show_settings_cb} {share_cb} />
             <Welcome host={welcome_window_host} show_feedback_cb={show_feedback_cb.clone()} select_scenario_cb={select_scenario_cb2} />
             <EditorWindow host={editor_window0_host} editor_link={editor0_link} on_editor_action={on_editor0_action} team=0 />
             <EditorWindow host={editor_window1_host} editor_link={editor1_link} on_editor_action={on_editor1_action} team=1 />
-            <SimulationWindow host={simulation_window_host} {on_simulation_finished} {register_link} {version} canvas_ref={self.simulation_canvas_ref.clone()} />
-            <Documentation host={documentation_window_host} {show_feedback_cb} />
-            <CompilerOutputWindow host={compiler_output_window_host} {compiler_errors} />
+            <SimulationWindow host={simulation_window_host} {on_simulation_finished} {on_code_dropped} {on_show_settings} {register_link} {version} canvas_ref={self.simulation_canvas_ref.clone()} />
+            <Documentation host={documentation_window_host} scenario_name={context.props().scenario.clone()} {show_feedback_cb} />
+            <CompilerOutputWindow host={compiler_output_window_host} {compiler_errors} {live_code} />
             <LeaderboardWindow host={leaderboard_window_host} scenario_name={context.props().scenario.clone()} {play_cb} />
             <VersionsWindow host={versions_window_host} scenario_name={context.props().scenario.clone()} {load_cb} {save_cb} update_timestamp={self.versions_update_timestamp} />
             <SeedWindow host={seed_window_host} {current_seed} change_cb={change_seed_cb} />
@@ -629,7 +985,21 @@ impl Component for Game {
         }
 
         if props.scenario != old_props.scenario {
-            self.save_current_code(context, &old_props.scenario, None);
+            // A locked scenario was never loaded, so the editors still hold
+            // the code of whatever came before it.
+            if codestorage::locked_by(&old_props.scenario).is_none() {
+                if self.challenge.is_none() {
+                    if let Some(view_state) =
+                        self.teams.first().and_then(|team| team.save_view_state())
+                    {
+                        codestorage::save_view_state(&old_props.scenario, &view_state);
+                    }
+                }
+                self.save_current_code(context, &old_props.scenario, None);
+            }
+            if self.redirect_if_locked(context, &props.scenario) {
+                return false;
+            }
             self.change_scenario(context, &props.scenario, true);
             return true;
         }
@@ -664,6 +1034,17 @@ impl Game {
             return true;
         }
 
+        if let Some(challenge) = self.challenge.as_ref() {
+            if status != Status::Running {
+                self.overlay = Some(Overlay::ChallengeResult {
+                    status,
+                    seed: challenge.seed,
+                });
+            }
+            self.last_snapshot = Some(snapshot);
+            return true;
+        }
+
         if context.props().demo && status != Status::Running {
             self.run(context, ExecutionMode::Run);
             return false;
@@ -671,6 +1052,21 @@ impl Game {
 
         if self.execution_mode == ExecutionMode::Run {
             if let Status::Victory { team: 0 } = status {
+                // Beating frozen enemies doesn't unlock anything.
+                if !self.practice_run {
+                    codestorage::mark_completed(&context.props().scenario);
+                    if let Some(par_ticks) = scenario::load(&context.props().scenario).par_ticks() {
+                        codestorage::record_stars(
+                            &context.props().scenario,
+                            scenario::stars(par_ticks, snapshot.tick),
+                        );
+                    }
+                }
+                services::send_telemetry(Telemetry::ScenarioComplete {
+                    scenario_name: context.props().scenario.clone(),
+                    ticks: (snapshot.time / PHYSICS_TICK_LENGTH).round() as u32,
+                    seed: self.previous_seed.unwrap_or_default(),
+                });
                 self.background_agents.clear();
                 self.background_snapshots.clear();
                 self.background_nonce = rand::thread_rng().gen();
@@ -690,6 +1086,7 @@ impl Game {
                         seed,
                         codes: codes.clone(),
                         nonce: self.background_nonce,
+                        practice: self.practice_run,
                     });
                     self.background_agents.push(sim_agent);
                 }
@@ -731,9 +1128,68 @@ impl Game {
                 <div class={inner_class} onclick={inner_click_cb}>{
                     match &self.overlay {
                         Some(Overlay::MissionComplete) => self.render_mission_complete_overlay(context),
-                        Some(Overlay::Compiling) => html! { <h1 class="compiling">{ "Compiling..." }</h1> },
+                        Some(Overlay::Compiling) => {
+                            let cancel_cb = context.link().callback(|_| Msg::CancelCompile);
+                            html! {
+                                <div class="compiling">
+                                    <div class="spinner" />
+                                    <h1>{ self.compile_status.as_deref().unwrap_or("Compiling...") }</h1>
+                                    <button onclick={cancel_cb}>{ "Cancel" }</button>
+                                </div>
+                            }
+                        }
                         Some(Overlay::Feedback) => html! { <crate::feedback::Feedback {close_overlay_cb} /> },
+                        Some(Overlay::Settings) => self.render_settings_overlay(context),
                         Some(Overlay::Error(e)) => html! { <><h1>{ "Error" }</h1><span>{ e }</span></> },
+                        Some(Overlay::SharedCode(code)) => {
+                            let text = code.clone();
+                            let load_cb = context.link().batch_callback(move |_| {
+                                vec![
+                                    Msg::ReplaceCode { team: 0, text: text.clone() },
+                                    Msg::DismissOverlay,
+                                ]
+                            });
+                            let cancel_cb = context.link().callback(|_| Msg::DismissOverlay);
+                            html! {
+                                <>
+                                    <h1>{ "Load shared code?" }</h1>
+                                    <p>{ "This link contains code that will replace the code in your editor. Review it before running it." }</p>
+                                    <pre class="shared-code">{ code }</pre>
+                                    <button onclick={load_cb}>{ "Load" }</button>
+                                    { "\u{00a0}" }
+                                    <button onclick={cancel_cb}>{ "Cancel" }</button>
+                                </>
+                            }
+                        }
+                        Some(Overlay::Challenge(bundle)) => {
+                            let accept_cb = context.link().callback(|_| Msg::AcceptChallenge);
+                            let cancel_cb = context.link().callback(|_| Msg::DismissOverlay);
+                            html! {
+                                <>
+                                    <h1>{ "Accept versus challenge?" }</h1>
+                                    <p>{ format!("The challenger's code will run as team 0 against the code in your editor as team 1, on seed {}. Review it before running it.", bundle.seed) }</p>
+                                    <pre class="shared-code">{ &bundle.code }</pre>
+                                    <button onclick={accept_cb}>{ "Accept" }</button>
+                                    { "\u{00a0}" }
+                                    <button onclick={cancel_cb}>{ "Cancel" }</button>
+                                </>
+                            }
+                        }
+                        Some(Overlay::ChallengeResult { status, seed }) => {
+                            let result = match status {
+                                Status::Victory { team: 0 } => "Team 0 (challenger) won".to_string(),
+                                Status::Victory { team: 1 } => "Team 1 (you) won".to_string(),
+                                Status::Victory { team } => format!("Team {team} won"),
+                                Status::Draw | Status::Failed | Status::Running => "Draw".to_string(),
+                            };
+                            html! {
+                                <>
+                                    <h1>{ "Challenge complete" }</h1>
+                                    <p>{ result }</p>
+                                    <p>{ format!("Seed: {seed}") }</p>
+                                </>
+                            }
+                        }
                         None => unreachable!(),
                     }
                 }</div>
@@ -847,12 +1303,105 @@ impl Game {
         })
     }
 
+    /// Each change starts from the stored settings rather than a copy taken
+    /// when the overlay was drawn, since the simulation window's keyboard
+    /// shortcuts save settings too.
+    fn render_settings_overlay(&self, context: &yew::Context<Self>) -> Html {
+        let settings = Settings::load();
+        let checkbox = |label: &str, field: fn(&mut Settings) -> &mut bool| {
+            let checked = *field(&mut settings.clone());
+            let onchange = context.link().callback(move |e: Event| {
+                let mut settings = Settings::load();
+                *field(&mut settings) = e.target_unchecked_into::<HtmlInputElement>().checked();
+                Msg::SettingsChanged(settings)
+            });
+            html! {
+                <div><label><input type="checkbox" {checked} {onchange} />{ label.to_string() }</label></div>
+            }
+        };
+        let slider = |label: &str, min: f32, max: f32, field: fn(&mut Settings) -> &mut f32| {
+            let value = *field(&mut settings.clone());
+            let oninput = context.link().callback(move |e: InputEvent| {
+                let mut settings = Settings::load();
+                *field(&mut settings) = e
+                    .target_unchecked_into::<HtmlInputElement>()
+                    .value_as_number() as f32;
+                Msg::SettingsChanged(settings)
+            });
+            html! {
+                <div>
+                    <label>
+                        { label.to_string() }
+                        <input type="range" min={min.to_string()} max={max.to_string()} step="0.05" value={value.to_string()} {oninput} />
+                        { format!("{:.0}%", value * 100.0) }
+                    </label>
+                </div>
+            }
+        };
+
+        html! {
+            <div class="settings">
+                <h1>{ "Settings" }</h1>
+                <h2>{ "Display" }</h2>
+                { slider("UI scale", Settings::MIN_UI_SCALE, Settings::MAX_UI_SCALE, |s| &mut s.ui_scale) }
+                { checkbox("Reduce motion (no particles or trails)", |s| &mut s.reduce_motion) }
+                { checkbox("Postprocessing (blur)", |s| &mut s.blur) }
+                { checkbox("NLIPS", |s| &mut s.nlips) }
+                { checkbox("Heading and velocity indicators", |s| &mut s.ship_vectors) }
+                { checkbox("Thrust plumes", |s| &mut s.thrust_plumes) }
+                { checkbox("Grid and world boundary", |s| &mut s.grid) }
+                { checkbox("Minimap", |s| &mut s.minimap) }
+                { checkbox("Ship list", |s| &mut s.spectator) }
+                { checkbox("Debug lines for all ships", |s| &mut s.debug) }
+                <h2>{ "Sound" }</h2>
+                { checkbox("Mute", |s| &mut s.mute) }
+                { slider("Volume", 0.0, 1.0, |s| &mut s.volume) }
+                <h2>{ "Simulation" }</h2>
+                { checkbox("Pause when one of your ships is destroyed", |s| &mut s.pause_on_death) }
+                { checkbox("Keep running in a background tab", |s| &mut s.keep_running_in_background) }
+            </div>
+        }
+    }
+
     fn render_mission_complete_overlay(&self, context: &yew::Context<Self>) -> Html {
         let score_time = if let Some(snapshot) = self.last_snapshot.as_ref() {
             snapshot.score_time
         } else {
             0.0
         };
+        let stars = match (
+            scenario::load(&context.props().scenario).par_ticks(),
+            self.last_snapshot.as_ref(),
+        ) {
+            (Some(par_ticks), Some(snapshot)) => {
+                let stars = scenario::stars(par_ticks, snapshot.tick);
+                let best = codestorage::best_stars(&context.props().scenario);
+                html! {
+                    <>
+                        <span class="stars">{ codestorage::format_stars(stars) }</span>
+                        { if best > stars { format!(" (best: {})", codestorage::format_stars(best)) } else { String::new() } }
+                        <br/>
+                    </>
+                }
+            }
+            _ => html! {},
+        };
+        let record = match self
+            .last_snapshot
+            .as_ref()
+            .and_then(|snapshot| snapshot.rounds.as_ref())
+        {
+            Some(tally) => html! {
+                <>
+                    { format!(
+                        "Record: {} wins, {} losses, {} draws in {} rounds",
+                        tally.wins, tally.losses, tally.draws, tally.total
+                    ) }
+                    <br/>
+                </>
+            },
+            None => html! {},
+        };
         let source_code = code_to_string(&self.player_team().running_source_code);
         let code_size = crate::code_size::calculate(&source_code);
         let leaderboard_eligible = self.leaderboard_eligible();
@@ -980,15 +1529,23 @@ impl Game {
                     vec![Msg::DismissOverlay]
                 })
             };
-            let leaderboard_submission = (leaderboard_eligible && summary.failed_seeds.is_empty())
-                .then(|| LeaderboardSubmission {
+            let token = match &self.leaderboard_token {
+                LeaderboardToken::Received(token) => Some(token.clone()),
+                _ => None,
+            };
+            let leaderboard_submission = token
+                .filter(|_| leaderboard_eligible && summary.failed_seeds.is_empty())
+                .map(|token| LeaderboardSubmission {
                     userid: userid::get_userid(),
                     username: userid::get_username(),
                     timestamp: chrono::Utc::now(),
                     scenario_name: summary.scenario_name.clone(),
+                    code_hash: oort_envelope::hash_code(&source_code),
                     code: source_code.clone(),
                     code_size,
                     time: summary.average_time.unwrap(),
+                    seed: self.previous_seed,
+                    token,
                 });
             html! {
                 <>
@@ -1012,7 +1569,21 @@ impl Game {
                     { next_scenario_link }
                     <br />
                     {
-                        if leaderboard_eligible { html! { <Leaderboard scenario_name={ context.props().scenario.clone() } submission={leaderboard_submission} {play_cb} /> } }
+                        if leaderboard_eligible {
+                            match &self.leaderboard_token {
+                                // The leaderboard is only created once the token arrives so it
+                                // submits with it.
+                                LeaderboardToken::Pending => html! { <p>{ "Waiting for leaderboard token..." }</p> },
+                                LeaderboardToken::Failed(e) => html! {
+                                    <>
+                                        <p>{ format!("Not submitted to the leaderboard, failed to get a token: {e}") }</p>
+                                        <Leaderboard scenario_name={ context.props().scenario.clone() } submission={None::<LeaderboardSubmission>} {play_cb} />
+                                    </>
+                                },
+                                _ => html! { <Leaderboard scenario_name={ context.props().scenario.clone() } submission={leaderboard_submission} {play_cb} /> },
+                            }
+                        }
+                        else if self.practice_run { html! { <p>{ "Leaderboard skipped in practice mode" }</p> } }
                         else { html! { <p>{ "Leaderboard skipped due to modified opponent code" }</p> } }
                     }
                 </>
@@ -1023,7 +1594,12 @@ impl Game {
 
         html! {
             <div class="centered">
-                <h1>{ "Mission Complete" }</h1>
+                <h1>
+                    { "Mission Complete" }
+                    { if self.practice_run { html! { <>{ " " }<span class="practice">{ "PRACTICE" }</span></> } } else { html! {} } }
+                </h1>
+                { stars }
+                { record }
                 { "Time: " }{ format!("{score_time:.3}") }{ " seconds" }<br/>
                 { "Code size: " }{ code_size }{ " bytes" }<br/><br/>
                 { background_status }<br/><br/>
@@ -1032,44 +1608,145 @@ impl Game {
         }
     }
 
+    fn abort_compile(&mut self) {
+        self.compile_cancelled.set(true);
+        self.compile_status = None;
+        if let Some(controller) = self.compile_abort.take() {
+            controller.abort();
+        }
+    }
+
     pub fn start_compile(&mut self, context: &Context<Self>, execution_mode: ExecutionMode) {
+        let now = instant::Instant::now();
+        if self.compile_state.is_repeat(now) {
+            log::info!("Ignoring repeated compile request");
+            return;
+        }
+
+        // Any compile still in flight is superseded by this one.
+        self.abort_compile();
+        let cancelled = Rc::new(Cell::new(false));
+        self.compile_cancelled = cancelled.clone();
+        let controller = web_sys::AbortController::new().ok();
+        let signal = controller.as_ref().map(|x| x.signal());
+        self.compile_abort = controller;
+
         self.compiler_errors = None;
+        self.compile_state = CompileState::Compiling { started_at: now };
+        if let Some(Code::Rust(text)) = self.teams.first().map(|x| &x.running_source_code) {
+            crate::diagnostics::set_code(text);
+        }
         self.overlay = Some(Overlay::Compiling);
+        let may_be_cold = match self.compiler_status_checked {
+            Some((checked_at, latency)) => compiler_status::may_be_cold_starting(
+                &self.compiler_health,
+                latency.as_secs_f64() * 1e3,
+                (now - checked_at).as_secs_f64() * 1e3,
+            ),
+            None => true,
+        };
+        if may_be_cold {
+            self.compile_status =
+                Some("Compiler may be cold-starting, expect a delay.".to_string());
+        }
 
         let finished_callback = context
             .link()
             .callback(move |results| Msg::CompileFinished(results, execution_mode));
+        let progress_callback = context.link().callback(Msg::CompileProgress);
+        {
+            let link = context.link().clone();
+            gloo_timers::callback::Timeout::new(
+                compile_state::TIMEOUT.as_millis() as u32,
+                move || link.send_message(Msg::CompileTimedOut(now)),
+            )
+            .forget();
+        }
 
-        async fn compile(text: String) -> Result<Code, String> {
-            if text.trim().is_empty() {
-                return Ok(Code::None);
+        fn log_error(e: impl std::fmt::Display) -> String {
+            log::error!("Compile error: {}", e);
+            e.to_string()
+        }
+
+        // Returns None if a request reached a compiler instance that doesn't
+        // have the job. Session affinity is best effort.
+        async fn compile_job(
+            text: String,
+            progress: &Callback<String>,
+            cancelled: &Cell<bool>,
+            signal: Option<&web_sys::AbortSignal>,
+        ) -> Result<Option<Vec<u8>>, String> {
+            let job_id = services::start_compile(text, signal)
+                .await
+                .map_err(log_error)?;
+
+            loop {
+                let status = match services::poll_compile(&job_id, signal).await {
+                    _ if cancelled.get() => {
+                        // Aborting the poll request doesn't stop the job, so
+                        // free up the compiler explicitly.
+                        services::cancel_compile(&job_id).await;
+                        return Err("Compile cancelled".to_string());
+                    }
+                    Ok(Some(status)) => status,
+                    Ok(None) => {
+                        log::warn!("Compile job {} not found", job_id);
+                        return Ok(None);
+                    }
+                    Err(e) => return Err(log_error(e)),
+                };
+                match status {
+                    CompileStatus::Queued => progress.emit("Waiting for compiler...".to_string()),
+                    CompileStatus::Compiling => progress.emit("Compiling...".to_string()),
+                    CompileStatus::Done => break,
+                    CompileStatus::Error { error } => {
+                        return Err(match error {
+                            CompileFailure::CompileError(message) => log_error(message),
+                            CompileFailure::Timeout => {
+                                "Compilation took too long. Try simplifying your code, \
+                                 e.g. by reducing large constant arrays or deeply nested generics."
+                                    .to_string()
+                            }
+                            CompileFailure::ResourceLimit => {
+                                "Compilation ran out of memory. Try simplifying your code, \
+                                 e.g. by reducing large constant arrays or deeply nested generics."
+                                    .to_string()
+                            }
+                        })
+                    }
+                }
+                gloo_timers::future::TimeoutFuture::new(COMPILE_POLL_INTERVAL_MS).await;
             }
 
-            let start_time = instant::Instant::now();
+            services::fetch_compiled(&job_id, signal)
+                .await
+                .map_err(log_error)
+        }
 
-            let url = format!("{}/compile", services::compiler_url());
-            let result = Request::post(&url).body(text).send().await;
-            if let Err(e) = result {
-                log::error!("Compile error: {}", e);
-                return Err(e.to_string());
+        async fn compile(
+            text: String,
+            progress: &Callback<String>,
+            cancelled: &Cell<bool>,
+            signal: Option<&web_sys::AbortSignal>,
+        ) -> Result<Code, String> {
+            if text.trim().is_empty() {
+                return Ok(Code::None);
             }
 
-            let response = result.unwrap();
-            if !response.ok() {
-                let error = response.text().await.unwrap();
-                log::error!("Compile error: {}", error);
-                return Err(error);
-            }
+            let start_time = instant::Instant::now();
 
-            let wasm = response.binary().await;
-            if let Err(e) = wasm {
-                log::error!("Compile error: {}", e);
-                return Err(e.to_string());
-            }
+            let wasm = match compile_job(text.clone(), progress, cancelled, signal).await? {
+                Some(wasm) => wasm,
+                None => {
+                    log::info!("Falling back to synchronous compile");
+                    progress.emit("Compiling...".to_string());
+                    services::compile(text, signal).await.map_err(log_error)?
+                }
+            };
 
             let elapsed = instant::Instant::now() - start_time;
             log::info!("Compile succeeded in {:?}", elapsed);
-            Ok(Code::Wasm(wasm.unwrap()))
+            Ok(Code::Wasm(wasm))
         }
 
         let source_codes: Vec<_> = self
@@ -1096,13 +1773,17 @@ impl Game {
             let mut results = vec![];
             for source_code in source_codes {
                 let result = match source_code {
-                    Code::Rust(text) => compile(text).await,
+                    Code::Rust(text) => {
+                        compile(text, &progress_callback, &cancelled, signal.as_ref()).await
+                    }
                     Code::Builtin(name) => oort_simulator::vm::builtin::load_compiled(&name),
                     other => Ok(other),
                 };
                 results.push(result);
             }
-            finished_callback.emit(results);
+            if !cancelled.get() {
+                finished_callback.emit(results);
+            }
         });
     }
 
@@ -1126,6 +1807,18 @@ impl Game {
         let start_paused = matches!(execution_mode, ExecutionMode::Replay { paused: true });
         self.previous_seed = Some(seed);
         self.execution_mode = execution_mode;
+        self.practice_run = self.practice;
+        self.live_codes = self
+            .teams
+            .iter()
+            .map(|x| {
+                (
+                    x.running_source_code.clone(),
+                    x.running_compiled_code.clone(),
+                )
+            })
+            .collect();
+        self.live_code_time = Some(chrono::Local::now().format("%H:%M:%S").to_string());
 
         if let Some(link) = self.simulation_window_link.as_ref() {
             link.send_message(crate::simulation_window::Msg::StartSimulation {
@@ -1133,6 +1826,7 @@ impl Game {
                 seed,
                 start_paused,
                 codes: codes.to_vec(),
+                practice: self.practice_run,
             });
         } else {
             log::error!("Missing SimulationWindow");
@@ -1142,8 +1836,36 @@ impl Game {
         self.background_nonce = 0;
     }
 
+    /// Sends the player to the prerequisite instead of loading a locked scenario.
+    ///
+    /// Covers direct URLs and "Next mission" links, which bypass the locked
+    /// entries in the scenario dropdown.
+    fn redirect_if_locked(&self, context: &Context<Self>, scenario_name: &str) -> bool {
+        if context.props().demo {
+            return false;
+        }
+        let prerequisite = match codestorage::locked_by(scenario_name) {
+            Some(prerequisite) => prerequisite,
+            None => return false,
+        };
+        context.link().send_message(Msg::ShowError(format!(
+            "Complete {} to unlock this scenario.",
+            scenario::load(&prerequisite).human_name()
+        )));
+        context
+            .link()
+            .navigator()
+            .unwrap()
+            .replace(&crate::Route::Scenario {
+                scenario: prerequisite,
+            });
+        true
+    }
+
     pub fn change_scenario(&mut self, context: &Context<Self>, scenario_name: &str, run: bool) {
-        let codes = crate::codestorage::load(&context.props().scenario);
+        self.challenge = None;
+        crate::diagnostics::set_scenario(scenario_name);
+        let codes = codestorage::load_from(self.code_store.as_ref(), &context.props().scenario);
         let scenario = oort_simulator::scenario::load(&context.props().scenario);
 
         let to_source_code = |code: &Code| match code {
@@ -1179,6 +1901,11 @@ impl Game {
         }
 
         player_team.set_editor_text(&code_to_string(&player_team.initial_source_code));
+        if !context.props().demo && context.props().scenario != "welcome" {
+            if let Some(view_state) = codestorage::load_view_state(scenario_name) {
+                player_team.restore_view_state(&view_state);
+            }
+        }
         self.teams = vec![player_team];
 
         let enemy_code = if codes.len() > 1 {
@@ -1218,6 +1945,9 @@ impl Game {
     }
 
     pub fn leaderboard_eligible(&self) -> bool {
+        if self.practice_run {
+            return false;
+        }
         for team in &self.teams.as_slice()[1..] {
             if team.running_source_code != team.initial_source_code {
                 log::info!("Not eligible for leaderboard due to modified opponent code");
@@ -1235,7 +1965,8 @@ impl Game {
         scenario_name: &str,
         label: Option<String>,
     ) {
-        if self.teams.is_empty() {
+        // During a challenge the player editor holds the challenger's code.
+        if self.teams.is_empty() || self.challenge.is_some() {
             return;
         }
         let code = self.player_team().get_editor_code();
@@ -1243,7 +1974,7 @@ impl Game {
             return;
         }
 
-        codestorage::save(scenario_name, &code);
+        codestorage::save_to(self.code_store.as_ref(), scenario_name, &code);
 
         let scenario_name = scenario_name.to_string();
         try_send_future(context.link(), async move {
@@ -1262,7 +1993,10 @@ impl Game {
     }
 
     fn configured_seed(&self, context: &Context<Self>) -> Option<u32> {
-        context.props().seed
+        context
+            .props()
+            .seed
+            .or_else(|| self.challenge.as_ref().map(|x| x.seed))
     }
 }
 
@@ -1300,6 +2034,29 @@ impl Team {
         // TODO trigger analyzer run
     }
 
+    /// Cursor and scroll position as JSON, for restoring after the editor has
+    /// shown other code.
+    pub fn save_view_state(&self) -> Option<String> {
+        self.editor_link
+            .with_editor(|editor| {
+                let view_state = editor.as_ref().save_view_state()?;
+                js_sys::JSON::stringify(&view_state).ok()?.as_string()
+            })
+            .flatten()
+    }
+
+    pub fn restore_view_state(&self, json: &str) {
+        let view_state = match js_sys::JSON::parse(json) {
+            Ok(view_state) => view_state,
+            Err(_) => return,
+        };
+        self.editor_link.with_editor(|editor| {
+            editor
+                .as_ref()
+                .restore_view_state(view_state.unchecked_ref());
+        });
+    }
+
     pub fn set_editor_text_preserving_cursor(&self, text: &str) {
         self.editor_link.with_editor(|editor| {
             let saved = editor.as_ref().save_view_state();
@@ -1415,3 +2172,16 @@ where
         }
     });
 }
+
+/// Scales text and everything sized in rem, based on the browser's default
+/// 16px font.
+fn apply_ui_scale(ui_scale: f32) {
+    if let Some(root) = gloo_utils::document()
+        .document_element()
+        .and_then(|e| e.dyn_into::<web_sys::HtmlElement>().ok())
+    {
+        let _ = root
+            .style()
+            .set_property("font-size", &format!("{}px", 16.0 * ui_scale));
+    }
+}
@@ -1,8 +1,12 @@
 mod analyzer_stub;
 pub mod benchmark;
+pub mod challenge;
 pub mod code_size;
 pub mod codestorage;
+pub mod compile_state;
 pub mod compiler_output_window;
+pub mod compiler_status;
+pub mod diagnostics;
 pub mod documentation;
 pub mod editor_window;
 pub mod feedback;
@@ -13,6 +17,7 @@ pub mod leaderboard;
 pub mod leaderboard_window;
 pub mod seed_window;
 pub mod services;
+pub mod share;
 pub mod simulation_window;
 pub mod toolbar;
 pub mod tournament;
@@ -21,6 +26,7 @@ pub mod userid;
 pub mod versions_window;
 pub mod welcome;
 
+use oort_simulator::scenario;
 use oort_version::version;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -47,6 +53,7 @@ pub struct QueryParams {
     pub seed: Option<u32>,
     pub player0: Option<String>,
     pub player1: Option<String>,
+    pub unlock: Option<u32>,
 }
 
 #[function_component(Main)]
@@ -63,19 +70,25 @@ struct GameWrapperProps {
     scenario: String,
     #[prop_or_default]
     demo: bool,
+    #[prop_or_default]
+    error: Option<String>,
 }
 
 #[function_component(GameWrapper)]
 fn game_wrapper(props: &GameWrapperProps) -> Html {
     let location = use_location().expect("use_location");
     let q = query_params(&location);
+    if let Some(unlock) = q.unlock {
+        codestorage::set_unlock_all(unlock != 0);
+    }
     html! {
         <game::Game
             version={version()}
             scenario={props.scenario.clone()}
             seed={q.seed}
             player0={q.player0.clone()}
-            player1={q.player1.clone()} />
+            player1={q.player1.clone()}
+            error={props.error.clone()} />
     }
 }
 
@@ -84,12 +97,8 @@ fn switch(routes: Route) -> Html {
         Route::Home => html! {
             <GameWrapper scenario="welcome" />
         },
-        Route::Scenario { scenario } => html! {
-            <GameWrapper scenario={scenario} />
-        },
-        Route::Demo { scenario } => html! {
-            <GameWrapper scenario={scenario} demo=true />
-        },
+        Route::Scenario { scenario } => scenario_game(scenario, false),
+        Route::Demo { scenario } => scenario_game(scenario, true),
         Route::Benchmark { scenario } => html! {
             <benchmark::Benchmark scenario={scenario} />
         },
@@ -99,6 +108,23 @@ fn switch(routes: Route) -> Html {
     }
 }
 
+// Keeps links to renamed scenarios (e.g. "tutorial06") working, and falls
+// back to the welcome scenario for ones that don't exist.
+fn scenario_game(name: String, demo: bool) -> Html {
+    match scenario::resolve_name(&name) {
+        Some(scenario) => html! {
+            <GameWrapper {scenario} {demo} />
+        },
+        None => {
+            let error = scenario::ScenarioError::UnknownScenario(name).to_string();
+            log::error!("{}", error);
+            html! {
+                <GameWrapper scenario="welcome" {demo} error={Some(error)} />
+            }
+        }
+    }
+}
+
 pub fn query_params(location: &Location) -> QueryParams {
     match location.query::<QueryParams>() {
         Ok(q) => q,
@@ -132,6 +158,7 @@ pub fn run_app() -> Result<(), JsValue> {
         "hashed envelope secret: {:?}",
         &oort_envelope::hashed_secret()
     );
+    diagnostics::install();
     js::completion::init();
     prevent_drag_and_drop();
     yew::Renderer::<Main>::with_root(
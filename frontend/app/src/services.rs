@@ -1,10 +1,12 @@
 use crate::userid;
 use anyhow::anyhow;
 use chrono::Utc;
+use oort_proto::{CompileJobId, CompileStatus, CompilerStatus};
 use oort_proto::{LeaderboardData, LeaderboardSubmission, TournamentResults};
 use oort_proto::{ShortcodeUpload, TournamentSubmission};
 use oort_proto::{Telemetry, TelemetryMsg};
-use reqwasm::http::{Request, Response};
+use reqwasm::http::{Request, RequestCredentials, Response};
+use web_sys::AbortSignal;
 
 pub fn is_local() -> bool {
     gloo_utils::document()
@@ -59,6 +61,21 @@ pub fn get_leaderboard(
     });
 }
 
+pub fn get_leaderboard_token(scenario_name: &str, callback: yew::Callback<anyhow::Result<String>>) {
+    let url = format!("{}/leaderboard/token/{}", backend_url(), scenario_name);
+    wasm_bindgen_futures::spawn_local(async move {
+        match send_request(Request::get(&url)).await {
+            Err(e) => {
+                log::warn!("Error fetching leaderboard token: {:?}", e);
+                callback.emit(Err(e));
+            }
+            Ok(response) => {
+                callback.emit(response.text().await.map_err(|e| e.into()));
+            }
+        }
+    });
+}
+
 pub fn post_leaderboard(
     msg: LeaderboardSubmission,
     callback: yew::Callback<Result<LeaderboardData, anyhow::Error>>,
@@ -83,6 +100,7 @@ pub fn post_leaderboard(
 }
 
 pub fn send_telemetry(payload: Telemetry) {
+    crate::diagnostics::record(crate::diagnostics::describe(&payload));
     let userid = userid::get_userid();
     let username = userid::get_username();
     let msg = TelemetryMsg {
@@ -108,6 +126,92 @@ pub fn send_telemetry(payload: Telemetry) {
     });
 }
 
+pub async fn compile(code: String, signal: Option<&AbortSignal>) -> anyhow::Result<Vec<u8>> {
+    let url = format!("{}/compile", compiler_url());
+    let response = send_request(Request::post(&url).body(code).abort_signal(signal)).await?;
+    response.binary().await.map_err(|e| e.into())
+}
+
+// Compile jobs live in the memory of one compiler instance. The compiler
+// service is deployed with session affinity, which relies on a cookie, so
+// job requests include credentials.
+fn job_request(request: Request, signal: Option<&AbortSignal>) -> Request {
+    request
+        .credentials(RequestCredentials::Include)
+        .abort_signal(signal)
+}
+
+/// Sends a request for an existing compile job. Returns `None` if the job is
+/// unknown, e.g. because the request reached a different compiler instance.
+async fn send_job_request(request: Request) -> anyhow::Result<Option<Response>> {
+    match request.send().await {
+        Ok(response) if response.status() == 404 => Ok(None),
+        Ok(response) if response.ok() => Ok(Some(response)),
+        Ok(response) => Err(anyhow!(
+            "Request to {} failed with status {}: {}",
+            response.url(),
+            response.status(),
+            response.text().await.unwrap_or_else(|e| format!("{e:?}"))
+        )),
+        Err(e) => Err(anyhow!("Request failed: {:?}", e)),
+    }
+}
+
+pub async fn start_compile(
+    code: String,
+    signal: Option<&AbortSignal>,
+) -> anyhow::Result<CompileJobId> {
+    let url = format!("{}/compile/jobs", compiler_url());
+    let response = send_request(job_request(Request::post(&url).body(code), signal)).await?;
+    response.json().await.map_err(|e| e.into())
+}
+
+pub async fn poll_compile(
+    job_id: &CompileJobId,
+    signal: Option<&AbortSignal>,
+) -> anyhow::Result<Option<CompileStatus>> {
+    let url = format!("{}/compile/jobs/{}", compiler_url(), job_id);
+    match send_job_request(job_request(Request::get(&url), signal)).await? {
+        Some(response) => Ok(Some(response.json().await?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn fetch_compiled(
+    job_id: &CompileJobId,
+    signal: Option<&AbortSignal>,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let url = format!("{}/compile/jobs/{}/wasm", compiler_url(), job_id);
+    match send_job_request(job_request(Request::get(&url), signal)).await? {
+        Some(response) => Ok(Some(response.binary().await?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn cancel_compile(job_id: &CompileJobId) {
+    let url = format!("{}/compile/jobs/{}", compiler_url(), job_id);
+    if let Err(e) = send_request(job_request(Request::delete(&url), None)).await {
+        log::warn!("Error cancelling compile: {:?}", e);
+    }
+}
+
+pub fn get_compiler_status(callback: yew::Callback<anyhow::Result<CompilerStatus>>) {
+    let url = format!("{}/status", compiler_url());
+    wasm_bindgen_futures::spawn_local(async move {
+        match send_request(Request::get(&url)).await {
+            Err(e) => {
+                log::warn!("Error fetching compiler status: {:?}", e);
+                callback.emit(Err(e));
+            }
+            Ok(response) => {
+                let data: Result<CompilerStatus, anyhow::Error> =
+                    response.json().await.map_err(|e| e.into());
+                callback.emit(data);
+            }
+        }
+    });
+}
+
 pub fn format(text: String, cb: yew::Callback<String>) {
     wasm_bindgen_futures::spawn_local(async move {
         let url = format!("{}/format", compiler_url());
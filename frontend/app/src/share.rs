@@ -0,0 +1,130 @@
+use base64::Engine as _;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// Maximum size of the compressed code in a share link.
+pub const MAX_COMPRESSED_SIZE: usize = 8192;
+
+/// Upper bound on decompressed size, to guard against malicious links.
+const MAX_DECOMPRESSED_SIZE: u64 = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedCode {
+    pub scenario_name: String,
+    pub code: String,
+}
+
+/// Encodes code into a URL fragment of the form `code=...&scenario=...`.
+pub fn encode(scenario_name: &str, code: &str) -> Result<String, String> {
+    let mut e = DeflateEncoder::new(Vec::new(), Compression::best());
+    e.write_all(code.as_bytes()).expect("compression failed");
+    let compressed = e.finish().expect("compression failed");
+    if compressed.len() > MAX_COMPRESSED_SIZE {
+        return Err(format!(
+            "Code is too large to share ({} bytes compressed, limit is {} bytes)",
+            compressed.len(),
+            MAX_COMPRESSED_SIZE
+        ));
+    }
+    let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(compressed);
+    Ok(format!("code={encoded}&scenario={scenario_name}"))
+}
+
+/// Decodes a URL fragment created by `encode`. A leading '#' is ignored.
+pub fn decode(fragment: &str) -> Option<SharedCode> {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    let mut encoded = None;
+    let mut scenario_name = None;
+    for param in fragment.split('&') {
+        match param.split_once('=') {
+            Some(("code", v)) => encoded = Some(v),
+            Some(("scenario", v)) => scenario_name = Some(v),
+            _ => {}
+        }
+    }
+
+    let encoded = encoded?;
+    if encoded.len() > base64::encoded_len(MAX_COMPRESSED_SIZE, false)? {
+        return None;
+    }
+    let compressed = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(encoded)
+        .ok()?;
+    let mut code = String::new();
+    DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_DECOMPRESSED_SIZE)
+        .read_to_string(&mut code)
+        .ok()?;
+
+    Some(SharedCode {
+        scenario_name: scenario_name?.to_string(),
+        code,
+    })
+}
+
+/// Returns a full link to the given scenario with the code in the fragment.
+pub fn make_link(scenario_name: &str, code: &str) -> Result<String, String> {
+    let fragment = encode(scenario_name, code)?;
+    let origin = gloo_utils::window()
+        .location()
+        .origin()
+        .map_err(|e| format!("Failed to get origin: {e:?}"))?;
+    Ok(format!("{origin}/scenario/{scenario_name}#{fragment}"))
+}
+
+/// Decodes shared code from the current URL and removes it from the address bar
+/// so that reloading the page doesn't prompt again.
+pub fn take_from_location() -> Option<SharedCode> {
+    let window = gloo_utils::window();
+    let location = window.location();
+    let shared = decode(&location.hash().ok()?)?;
+    let url = format!(
+        "{}{}",
+        location.pathname().unwrap_or_default(),
+        location.search().unwrap_or_default()
+    );
+    if let Ok(history) = window.history() {
+        let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url));
+    }
+    Some(shared)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let code = "use oort_api::prelude::*;\n\n// Ünïcödé\npub struct Ship {}\n";
+        let fragment = encode("tutorial_guns", code).unwrap();
+        assert!(fragment.starts_with("code="));
+        assert!(fragment.ends_with("&scenario=tutorial_guns"));
+        assert_eq!(
+            decode(&format!("#{fragment}")),
+            Some(SharedCode {
+                scenario_name: "tutorial_guns".to_string(),
+                code: code.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_too_large() {
+        let code: String = (0..100000u32)
+            .map(|i| char::from(b'a' + (i.wrapping_mul(2654435761) >> 27) as u8 % 26))
+            .collect();
+        assert!(encode("tutorial_guns", &code).is_err());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(decode(""), None);
+        assert_eq!(decode("#scenario=tutorial_guns"), None);
+        assert_eq!(decode("#code=!!!&scenario=tutorial_guns"), None);
+        let fragment = encode("tutorial_guns", "foo").unwrap();
+        let (code_param, _) = fragment.split_once('&').unwrap();
+        assert_eq!(decode(code_param), None);
+    }
+}
@@ -1,9 +1,16 @@
-use crate::ui::UI;
+use crate::ui::host::WebHost;
+use crate::ui::recorder::Recorder;
+use crate::ui::setting::{self, Settings};
+use crate::ui::{ghost, UI};
 use gloo_render::{request_animation_frame, AnimationFrame};
+use gloo_timers::callback::Interval;
 use oort_simulation_worker::SimAgent;
+use oort_simulator::scenario::sandbox::SandboxCommand;
 use oort_simulator::{scenario, simulation::Code, snapshot::Snapshot};
 use rand::Rng;
 use std::rc::Rc;
+use wasm_bindgen::prelude::Closure;
+use wasm_bindgen::JsCast;
 use yew::html::Scope;
 use yew::prelude::*;
 use yew_agent::{Bridge, Bridged};
@@ -15,13 +22,22 @@ pub enum Msg {
         start_paused: bool,
         seed: u32,
         codes: Vec<Code>,
+        practice: bool,
     },
+    Halt(String),
     Render,
     KeyEvent(web_sys::KeyboardEvent),
     WheelEvent(web_sys::WheelEvent),
     PointerEvent(web_sys::PointerEvent),
     BlurEvent(web_sys::FocusEvent),
-    RequestSnapshot,
+    DragOverEvent(web_sys::DragEvent),
+    DropEvent(web_sys::DragEvent),
+    RequestSnapshot(u32),
+    VisibilityChange,
+    BackgroundStep,
+    SandboxCommand(SandboxCommand),
+    ToggleRecording,
+    SettingsChanged(Settings),
     ReceivedSimAgentResponse(oort_simulation_worker::Response),
 }
 
@@ -29,6 +45,8 @@ pub enum Msg {
 pub struct SimulationWindowProps {
     pub host: web_sys::Element,
     pub on_simulation_finished: Callback<Snapshot>,
+    pub on_code_dropped: Callback<String>,
+    pub on_show_settings: Callback<()>,
     pub register_link: Callback<Scope<SimulationWindow>>,
     pub version: String,
     pub canvas_ref: NodeRef,
@@ -43,6 +61,12 @@ pub struct SimulationWindow {
     canvas_ref: NodeRef,
     status_ref: NodeRef,
     picked_ref: NodeRef,
+    spectator_ref: NodeRef,
+    recorder: Option<Recorder>,
+    background_interval: Option<Interval>,
+    ghost_scenario: Option<String>,
+    /// Registered on the document, so it's removed in `destroy`.
+    visibility_listener: Closure<dyn Fn()>,
 }
 
 impl Component for SimulationWindow {
@@ -62,6 +86,16 @@ impl Component for SimulationWindow {
                 link2.send_message(Msg::Render)
             }))
         };
+        let visibility_listener: Closure<dyn Fn()> = {
+            let link = context.link().clone();
+            Closure::new(move || link.send_message(Msg::VisibilityChange))
+        };
+        gloo_utils::document()
+            .add_event_listener_with_callback(
+                "visibilitychange",
+                visibility_listener.as_ref().unchecked_ref(),
+            )
+            .unwrap();
         Self {
             ui: None,
             render_handle,
@@ -71,6 +105,11 @@ impl Component for SimulationWindow {
             canvas_ref: context.props().canvas_ref.clone(),
             status_ref: NodeRef::default(),
             picked_ref: NodeRef::default(),
+            spectator_ref: NodeRef::default(),
+            recorder: None,
+            background_interval: None,
+            ghost_scenario: None,
+            visibility_listener,
         }
     }
 
@@ -81,42 +120,111 @@ impl Component for SimulationWindow {
                 seed,
                 start_paused,
                 codes,
+                practice,
             } => {
                 self.nonce = rand::thread_rng().gen();
                 self.ui = Some(Box::new(UI::new(
-                    context.link().callback(|_| Msg::RequestSnapshot),
+                    context.link().callback(Msg::RequestSnapshot),
                     seed,
                     self.nonce,
                     context.props().version.clone(),
                     self.canvas_ref.clone(),
-                    self.status_ref.clone(),
-                    self.picked_ref.clone(),
+                    Box::new(WebHost {
+                        status_ref: self.status_ref.clone(),
+                        picked_ref: self.picked_ref.clone(),
+                        spectator_ref: self.spectator_ref.clone(),
+                    }),
                     start_paused,
+                    scenario::load_safe(&scenario_name)
+                        .ok()
+                        .and_then(|x| x.initial_camera()),
                 )));
+                if let Some(ui) = self.ui.as_mut() {
+                    ui.set_recording(self.recorder.is_some());
+                    ui.set_hidden(gloo_utils::document().hidden());
+                    if scenario_name == "sandbox" {
+                        ui.set_sandbox(context.link().callback(Msg::SandboxCommand));
+                    }
+                }
+                // Practice runs against frozen enemies don't count as a best run.
+                self.ghost_scenario = if practice {
+                    None
+                } else {
+                    Some(scenario_name.clone())
+                };
+                if let (Some(ui), Some(scenario_name)) =
+                    (self.ui.as_mut(), self.ghost_scenario.as_ref())
+                {
+                    ui.set_ghost(ghost::load_from(&setting::LocalStorage, scenario_name));
+                }
                 self.sim_agent
                     .send(oort_simulation_worker::Request::StartScenario {
                         scenario_name,
                         seed,
                         codes: codes.to_vec(),
                         nonce: self.nonce,
+                        practice,
                     });
                 false
             }
+            Msg::Halt(reason) => {
+                if let Some(ui) = self.ui.as_mut() {
+                    ui.halt(&reason);
+                }
+                false
+            }
             Msg::Render => {
+                if self.recorder.as_ref().map_or(false, |r| r.over_limit()) {
+                    self.stop_recording();
+                }
                 if let Some(ui) = self.ui.as_mut() {
                     ui.render();
                 }
                 self.check_status(context)
             }
-            Msg::RequestSnapshot => {
+            Msg::RequestSnapshot(ticks) => {
                 self.sim_agent
                     .send(oort_simulation_worker::Request::Snapshot {
-                        ticks: 1,
+                        ticks,
                         nonce: self.nonce,
                     });
                 false
             }
+            Msg::VisibilityChange => {
+                let hidden = gloo_utils::document().hidden();
+                if let Some(ui) = self.ui.as_mut() {
+                    ui.set_hidden(hidden);
+                }
+                // The worker only steps when asked for a snapshot, so a hidden
+                // tab is paused unless the user opted to keep it running.
+                if hidden && Settings::load().keep_running_in_background {
+                    let link = context.link().clone();
+                    self.background_interval = Some(Interval::new(1000, move || {
+                        link.send_message(Msg::BackgroundStep)
+                    }));
+                } else {
+                    self.background_interval = None;
+                }
+                false
+            }
+            Msg::BackgroundStep => {
+                if let Some(ui) = self.ui.as_mut() {
+                    ui.background_step();
+                }
+                self.check_status(context)
+            }
+            Msg::SandboxCommand(command) => {
+                self.sim_agent
+                    .send(oort_simulation_worker::Request::Sandbox { command });
+                false
+            }
             Msg::KeyEvent(e) => {
+                if e.type_() == "keydown" && e.key() == "r" && !e.repeat() {
+                    context.link().send_message(Msg::ToggleRecording);
+                }
+                if e.type_() == "keydown" && e.key() == "," && !e.repeat() {
+                    context.props().on_show_settings.emit(());
+                }
                 if let Some(ui) = self.ui.as_mut() {
                     ui.on_key_event(e);
                 }
@@ -140,6 +248,46 @@ impl Component for SimulationWindow {
                 }
                 false
             }
+            Msg::DragOverEvent(e) => {
+                // Required for the canvas to accept drops.
+                e.prevent_default();
+                false
+            }
+            Msg::DropEvent(e) => {
+                e.prevent_default();
+                if let Some(file) = e
+                    .data_transfer()
+                    .and_then(|data_transfer| data_transfer.files())
+                    .and_then(|files| files.get(0))
+                {
+                    if file.name().ends_with(".rs") {
+                        let cb = context.props().on_code_dropped.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            match wasm_bindgen_futures::JsFuture::from(file.text()).await {
+                                Ok(text) => cb.emit(text.as_string().unwrap()),
+                                Err(e) => log::error!("Failed to read dropped file: {:?}", e),
+                            }
+                        });
+                    } else {
+                        log::warn!("Ignoring dropped file {:?}, expected .rs", file.name());
+                    }
+                }
+                false
+            }
+            Msg::ToggleRecording => {
+                if self.recorder.is_some() {
+                    self.stop_recording();
+                } else {
+                    self.start_recording();
+                }
+                false
+            }
+            Msg::SettingsChanged(settings) => {
+                if let Some(ui) = self.ui.as_mut() {
+                    ui.set_settings(settings);
+                }
+                false
+            }
             Msg::ReceivedSimAgentResponse(oort_simulation_worker::Response::Snapshot {
                 snapshot,
             }) => {
@@ -169,6 +317,8 @@ impl Component for SimulationWindow {
         let wheel_event_cb = context.link().callback(Msg::WheelEvent);
         let pointer_event_cb = context.link().callback(Msg::PointerEvent);
         let blur_event_cb = context.link().callback(Msg::BlurEvent);
+        let drag_over_event_cb = context.link().callback(Msg::DragOverEvent);
+        let drop_event_cb = context.link().callback(Msg::DropEvent);
 
         create_portal(
             html! {
@@ -182,22 +332,74 @@ impl Component for SimulationWindow {
                         onpointermove={pointer_event_cb.clone()}
                         onpointerup={pointer_event_cb.clone()}
                         onpointerdown={pointer_event_cb}
-                        onblur={blur_event_cb} />
+                        onblur={blur_event_cb}
+                        ondragover={drag_over_event_cb}
+                        ondrop={drop_event_cb} />
                     <div class="status" ref={self.status_ref.clone()} />
                     <div class="picked">
                         <pre ref={self.picked_ref.clone()}></pre>
                     </div>
+                    <div class="spectator">
+                        <pre ref={self.spectator_ref.clone()}></pre>
+                    </div>
                 </>
             },
             context.props().host.clone(),
         )
     }
+
+    fn destroy(&mut self, _context: &yew::Context<Self>) {
+        gloo_utils::document()
+            .remove_event_listener_with_callback(
+                "visibilitychange",
+                self.visibility_listener.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+    }
 }
 
 impl SimulationWindow {
+    fn start_recording(&mut self) {
+        let canvas = match self.canvas_ref.cast::<web_sys::HtmlCanvasElement>() {
+            Some(canvas) => canvas,
+            None => return,
+        };
+        match Recorder::start(&canvas) {
+            Ok(recorder) => self.recorder = Some(recorder),
+            Err(e) => {
+                log::warn!("{}", e);
+                if let Some(ui) = self.ui.as_ref() {
+                    ui.set_status_message(&e);
+                }
+                return;
+            }
+        }
+        if let Some(ui) = self.ui.as_mut() {
+            ui.set_recording(true);
+        }
+    }
+
+    fn stop_recording(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            recorder.stop();
+        }
+        if let Some(ui) = self.ui.as_mut() {
+            ui.set_recording(false);
+        }
+    }
+
     fn check_status(&mut self, context: &Context<Self>) -> bool {
         if let Some(ui) = self.ui.as_ref() {
             let status = ui.status();
+            if self.last_status != status && status == (scenario::Status::Victory { team: 0 }) {
+                if let (Some(track), Some(scenario_name)) =
+                    (ui.track(), self.ghost_scenario.as_ref())
+                {
+                    if ghost::save_if_best(&setting::LocalStorage, scenario_name, track) {
+                        log::info!("Saved new best run for {}", scenario_name);
+                    }
+                }
+            }
             if self.last_status != status && status != scenario::Status::Running {
                 context
                     .props()
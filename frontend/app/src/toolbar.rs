@@ -1,3 +1,5 @@
+use crate::compile_state::CompileState;
+use crate::compiler_status::CompilerHealth;
 use oort_simulator::scenario;
 use regex::Regex;
 use wasm_bindgen::JsCast;
@@ -14,8 +16,14 @@ pub enum Msg {
 #[derive(Properties, Clone, PartialEq)]
 pub struct ToolbarProps {
     pub select_scenario_cb: Callback<Event>,
+    pub toggle_practice_cb: Callback<Event>,
     pub show_feedback_cb: Callback<web_sys::MouseEvent>,
+    pub show_settings_cb: Callback<web_sys::MouseEvent>,
+    pub share_cb: Callback<web_sys::MouseEvent>,
     pub scenario_name: String,
+    pub practice: bool,
+    pub compile_state: CompileState,
+    pub compiler_health: CompilerHealth,
 }
 
 pub struct Toolbar {}
@@ -55,23 +63,42 @@ impl Component for Toolbar {
             .get_element_by_id("toolbar")
             .expect("a #toolbar element");
 
-        let render_scenario_option = |name: &str| {
-            let scenario = scenario::load(name);
+        let render_scenario_option = |info: &scenario::ScenarioInfo| {
+            let name = info.name.as_str();
             let selected = name == context.props().scenario_name;
-            html! { <option value={name.to_string()} selected={selected}>{scenario.human_name()}</option> }
+            let stars = match crate::codestorage::best_stars(name) {
+                0 => String::new(),
+                n => format!(" {}", crate::codestorage::format_stars(n)),
+            };
+            if crate::codestorage::locked_by(name).is_some() && !selected {
+                html! { <option value={name.to_string()} class="locked-scenario" disabled=true>{ "\u{1f512} " }{info.human_name.clone()}</option> }
+            } else {
+                html! { <option value={name.to_string()} selected={selected}>{info.human_name.clone()}{stars}</option> }
+            }
         };
 
-        let render_scenario_category = |category: &str, scenario_names: &[String]| {
+        let render_scenario_category = |category: &str, scenarios: &[scenario::ScenarioInfo]| {
             html! {
                 <optgroup label={category.to_string()}>
-                { for scenario_names.iter().map(|x| render_scenario_option(x.as_str())) }
+                { for scenarios.iter().map(render_scenario_option) }
                 </optgroup>
             }
         };
 
+        let mut scenario_categories: Vec<(String, Vec<scenario::ScenarioInfo>)> = vec![];
+        for info in scenario::list_with_metadata() {
+            match scenario_categories.last_mut() {
+                Some((category, infos)) if *category == info.category => infos.push(info),
+                _ => scenario_categories.push((info.category.clone(), vec![info])),
+            }
+        }
+
         let username = crate::userid::get_username();
         let select_scenario_cb = context.props().select_scenario_cb.clone();
+        let toggle_practice_cb = context.props().toggle_practice_cb.clone();
         let show_feedback_cb = context.props().show_feedback_cb.clone();
+        let show_settings_cb = context.props().show_settings_cb.clone();
+        let share_cb = context.props().share_cb.clone();
 
         let username_keydown_cb = context
             .link()
@@ -88,16 +115,30 @@ impl Component for Toolbar {
             Msg::ChangeUsername(input_box.value())
         });
         let discord_cb = Callback::from(|_| crate::gtag::discord());
+        let compile_state = &context.props().compile_state;
+        let compiler_health = &context.props().compiler_health;
 
         create_portal(
             html! {
                 <>
                     <div class="toolbar-elem title">{ "Oort" }</div>
+                    <div class={classes!("toolbar-elem", "compile-state", compile_state.css_class())} title={compile_state.tooltip()}>
+                        { compile_state.label() }
+                    </div>
+                    <div class={classes!("toolbar-elem", "compiler-health", compiler_health.css_class())} title={compiler_health.tooltip()} />
                     <div class="toolbar-elem right">
                         <select onchange={select_scenario_cb}>
-                            { for scenario::list().iter().map(|x| render_scenario_category(&x.0, &x.1)) }
+                            { for scenario_categories.iter().map(|x| render_scenario_category(&x.0, &x.1)) }
                         </select>
                     </div>
+                    <div id="practice" class="toolbar-elem right" title="Freeze enemy ships. Practice runs don't count for the leaderboard.">
+                        <label>
+                            <input type="checkbox" checked={context.props().practice} onchange={toggle_practice_cb} />
+                            { "Practice" }
+                        </label>
+                    </div>
+                    <div class="toolbar-elem right"><a href="#" onclick={share_cb} title="Copy a link to your code">{ "Share" }</a></div>
+                    <div class="toolbar-elem right"><a href="#" onclick={show_settings_cb} title="Settings (,)">{ "\u{2699}" }</a></div>
                     <div class="toolbar-elem right"><a href="#" onclick={show_feedback_cb}>{ "Feedback" }</a></div>
                     <div class="toolbar-elem right"><a href="https://docs.rs/oort_api" target="_blank">{ "API Reference" }</a></div>
                     <div class="toolbar-elem right"><a href="http://github.com/rlane/oort3/wiki" target="_blank">{ "Wiki" }</a></div>
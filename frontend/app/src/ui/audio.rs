@@ -0,0 +1,253 @@
+use nalgebra::{Point2, Vector2};
+use oort_simulator::simulation::SimEvent;
+use std::cell::RefCell;
+use wasm_bindgen::JsValue;
+use web_sys::{
+    AudioBuffer, AudioContext, AudioContextState, AudioNode, BiquadFilterType, OscillatorType,
+};
+
+// Browsers get unhappy with hundreds of overlapping nodes, and a big fight
+// doesn't sound any better for it.
+const MAX_VOICES: usize = 24;
+const MASTER_GAIN: f32 = 0.3;
+const MIN_GAIN: f32 = 0.01;
+const NOISE_SECONDS: f32 = 1.0;
+
+thread_local! {
+    // A new UI is created for every run, but the context should outlive it so
+    // sound doesn't stop until the next click.
+    static SHARED: RefCell<Option<(AudioContext, AudioBuffer)>> = RefCell::new(None);
+}
+
+/// Synthesizes sound effects for simulation events.
+///
+/// Browsers only allow an AudioContext to start from a user gesture, so it's
+/// created on the first call to `on_user_gesture`.
+pub struct Audio {
+    context: Option<AudioContext>,
+    noise: Option<AudioBuffer>,
+    muted: bool,
+    volume: f32,
+    voice_end_times: Vec<f64>,
+}
+
+impl Audio {
+    pub fn new() -> Self {
+        let (context, noise) = SHARED.with(|shared| shared.borrow().clone()).unzip();
+        Self {
+            context,
+            noise,
+            muted: false,
+            volume: 1.0,
+            voice_end_times: Vec::new(),
+        }
+    }
+
+    pub fn on_user_gesture(&mut self) {
+        if self.muted {
+            return;
+        }
+        if let Some(context) = self.context.as_ref() {
+            if context.state() == AudioContextState::Suspended {
+                let _ = context.resume();
+            }
+            return;
+        }
+        match self.create_context() {
+            Ok((context, noise)) => {
+                SHARED.with(|shared| *shared.borrow_mut() = Some((context.clone(), noise.clone())));
+                self.context = Some(context);
+                self.noise = Some(noise);
+            }
+            Err(e) => log::warn!("Failed to create audio context: {:?}", e),
+        }
+    }
+
+    fn create_context(&self) -> Result<(AudioContext, AudioBuffer), JsValue> {
+        let context = AudioContext::new()?;
+        let sample_rate = context.sample_rate();
+        let length = (sample_rate * NOISE_SECONDS) as u32;
+        let noise = context.create_buffer(1, length, sample_rate)?;
+        // Deterministic white noise, shared by every impact and explosion.
+        let mut state: u32 = 0x1234_5678;
+        let mut data: Vec<f32> = (0..length)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 8) as f32 / (1 << 23) as f32 - 1.0
+            })
+            .collect();
+        noise.copy_to_channel(&mut data, 0)?;
+        Ok((context, noise))
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        if muted == self.muted {
+            return;
+        }
+        self.muted = muted;
+        if let Some(context) = self.context.as_ref() {
+            let _ = if self.muted {
+                context.suspend()
+            } else {
+                context.resume()
+            };
+        } else if !self.muted {
+            // Unmuting is itself a user gesture.
+            self.on_user_gesture();
+        }
+    }
+
+    /// Sets the master volume, from 0 to 1.
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    /// Plays the loudest events, attenuated by distance from the camera.
+    ///
+    /// `hearing_distance` is the distance at which a sound is played at half
+    /// volume, normally about the size of the visible area.
+    pub fn play(&mut self, events: &[SimEvent], listener: Point2<f64>, hearing_distance: f64) {
+        if self.muted || self.volume <= 0.0 || events.is_empty() {
+            return;
+        }
+        let context = match self.context.as_ref() {
+            Some(context) if context.state() == AudioContextState::Running => context.clone(),
+            _ => return,
+        };
+
+        let now = context.current_time();
+        self.voice_end_times.retain(|&t| t > now);
+
+        let mut sounds: Vec<(f32, &SimEvent)> = events
+            .iter()
+            .map(|event| {
+                let d = (position(event) - listener.coords).magnitude() / hearing_distance;
+                (volume(event) / (1.0 + d * d) as f32, event)
+            })
+            .filter(|(gain, _)| *gain >= MIN_GAIN)
+            .collect();
+        sounds.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        for (gain, event) in sounds {
+            if self.voice_end_times.len() >= MAX_VOICES {
+                break;
+            }
+            match self.play_one(&context, event, gain * MASTER_GAIN * self.volume, now) {
+                Ok(end_time) => self.voice_end_times.push(end_time),
+                Err(e) => log::warn!("Failed to play sound: {:?}", e),
+            }
+        }
+    }
+
+    /// Schedules a single sound and returns the time it will finish.
+    fn play_one(
+        &self,
+        context: &AudioContext,
+        event: &SimEvent,
+        gain: f32,
+        now: f64,
+    ) -> Result<f64, JsValue> {
+        let envelope = context.create_gain()?;
+        envelope.connect_with_audio_node(&context.destination())?;
+
+        let duration = match *event {
+            SimEvent::GunFired { .. } => self.tone(context, &envelope, 900.0, 300.0, 0.06, now)?,
+            SimEvent::MissileLaunched { .. } => {
+                self.tone(context, &envelope, 150.0, 600.0, 0.3, now)?
+            }
+            SimEvent::BulletHit { .. } => self.noise(context, &envelope, 4000.0, 0.08, now)?,
+            SimEvent::LaserFired { .. } => {
+                self.tone(context, &envelope, 2400.0, 1200.0, 0.12, now)?
+            }
+            SimEvent::Explosion { mass, .. } => {
+                // Bigger ships rumble lower and longer.
+                let size = (mass.max(1.0).log10() as f32).clamp(0.0, 7.0);
+                self.noise(
+                    context,
+                    &envelope,
+                    1200.0 / (1.0 + size),
+                    0.3 + 0.2 * size,
+                    now,
+                )?
+            }
+        };
+
+        let end_time = now + duration as f64;
+        let param = envelope.gain();
+        param.set_value_at_time(gain, now)?;
+        param.exponential_ramp_to_value_at_time(1e-4, end_time)?;
+        Ok(end_time)
+    }
+
+    fn tone(
+        &self,
+        context: &AudioContext,
+        output: &AudioNode,
+        start_frequency: f32,
+        end_frequency: f32,
+        duration: f32,
+        now: f64,
+    ) -> Result<f32, JsValue> {
+        let oscillator = context.create_oscillator()?;
+        oscillator.set_type(OscillatorType::Square);
+        let frequency = oscillator.frequency();
+        frequency.set_value_at_time(start_frequency, now)?;
+        frequency.exponential_ramp_to_value_at_time(end_frequency, now + duration as f64)?;
+        oscillator.connect_with_audio_node(output)?;
+        oscillator.start_with_when(now)?;
+        oscillator.stop_with_when(now + duration as f64)?;
+        Ok(duration)
+    }
+
+    fn noise(
+        &self,
+        context: &AudioContext,
+        output: &AudioNode,
+        cutoff: f32,
+        duration: f32,
+        now: f64,
+    ) -> Result<f32, JsValue> {
+        let duration = duration.min(NOISE_SECONDS);
+        let filter = context.create_biquad_filter()?;
+        filter.set_type(BiquadFilterType::Lowpass);
+        filter.frequency().set_value(cutoff);
+        filter.connect_with_audio_node(output)?;
+
+        let source = context.create_buffer_source()?;
+        source.set_buffer(self.noise.as_ref());
+        source.connect_with_audio_node(&filter)?;
+        source.start_with_when(now)?;
+        source.stop_with_when(now + duration as f64)?;
+        Ok(duration)
+    }
+}
+
+impl Default for Audio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn position(event: &SimEvent) -> Vector2<f64> {
+    match *event {
+        SimEvent::GunFired { position }
+        | SimEvent::MissileLaunched { position }
+        | SimEvent::BulletHit { position }
+        | SimEvent::Explosion { position, .. }
+        | SimEvent::LaserFired {
+            start: position, ..
+        } => position,
+    }
+}
+
+fn volume(event: &SimEvent) -> f32 {
+    match event {
+        SimEvent::GunFired { .. } => 0.3,
+        SimEvent::MissileLaunched { .. } => 0.4,
+        SimEvent::BulletHit { .. } => 0.5,
+        SimEvent::LaserFired { .. } => 0.4,
+        SimEvent::Explosion { .. } => 1.0,
+    }
+}
@@ -0,0 +1,219 @@
+use super::setting::{self, Store};
+use nalgebra::point;
+use oort_renderer::GhostShip;
+use oort_simulator::ship::ShipClass;
+use oort_simulator::snapshot::{ShipSnapshot, Snapshot};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, TAU};
+
+/// Ticks between the positions of a saved track. The ghost is interpolated
+/// in between.
+const SAVE_INTERVAL: usize = 6;
+/// Longer runs are thinned out further to stay under this many positions,
+/// about 30 KB of JSON, so tracks for every scenario fit in localStorage.
+const MAX_SAVED_POSITIONS: usize = 1000;
+
+/// The player's ship position and heading over a run, replayed as a "ghost"
+/// in later attempts at the same scenario.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Track {
+    pub class: ShipClass,
+    /// Length of the run in ticks.
+    pub ticks: usize,
+    /// Ticks between entries in `positions`. Runs are recorded every tick
+    /// and thinned out when saved.
+    pub interval: usize,
+    /// (x, y, heading) every `interval` ticks, plus the last tick.
+    pub positions: Vec<[f32; 3]>,
+}
+
+fn key(scenario_name: &str) -> String {
+    format!("/ghost/{scenario_name}")
+}
+
+pub fn load_from(store: &dyn Store, scenario_name: &str) -> Option<Track> {
+    setting::read_from(store, &key(scenario_name), None)
+}
+
+/// Saves the track if it's faster than the stored one. Returns whether it
+/// was saved.
+pub fn save_if_best(store: &dyn Store, scenario_name: &str, track: &Track) -> bool {
+    if let Some(best) = load_from(store, scenario_name) {
+        if best.ticks <= track.ticks {
+            return false;
+        }
+    }
+    setting::write_to(store, &key(scenario_name), &Some(track.thinned()));
+    true
+}
+
+impl Track {
+    /// Starts a track if the player controls exactly one ship. Missiles and
+    /// torpedoes don't count.
+    pub fn start(snapshot: &Snapshot) -> Option<Self> {
+        let mut track = Track {
+            class: player_ship(snapshot)?.class,
+            ticks: 0,
+            interval: 1,
+            positions: vec![],
+        };
+        track.record(snapshot);
+        Some(track)
+    }
+
+    /// Adds the player's position at the snapshot's tick. Ticks skipped since
+    /// the last recorded one are filled in by interpolation.
+    pub fn record(&mut self, snapshot: &Snapshot) {
+        let ship = match player_ship(snapshot) {
+            Some(ship) if ship.class == self.class && self.interval == 1 => ship,
+            _ => return,
+        };
+        let tick = snapshot.tick as usize;
+        let entry = [
+            ship.position.x as f32,
+            ship.position.y as f32,
+            ship.heading as f32,
+        ];
+        if tick < self.positions.len() {
+            return;
+        }
+        match self.positions.last().copied() {
+            Some(last) => {
+                let start = self.positions.len() - 1;
+                for i in self.positions.len()..tick {
+                    let t = (i - start) as f32 / (tick - start) as f32;
+                    self.positions.push([
+                        last[0] + (entry[0] - last[0]) * t,
+                        last[1] + (entry[1] - last[1]) * t,
+                        entry[2],
+                    ]);
+                }
+            }
+            None => self.positions.resize(tick, entry),
+        }
+        self.positions.push(entry);
+        self.ticks = self.positions.len();
+    }
+
+    /// Returns a copy with fewer positions, for saving.
+    fn thinned(&self) -> Track {
+        let mut step = (SAVE_INTERVAL / self.interval).max(1);
+        while self.positions.len() / step > MAX_SAVED_POSITIONS {
+            step *= 2;
+        }
+        let mut positions: Vec<_> = self.positions.iter().copied().step_by(step).collect();
+        if self.positions.len().saturating_sub(1) % step != 0 {
+            positions.extend(self.positions.last());
+        }
+        Track {
+            class: self.class,
+            ticks: self.ticks,
+            interval: self.interval * step,
+            positions,
+        }
+    }
+
+    /// Returns the ghost at a time in ticks. After the end of the track the
+    /// ghost stays where it finished.
+    pub fn at(&self, ticks: f64) -> Option<GhostShip> {
+        let last = self.positions.len().checked_sub(1)?;
+        let last_tick = self.ticks.saturating_sub(1) as f64;
+        let ticks = ticks.clamp(0.0, last_tick);
+        let i = ((ticks / self.interval as f64).floor() as usize).min(last);
+        let (a, b) = (self.positions[i], self.positions[(i + 1).min(last)]);
+        // The final position can be closer than `interval` to the one before.
+        let a_tick = (i * self.interval) as f64;
+        let b_tick = (((i + 1) * self.interval) as f64).min(last_tick);
+        let t = if b_tick > a_tick {
+            ((ticks - a_tick) / (b_tick - a_tick)) as f32
+        } else {
+            0.0
+        };
+        let heading_delta = (b[2] - a[2] + PI).rem_euclid(TAU) - PI;
+        Some(GhostShip {
+            class: self.class,
+            position: point![
+                (a[0] + (b[0] - a[0]) * t) as f64,
+                (a[1] + (b[1] - a[1]) * t) as f64
+            ],
+            heading: (a[2] + heading_delta * t) as f64,
+        })
+    }
+}
+
+fn player_ship(snapshot: &Snapshot) -> Option<&ShipSnapshot> {
+    let mut ships = snapshot.ships.iter().filter(|ship| {
+        ship.team == 0 && !matches!(ship.class, ShipClass::Missile | ShipClass::Torpedo)
+    });
+    let ship = ships.next()?;
+    if ships.next().is_some() {
+        return None;
+    }
+    Some(ship)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ui::setting::MemoryStore;
+
+    fn track(len: usize) -> Track {
+        Track {
+            class: ShipClass::Fighter,
+            ticks: len,
+            interval: 1,
+            positions: (0..len).map(|i| [i as f32, 0.0, 0.0]).collect(),
+        }
+    }
+
+    #[test]
+    fn test_save_if_best() {
+        let store = MemoryStore::default();
+        assert_eq!(load_from(&store, "tutorial_acceleration"), None);
+        assert!(save_if_best(&store, "tutorial_acceleration", &track(100)));
+        assert!(!save_if_best(&store, "tutorial_acceleration", &track(120)));
+        assert!(save_if_best(&store, "tutorial_acceleration", &track(80)));
+        assert_eq!(
+            load_from(&store, "tutorial_acceleration").map(|x| x.positions.len()),
+            Some(80)
+        );
+        assert_eq!(load_from(&store, "tutorial_rotation"), None);
+    }
+
+    #[test]
+    fn test_at() {
+        let track = track(10);
+        assert_eq!(track.at(0.0).unwrap().position, point![0.0, 0.0]);
+        assert_eq!(track.at(2.5).unwrap().position, point![2.5, 0.0]);
+        // The ghost stays at the finish once its run is over.
+        assert_eq!(track.at(50.0).unwrap().position, point![9.0, 0.0]);
+        assert!(Track {
+            class: ShipClass::Fighter,
+            ticks: 0,
+            interval: 1,
+            positions: vec![],
+        }
+        .at(0.0)
+        .is_none());
+    }
+
+    #[test]
+    fn test_thinned() {
+        let store = MemoryStore::default();
+        save_if_best(&store, "tutorial_acceleration", &track(100));
+        let saved = load_from(&store, "tutorial_acceleration").unwrap();
+        assert_eq!((saved.ticks, saved.interval), (100, SAVE_INTERVAL));
+        // Every sixth tick from 0 to 96, then the last tick.
+        assert_eq!(saved.positions.len(), 18);
+        assert_eq!(saved.at(50.0).unwrap().position, point![50.0, 0.0]);
+        assert_eq!(saved.at(98.5).unwrap().position, point![98.5, 0.0]);
+        assert_eq!(saved.at(500.0).unwrap().position, point![99.0, 0.0]);
+
+        // A long run is capped.
+        let saved = track(100_000).thinned();
+        assert!(saved.positions.len() <= MAX_SAVED_POSITIONS + 1);
+        assert_eq!(saved.ticks, 100_000);
+        assert_eq!(saved.at(12345.0).unwrap().position, point![12345.0, 0.0]);
+        assert_eq!(saved.at(1e9).unwrap().position, point![99999.0, 0.0]);
+    }
+}
@@ -0,0 +1,46 @@
+use super::setting::{self, Store};
+use web_sys::Element;
+use yew::NodeRef;
+
+/// Where the UI shows text outside the canvas and keeps its settings.
+///
+/// The web app writes into the simulation window's DOM and localStorage.
+/// Other targets can show the text however they like.
+pub trait Host {
+    fn set_status(&self, text: &str);
+    /// Details about the selected ship, or empty if none is selected.
+    fn set_picked(&self, text: &str);
+    /// The ship list, or empty if it's hidden.
+    fn set_spectator(&self, text: &str);
+    fn store(&self) -> &dyn Store;
+}
+
+pub struct WebHost {
+    pub status_ref: NodeRef,
+    pub picked_ref: NodeRef,
+    pub spectator_ref: NodeRef,
+}
+
+fn set_text(node_ref: &NodeRef, text: &str) {
+    if let Some(elem) = node_ref.cast::<Element>() {
+        elem.set_text_content(Some(text));
+    }
+}
+
+impl Host for WebHost {
+    fn set_status(&self, text: &str) {
+        set_text(&self.status_ref, text);
+    }
+
+    fn set_picked(&self, text: &str) {
+        set_text(&self.picked_ref, text);
+    }
+
+    fn set_spectator(&self, text: &str) {
+        set_text(&self.spectator_ref, text);
+    }
+
+    fn store(&self) -> &dyn Store {
+        &setting::LocalStorage
+    }
+}
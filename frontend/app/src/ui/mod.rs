@@ -1,25 +1,93 @@
+pub mod audio;
 pub mod fps;
 pub mod frame_timer;
+pub mod ghost;
+pub mod host;
+pub mod recorder;
 pub mod setting;
 
+use host::Host;
 use log::{debug, info};
-use nalgebra::{point, vector, Point2};
+use nalgebra::{point, vector, Point2, Vector2};
 use oort_renderer::Renderer;
 use oort_simulator::model;
-use oort_simulator::scenario::Status;
-use oort_simulator::simulation::{self, PHYSICS_TICK_LENGTH};
-use oort_simulator::snapshot::{self, ShipSnapshot, Snapshot};
+use oort_simulator::scenario::sandbox::{SandboxCommand, SpawnKind};
+use oort_simulator::scenario::{Camera, Status};
+use oort_simulator::ship::ShipClass;
+use oort_simulator::simulation::{self, SimEvent, PHYSICS_TICK_LENGTH};
+use oort_simulator::snapshot::{self, ScriptStatus, ShipSnapshot, Snapshot};
+use setting::Settings;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::Duration;
-use web_sys::{Element, HtmlCanvasElement};
+use web_sys::HtmlCanvasElement;
 use yew::NodeRef;
 
 const ZOOM_SPEED: f32 = 0.02;
 const MIN_ZOOM: f32 = 5e-6;
 const MAX_ZOOM: f32 = 5e-3;
+const FIT_MARGIN: f64 = 1.2;
+const FIT_MIN_EXTENT: f64 = 1000.0;
 const INITIAL_ZOOM: f32 = 1e-3;
 const SNAPSHOT_PRELOAD: usize = 5;
 const MAX_SNAPSHOT_REQUESTS_IN_FLIGHT: usize = 10;
+/// Longest gap between frames that advances physics time, so a stalled tab
+/// doesn't try to catch up all at once.
+const MAX_FRAME_ELAPSED: Duration = Duration::from_millis(100);
+/// Ticks simulated per `background_step` while the tab is hidden.
+pub const BACKGROUND_TICKS: u32 = 60;
+
+/// Keys handled in `UI::render` and their descriptions for the documentation.
+pub const KEYBINDINGS: &[(&str, &str)] = &[
+    ("W/A/S/D", "Pan the camera."),
+    ("Space", "Pause/resume."),
+    (
+        "N",
+        "Single-step (advance time by one tick and then pause).",
+    ),
+    ("F", "Fast-forward."),
+    ("M", "Slow motion."),
+    ("G", "Show debug lines for all ships."),
+    (
+        "V",
+        "Toggle NLIPS, which makes smaller ships more visible when zoomed out.",
+    ),
+    ("B", "Toggle postprocessing (blur)."),
+    (
+        "H",
+        "Toggle heading and velocity indicators for your ships.",
+    ),
+    (
+        "T",
+        "Toggle thrust plumes, which show each ship's current acceleration.",
+    ),
+    ("C", "Toggle the background grid and world boundary."),
+    ("U", "Toggle sound effects."),
+    (
+        "O",
+        "Toggle the minimap. Click or drag on it to move the camera.",
+    ),
+    ("E", "Zoom to fit all ships."),
+    ("Home", "Reset the camera to the scenario's starting view."),
+    (
+        "P",
+        "Toggle the ship list, showing each ship's health and script status.",
+    ),
+    ("R", "Start/stop recording a video of the simulation."),
+    (",", "Open the settings."),
+    ("Escape", "Deselect the selected ship."),
+    (
+        "K",
+        "Toggle pausing the simulation when one of your ships is destroyed.",
+    ),
+    (
+        "1-8",
+        "Sandbox only: spawn a fighter, frigate, cruiser, missile, torpedo, asteroid, target or bullet at the cursor.",
+    ),
+    ("J", "Sandbox only: switch the team of spawned entities."),
+    ("Delete", "Sandbox only: remove all ships and bullets."),
+    ("Mouse wheel", "Zoom."),
+    ("Mouse click", "Select a ship to show debugging info."),
+];
 
 pub struct UI {
     version: String,
@@ -42,43 +110,63 @@ pub struct UI {
     last_render_time: instant::Instant,
     physics_time: std::time::Duration,
     fps: fps::FPS,
-    debug: bool,
     last_status_msg: String,
     snapshot_requests_in_flight: usize,
     nonce: u32,
-    request_snapshot: yew::Callback<()>,
+    last_snapshot_tick: Option<u32>,
+    ghost: Option<ghost::Track>,
+    track: Option<ghost::Track>,
+    request_snapshot: yew::Callback<u32>,
+    hidden: bool,
     picked_ship_id: Option<u64>,
-    status_ref: NodeRef,
-    picked_ref: NodeRef,
+    host: Box<dyn Host>,
     touches: HashMap<i32, Touch>,
     drag_start: Option<Point2<i32>>,
     needs_render: bool,
+    audio: audio::Audio,
+    recording: bool,
+    sandbox: Option<yew::Callback<SandboxCommand>>,
+    sandbox_team: i32,
+    cursor_position: Option<Point2<f64>>,
+    // Whether to zoom to fit the first snapshot.
+    fit_first_snapshot: bool,
+    initial_camera: Option<Camera>,
+    settings: Settings,
 }
 
 unsafe impl Send for UI {}
 
+/// Returns the zoom level that shows an area of the given width and height.
+fn zoom_for_extent(canvas: &HtmlCanvasElement, extent: Vector2<f64>) -> f32 {
+    let aspect = canvas.height() as f64 / canvas.width().max(1) as f64;
+    let zoom = (1.0 / extent.x).min(aspect / extent.y);
+    (zoom as f32).clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
 impl UI {
-    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        request_snapshot: yew::Callback<()>,
+        request_snapshot: yew::Callback<u32>,
         seed: u32,
         nonce: u32,
         version: String,
         canvas_ref: NodeRef,
-        status_ref: NodeRef,
-        picked_ref: NodeRef,
+        host: Box<dyn Host>,
         paused: bool,
+        initial_camera: Option<Camera>,
     ) -> Self {
-        if let Some(elem) = status_ref.cast::<Element>() {
-            elem.set_text_content(Some("LOADING..."));
-        }
+        host.set_status("LOADING...");
 
         let canvas = canvas_ref
             .cast::<HtmlCanvasElement>()
             .expect("canvas element");
         let mut renderer = Renderer::new(canvas.clone()).expect("Failed to create renderer");
-        let zoom = INITIAL_ZOOM;
-        let camera_target = point![0.0, 0.0];
+        let (camera_target, zoom) = match initial_camera {
+            Some(camera) => (
+                camera.target.cast::<f32>(),
+                zoom_for_extent(&canvas, vector![camera.extent, camera.extent]),
+            ),
+            None => (point![0.0, 0.0], INITIAL_ZOOM),
+        };
         renderer.set_view(zoom, point![camera_target.x, camera_target.y]);
         let frame_timer: frame_timer::FrameTimer = Default::default();
         let single_steps = 0;
@@ -86,12 +174,9 @@ impl UI {
         let keys_down = std::collections::HashSet::<String>::new();
         let keys_ignored = std::collections::HashSet::<String>::new();
 
-        let debug = setting::read("debug", false);
-        renderer.set_debug(debug);
-        renderer.set_blur(setting::read("blur", true));
-        renderer.set_nlips(setting::read("nlips", false));
+        let settings = Settings::load_from(host.store());
 
-        UI {
+        let mut ui = UI {
             version,
             seed,
             snapshot: None,
@@ -112,18 +197,30 @@ impl UI {
             last_render_time: instant::Instant::now(),
             physics_time: std::time::Duration::ZERO,
             fps: fps::FPS::new(),
-            debug,
             last_status_msg: "".to_owned(),
             snapshot_requests_in_flight: 0,
             nonce,
+            last_snapshot_tick: None,
+            ghost: None,
+            track: None,
             request_snapshot,
+            hidden: false,
             picked_ship_id: None,
-            status_ref,
-            picked_ref,
+            host,
             touches: HashMap::new(),
             drag_start: None,
             needs_render: true,
-        }
+            audio: audio::Audio::new(),
+            recording: false,
+            sandbox: None,
+            sandbox_team: 0,
+            cursor_position: None,
+            fit_first_snapshot: initial_camera.is_none(),
+            initial_camera,
+            settings: settings.clone(),
+        };
+        ui.set_settings(settings);
+        ui
     }
 
     pub fn render(&mut self) {
@@ -176,9 +273,13 @@ impl UI {
         }
         if self.keys_down.contains("g") && !self.keys_ignored.contains("g") {
             self.keys_ignored.insert("g".to_string());
-            self.debug = !self.debug;
-            self.renderer.set_debug(self.debug);
-            setting::write("debug", &self.debug);
+            self.settings.debug = !self.settings.debug;
+            self.save_settings();
+        }
+        if self.keys_down.contains("Escape") && !self.keys_ignored.contains("Escape") {
+            self.keys_ignored.insert("Escape".to_string());
+            self.picked_ship_id = None;
+            self.update_picked();
         }
         if self.keys_down.contains("q") {
             self.set_status_message("EXITED");
@@ -188,17 +289,91 @@ impl UI {
         let slowmo = self.keys_down.contains("m");
         if self.keys_down.contains("b") && !self.keys_ignored.contains("b") {
             self.keys_ignored.insert("b".to_string());
-            self.renderer.set_blur(!self.renderer.get_blur());
-            setting::write("blur", &self.renderer.get_blur());
+            self.settings.blur = !self.settings.blur;
+            self.save_settings();
         }
         if self.keys_down.contains("v") && !self.keys_ignored.contains("v") {
             self.keys_ignored.insert("v".to_string());
-            self.renderer.set_nlips(!self.renderer.get_nlips());
-            setting::write("nlips", &self.renderer.get_nlips());
+            self.settings.nlips = !self.settings.nlips;
+            self.save_settings();
+        }
+        if self.keys_down.contains("h") && !self.keys_ignored.contains("h") {
+            self.keys_ignored.insert("h".to_string());
+            self.settings.ship_vectors = !self.settings.ship_vectors;
+            self.save_settings();
+        }
+        if self.keys_down.contains("t") && !self.keys_ignored.contains("t") {
+            self.keys_ignored.insert("t".to_string());
+            self.settings.thrust_plumes = !self.settings.thrust_plumes;
+            self.save_settings();
+        }
+        if self.keys_down.contains("p") && !self.keys_ignored.contains("p") {
+            self.keys_ignored.insert("p".to_string());
+            self.settings.spectator = !self.settings.spectator;
+            self.save_settings();
+        }
+        if self.keys_down.contains("c") && !self.keys_ignored.contains("c") {
+            self.keys_ignored.insert("c".to_string());
+            self.settings.grid = !self.settings.grid;
+            self.save_settings();
+        }
+        if self.keys_down.contains("o") && !self.keys_ignored.contains("o") {
+            self.keys_ignored.insert("o".to_string());
+            self.settings.minimap = !self.settings.minimap;
+            self.save_settings();
+        }
+        if self.keys_down.contains("e") && !self.keys_ignored.contains("e") {
+            self.keys_ignored.insert("e".to_string());
+            self.zoom_to_fit();
+        }
+        if self.keys_down.contains("Home") && !self.keys_ignored.contains("Home") {
+            self.keys_ignored.insert("Home".to_string());
+            self.reset_camera();
+        }
+        if self.keys_down.contains("k") && !self.keys_ignored.contains("k") {
+            self.keys_ignored.insert("k".to_string());
+            self.settings.pause_on_death = !self.settings.pause_on_death;
+            self.save_settings();
+        }
+        if self.settings.pause_on_death {
+            status_msgs.push("PAUSE ON DEATH".to_string());
+        }
+        if self.keys_down.contains("u") && !self.keys_ignored.contains("u") {
+            self.keys_ignored.insert("u".to_string());
+            self.settings.mute = !self.settings.mute;
+            self.save_settings();
+        }
+        if self.settings.debug && self.keys_down.contains("l") && !self.keys_ignored.contains("l") {
+            self.keys_ignored.insert("l".to_string());
+            self.renderer.toggle_context_loss();
+        }
+        if let Some(sandbox) = self.sandbox.as_ref() {
+            for (i, &kind) in SpawnKind::ALL.iter().enumerate() {
+                let key = (i + 1).to_string();
+                if self.keys_down.contains(&key) && !self.keys_ignored.contains(&key) {
+                    self.keys_ignored.insert(key);
+                    if let Some(position) = self.cursor_position {
+                        sandbox.emit(SandboxCommand::Spawn {
+                            kind,
+                            team: self.sandbox_team,
+                            position,
+                        });
+                    }
+                }
+            }
+            if self.keys_down.contains("j") && !self.keys_ignored.contains("j") {
+                self.keys_ignored.insert("j".to_string());
+                self.sandbox_team = 1 - self.sandbox_team;
+            }
+            if self.keys_down.contains("Delete") && !self.keys_ignored.contains("Delete") {
+                self.keys_ignored.insert("Delete".to_string());
+                sandbox.emit(SandboxCommand::Clear);
+            }
+            status_msgs.push(format!("SANDBOX TEAM {}", self.sandbox_team));
         }
 
         if !self.paused && !slowmo {
-            self.physics_time += elapsed;
+            self.physics_time += elapsed.min(MAX_FRAME_ELAPSED);
         }
 
         if self.status == Status::Running
@@ -228,7 +403,9 @@ impl UI {
             }
         }
 
-        if self.snapshot.is_some() {
+        if self.renderer.is_context_lost() {
+            status_msgs.push("GPU context lost, recovering…".to_string());
+        } else if self.snapshot.is_some() {
             self.renderer.render(
                 self.camera_target,
                 self.zoom,
@@ -238,6 +415,9 @@ impl UI {
             if self.snapshot.as_ref().unwrap().cheats {
                 status_msgs.push("CHEATS".to_string());
             }
+            if let Some(tally) = self.snapshot.as_ref().unwrap().rounds.as_ref() {
+                status_msgs.push(tally.summary().to_uppercase());
+            }
         }
 
         match self.status {
@@ -259,11 +439,15 @@ impl UI {
             _ => {}
         }
 
+        if self.recording {
+            status_msgs.push("REC".to_string());
+        }
+
         if self.pending_snapshots.len() <= 1 && !fast_forward {
             status_msgs.push("SLOW SIM".to_owned());
         }
 
-        if self.debug {
+        if self.settings.debug {
             status_msgs.push(format!("SEED {}", self.seed));
             if let Some(snapshot) = self.snapshot.as_ref() {
                 status_msgs.push(format!(
@@ -277,7 +461,7 @@ impl UI {
             if self.status == Status::Running {
                 status_msgs.push(format!("{:.0} fps", self.fps.fps()));
             }
-            if self.debug {
+            if self.settings.debug {
                 let (a, b, c) = self.frame_timer.get_latency();
                 status_msgs.push(format!("UI {a:.1}/{b:.1}/{c:.1} ms",));
                 if let Some(snapshot) = self.snapshot.as_ref() {
@@ -314,11 +498,28 @@ impl UI {
             return;
         }
 
-        self.pending_snapshots.push_back(snapshot);
         if self.snapshot_requests_in_flight > 0 {
             self.snapshot_requests_in_flight -= 1;
         }
 
+        if self
+            .last_snapshot_tick
+            .map_or(false, |tick| snapshot.tick < tick)
+        {
+            log::warn!("Dropping out-of-order snapshot for tick {}", snapshot.tick);
+            return;
+        }
+        let first_snapshot = self.last_snapshot_tick.is_none();
+        self.last_snapshot_tick = Some(snapshot.tick);
+
+        match self.track.as_mut() {
+            Some(track) => track.record(&snapshot),
+            None if first_snapshot => self.track = ghost::Track::start(&snapshot),
+            None => {}
+        }
+
+        self.pending_snapshots.push_back(snapshot);
+
         self.needs_render = true;
     }
 
@@ -327,14 +528,23 @@ impl UI {
             && std::time::Duration::from_secs_f64(self.pending_snapshots[1].time)
                 <= self.physics_time
         {
-            self.pending_snapshots.pop_front();
+            // Hand the skipped snapshot's explosions to the next one so they
+            // still get drawn.
+            let skipped = self.pending_snapshots.pop_front().unwrap();
+            self.pending_snapshots[0].events.extend(
+                skipped
+                    .events
+                    .into_iter()
+                    .filter(|event| matches!(event, SimEvent::Explosion { .. })),
+            );
         }
 
-        if self.pending_snapshots.len() < SNAPSHOT_PRELOAD
+        if !self.hidden
+            && self.pending_snapshots.len() < SNAPSHOT_PRELOAD
             && self.snapshot_requests_in_flight < MAX_SNAPSHOT_REQUESTS_IN_FLIGHT
         {
-            self.request_snapshot.emit(());
-            self.request_snapshot.emit(());
+            self.request_snapshot.emit(1);
+            self.request_snapshot.emit(1);
             self.snapshot_requests_in_flight += 2;
         }
 
@@ -343,11 +553,24 @@ impl UI {
                 <= self.physics_time
         {
             let first_snapshot = self.snapshot.is_none();
+            let own_ship_ids: Vec<u64> = self
+                .snapshot
+                .iter()
+                .flat_map(|snapshot| snapshot.ships.iter())
+                .filter(|ship| is_own_ship(ship))
+                .map(|ship| ship.id)
+                .collect();
 
             self.snapshot = self.pending_snapshots.pop_front();
             let snapshot = self.snapshot.as_mut().unwrap();
 
-            if first_snapshot {
+            self.audio.play(
+                &snapshot.events,
+                point![self.camera_target.x as f64, self.camera_target.y as f64],
+                1.0 / self.zoom as f64,
+            );
+
+            if first_snapshot && self.fit_first_snapshot {
                 // Zoom out to show all ships.
                 let mut points = snapshot
                     .ships
@@ -368,7 +591,9 @@ impl UI {
                 let view_dim = top_left.x.abs().max(top_left.y.abs());
                 self.zoom = (0.8 * self.zoom * view_dim as f32 / max_dim as f32)
                     .clamp(MIN_ZOOM, INITIAL_ZOOM);
+            }
 
+            if first_snapshot {
                 // Pick player ship if there's only one.
                 let own_ships: Vec<_> = snapshot
                     .ships
@@ -384,7 +609,18 @@ impl UI {
                 self.paused = true;
             }
 
+            // Stop on the tick one of the player's ships disappears, so the
+            // cause is still on screen.
+            if self.settings.pause_on_death
+                && own_ship_ids
+                    .iter()
+                    .any(|id| !snapshot.ships.iter().any(|ship| ship.id == *id))
+            {
+                self.paused = true;
+            }
+
             self.status = snapshot.status;
+            self.update_spectator();
         }
 
         if let Some(snapshot) = self.snapshot.as_mut() {
@@ -398,6 +634,11 @@ impl UI {
 
             snapshot::interpolate(snapshot, delta.as_secs_f64());
 
+            self.renderer.set_ghost(
+                self.ghost
+                    .as_ref()
+                    .and_then(|ghost| ghost.at((t + delta).as_secs_f64() / PHYSICS_TICK_LENGTH)),
+            );
             self.renderer.update(snapshot);
 
             snapshot.particles.clear();
@@ -406,6 +647,7 @@ impl UI {
 
     pub fn on_key_event(&mut self, e: web_sys::KeyboardEvent) {
         if e.type_() == "keydown" {
+            self.audio.on_user_gesture();
             self.keys_down.insert(e.key());
         } else if e.type_() == "keyup" {
             self.keys_down.remove(&e.key());
@@ -440,6 +682,7 @@ impl UI {
             .renderer
             .unproject(canvas_position.x, canvas_position.y)
             + vector![self.camera_target.x as f64, self.camera_target.y as f64];
+        self.cursor_position = Some(world_position);
 
         log::debug!(
             "PointerEvent: pointer_id={} pointer_type={} buttons={} canvas={:?} world={:?}",
@@ -450,6 +693,21 @@ impl UI {
             world_position
         );
 
+        // Pressing or dragging on the minimap recenters the camera, unless the
+        // pointer started a pan elsewhere.
+        if e.buttons() != 0 && !self.touches.contains_key(&e.pointer_id()) {
+            if let Some(target) = self
+                .renderer
+                .minimap_to_world(canvas_position.x, canvas_position.y)
+            {
+                self.camera_target = point![target.x as f32, target.y as f32];
+                self.renderer.set_view(self.zoom, self.camera_target);
+                self.needs_render = true;
+                let _ = self.canvas.focus();
+                return;
+            }
+        }
+
         if e.buttons() == 0 {
             self.touches.remove(&e.pointer_id());
             if let Some(start_canvas_position) = std::mem::take(&mut self.drag_start) {
@@ -501,6 +759,7 @@ impl UI {
             self.camera_target += diff;
             self.renderer.set_view(self.zoom, self.camera_target);
         } else {
+            self.audio.on_user_gesture();
             self.touches
                 .insert(e.pointer_id(), Touch { world_position });
         }
@@ -526,7 +785,44 @@ impl UI {
         self.snapshot.clone()
     }
 
+    /// Moves the camera so that every ship is visible.
+    pub fn zoom_to_fit(&mut self) {
+        let snapshot = match self.snapshot.as_ref() {
+            Some(snapshot) if !snapshot.ships.is_empty() => snapshot,
+            _ => return,
+        };
+        let (mut min, mut max) = (snapshot.ships[0].position, snapshot.ships[0].position);
+        for ship in snapshot.ships.iter() {
+            min = min.inf(&ship.position);
+            max = max.sup(&ship.position);
+        }
+        self.camera_target = nalgebra::center(&min, &max).cast();
+
+        // Avoid zooming all the way in on a single ship.
+        let extent = (max - min).map(|x| x.max(FIT_MIN_EXTENT)) * FIT_MARGIN;
+        self.zoom = zoom_for_extent(&self.canvas, extent);
+    }
+
+    /// Returns the camera to the scenario's initial view, or zooms to fit if
+    /// the scenario doesn't have one.
+    pub fn reset_camera(&mut self) {
+        match self.initial_camera {
+            Some(camera) => {
+                self.camera_target = camera.target.cast();
+                self.zoom = zoom_for_extent(&self.canvas, vector![camera.extent, camera.extent]);
+            }
+            None => self.zoom_to_fit(),
+        }
+    }
+
     pub fn update_picked(&mut self) {
+        // Drop the selection once the picked ship has been destroyed.
+        if let (Some(id), Some(snapshot)) = (self.picked_ship_id, self.snapshot.as_ref()) {
+            if !snapshot.ships.iter().any(|ship| ship.id == id) {
+                self.picked_ship_id = None;
+            }
+        }
+
         if let Some(ship) = self.picked_ship_id.and_then(|id| {
             self.snapshot
                 .as_ref()
@@ -535,6 +831,9 @@ impl UI {
             let ShipSnapshot {
                 class,
                 team,
+                position,
+                velocity,
+                heading,
                 health,
                 fuel,
                 active_abilities,
@@ -546,40 +845,154 @@ impl UI {
                 .and_then(|s| s.debug_text.get(&self.picked_ship_id.unwrap()))
                 .cloned()
                 .unwrap_or_default();
-            if let Some(elem) = self.picked_ref.cast::<Element>() {
-                let fuel_text = if let Some(fuel) = fuel {
-                    format!("Fuel: {:.0}\n", fuel)
-                } else {
-                    "".to_string()
-                };
-                let active_abilities_text = if !active_abilities.is_empty() {
-                    format!(
-                        "Active abilities: {}\n",
-                        active_abilities
-                            .iter()
-                            .map(|ability| format!("{:?}", ability))
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    )
-                } else {
-                    "".to_string()
-                };
-                elem.set_text_content(Some(&format!(
-                    "{class:?}\nTeam: {team:?}\nHealth: {health:.0}\n{fuel_text}{active_abilities_text}{debug_text}"
-                )));
-            }
-        } else if let Some(elem) = self.picked_ref.cast::<Element>() {
-            elem.set_text_content(Some(""));
+            let fuel_text = if let Some(fuel) = fuel {
+                format!("Fuel: {:.0}\n", fuel)
+            } else {
+                "".to_string()
+            };
+            let active_abilities_text = if !active_abilities.is_empty() {
+                format!(
+                    "Active abilities: {}\n",
+                    active_abilities
+                        .iter()
+                        .map(|ability| format!("{:?}", ability))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            } else {
+                "".to_string()
+            };
+            let (px, py, vx, vy) = (position.x, position.y, velocity.x, velocity.y);
+            self.host.set_picked(&format!(
+                "{class:?}\nTeam: {team:?}\nPosition: ({px:.0}, {py:.0})\nVelocity: ({vx:.1}, {vy:.1})\nHeading: {heading:.2}\nHealth: {health:.0}\n{fuel_text}{active_abilities_text}{debug_text}"
+            ));
+        } else {
+            self.host.set_picked("");
         }
         self.renderer.set_picked_ship(self.picked_ship_id);
     }
 
-    pub fn set_status_message(&self, text: &str) {
-        if let Some(elem) = self.status_ref.cast::<Element>() {
-            elem.set_text_content(Some(text));
+    /// Lists every ship with its team, class, health and script status.
+    pub fn update_spectator(&self) {
+        let snapshot = match self.snapshot.as_ref() {
+            Some(snapshot) if self.settings.spectator => snapshot,
+            _ => {
+                self.host.set_spectator("");
+                return;
+            }
+        };
+        let mut text = format!(
+            "{:>6} {:>4} {:<10} {:>6}  Script\n",
+            "ID", "Team", "Class", "Health"
+        );
+        for ship in snapshot.ships.iter() {
+            let script_status = match &ship.script_status {
+                ScriptStatus::Ok => "ok".to_string(),
+                ScriptStatus::Disabled => "disabled".to_string(),
+                ScriptStatus::Crashed(msg) => {
+                    let msg = msg.lines().next().unwrap_or_default();
+                    format!("crashed: {}", msg.chars().take(60).collect::<String>())
+                }
+            };
+            text.push_str(&format!(
+                "{:>6} {:>4} {:<10} {:>6.0}  {}\n",
+                ship.id,
+                ship.team,
+                ship.class.name(),
+                ship.health,
+                script_status
+            ));
+        }
+        self.host.set_spectator(&text);
+    }
+
+    /// Stops the simulation, leaving the last frame on screen.
+    pub fn halt(&mut self, reason: &str) {
+        self.set_status_message(reason);
+        self.quit = true;
+    }
+
+    /// Called when the browser tab is hidden or shown. While hidden no
+    /// snapshots are requested, which pauses the simulation worker.
+    pub fn set_hidden(&mut self, hidden: bool) {
+        if self.hidden == hidden {
+            return;
+        }
+        self.hidden = hidden;
+        if !hidden {
+            self.last_render_time = instant::Instant::now();
+            self.needs_render = true;
         }
     }
 
+    /// Advances a hidden simulation by `BACKGROUND_TICKS` without rendering.
+    /// Does nothing if the user paused the simulation.
+    pub fn background_step(&mut self) {
+        if self.quit || !self.hidden {
+            return;
+        }
+        if let Some(snapshot) = self.pending_snapshots.pop_back() {
+            self.pending_snapshots.clear();
+            self.physics_time = Duration::from_secs_f64(snapshot.time);
+            self.status = snapshot.status;
+            if !snapshot.errors.is_empty() {
+                self.paused = true;
+            }
+            self.snapshot = Some(snapshot);
+        }
+        if self.status == Status::Running && !self.paused && self.snapshot_requests_in_flight == 0 {
+            self.request_snapshot.emit(BACKGROUND_TICKS);
+            self.snapshot_requests_in_flight += 1;
+        }
+    }
+
+    /// Sets the previous best run to draw alongside this one.
+    pub fn set_ghost(&mut self, ghost: Option<ghost::Track>) {
+        self.ghost = ghost;
+    }
+
+    /// The player's ship positions so far, if they control a single ship.
+    pub fn track(&self) -> Option<&ghost::Track> {
+        self.track.as_ref()
+    }
+
+    /// Enables the sandbox keys, which send commands to `callback`.
+    pub fn set_sandbox(&mut self, callback: yew::Callback<SandboxCommand>) {
+        self.sandbox = Some(callback);
+    }
+
+    /// Applies settings changed outside the UI, e.g. from the settings
+    /// overlay. They're saved by whoever changed them.
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.renderer.set_debug(settings.debug);
+        self.renderer.set_blur(settings.blur);
+        self.renderer.set_nlips(settings.nlips);
+        self.renderer.set_ship_vectors(settings.ship_vectors);
+        self.renderer.set_thrust_plumes(settings.thrust_plumes);
+        self.renderer.set_grid(settings.grid);
+        self.renderer.set_minimap(settings.minimap);
+        self.renderer.set_reduce_motion(settings.reduce_motion);
+        self.audio.set_volume(settings.volume);
+        self.audio.set_muted(settings.mute);
+        self.settings = settings;
+        self.update_spectator();
+        self.needs_render = true;
+    }
+
+    fn save_settings(&mut self) {
+        self.set_settings(self.settings.clone());
+        self.settings.save_to(self.host.store());
+    }
+
+    pub fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+        self.needs_render = true;
+    }
+
+    pub fn set_status_message(&self, text: &str) {
+        self.host.set_status(text);
+    }
+
     pub fn frame(&self) -> u64 {
         self.frame
     }
@@ -595,3 +1008,9 @@ impl UI {
 struct Touch {
     world_position: Point2<f64>,
 }
+
+/// Whether the ship is one of the player's, not counting missiles and
+/// torpedoes.
+fn is_own_ship(ship: &ShipSnapshot) -> bool {
+    ship.team == 0 && !matches!(ship.class, ShipClass::Missile | ShipClass::Torpedo)
+}
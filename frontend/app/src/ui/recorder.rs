@@ -0,0 +1,120 @@
+use js_sys::{Array, Reflect};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    Blob, BlobEvent, BlobPropertyBag, HtmlAnchorElement, HtmlCanvasElement, MediaRecorder,
+    MediaRecorderOptions, Url,
+};
+
+// Every chunk is held in memory until the download, so keep recordings short.
+const MAX_DURATION: Duration = Duration::from_secs(60);
+const MAX_BYTES: f64 = 100e6;
+const FRAME_RATE: f64 = 60.0;
+const CHUNK_MILLIS: i32 = 1000;
+const MIME_TYPES: &[&str] = &["video/webm;codecs=vp9", "video/webm"];
+
+/// Records the simulation canvas to a WebM video.
+///
+/// The file is downloaded when the recording is stopped, either explicitly or
+/// when it hits the duration or size limit.
+pub struct Recorder {
+    recorder: MediaRecorder,
+    start_time: instant::Instant,
+    bytes: Rc<Cell<f64>>,
+}
+
+impl Recorder {
+    pub fn start(canvas: &HtmlCanvasElement) -> Result<Self, String> {
+        let window = web_sys::window().unwrap();
+        if !Reflect::has(&window, &"MediaRecorder".into()).unwrap_or(false)
+            || !Reflect::has(canvas, &"captureStream".into()).unwrap_or(false)
+        {
+            return Err("Recording is not supported by this browser".to_string());
+        }
+        let mime_type = match MIME_TYPES
+            .iter()
+            .find(|x| MediaRecorder::is_type_supported(x))
+        {
+            Some(mime_type) => *mime_type,
+            None => return Err("WebM recording is not supported by this browser".to_string()),
+        };
+
+        let stream = canvas
+            .capture_stream_with_frame_request_rate(FRAME_RATE)
+            .map_err(|e| format!("Failed to capture canvas: {e:?}"))?;
+        let mut options = MediaRecorderOptions::new();
+        options.mime_type(mime_type);
+        let recorder =
+            MediaRecorder::new_with_media_stream_and_media_recorder_options(&stream, &options)
+                .map_err(|e| format!("Failed to create MediaRecorder: {e:?}"))?;
+
+        let chunks = Array::new();
+        let bytes = Rc::new(Cell::new(0.0));
+        let ondataavailable = {
+            let chunks = chunks.clone();
+            let bytes = bytes.clone();
+            Closure::<dyn FnMut(BlobEvent)>::new(move |e: BlobEvent| {
+                if let Some(data) = e.data() {
+                    bytes.set(bytes.get() + data.size());
+                    chunks.push(&data);
+                }
+            })
+        };
+        recorder.set_ondataavailable(Some(ondataavailable.as_ref().unchecked_ref()));
+        // The last chunk arrives after stop() is called, so the data callback
+        // has to live until the stop event.
+        let onstop = Closure::once_into_js(move || {
+            drop(ondataavailable);
+            if let Err(e) = download(&chunks, mime_type) {
+                log::error!("Failed to download recording: {:?}", e);
+            }
+        });
+        recorder.set_onstop(Some(onstop.unchecked_ref()));
+
+        recorder
+            .start_with_time_slice(CHUNK_MILLIS)
+            .map_err(|e| format!("Failed to start recording: {e:?}"))?;
+
+        Ok(Self {
+            recorder,
+            start_time: instant::Instant::now(),
+            bytes,
+        })
+    }
+
+    pub fn stop(&self) {
+        if let Err(e) = self.recorder.stop() {
+            log::error!("Failed to stop recording: {:?}", e);
+        }
+    }
+
+    pub fn over_limit(&self) -> bool {
+        self.start_time.elapsed() >= MAX_DURATION || self.bytes.get() >= MAX_BYTES
+    }
+}
+
+fn download(chunks: &Array, mime_type: &str) -> Result<(), JsValue> {
+    let mut options = BlobPropertyBag::new();
+    options.type_(mime_type);
+    let blob = Blob::new_with_blob_sequence_and_options(chunks, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let document = gloo_utils::document();
+    let anchor: HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(&format!(
+        "oort-{}.webm",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    anchor.click();
+
+    // Give the browser a moment to start the download before freeing the blob.
+    gloo_timers::callback::Timeout::new(1000, move || {
+        let _ = Url::revoke_object_url(&url);
+    })
+    .forget();
+    Ok(())
+}
@@ -1,22 +1,234 @@
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
 
-pub fn read<T: for<'a> Deserialize<'a>>(name: &str, default: T) -> T {
-    fn read_setting_inner<T: for<'a> Deserialize<'a>>(name: &str) -> Option<T> {
+/// Key-value storage for settings.
+pub trait Store {
+    fn get_item(&self, key: &str) -> Option<String>;
+    fn set_item(&self, key: &str, value: &str) -> Option<()>;
+}
+
+/// The browser's localStorage.
+pub struct LocalStorage;
+
+impl Store for LocalStorage {
+    fn get_item(&self, key: &str) -> Option<String> {
         let storage = web_sys::window()?.local_storage().ok()??;
-        let value = storage.get_item(name).ok()??;
-        serde_json::from_str(&value).ok()
+        storage.get_item(key).ok()?
     }
-    read_setting_inner(name).unwrap_or(default)
-}
 
-pub fn write<T: Serialize>(name: &str, value: &T) {
-    fn write_setting_inner<T: Serialize>(name: &str, value: &T) -> Option<()> {
+    fn set_item(&self, key: &str, value: &str) -> Option<()> {
         let storage = web_sys::window()?.local_storage().ok()??;
-        let value = serde_json::to_string(value).ok()?;
-        storage.set_item(name, &value).ok()?;
+        storage.set_item(key, value).ok()
+    }
+}
+
+/// Settings that only last as long as the process, for tests and targets
+/// without a browser.
+#[derive(Default)]
+pub struct MemoryStore {
+    items: RefCell<HashMap<String, String>>,
+}
+
+impl Store for MemoryStore {
+    fn get_item(&self, key: &str) -> Option<String> {
+        self.items.borrow().get(key).cloned()
+    }
+
+    fn set_item(&self, key: &str, value: &str) -> Option<()> {
+        self.items
+            .borrow_mut()
+            .insert(key.to_string(), value.to_string());
         Some(())
     }
-    if write_setting_inner(name, value).is_none() {
+}
+
+pub fn read<T: for<'a> Deserialize<'a>>(name: &str, default: T) -> T {
+    read_from(&LocalStorage, name, default)
+}
+
+pub fn write<T: Serialize>(name: &str, value: &T) {
+    write_to(&LocalStorage, name, value)
+}
+
+pub fn read_from<T: for<'a> Deserialize<'a>>(store: &dyn Store, name: &str, default: T) -> T {
+    store
+        .get_item(name)
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or(default)
+}
+
+pub fn write_to<T: Serialize>(store: &dyn Store, name: &str, value: &T) {
+    let result = serde_json::to_string(value)
+        .ok()
+        .and_then(|value| store.set_item(name, &value));
+    if result.is_none() {
         log::warn!("Failed to write setting {}", name);
     }
 }
+
+const SETTINGS_KEY: &str = "/settings";
+const SETTINGS_VERSION: u32 = 1;
+
+/// Display and sound preferences, stored together as one JSON object.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    pub debug: bool,
+    pub blur: bool,
+    pub nlips: bool,
+    pub ship_vectors: bool,
+    pub thrust_plumes: bool,
+    pub grid: bool,
+    pub minimap: bool,
+    pub spectator: bool,
+    pub pause_on_death: bool,
+    pub mute: bool,
+    pub keep_running_in_background: bool,
+    /// Master volume, from 0 to 1.
+    pub volume: f32,
+    /// Multiplier for the size of text and controls.
+    pub ui_scale: f32,
+    /// Turns off particles and trails.
+    pub reduce_motion: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            debug: false,
+            blur: true,
+            nlips: false,
+            ship_vectors: true,
+            thrust_plumes: true,
+            grid: true,
+            minimap: false,
+            spectator: false,
+            pause_on_death: false,
+            mute: false,
+            keep_running_in_background: false,
+            volume: 1.0,
+            ui_scale: 1.0,
+            reduce_motion: false,
+        }
+    }
+}
+
+impl Settings {
+    pub const MIN_UI_SCALE: f32 = 0.5;
+    pub const MAX_UI_SCALE: f32 = 2.0;
+
+    pub fn load() -> Self {
+        Self::load_from(&LocalStorage)
+    }
+
+    pub fn save(&self) {
+        self.save_to(&LocalStorage)
+    }
+
+    pub fn load_from(store: &dyn Store) -> Self {
+        let mut settings = match read_from(store, SETTINGS_KEY, None::<Settings>) {
+            Some(settings) => settings,
+            None => {
+                let settings = Self::from_individual_keys(store);
+                settings.save_to(store);
+                settings
+            }
+        };
+        settings.volume = settings.volume.clamp(0.0, 1.0);
+        settings.ui_scale = settings
+            .ui_scale
+            .clamp(Self::MIN_UI_SCALE, Self::MAX_UI_SCALE);
+        settings
+    }
+
+    pub fn save_to(&self, store: &dyn Store) {
+        write_to(store, SETTINGS_KEY, self)
+    }
+
+    /// Version 0 stored each setting under its own key.
+    fn from_individual_keys(store: &dyn Store) -> Self {
+        let default = Self::default();
+        Self {
+            version: SETTINGS_VERSION,
+            debug: read_from(store, "debug", default.debug),
+            blur: read_from(store, "blur", default.blur),
+            nlips: read_from(store, "nlips", default.nlips),
+            ship_vectors: read_from(store, "ship_vectors", default.ship_vectors),
+            thrust_plumes: read_from(store, "thrust_plumes", default.thrust_plumes),
+            grid: read_from(store, "grid", default.grid),
+            minimap: read_from(store, "minimap", default.minimap),
+            spectator: read_from(store, "spectator", default.spectator),
+            pause_on_death: read_from(store, "pause_on_death", default.pause_on_death),
+            mute: read_from(store, "mute", default.mute),
+            keep_running_in_background: read_from(
+                store,
+                "keep_running_in_background",
+                default.keep_running_in_background,
+            ),
+            ..default
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemoryStore::default();
+        assert!(read_from(&store, "blur", true));
+        write_to(&store, "blur", &false);
+        assert!(!read_from(&store, "blur", true));
+
+        // Values that don't parse fall back to the default.
+        store.set_item("zoom", "not json");
+        assert_eq!(read_from(&store, "zoom", 1.0), 1.0);
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let store = MemoryStore::default();
+        assert_eq!(Settings::load_from(&store), Settings::default());
+
+        let settings = Settings {
+            grid: false,
+            volume: 0.5,
+            reduce_motion: true,
+            ..Default::default()
+        };
+        settings.save_to(&store);
+        assert_eq!(Settings::load_from(&store), settings);
+    }
+
+    #[test]
+    fn test_settings_migration() {
+        let store = MemoryStore::default();
+        write_to(&store, "blur", &false);
+        write_to(&store, "minimap", &true);
+        write_to(&store, "mute", &true);
+        let settings = Settings::load_from(&store);
+        assert!(!settings.blur);
+        assert!(settings.minimap);
+        assert!(settings.mute);
+        assert!(settings.grid);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+
+        // Later changes to the old keys are ignored.
+        write_to(&store, "blur", &true);
+        assert!(!Settings::load_from(&store).blur);
+    }
+
+    #[test]
+    fn test_settings_clamped() {
+        let store = MemoryStore::default();
+        store.set_item(SETTINGS_KEY, r#"{"volume": 3.0, "ui_scale": 0.0}"#);
+        let settings = Settings::load_from(&store);
+        assert_eq!(settings.volume, 1.0);
+        assert_eq!(settings.ui_scale, Settings::MIN_UI_SCALE);
+        assert!(settings.blur);
+    }
+}
@@ -1,6 +1,6 @@
 use super::{buffer_arena, geometry, glutil};
 use glutil::VertexAttribBuilder;
-use nalgebra::{Matrix4, Point2, Vector2, Vector4};
+use nalgebra::{vector, Matrix4, Point2, Vector2, Vector4};
 use oort_simulator::color;
 use oort_simulator::simulation::PHYSICS_TICK_LENGTH;
 use oort_simulator::snapshot::Snapshot;
@@ -44,10 +44,12 @@ layout(location = 0) in vec4 vertex;
 layout(location = 1) in vec4 color;
 layout(location = 2) in mat4 transform;
 out vec4 varying_color;
+out float varying_across;
 
 void main() {
     gl_Position = projection * (transform * vertex);
     varying_color = color * clamp(float(gl_VertexID & 2), 0.1, 1.0);
+    varying_across = vertex.y * 2.0;
 }
     "#,
         )?;
@@ -57,9 +59,11 @@ void main() {
             r#"#version 300 es
 precision mediump float;
 in vec4 varying_color;
+in float varying_across;
 out vec4 fragmentColor;
 void main() {
-    fragmentColor = varying_color;
+    float core = 1.0 - abs(varying_across);
+    fragmentColor = vec4(mix(varying_color.rgb, vec3(1.0), 0.6 * core * core), varying_color.a);
 }
     "#,
         )?;
@@ -98,21 +102,35 @@ void main() {
         let vertices = geometry::quad();
         let vertices_token = self.buffer_arena.write(&vertices);
 
+        let view = geometry::Rect::visible(projection_matrix);
+        let dt = PHYSICS_TICK_LENGTH as f32;
+        let visible_bullets: Vec<_> = snapshot
+            .bullets
+            .iter()
+            .filter(|bullet| {
+                let v: Vector2<f32> = bullet.velocity.cast();
+                view.intersects_circle(
+                    bullet.position.cast(),
+                    v.magnitude() * dt + 2.0 * base_line_width,
+                )
+            })
+            .collect();
+
         let mut draws = vec![];
-        for bullets in snapshot.bullets.chunks(1000) {
+        for bullets in visible_bullets.chunks(1000) {
             let mut attribs = vec![];
             attribs.reserve(bullets.len());
             for bullet in bullets.iter() {
                 let p: Point2<f32> = bullet.position.cast();
                 let v: Vector2<f32> = bullet.velocity.cast();
-                let dt = PHYSICS_TICK_LENGTH as f32;
                 let mut color = color::from_u32(bullet.color);
                 if bullet.ttl < 0.3 {
                     color.w *= bullet.ttl + 0.3;
                 }
+                let (p1, p2) = tracer_endpoints(p, v, dt, 2.0 * base_line_width);
                 attribs.push(Attribs {
                     color,
-                    transform: geometry::line_transform(p - 2.0 * v * dt, p, base_line_width),
+                    transform: geometry::line_transform(p1, p2, base_line_width),
                 });
             }
             draws.push(Draw {
@@ -132,6 +150,7 @@ void main() {
     pub fn draw(&mut self, drawset: &DrawSet) {
         self.context.use_program(Some(&self.program));
         self.context.bind_vertex_array(Some(&self.vao));
+        self.context.blend_func(gl::SRC_ALPHA, gl::ONE);
 
         // projection
         self.context.uniform_matrix4fv_with_f32_array(
@@ -174,5 +193,54 @@ void main() {
         }
 
         self.context.bind_vertex_array(None);
+        self.context
+            .blend_func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+    }
+}
+
+/// Returns the endpoints of a tracer centered on the bullet and covering the
+/// distance it travels in one tick, stretched to at least `min_length`.
+pub fn tracer_endpoints(
+    p: Point2<f32>,
+    v: Vector2<f32>,
+    dt: f32,
+    min_length: f32,
+) -> (Point2<f32>, Point2<f32>) {
+    let mut half = v * dt * 0.5;
+    let length = 2.0 * half.magnitude();
+    if length < min_length {
+        half = if length > 0.0 {
+            half * (min_length / length)
+        } else {
+            vector![min_length * 0.5, 0.0]
+        };
+    }
+    (p - half, p + half)
+}
+
+#[cfg(test)]
+mod test {
+    use super::tracer_endpoints;
+    use nalgebra::{point, vector};
+
+    #[test]
+    fn test_tracer_endpoints() {
+        let p = point![100.0, -50.0];
+        let v = vector![1000.0, 500.0];
+        let dt = 1.0 / 60.0;
+        let (p1, p2) = tracer_endpoints(p, v, dt, 1.0);
+        assert!((p1 - (p - v * dt / 2.0)).magnitude() < 1e-4);
+        assert!((p2 - (p + v * dt / 2.0)).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn test_tracer_min_length() {
+        let p = point![0.0, 0.0];
+        let (p1, p2) = tracer_endpoints(p, vector![0.0, 6.0], 1.0 / 60.0, 10.0);
+        assert!((p1 - point![0.0, -5.0]).magnitude() < 1e-4);
+        assert!((p2 - point![0.0, 5.0]).magnitude() < 1e-4);
+
+        let (p1, p2) = tracer_endpoints(p, vector![0.0, 0.0], 1.0 / 60.0, 10.0);
+        assert!(((p2 - p1).magnitude() - 10.0).abs() < 1e-4);
     }
 }
@@ -1,5 +1,37 @@
 use nalgebra::{point, vector, Matrix4, Point2, Unit, Vector2};
 
+/// An axis-aligned rectangle in world coordinates, used to skip drawing
+/// things that are off screen.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub min: Point2<f32>,
+    pub max: Point2<f32>,
+}
+
+impl Rect {
+    /// The part of the world visible through an orthographic projection.
+    pub fn visible(projection_matrix: &Matrix4<f32>) -> Rect {
+        let inverse = projection_matrix
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity);
+        let a = inverse.transform_point(&point![-1.0, -1.0, 0.0]);
+        let b = inverse.transform_point(&point![1.0, 1.0, 0.0]);
+        Rect {
+            min: point![a.x.min(b.x), a.y.min(b.y)],
+            max: point![a.x.max(b.x), a.y.max(b.y)],
+        }
+    }
+
+    /// Whether any part of a circle overlaps the rectangle. Conservative near
+    /// the corners.
+    pub fn intersects_circle(&self, center: Point2<f32>, radius: f32) -> bool {
+        center.x + radius >= self.min.x
+            && center.x - radius <= self.max.x
+            && center.y + radius >= self.min.y
+            && center.y - radius <= self.max.y
+    }
+}
+
 pub fn quad() -> [Point2<f32>; 4] {
     let x = 0.5;
     [point![-x, -x], point![-x, x], point![x, -x], point![x, x]]
@@ -74,3 +106,29 @@ pub fn line_loop_mesh(vertices: &[Vector2<f32>], width: f32) -> Vec<Vector2<f32>
     }
     result
 }
+
+#[cfg(test)]
+mod test {
+    use super::Rect;
+    use nalgebra::{point, Matrix4};
+
+    #[test]
+    fn test_visible_rect() {
+        let projection = Matrix4::new_orthographic(-100.0, 300.0, -50.0, 150.0, -1.0, 1.0);
+        let rect = Rect::visible(&projection);
+        assert!((rect.min - point![-100.0, -50.0]).magnitude() < 1e-3);
+        assert!((rect.max - point![300.0, 150.0]).magnitude() < 1e-3);
+    }
+
+    #[test]
+    fn test_intersects_circle() {
+        let rect = Rect {
+            min: point![0.0, 0.0],
+            max: point![100.0, 100.0],
+        };
+        assert!(rect.intersects_circle(point![50.0, 50.0], 1.0));
+        assert!(rect.intersects_circle(point![-5.0, 50.0], 10.0));
+        assert!(!rect.intersects_circle(point![-15.0, 50.0], 10.0));
+        assert!(!rect.intersects_circle(point![50.0, 120.0], 10.0));
+    }
+}
@@ -6,8 +6,10 @@ pub mod geometry;
 pub mod glutil;
 pub mod grid_renderer;
 pub mod line_renderer;
+pub mod minimap;
 pub mod particle_renderer;
 pub mod ship_renderer;
+pub mod starfield_renderer;
 pub mod text_renderer;
 pub mod trail_renderer;
 
@@ -21,20 +23,34 @@ use grid_renderer::GridRenderer;
 use line_renderer::LineRenderer;
 use nalgebra::{point, vector, Matrix4, Point2};
 use oort_api::Text;
-use oort_simulator::simulation::Line;
+use oort_simulator::model;
+use oort_simulator::ship::ShipClass;
+use oort_simulator::simulation::{Line, LineStyle, SimEvent};
 use oort_simulator::snapshot::Snapshot;
 use particle_renderer::ParticleRenderer;
 use ship_renderer::ShipRenderer;
+use starfield_renderer::StarfieldRenderer;
+use std::cell::Cell;
+use std::rc::Rc;
 use text_renderer::TextRenderer;
 use trail_renderer::TrailRenderer;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlCanvasElement, WebGl2RenderingContext};
+use web_sys::{Event, HtmlCanvasElement, WebGl2RenderingContext, WebglLoseContext};
 use WebGl2RenderingContext as gl;
 
+/// A translucent ship drawn where the player's previous best run was.
+#[derive(Clone, Copy, Debug)]
+pub struct GhostShip {
+    pub class: ShipClass,
+    pub position: Point2<f64>,
+    pub heading: f64,
+}
+
 pub struct Renderer {
     canvas: HtmlCanvasElement,
     context: WebGl2RenderingContext,
+    starfield_renderer: StarfieldRenderer,
     grid_renderer: GridRenderer,
     line_renderer: LineRenderer,
     ship_renderer: ShipRenderer,
@@ -48,8 +64,74 @@ pub struct Renderer {
     base_line_width: f32,
     debug: bool,
     picked_ship: Option<u64>,
+    ghost: Option<GhostShip>,
     blur_enabled: bool,
     nlips_enabled: bool,
+    ship_vectors_enabled: bool,
+    thrust_plumes_enabled: bool,
+    grid_enabled: bool,
+    minimap_enabled: bool,
+    reduce_motion: bool,
+    world_size: f64,
+    context_lost: Rc<Cell<bool>>,
+    context_restored: Rc<Cell<bool>>,
+    lose_context: Option<WebglLoseContext>,
+    _context_listeners: ContextListeners,
+}
+
+/// Keeps the webglcontextlost/webglcontextrestored handlers registered for
+/// as long as the renderer exists.
+struct ContextListeners {
+    canvas: HtmlCanvasElement,
+    lost: Closure<dyn FnMut(Event)>,
+    restored: Closure<dyn FnMut(Event)>,
+}
+
+impl ContextListeners {
+    fn new(
+        canvas: HtmlCanvasElement,
+        context_lost: Rc<Cell<bool>>,
+        context_restored: Rc<Cell<bool>>,
+    ) -> Result<Self, JsValue> {
+        let lost = Closure::<dyn FnMut(Event)>::new({
+            let context_lost = context_lost.clone();
+            move |e: Event| {
+                log::warn!("WebGL context lost");
+                // Tells the browser we'd like the context back.
+                e.prevent_default();
+                context_lost.set(true);
+            }
+        });
+        let restored = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+            log::info!("WebGL context restored");
+            context_lost.set(false);
+            context_restored.set(true);
+        });
+        canvas
+            .add_event_listener_with_callback("webglcontextlost", lost.as_ref().unchecked_ref())?;
+        canvas.add_event_listener_with_callback(
+            "webglcontextrestored",
+            restored.as_ref().unchecked_ref(),
+        )?;
+        Ok(Self {
+            canvas,
+            lost,
+            restored,
+        })
+    }
+}
+
+impl Drop for ContextListeners {
+    fn drop(&mut self) {
+        let _ = self.canvas.remove_event_listener_with_callback(
+            "webglcontextlost",
+            self.lost.as_ref().unchecked_ref(),
+        );
+        let _ = self.canvas.remove_event_listener_with_callback(
+            "webglcontextrestored",
+            self.restored.as_ref().unchecked_ref(),
+        );
+    }
 }
 
 impl Renderer {
@@ -72,9 +154,26 @@ impl Renderer {
         context.enable(gl::BLEND);
         context.blend_func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
 
+        // getExtension returns null while the context is lost, so keep it
+        // around for restoring.
+        let lose_context = context
+            .get_extension("WEBGL_lose_context")
+            .ok()
+            .flatten()
+            .and_then(|ext| ext.dyn_into::<WebglLoseContext>().ok());
+
+        let context_lost = Rc::new(Cell::new(false));
+        let context_restored = Rc::new(Cell::new(false));
+        let context_listeners = ContextListeners::new(
+            canvas.clone(),
+            context_lost.clone(),
+            context_restored.clone(),
+        )?;
+
         Ok(Renderer {
             canvas,
             context: context.clone(),
+            starfield_renderer: StarfieldRenderer::new(context.clone())?,
             grid_renderer: GridRenderer::new(context.clone())?,
             line_renderer: LineRenderer::new(context.clone())?,
             ship_renderer: ShipRenderer::new(context.clone())?,
@@ -88,11 +187,55 @@ impl Renderer {
             base_line_width: 1.0,
             debug: false,
             picked_ship: None,
+            ghost: None,
             blur_enabled: true,
             nlips_enabled: false,
+            ship_vectors_enabled: true,
+            thrust_plumes_enabled: true,
+            grid_enabled: true,
+            minimap_enabled: false,
+            reduce_motion: false,
+            world_size: 0.0,
+            context_lost,
+            context_restored,
+            lose_context,
+            _context_listeners: context_listeners,
         })
     }
 
+    /// Recreates every GPU resource after the WebGL context is restored.
+    pub fn reinitialize(&mut self) -> Result<(), JsValue> {
+        let context = self.context.clone();
+        context.enable(gl::BLEND);
+        context.blend_func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+        self.starfield_renderer = StarfieldRenderer::new(context.clone())?;
+        self.grid_renderer = GridRenderer::new(context.clone())?;
+        self.line_renderer = LineRenderer::new(context.clone())?;
+        self.ship_renderer = ShipRenderer::new(context.clone())?;
+        self.bullet_renderer = BulletRenderer::new(context.clone())?;
+        self.particle_renderer = ParticleRenderer::new(context.clone())?;
+        self.trail_renderer = TrailRenderer::new(context.clone())?;
+        self.text_renderer = TextRenderer::new(context.clone())?;
+        self.flare_renderer = FlareRenderer::new(context.clone())?;
+        self.blur = Blur::new(context)?;
+        Ok(())
+    }
+
+    /// Whether the WebGL context is currently lost. Nothing is drawn until
+    /// the browser restores it.
+    pub fn is_context_lost(&self) -> bool {
+        self.context_lost.get()
+    }
+
+    /// Loses or restores the context on purpose, for testing recovery.
+    pub fn toggle_context_loss(&self) {
+        match self.lose_context.as_ref() {
+            Some(ext) if self.is_context_lost() => ext.restore_context(),
+            Some(ext) => ext.lose_context(),
+            None => log::warn!("WEBGL_lose_context is not supported"),
+        }
+    }
+
     pub fn set_view(&mut self, zoom: f32, center: Point2<f32>) {
         let screen_width = self.context.drawing_buffer_width() as f32;
         let screen_height = self.context.drawing_buffer_height() as f32;
@@ -134,6 +277,15 @@ impl Renderer {
     }
 
     pub fn render(&mut self, camera_target: Point2<f32>, zoom: f32, snapshot: &Snapshot) {
+        if self.context_restored.replace(false) {
+            if let Err(e) = self.reinitialize() {
+                log::error!("Failed to reinitialize renderer: {:?}", e);
+            }
+        }
+        if self.is_context_lost() {
+            return;
+        }
+
         let dpr = gloo_utils::window().device_pixel_ratio();
         let new_width = (self.canvas.client_width() as f64 * dpr) as u32;
         let new_height = (self.canvas.client_height() as f64 * dpr) as u32;
@@ -156,6 +308,7 @@ impl Renderer {
         let screen_width = self.context.drawing_buffer_width();
         let screen_height = self.context.drawing_buffer_height();
         self.set_view(zoom, point![camera_target.x, camera_target.y]);
+        self.world_size = snapshot.world_size;
 
         self.grid_renderer
             .update_projection_matrix(&self.projection_matrix);
@@ -193,12 +346,28 @@ impl Renderer {
                     texts.extend(drawn_text.iter().cloned());
                 }
             }
-            self.text_renderer.upload(&self.projection_matrix, &texts)
+            self.text_renderer
+                .upload(&self.projection_matrix, &texts, &snapshot.scenario_labels)
         };
 
-        let scenario_line_drawset = self
-            .line_renderer
-            .upload(&self.projection_matrix, &snapshot.scenario_lines);
+        let pixel_size = (self.unproject(1, 0) - self.unproject(0, 0)).x;
+        let scenario_line_drawset = {
+            let mut lines = snapshot.scenario_lines.clone();
+            lines.extend(self.ghost_lines());
+            lines.extend(shield_lines(snapshot));
+            lines.extend(laser_lines(snapshot));
+            if self.ship_vectors_enabled {
+                lines.extend(self.ship_vector_lines(snapshot));
+            }
+            if self.thrust_plumes_enabled {
+                lines.extend(thrust_plume_lines(snapshot));
+            }
+            if self.grid_enabled {
+                lines.extend(world_boundary_lines(snapshot.world_size));
+            }
+            self.line_renderer
+                .upload(&self.projection_matrix, &lines, pixel_size)
+        };
 
         let debug_line_drawset = {
             let mut lines: Vec<Line> = Vec::new();
@@ -213,7 +382,23 @@ impl Renderer {
                     }
                 }
             }
-            self.line_renderer.upload(&self.projection_matrix, &lines)
+            lines.extend(self.picked_ship_ring(snapshot));
+            self.line_renderer
+                .upload(&self.projection_matrix, &lines, pixel_size)
+        };
+
+        let minimap_drawset = if self.minimap_enabled {
+            let view = (
+                self.unproject(0, 0),
+                self.unproject(self.canvas.client_width(), self.canvas.client_height()),
+            );
+            Some(self.line_renderer.upload(
+                &minimap::projection_matrix(snapshot.world_size),
+                &minimap::lines(snapshot, view),
+                snapshot.world_size / minimap::SIZE as f64,
+            ))
+        } else {
+            None
         };
 
         self.context.viewport(0, 0, screen_width, screen_height);
@@ -229,11 +414,15 @@ impl Renderer {
             // Render to blur source texture
             self.context.clear_color(0.0, 0.0, 0.0, 0.0);
             self.context.clear(gl::COLOR_BUFFER_BIT);
-            self.trail_renderer.draw(snapshot.time as f32, 2.0);
+            if !self.reduce_motion {
+                self.trail_renderer.draw(snapshot.time as f32, 2.0);
+            }
             self.flare_renderer.draw(&flare_drawset);
             self.bullet_renderer.draw(&blur_bullet_drawset);
-            self.particle_renderer
-                .draw(&particle_drawset, 10.0 * self.base_line_width);
+            if !self.reduce_motion {
+                self.particle_renderer
+                    .draw(&particle_drawset, 10.0 * self.base_line_width);
+            }
             self.line_renderer.draw(&scenario_line_drawset);
             self.ship_renderer.draw(&ship_drawset);
             self.blur.finish();
@@ -243,24 +432,165 @@ impl Renderer {
             // Render non-blurred graphics
             self.context.clear_color(0.0, 0.0, 0.0, 0.0);
             self.context.clear(gl::COLOR_BUFFER_BIT);
-            self.grid_renderer
-                .draw(zoom, camera_target, snapshot.world_size);
+            self.starfield_renderer.draw(zoom, camera_target);
+            if self.grid_enabled {
+                self.grid_renderer
+                    .draw(zoom, camera_target, snapshot.world_size);
+            }
             if self.blur_enabled {
                 self.blur.draw();
             }
-            self.trail_renderer.draw(snapshot.time as f32, 2.0);
+            if !self.reduce_motion {
+                self.trail_renderer.draw(snapshot.time as f32, 2.0);
+            }
             self.flare_renderer.draw(&flare_drawset);
             self.bullet_renderer.draw(&bullet_drawset);
-            self.particle_renderer
-                .draw(&particle_drawset, 5.0 * self.base_line_width);
+            if !self.reduce_motion {
+                self.particle_renderer
+                    .draw(&particle_drawset, 5.0 * self.base_line_width);
+            }
             self.line_renderer.draw(&scenario_line_drawset);
             self.line_renderer.draw(&debug_line_drawset);
             self.ship_renderer.draw(&ship_drawset);
             self.text_renderer.draw(&text_drawset);
         }
+
+        if let Some(minimap_drawset) = minimap_drawset {
+            let dpr = gloo_utils::window().device_pixel_ratio();
+            let size = (minimap::SIZE as f64 * dpr) as i32;
+            let margin = (minimap::MARGIN as f64 * dpr) as i32;
+            let x = screen_width - margin - size;
+            let y = margin;
+            self.context.enable(gl::SCISSOR_TEST);
+            self.context.scissor(x, y, size, size);
+            self.context.clear_color(0.0, 0.0, 0.0, 0.8);
+            self.context.clear(gl::COLOR_BUFFER_BIT);
+            self.context.disable(gl::SCISSOR_TEST);
+            self.context.viewport(x, y, size, size);
+            self.line_renderer.draw(&minimap_drawset);
+            self.context.viewport(0, 0, screen_width, screen_height);
+        }
+    }
+
+    /// Returns the world position under a point on the minimap, given in CSS
+    /// pixels relative to the canvas.
+    pub fn minimap_to_world(&self, x: i32, y: i32) -> Option<Point2<f64>> {
+        if !self.minimap_enabled || self.world_size <= 0.0 {
+            return None;
+        }
+        minimap::to_world(
+            (self.canvas.client_width(), self.canvas.client_height()),
+            self.world_size,
+            x,
+            y,
+        )
+    }
+
+    pub fn set_ghost(&mut self, ghost: Option<GhostShip>) {
+        self.ghost = ghost;
+    }
+
+    fn ghost_lines(&self) -> Vec<Line> {
+        let ghost = match self.ghost {
+            Some(ghost) => ghost,
+            None => return vec![],
+        };
+        let mut color = ShipRenderer::team_color(0);
+        color.w = 0.3;
+        let rotation = nalgebra::Rotation2::new(ghost.heading);
+        let points: Vec<Point2<f64>> = model::load(ghost.class)
+            .iter()
+            .map(|v| ghost.position + rotation * vector![v.x as f64, v.y as f64])
+            .collect();
+        (0..points.len())
+            .map(|i| Line {
+                a: points[i],
+                b: points[(i + 1) % points.len()],
+                color,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn picked_ship_ring(&self, snapshot: &Snapshot) -> Vec<Line> {
+        const SEGMENTS: usize = 32;
+        let ship = match self
+            .picked_ship
+            .and_then(|id| snapshot.ships.iter().find(|ship| ship.id == id))
+        {
+            Some(ship) => ship,
+            None => return vec![],
+        };
+        let pixel_size = (self.unproject(1, 0) - self.unproject(0, 0)).x;
+        let radius = model::radius(ship.class) as f64 * 1.5 + 8.0 * pixel_size;
+        let mut color = ShipRenderer::team_color(ship.team);
+        color.w = 0.6;
+        let point = |i: usize| {
+            let angle = i as f64 * std::f64::consts::TAU / SEGMENTS as f64;
+            ship.position + vector![angle.cos(), angle.sin()] * radius
+        };
+        (0..SEGMENTS)
+            .map(|i| Line {
+                a: point(i),
+                b: point(i + 1),
+                color,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    // Heading lines and velocity arrows for the player's ships.
+    fn ship_vector_lines(&self, snapshot: &Snapshot) -> Vec<Line> {
+        const VELOCITY_SCALE: f64 = 1.0;
+        const MAX_VELOCITY_PIXELS: f64 = 100.0;
+        let pixel_size = (self.unproject(1, 0) - self.unproject(0, 0)).x;
+        let mut lines = vec![];
+        for ship in snapshot.ships.iter() {
+            if ship.team != 0
+                || matches!(
+                    ship.class,
+                    ShipClass::Missile | ShipClass::Torpedo | ShipClass::Asteroid { .. }
+                )
+            {
+                continue;
+            }
+            let mut color = ShipRenderer::team_color(ship.team);
+            color.w = 0.4;
+
+            let radius = model::radius(ship.class) as f64;
+            let heading = vector![ship.heading.cos(), ship.heading.sin()];
+            lines.push(Line {
+                a: ship.position + heading * radius,
+                b: ship.position + heading * radius * 2.0,
+                color,
+                ..Default::default()
+            });
+
+            let max_length = MAX_VELOCITY_PIXELS * pixel_size;
+            let v = ship.velocity * VELOCITY_SCALE;
+            if v.magnitude() < pixel_size {
+                continue;
+            }
+            let v = if v.magnitude() > max_length {
+                v.normalize() * max_length
+            } else {
+                v
+            };
+            lines.push(Line {
+                a: ship.position,
+                b: ship.position + v,
+                color,
+                style: LineStyle::Arrow,
+                ..Default::default()
+            });
+        }
+        lines
     }
 
     pub fn update(&mut self, snapshot: &Snapshot) {
+        if self.reduce_motion {
+            return;
+        }
         self.particle_renderer.update(snapshot);
         self.trail_renderer.update(snapshot);
     }
@@ -280,4 +610,138 @@ impl Renderer {
     pub fn get_nlips(&self) -> bool {
         self.nlips_enabled
     }
+
+    pub fn set_ship_vectors(&mut self, ship_vectors: bool) {
+        self.ship_vectors_enabled = ship_vectors;
+    }
+
+    pub fn get_ship_vectors(&self) -> bool {
+        self.ship_vectors_enabled
+    }
+
+    pub fn set_thrust_plumes(&mut self, thrust_plumes: bool) {
+        self.thrust_plumes_enabled = thrust_plumes;
+    }
+
+    pub fn get_thrust_plumes(&self) -> bool {
+        self.thrust_plumes_enabled
+    }
+
+    pub fn set_grid(&mut self, grid: bool) {
+        self.grid_enabled = grid;
+    }
+
+    pub fn get_grid(&self) -> bool {
+        self.grid_enabled
+    }
+
+    pub fn set_minimap(&mut self, minimap: bool) {
+        self.minimap_enabled = minimap;
+    }
+
+    pub fn get_minimap(&self) -> bool {
+        self.minimap_enabled
+    }
+
+    /// Turns off particles and trails.
+    pub fn set_reduce_motion(&mut self, reduce_motion: bool) {
+        self.reduce_motion = reduce_motion;
+    }
+}
+
+// A plume pointing away from each ship's applied acceleration, with length
+// proportional to the thrust. Drawn as layered lines getting hotter and
+// thinner towards the core.
+fn thrust_plume_lines(snapshot: &Snapshot) -> Vec<Line> {
+    const MIN_ACCELERATION: f64 = 1.0;
+    // Ship radii of plume per m/s² of acceleration.
+    const LENGTH_SCALE: f64 = 0.05;
+    const MAX_LENGTH: f64 = 4.0;
+    const LAYERS: [(f64, f32, [f32; 4]); 3] = [
+        (1.0, 6.0, [1.0, 0.3, 0.0, 0.3]),
+        (0.7, 3.0, [1.0, 0.7, 0.2, 0.6]),
+        (0.4, 1.5, [1.0, 1.0, 0.8, 0.9]),
+    ];
+    let mut lines = vec![];
+    for ship in snapshot.ships.iter() {
+        if matches!(ship.class, ShipClass::Asteroid { .. } | ShipClass::Planet) {
+            continue;
+        }
+        let magnitude = ship.acceleration.magnitude();
+        if magnitude < MIN_ACCELERATION {
+            continue;
+        }
+        let radius = model::radius(ship.class) as f64;
+        let dir = -ship.acceleration / magnitude;
+        let length = radius * (magnitude * LENGTH_SCALE).min(MAX_LENGTH);
+        let start = ship.position + dir * radius * 0.5;
+        for (fraction, width, color) in LAYERS {
+            lines.push(Line {
+                a: start,
+                b: start + dir * length * fraction,
+                color: color.into(),
+                width,
+                ..Default::default()
+            });
+        }
+    }
+    lines
+}
+
+// Beams fired since the last snapshot, shown for a single frame.
+fn laser_lines(snapshot: &Snapshot) -> Vec<Line> {
+    snapshot
+        .events
+        .iter()
+        .filter_map(|event| match *event {
+            SimEvent::LaserFired { start, end } => Some(Line {
+                a: start.into(),
+                b: end.into(),
+                color: vector![1.0, 0.3, 0.3, 1.0],
+                width: 3.0,
+                ..Default::default()
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+// A faint ring around shielded ships that fades as the shield drains.
+fn shield_lines(snapshot: &Snapshot) -> Vec<Line> {
+    const SEGMENTS: usize = 32;
+    const MAX_ALPHA: f32 = 0.4;
+    let mut lines = vec![];
+    for ship in snapshot.ships.iter() {
+        let fraction = match ship.shield {
+            Some(x) if x > 0.0 => x,
+            _ => continue,
+        };
+        let radius = model::radius(ship.class) as f64 * 1.3;
+        let color = vector![0.3, 0.6, 1.0, fraction as f32 * MAX_ALPHA];
+        let point = |i: usize| {
+            let angle = i as f64 * std::f64::consts::TAU / SEGMENTS as f64;
+            ship.position + vector![angle.cos(), angle.sin()] * radius
+        };
+        lines.extend((0..SEGMENTS).map(|i| Line {
+            a: point(i),
+            b: point(i + 1),
+            color,
+            ..Default::default()
+        }));
+    }
+    lines
+}
+
+fn world_boundary_lines(world_size: f64) -> Vec<Line> {
+    let h = world_size * 0.5;
+    let color = vector![0.0, 0.6, 0.0, 1.0];
+    let corners = [point![-h, -h], point![h, -h], point![h, h], point![-h, h]];
+    (0..corners.len())
+        .map(|i| Line {
+            a: corners[i],
+            b: corners[(i + 1) % corners.len()],
+            color,
+            ..Default::default()
+        })
+        .collect()
 }
@@ -1,7 +1,7 @@
 use super::{buffer_arena, glutil};
 use glutil::VertexAttribBuilder;
-use nalgebra::{vector, Matrix4, Vector4};
-use oort_simulator::simulation::Line;
+use nalgebra::{vector, Matrix4, Point2, Vector2, Vector4};
+use oort_simulator::simulation::{Line, LineStyle};
 use wasm_bindgen::prelude::*;
 use web_sys::{WebGl2RenderingContext, WebGlProgram, WebGlUniformLocation, WebGlVertexArrayObject};
 use WebGl2RenderingContext as gl;
@@ -19,16 +19,23 @@ pub struct DrawSet {
     draws: Vec<Draw>,
 }
 
+// Written to the buffer arena right before it's drawn. Uploading every draw
+// in `upload` would let the arena wrap around and overwrite earlier draws
+// when a frame has more lines than fit in one buffer.
 pub struct Draw {
-    num_vertices: usize,
-    attribs_token: buffer_arena::Token,
+    attribs: Vec<Attribs>,
 }
 
 struct Attribs {
     vertex: Vector4<f32>,
     color: Vector4<f32>,
+    // Distance along the line, dash length and dash period, in pixels.
+    dash: Vector4<f32>,
 }
 
+// Bounded by the buffer arena size, 9 vertices per line.
+const MAX_LINES_PER_DRAW: usize = 2000;
+
 impl LineRenderer {
     pub fn new(context: WebGl2RenderingContext) -> Result<Self, JsValue> {
         let vert_shader = glutil::compile_shader(
@@ -38,10 +45,13 @@ impl LineRenderer {
 uniform mat4 transform;
 layout(location = 0) in vec4 vertex;
 layout(location = 1) in vec4 color;
+layout(location = 2) in vec4 dash;
 out vec4 varying_color;
+out vec3 varying_dash;
 void main() {
     gl_Position = transform * vertex;
     varying_color = color;
+    varying_dash = dash.xyz;
 }
     "#,
         )?;
@@ -51,8 +61,12 @@ void main() {
             r#"#version 300 es
 precision mediump float;
 in vec4 varying_color;
+in vec3 varying_dash;
 out vec4 fragmentColor;
 void main() {
+    if (varying_dash.z > 0.0 && mod(varying_dash.x, varying_dash.z) > varying_dash.y) {
+        discard;
+    }
     fragmentColor = varying_color;
 }
     "#,
@@ -83,28 +97,23 @@ void main() {
         })
     }
 
-    pub fn upload(&mut self, projection_matrix: &Matrix4<f32>, lines: &[Line]) -> DrawSet {
+    /// Expands the lines into screen-space quads. `pixel_size` is the size of
+    /// a CSS pixel in world units.
+    pub fn upload(
+        &mut self,
+        projection_matrix: &Matrix4<f32>,
+        lines: &[Line],
+        pixel_size: f64,
+    ) -> DrawSet {
         let mut draws = vec![];
-        for lines in lines.chunks(1000) {
+        for lines in lines.chunks(MAX_LINES_PER_DRAW) {
             let mut attribs = vec![];
-            attribs.reserve(2 * lines.len());
+            attribs.reserve(9 * lines.len());
             for line in lines {
-                for position in [line.a, line.b] {
-                    let p = position.coords.cast();
-                    let mut color = line.color;
-                    color.w *= 0.5; // Will be drawn twice
-                    attribs.push(Attribs {
-                        vertex: vector![p.x, p.y, 0.0, 1.0],
-                        color,
-                    });
-                }
+                push_line(&mut attribs, line, pixel_size);
             }
 
-            let attribs_token = self.buffer_arena.write(&attribs);
-            draws.push(Draw {
-                num_vertices: attribs.len(),
-                attribs_token,
-            });
+            draws.push(Draw { attribs });
         }
         DrawSet {
             projection_matrix: *projection_matrix,
@@ -120,35 +129,80 @@ void main() {
         self.context.use_program(Some(&self.program));
         self.context.bind_vertex_array(Some(&self.vao));
 
-        let mut line_width = 1.0;
-
-        for _ in 0..2 {
-            self.context.line_width(line_width);
-
-            self.context.uniform_matrix4fv_with_f32_array(
-                Some(&self.transform_loc),
-                false,
-                drawset.projection_matrix.data.as_slice(),
-            );
-
-            for draw in &drawset.draws {
-                let vab = VertexAttribBuilder::new(&self.context).data_token(&draw.attribs_token);
-                vab.index(0)
-                    .size(4)
-                    .offset(offset_of!(Attribs, vertex))
-                    .build();
-                vab.index(1)
-                    .size(4)
-                    .offset(offset_of!(Attribs, color))
-                    .build();
+        self.context.uniform_matrix4fv_with_f32_array(
+            Some(&self.transform_loc),
+            false,
+            drawset.projection_matrix.data.as_slice(),
+        );
+
+        for draw in &drawset.draws {
+            let attribs_token = self.buffer_arena.write(&draw.attribs);
+            let vab = VertexAttribBuilder::new(&self.context).data_token(&attribs_token);
+            vab.index(0)
+                .size(4)
+                .offset(offset_of!(Attribs, vertex))
+                .build();
+            vab.index(1)
+                .size(4)
+                .offset(offset_of!(Attribs, color))
+                .build();
+            vab.index(2)
+                .size(4)
+                .offset(offset_of!(Attribs, dash))
+                .build();
+
+            self.context
+                .draw_arrays(gl::TRIANGLES, 0, draw.attribs.len() as i32);
+        }
 
-                self.context
-                    .draw_arrays(gl::LINES, 0, draw.num_vertices as i32);
+        self.context.bind_vertex_array(None);
+    }
+}
 
-                line_width *= 2.0;
-            }
+fn push_line(attribs: &mut Vec<Attribs>, line: &Line, pixel_size: f64) {
+    let delta = line.b - line.a;
+    let length = delta.magnitude();
+    if length <= 0.0 {
+        return;
+    }
+    let dir = delta / length;
+    let normal = vector![-dir.y, dir.x];
+    let half_width = 0.5 * line.width.max(1.0) as f64 * pixel_size;
+
+    let (dash, period) = match line.style {
+        LineStyle::Dashed { dash, gap } => (dash, dash + gap),
+        _ => (0.0, 0.0),
+    };
+    let mut shaft_end = line.b;
+    if line.style == LineStyle::Arrow {
+        let head_length = (4.0 * line.width.max(2.0) as f64 * pixel_size).min(length);
+        let base = line.b - dir * head_length;
+        let side = normal * head_length * 0.5;
+        for p in [line.b, base + side, base - side] {
+            attribs.push(vertex(p, line.color, 0.0, 0.0, 0.0));
         }
+        shaft_end = base;
+    }
 
-        self.context.bind_vertex_array(None);
+    let end_distance = ((shaft_end - line.a).magnitude() / pixel_size) as f32;
+    let offset = normal * half_width;
+    let corners = [
+        (line.a - offset, 0.0),
+        (line.a + offset, 0.0),
+        (shaft_end + offset, end_distance),
+        (shaft_end - offset, end_distance),
+    ];
+    for i in [0, 1, 2, 0, 2, 3] {
+        let (p, distance) = corners[i];
+        attribs.push(vertex(p, line.color, distance, dash, period));
+    }
+}
+
+fn vertex(p: Point2<f64>, color: Vector4<f32>, distance: f32, dash: f32, period: f32) -> Attribs {
+    let p: Vector2<f32> = p.coords.cast();
+    Attribs {
+        vertex: vector![p.x, p.y, 0.0, 1.0],
+        color,
+        dash: vector![distance, dash, period, 0.0],
     }
 }
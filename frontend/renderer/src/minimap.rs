@@ -0,0 +1,86 @@
+use crate::ship_renderer::ShipRenderer;
+use nalgebra::{point, vector, Matrix4, Point2};
+use oort_simulator::ship::ShipClass;
+use oort_simulator::simulation::Line;
+use oort_simulator::snapshot::Snapshot;
+
+// In CSS pixels, measured from the bottom-right corner of the canvas.
+pub const SIZE: i32 = 200;
+pub const MARGIN: i32 = 10;
+
+// Bullets are subsampled above this to keep the line buffer small.
+const MAX_BULLETS: usize = 1000;
+
+pub fn projection_matrix(world_size: f64) -> Matrix4<f32> {
+    let h = world_size as f32 * 0.5;
+    Matrix4::new_orthographic(-h, h, -h, h, -1.0, 1.0)
+}
+
+/// Draws the world boundary, a blip for each ship, dim dots for bullets, and
+/// a rectangle for the area visible in the main view.
+pub fn lines(snapshot: &Snapshot, view: (Point2<f64>, Point2<f64>)) -> Vec<Line> {
+    let world_size = snapshot.world_size;
+    let mut lines = super::world_boundary_lines(world_size);
+
+    let bullet_color = vector![0.6, 0.6, 0.6, 0.3];
+    let step = (snapshot.bullets.len() / MAX_BULLETS).max(1);
+    for bullet in snapshot.bullets.iter().step_by(step) {
+        lines.extend(cross(bullet.position, world_size * 0.002, bullet_color));
+    }
+
+    for ship in snapshot.ships.iter() {
+        let size = match ship.class {
+            ShipClass::Missile | ShipClass::Torpedo => 0.004,
+            ShipClass::Frigate | ShipClass::Cruiser => 0.015,
+            _ => 0.008,
+        };
+        lines.extend(cross(
+            ship.position,
+            world_size * size,
+            ShipRenderer::team_color(ship.team),
+        ));
+    }
+
+    let (a, b) = view;
+    let color = vector![1.0, 1.0, 1.0, 0.8];
+    let corners = [a, point![b.x, a.y], b, point![a.x, b.y]];
+    lines.extend((0..corners.len()).map(|i| Line {
+        a: corners[i],
+        b: corners[(i + 1) % corners.len()],
+        color,
+        ..Default::default()
+    }));
+
+    lines
+}
+
+fn cross(p: Point2<f64>, r: f64, color: nalgebra::Vector4<f32>) -> [Line; 2] {
+    [
+        Line {
+            a: p + vector![-r, 0.0],
+            b: p + vector![r, 0.0],
+            color,
+            ..Default::default()
+        },
+        Line {
+            a: p + vector![0.0, -r],
+            b: p + vector![0.0, r],
+            color,
+            ..Default::default()
+        },
+    ]
+}
+
+/// Converts a position in CSS pixels relative to the canvas into a world
+/// position, if it's inside the minimap.
+pub fn to_world(canvas_size: (i32, i32), world_size: f64, x: i32, y: i32) -> Option<Point2<f64>> {
+    let left = canvas_size.0 - MARGIN - SIZE;
+    let top = canvas_size.1 - MARGIN - SIZE;
+    if x < left || x > left + SIZE || y < top || y > top + SIZE {
+        return None;
+    }
+    let fx = (x - left) as f64 / SIZE as f64;
+    let fy = (y - top) as f64 / SIZE as f64;
+    let h = world_size * 0.5;
+    Some(point![-h + fx * world_size, h - fy * world_size])
+}
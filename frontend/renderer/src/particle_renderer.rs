@@ -2,13 +2,18 @@ use crate::geometry;
 
 use super::{buffer_arena, glutil};
 use glutil::VertexAttribBuilder;
-use nalgebra::{vector, Matrix4, Vector2, Vector4};
+use nalgebra::{vector, Matrix4, Point2, Vector2, Vector4};
+use oort_simulator::model;
+use oort_simulator::ship::ShipClass;
+use oort_simulator::simulation::SimEvent;
 use oort_simulator::snapshot::Snapshot;
 use wasm_bindgen::prelude::*;
 use web_sys::{WebGl2RenderingContext, WebGlProgram, WebGlUniformLocation, WebGlVertexArrayObject};
 use WebGl2RenderingContext as gl;
 
-const MAX_PARTICLES: usize = 1000;
+const MAX_PARTICLES: usize = 4000;
+const MIN_EXPLOSION_PARTICLES: usize = 30;
+const MAX_EXPLOSION_PARTICLES: usize = 80;
 
 pub struct ParticleRenderer {
     context: WebGl2RenderingContext,
@@ -21,6 +26,10 @@ pub struct ParticleRenderer {
     next_particle_index: usize,
     max_particles_seen: usize,
     vao: WebGlVertexArrayObject,
+    /// The (nonce, tick) of the last snapshot whose explosions were added.
+    /// The same snapshot is drawn for several frames.
+    last_snapshot: Option<(u32, u32)>,
+    rng_state: u32,
 }
 
 #[repr(C, packed)]
@@ -129,6 +138,8 @@ void main() {
             next_particle_index: 0,
             max_particles_seen: MAX_PARTICLES,
             vao,
+            last_snapshot: None,
+            rng_state: 0x9e3779b9,
         })
     }
 
@@ -154,6 +165,76 @@ void main() {
                 creation_time: snapshot.time as f32,
             });
         }
+        self.add_explosions(snapshot);
+    }
+
+    /// Spawns a burst of particles for each ship destroyed since the
+    /// previous snapshot. Ships removed by the scenario or the sandbox don't
+    /// explode.
+    fn add_explosions(&mut self, snapshot: &Snapshot) {
+        let key = (snapshot.nonce, snapshot.tick);
+        if self.last_snapshot == Some(key) {
+            return;
+        }
+        self.last_snapshot = Some(key);
+
+        for event in snapshot.events.iter() {
+            if let SimEvent::Explosion {
+                position,
+                velocity,
+                class,
+                ..
+            } = *event
+            {
+                self.add_explosion(
+                    Point2::from(position.cast()),
+                    velocity.cast(),
+                    class,
+                    snapshot.time as f32,
+                );
+            }
+        }
+    }
+
+    fn add_explosion(
+        &mut self,
+        position: Point2<f32>,
+        velocity: Vector2<f32>,
+        class: ShipClass,
+        creation_time: f32,
+    ) {
+        let radius = model::radius(class);
+        for _ in 0..explosion_size(radius) {
+            let angle = self.random(0.0, std::f32::consts::TAU);
+            let speed = self.random(0.2, 1.0) * (radius * 3.0 + 50.0);
+            let direction = vector![angle.cos(), angle.sin()];
+            let offset = direction * self.random(0.0, radius * 0.5);
+            let color = vector![
+                1.0,
+                self.random(0.4, 0.9),
+                self.random(0.1, 0.4),
+                self.random(0.5, 1.0)
+            ];
+            let lifetime = self.random(0.5, 1.5);
+            self.add_particle(Particle {
+                position: position.coords + offset,
+                velocity: velocity + direction * speed,
+                color,
+                lifetime,
+                creation_time,
+            });
+        }
+    }
+
+    /// Returns a uniformly distributed value in `[min, max)`. Explosions
+    /// don't need to be reproducible, so a small xorshift is enough.
+    fn random(&mut self, min: f32, max: f32) -> f32 {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.rng_state = x;
+        min + (max - min) * (x >> 8) as f32 / (1 << 24) as f32
     }
 
     pub fn upload(&mut self, projection_matrix: &Matrix4<f32>, snapshot: &Snapshot) -> DrawSet {
@@ -185,6 +266,7 @@ void main() {
 
         self.context.use_program(Some(&self.program));
         self.context.bind_vertex_array(Some(&self.vao));
+        self.context.blend_func(gl::SRC_ALPHA, gl::ONE);
 
         self.context
             .uniform1f(Some(&self.current_time_loc), drawset.current_time);
@@ -238,5 +320,29 @@ void main() {
         );
 
         self.context.bind_vertex_array(None);
+        self.context
+            .blend_func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+    }
+}
+
+/// Number of particles in a ship's explosion, growing with its size.
+fn explosion_size(radius: f32) -> usize {
+    (radius as usize).clamp(MIN_EXPLOSION_PARTICLES, MAX_EXPLOSION_PARTICLES)
+}
+
+#[cfg(test)]
+mod test {
+    use super::explosion_size;
+    use oort_simulator::model;
+    use oort_simulator::ship::ShipClass;
+
+    #[test]
+    fn test_explosion_size() {
+        let missile = explosion_size(model::radius(ShipClass::Missile));
+        let fighter = explosion_size(model::radius(ShipClass::Fighter));
+        let cruiser = explosion_size(model::radius(ShipClass::Cruiser));
+        assert_eq!(missile, 30);
+        assert!(fighter <= cruiser);
+        assert_eq!(explosion_size(1e6), 80);
     }
 }
@@ -4,10 +4,15 @@ use nalgebra::{vector, Matrix4, Vector4};
 use oort_simulator::model;
 use oort_simulator::ship::ShipClass;
 use oort_simulator::snapshot::{ShipSnapshot, Snapshot};
+use oort_simulator::topology::{self, WorldTopology};
 use wasm_bindgen::prelude::*;
 use web_sys::{WebGl2RenderingContext, WebGlProgram, WebGlUniformLocation, WebGlVertexArrayObject};
 use WebGl2RenderingContext as gl;
 
+// Ships this close to the edge of a wrapping world are also drawn on the
+// opposite side.
+const SEAM_MARGIN: f64 = 500.0;
+
 pub struct ShipRenderer {
     context: WebGl2RenderingContext,
     program: WebGlProgram,
@@ -116,10 +121,20 @@ void main() {
                 .get_mut(&ship.class)
                 .unwrap()
                 .push((*ship).clone());
+            if snapshot.topology == WorldTopology::Wrapping {
+                for offset in
+                    topology::seam_offsets(ship.position, snapshot.world_size, SEAM_MARGIN)
+                {
+                    let mut copy = ship.clone();
+                    copy.position += offset;
+                    ships_by_class.get_mut(&ship.class).unwrap().push(copy);
+                }
+            }
         }
 
         let mut draws = vec![];
 
+        let view = geometry::Rect::visible(projection_matrix);
         let zoom_factor = 2e-3 / zoom;
 
         for (&class, ships) in ships_by_class.iter() {
@@ -149,6 +164,9 @@ void main() {
                 attribs.reserve(ships.len());
                 for ship in ships.iter() {
                     let p = ship.position.coords.cast::<f32>();
+                    if !view.intersects_circle(p.into(), radius * scale + base_line_width) {
+                        continue;
+                    }
                     let shielded = ship.active_abilities.contains(&oort_api::Ability::Shield);
                     let mut team_color = Self::team_color(ship.team);
                     if nlips_draw {
@@ -169,10 +187,13 @@ void main() {
                             * Matrix4::from_euler_angles(0.0, 0.0, ship.heading as f32),
                     });
                 }
+                if attribs.is_empty() {
+                    continue;
+                }
                 let attribs_token = self.buffer_arena.write(&attribs);
 
                 draws.push(Draw {
-                    num_instances: ships.len(),
+                    num_instances: attribs.len(),
                     vertices_token,
                     num_vertices,
                     attribs_token,
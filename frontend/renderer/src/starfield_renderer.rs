@@ -0,0 +1,171 @@
+use super::glutil;
+use nalgebra::{vector, Point2};
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext, WebGlBuffer, WebGlProgram, WebGlUniformLocation};
+use WebGl2RenderingContext as gl;
+
+const SEED: u32 = 0x5eed_1234;
+const FLOATS_PER_STAR: i32 = 3;
+
+struct Layer {
+    num_stars: i32,
+    // Fraction of a screen the stars move when the camera moves one screen.
+    parallax: f32,
+    point_size: f32,
+    brightness: f32,
+}
+
+const LAYERS: [Layer; 2] = [
+    Layer {
+        num_stars: 400,
+        parallax: 0.02,
+        point_size: 1.0,
+        brightness: 0.4,
+    },
+    Layer {
+        num_stars: 150,
+        parallax: 0.06,
+        point_size: 2.0,
+        brightness: 0.6,
+    },
+];
+
+pub struct StarfieldRenderer {
+    context: WebGl2RenderingContext,
+    program: WebGlProgram,
+    offset_loc: WebGlUniformLocation,
+    point_size_loc: WebGlUniformLocation,
+    brightness_loc: WebGlUniformLocation,
+    buffer: WebGlBuffer,
+}
+
+impl StarfieldRenderer {
+    pub fn new(context: WebGl2RenderingContext) -> Result<Self, JsValue> {
+        let vert_shader = glutil::compile_shader(
+            &context,
+            gl::VERTEX_SHADER,
+            r#"#version 300 es
+uniform vec2 offset;
+uniform float point_size;
+layout(location = 0) in vec3 star;
+out float varying_brightness;
+
+void main() {
+    gl_Position = vec4(fract(star.xy - offset) * 2.0 - 1.0, 0.0, 1.0);
+    gl_PointSize = point_size;
+    varying_brightness = star.z;
+}
+    "#,
+        )?;
+        let frag_shader = glutil::compile_shader(
+            &context,
+            gl::FRAGMENT_SHADER,
+            r#"#version 300 es
+precision mediump float;
+uniform float brightness;
+in float varying_brightness;
+out vec4 fragmentColor;
+
+void main() {
+    fragmentColor = vec4(1.0, 1.0, 1.0, brightness * varying_brightness);
+}
+    "#,
+        )?;
+        let program = glutil::link_program(&context, &vert_shader, &frag_shader)?;
+
+        let offset_loc = context
+            .get_uniform_location(&program, "offset")
+            .ok_or("did not find uniform")?;
+
+        let point_size_loc = context
+            .get_uniform_location(&program, "point_size")
+            .ok_or("did not find uniform")?;
+
+        let brightness_loc = context
+            .get_uniform_location(&program, "brightness")
+            .ok_or("did not find uniform")?;
+
+        // The stars never change, so upload them once.
+        let data = generate_stars();
+        let buffer = context.create_buffer().ok_or("failed to create buffer")?;
+        context.bind_buffer(gl::ARRAY_BUFFER, Some(&buffer));
+        unsafe {
+            // See TrailRenderer::write_data for why this is unsafe. No
+            // allocations happen while the view is alive.
+            let view = js_sys::Float32Array::view(&data);
+            context.buffer_data_with_array_buffer_view(gl::ARRAY_BUFFER, &view, gl::STATIC_DRAW);
+        }
+
+        assert_eq!(context.get_error(), gl::NO_ERROR);
+
+        Ok(Self {
+            context,
+            program,
+            offset_loc,
+            point_size_loc,
+            brightness_loc,
+            buffer,
+        })
+    }
+
+    pub fn draw(&mut self, zoom: f32, camera_target: Point2<f32>) {
+        self.context.use_program(Some(&self.program));
+
+        self.context
+            .bind_buffer(gl::ARRAY_BUFFER, Some(&self.buffer));
+        self.context.vertex_attrib_pointer_with_i32(
+            /*indx=*/ 0,
+            /*size=*/ 3,
+            /*type_=*/ gl::FLOAT,
+            /*normalized=*/ false,
+            /*stride=*/ FLOATS_PER_STAR * 4,
+            /*offset=*/ 0,
+        );
+        self.context.vertex_attrib_divisor(0, 0);
+        self.context.enable_vertex_attrib_array(0);
+
+        // Camera position in units of screens.
+        let screen_width = self.context.drawing_buffer_width() as f32;
+        let screen_height = self.context.drawing_buffer_height() as f32;
+        let screens = vector![
+            camera_target.x * zoom,
+            camera_target.y * zoom * screen_width / screen_height
+        ];
+
+        let mut first = 0;
+        for layer in LAYERS.iter() {
+            // Keep the offset small to avoid losing precision in the shader.
+            let offset = (screens * layer.parallax).map(|x| x.rem_euclid(1.0));
+            self.context
+                .uniform2fv_with_f32_array(Some(&self.offset_loc), offset.data.as_slice());
+            self.context
+                .uniform1f(Some(&self.point_size_loc), layer.point_size);
+            self.context
+                .uniform1f(Some(&self.brightness_loc), layer.brightness);
+            self.context.draw_arrays(gl::POINTS, first, layer.num_stars);
+            first += layer.num_stars;
+        }
+
+        self.context.disable_vertex_attrib_array(0);
+    }
+}
+
+/// Returns (x, y, brightness) for every star in every layer, in [0, 1).
+fn generate_stars() -> Vec<f32> {
+    let mut state = SEED;
+    let mut rand = move || {
+        // xorshift32
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (state >> 8) as f32 / (1 << 24) as f32
+    };
+    let num_stars: i32 = LAYERS.iter().map(|x| x.num_stars).sum();
+    let mut data = Vec::with_capacity((num_stars * FLOATS_PER_STAR) as usize);
+    for _ in 0..num_stars {
+        data.push(rand());
+        data.push(rand());
+        data.push(0.3 + 0.7 * rand());
+    }
+    data
+}
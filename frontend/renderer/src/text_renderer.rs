@@ -6,6 +6,7 @@ use image::EncodableLayout;
 use nalgebra::{point, vector, Matrix4, Vector2, Vector4};
 use oort_api::Text;
 use oort_simulator::color;
+use oort_simulator::simulation::Label;
 use wasm_bindgen::prelude::*;
 use web_sys::{
     WebGl2RenderingContext, WebGlProgram, WebGlTexture, WebGlUniformLocation,
@@ -38,6 +39,13 @@ pub struct DrawSet {
     screen_glyph_size: f32,
 }
 
+/// A run of glyphs starting at a world position.
+struct Span<'a> {
+    position: Vector2<f32>,
+    color: Vector4<f32>,
+    bytes: &'a [u8],
+}
+
 struct Attribs {
     position: Vector2<f32>,
     base_texcoord: Vector2<f32>,
@@ -157,7 +165,31 @@ void main() {
         })
     }
 
-    pub fn upload(&mut self, world_projection_matrix: &Matrix4<f32>, texts: &[Text]) -> DrawSet {
+    /// Uploads ship-drawn text and scenario labels. Both are laid out the
+    /// same way, starting at a world position and advancing in screen pixels.
+    pub fn upload(
+        &mut self,
+        world_projection_matrix: &Matrix4<f32>,
+        texts: &[Text],
+        labels: &[Label],
+    ) -> DrawSet {
+        let spans = texts
+            .iter()
+            .map(|text| Span {
+                position: vector![text.x as f32, text.y as f32],
+                color: color::from_u24(text.color),
+                bytes: &text.text[0..(text.length as usize).min(text.text.len())],
+            })
+            .chain(labels.iter().map(|label| Span {
+                position: label.position.coords.cast::<f32>(),
+                color: label.color,
+                bytes: label.text.as_bytes(),
+            }))
+            .collect::<Vec<_>>();
+        self.upload_spans(world_projection_matrix, &spans)
+    }
+
+    fn upload_spans(&mut self, world_projection_matrix: &Matrix4<f32>, spans: &[Span]) -> DrawSet {
         let screen_width = self.context.drawing_buffer_width() as f32;
         let screen_height = self.context.drawing_buffer_height() as f32;
 
@@ -174,7 +206,7 @@ void main() {
         let quad_vertices = geometry::triquad();
         let vertices_token = self.buffer_arena.write(&quad_vertices);
 
-        let num_glyphs: usize = texts.iter().map(|x| x.length as usize).sum();
+        let num_glyphs: usize = spans.iter().map(|x| x.bytes.len()).sum();
         let scale = 2.0;
         let screen_glyph_size = (FONT_GLYPH_SIZE - 1) as f32 * scale;
         let font_glyph_width = 1.0 / FONT_COLS as f32;
@@ -184,8 +216,8 @@ void main() {
 
         let mut attribs = vec![];
         attribs.reserve(num_glyphs);
-        for text in texts {
-            let worldpos = vector![text.x as f32, text.y as f32];
+        for span in spans {
+            let worldpos = span.position;
             let projected =
                 world_projection_matrix.transform_point(&point![worldpos.x, worldpos.y, 0.0]);
             let projected_pixels = vector![
@@ -193,9 +225,11 @@ void main() {
                 (projected.y + 1.0) * screen_height / 2.0
             ];
             let mut pos = vector![projected_pixels.x.floor(), projected_pixels.y.floor()];
-            let color = color::from_u24(text.color);
-            for i in 0..text.length {
-                let idx = (text.text[i as usize] as usize - 32).clamp(0, FONT_ROWS * FONT_COLS - 1);
+            let color = span.color;
+            for &byte in span.bytes {
+                let idx = (byte as usize)
+                    .saturating_sub(32)
+                    .min(FONT_ROWS * FONT_COLS - 1);
                 let row = FONT_ROWS - idx / FONT_COLS - 1;
                 let col = idx % FONT_COLS;
 
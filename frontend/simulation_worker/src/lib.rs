@@ -1,6 +1,6 @@
-use oort_simulator::scenario::{Status, MAX_TICKS};
-use oort_simulator::simulation::Code;
-use oort_simulator::simulation::Simulation;
+use oort_simulator::rounds::Session;
+use oort_simulator::scenario::sandbox::{self, SandboxCommand};
+use oort_simulator::simulation::{Code, SimEvent};
 use oort_simulator::snapshot::Snapshot;
 use serde::{Deserialize, Serialize};
 use yew_agent::{HandlerId, Private, WorkerLink};
@@ -12,22 +12,61 @@ pub enum Request {
         seed: u32,
         codes: Vec<Code>,
         nonce: u32,
+        practice: bool,
     },
     Snapshot {
         ticks: u32,
         nonce: u32,
     },
+    /// Spawns or clears entities. The change shows up in the next snapshot.
+    Sandbox {
+        command: SandboxCommand,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Response {
-    Snapshot { snapshot: Snapshot },
+    Snapshot {
+        #[cfg_attr(feature = "compress", serde(with = "compressed"))]
+        snapshot: Snapshot,
+    },
+}
+
+/// Snapshots are sent to the UI every tick and grow with the number of ships,
+/// so they're deflated before crossing the worker boundary. For the 1000
+/// asteroid "asteroid-stress" scenario, `test_snapshot_compression` prints
+/// the sizes.
+#[cfg(feature = "compress")]
+mod compressed {
+    use flate2::read::DeflateDecoder;
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+    use oort_simulator::snapshot::Snapshot;
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+    use std::io::{Read, Write};
+
+    pub fn serialize<S: Serializer>(snapshot: &Snapshot, serializer: S) -> Result<S::Ok, S::Error> {
+        use ser::Error;
+        let bytes = bincode::serialize(snapshot).map_err(S::Error::custom)?;
+        let mut e = DeflateEncoder::new(Vec::new(), Compression::fast());
+        e.write_all(&bytes).map_err(S::Error::custom)?;
+        e.finish().map_err(S::Error::custom)?.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Snapshot, D::Error> {
+        use de::Error;
+        let compressed = Vec::<u8>::deserialize(deserializer)?;
+        let mut bytes = Vec::new();
+        DeflateDecoder::new(compressed.as_slice())
+            .read_to_end(&mut bytes)
+            .map_err(D::Error::custom)?;
+        bincode::deserialize(&bytes).map_err(D::Error::custom)
+    }
 }
 
 pub struct SimAgent {
     link: WorkerLink<Self>,
-    sim: Option<Box<Simulation>>,
-    errored: bool,
+    state: SimState,
 }
 
 impl yew_agent::Worker for SimAgent {
@@ -39,49 +78,227 @@ impl yew_agent::Worker for SimAgent {
     fn create(link: WorkerLink<Self>) -> Self {
         Self {
             link,
-            sim: None,
-            errored: false,
+            state: SimState::default(),
         }
     }
 
     fn update(&mut self, _msg: Self::Message) {}
 
     fn handle_input(&mut self, request: Self::Input, who: HandlerId) {
+        if let Some(response) = self.state.handle(request) {
+            self.link.respond(who, response);
+        }
+    }
+
+    fn name_of_resource() -> &'static str {
+        "oort_simulation_worker.js"
+    }
+}
+
+#[derive(Default)]
+struct SimState {
+    session: Option<Session>,
+    errored: bool,
+}
+
+impl SimState {
+    /// Every snapshot is tagged with the nonce from the request so the UI can
+    /// drop responses to requests made before the most recent StartScenario.
+    fn handle(&mut self, request: Request) -> Option<Response> {
         match request {
             Request::StartScenario {
                 scenario_name,
                 seed,
                 codes,
                 nonce,
+                practice,
             } => {
-                self.sim = Some(Simulation::new(&scenario_name, seed, &codes));
-                let snapshot = self.sim().snapshot(nonce);
+                let mut session = Session::new(&scenario_name, seed, &codes);
+                session.set_practice(practice);
+                self.session = Some(session);
+                let snapshot = self.session().snapshot(nonce);
                 self.errored = !snapshot.errors.is_empty();
-                self.link.respond(who, Response::Snapshot { snapshot });
+                Some(Response::Snapshot { snapshot })
             }
             Request::Snapshot { ticks, nonce } => {
                 if self.errored {
-                    return;
+                    return None;
                 }
-                for _ in 0..ticks {
-                    if self.sim().status() == Status::Running && self.sim().tick() < MAX_TICKS {
-                        self.sim().step();
+                // A snapshot only carries the last tick's events, so keep
+                // the explosions from the ticks before it. Otherwise a ship
+                // destroyed in between vanishes without one.
+                let mut explosions = vec![];
+                for tick in 0..ticks {
+                    self.session().step();
+                    if tick + 1 < ticks {
+                        explosions.extend(
+                            self.session()
+                                .sim()
+                                .events()
+                                .sim_events
+                                .iter()
+                                .filter(|event| matches!(event, SimEvent::Explosion { .. }))
+                                .cloned(),
+                        );
                     }
                 }
-                let snapshot = self.sim().snapshot(nonce);
+                let mut snapshot = self.session().snapshot(nonce);
+                snapshot.events.extend(explosions);
                 self.errored = !snapshot.errors.is_empty();
-                self.link.respond(who, Response::Snapshot { snapshot });
+                Some(Response::Snapshot { snapshot })
             }
-        };
+            Request::Sandbox { command } => {
+                if let Some(session) = self.session.as_mut() {
+                    sandbox::apply(session.sim_mut(), &command);
+                }
+                None
+            }
+        }
     }
 
-    fn name_of_resource() -> &'static str {
-        "oort_simulation_worker.js"
+    fn session(&mut self) -> &mut Session {
+        self.session.as_mut().unwrap()
     }
 }
 
-impl SimAgent {
-    fn sim(&mut self) -> &mut Simulation {
-        self.sim.as_mut().unwrap()
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn start(state: &mut SimState, nonce: u32) -> Snapshot {
+        match state.handle(Request::StartScenario {
+            scenario_name: "test".to_string(),
+            seed: 0,
+            codes: vec![Code::None, Code::None],
+            nonce,
+            practice: false,
+        }) {
+            Some(Response::Snapshot { snapshot }) => snapshot,
+            None => panic!("expected a snapshot"),
+        }
+    }
+
+    fn step(state: &mut SimState, nonce: u32) -> Snapshot {
+        match state.handle(Request::Snapshot { ticks: 1, nonce }) {
+            Some(Response::Snapshot { snapshot }) => snapshot,
+            None => panic!("expected a snapshot"),
+        }
+    }
+
+    #[test]
+    fn test_scenario_switch() {
+        let mut state = SimState::default();
+
+        let snapshot = start(&mut state, 1);
+        assert_eq!((snapshot.nonce, snapshot.tick), (1, 0));
+        let snapshot = step(&mut state, 1);
+        assert_eq!((snapshot.nonce, snapshot.tick), (1, 1));
+
+        // A request sent before the switch is answered after it.
+        let snapshot = start(&mut state, 2);
+        assert_eq!((snapshot.nonce, snapshot.tick), (2, 0));
+        let stale = step(&mut state, 1);
+        assert_eq!(stale.nonce, 1);
+
+        let snapshot = step(&mut state, 2);
+        assert_eq!(snapshot.nonce, 2);
+        assert!(snapshot.tick > stale.tick);
+    }
+
+    #[test]
+    fn test_sandbox() {
+        use oort_simulator::scenario::prelude::Point2;
+        use oort_simulator::scenario::sandbox::SpawnKind;
+
+        let mut state = SimState::default();
+        assert!(state
+            .handle(Request::Sandbox {
+                command: SandboxCommand::Clear
+            })
+            .is_none());
+
+        state.handle(Request::StartScenario {
+            scenario_name: "sandbox".to_string(),
+            seed: 0,
+            codes: vec![Code::None, Code::None],
+            nonce: 0,
+            practice: false,
+        });
+        assert!(state
+            .handle(Request::Sandbox {
+                command: SandboxCommand::Spawn {
+                    kind: SpawnKind::Frigate,
+                    team: 1,
+                    position: Point2::new(1000.0, 0.0),
+                },
+            })
+            .is_none());
+        assert_eq!(step(&mut state, 0).ships.len(), 2);
+    }
+
+    #[test]
+    fn test_explosions_between_snapshots() {
+        use oort_simulator::scenario::prelude::Point2;
+        use oort_simulator::scenario::sandbox::SpawnKind;
+
+        let mut state = SimState::default();
+        state.handle(Request::StartScenario {
+            scenario_name: "sandbox".to_string(),
+            seed: 0,
+            codes: vec![Code::None, Code::None],
+            nonce: 0,
+            practice: false,
+        });
+        // The missile sits on top of the frigate and detonates on the next
+        // tick, long before the end of the request.
+        for (kind, team) in [(SpawnKind::Frigate, 0), (SpawnKind::Missile, 1)] {
+            state.handle(Request::Sandbox {
+                command: SandboxCommand::Spawn {
+                    kind,
+                    team,
+                    position: Point2::new(1000.0, 0.0),
+                },
+            });
+        }
+        let Some(Response::Snapshot { snapshot }) =
+            state.handle(Request::Snapshot { ticks: 60, nonce: 0 })
+        else {
+            panic!("expected a snapshot");
+        };
+        assert!(snapshot
+            .events
+            .iter()
+            .any(|event| matches!(event, SimEvent::Explosion { .. })));
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn test_snapshot_compression() {
+        let mut state = SimState::default();
+        state.handle(Request::StartScenario {
+            scenario_name: "asteroid-stress".to_string(),
+            seed: 0,
+            codes: vec![Code::None, Code::None],
+            nonce: 0,
+            practice: false,
+        });
+        let response = state
+            .handle(Request::Snapshot { ticks: 1, nonce: 0 })
+            .unwrap();
+        let Response::Snapshot { snapshot } = &response;
+        let uncompressed = bincode::serialize(snapshot).unwrap();
+        let compressed = bincode::serialize(&response).unwrap();
+        println!(
+            "asteroid-stress snapshot: {} bytes uncompressed, {} bytes compressed ({:.0}%)",
+            uncompressed.len(),
+            compressed.len(),
+            100.0 * compressed.len() as f64 / uncompressed.len() as f64
+        );
+        assert!(compressed.len() < uncompressed.len());
+
+        let Response::Snapshot { snapshot: decoded } =
+            bincode::deserialize::<Response>(&compressed).unwrap();
+        assert_eq!(decoded.tick, snapshot.tick);
+        assert_eq!(decoded.ships.len(), snapshot.ships.len());
     }
 }
@@ -0,0 +1,422 @@
+use crate::{discord, project_id};
+use oort_proto::{Telemetry, TelemetryMsg};
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+const MAX_BATCH_EVENTS: usize = 100;
+const MAX_BATCH_DELAY: Duration = Duration::from_secs(10);
+const SUMMARY_INTERVAL: Duration = Duration::from_secs(600);
+pub const MAX_RECENT_EVENTS: usize = 1000;
+
+pub type SinkFuture<'a> = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>>;
+
+/// Durable storage for batches of events, one JSON object per line.
+pub trait Sink: Send + Sync {
+    fn write<'a>(&'a self, lines: &'a [String]) -> SinkFuture<'a>;
+}
+
+/// Appends to a local file, for development.
+pub struct FileSink {
+    path: PathBuf,
+}
+
+impl Sink for FileSink {
+    fn write<'a>(&'a self, lines: &'a [String]) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let mut buf = String::new();
+            for line in lines {
+                buf.push_str(line);
+                buf.push('\n');
+            }
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await?;
+            file.write_all(buf.as_bytes()).await?;
+            // tokio finishes writes in the background unless flushed.
+            file.flush().await?;
+            Ok(())
+        })
+    }
+}
+
+/// Uploads each batch as a new object in a Cloud Storage bucket, since
+/// objects can't be appended to.
+pub struct GcsSink {
+    bucket: String,
+    client: reqwest::Client,
+}
+
+impl GcsSink {
+    async fn access_token(&self) -> anyhow::Result<String> {
+        #[derive(serde::Deserialize)]
+        struct Token {
+            access_token: String,
+        }
+        let url = "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+        let token: Token = self
+            .client
+            .get(url)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(token.access_token)
+    }
+}
+
+impl Sink for GcsSink {
+    fn write<'a>(&'a self, lines: &'a [String]) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let now = chrono::Utc::now();
+            let name = format!(
+                "telemetry/{}/{}-{:08x}.ndjson",
+                now.format("%Y-%m-%d"),
+                now.format("%H%M%S%.3f"),
+                rand::random::<u32>()
+            );
+            let url = format!(
+                "https://storage.googleapis.com/upload/storage/v1/b/{}/o",
+                self.bucket
+            );
+            let mut body = lines.join("\n");
+            body.push('\n');
+            self.client
+                .post(url)
+                .query(&[("uploadType", "media"), ("name", &name)])
+                .bearer_auth(self.access_token().await?)
+                .header("Content-Type", "application/x-ndjson")
+                .body(body)
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+/// Writes to the file named by TELEMETRY_LOG_FILE if set, otherwise to the
+/// TELEMETRY_BUCKET bucket (default "<project>-telemetry").
+pub fn sink_from_env() -> Arc<dyn Sink> {
+    if let Ok(path) = std::env::var("TELEMETRY_LOG_FILE") {
+        log::info!("Writing telemetry to {}", path);
+        return Arc::new(FileSink { path: path.into() });
+    }
+    let bucket =
+        std::env::var("TELEMETRY_BUCKET").unwrap_or_else(|_| format!("{}-telemetry", project_id()));
+    log::info!("Writing telemetry to bucket {}", bucket);
+    Arc::new(GcsSink {
+        bucket,
+        client: reqwest::Client::new(),
+    })
+}
+
+fn kind(payload: &Telemetry) -> &'static str {
+    match payload {
+        Telemetry::StartScenario { .. } => "StartScenario",
+        Telemetry::FinishScenario { .. } => "FinishScenario",
+        Telemetry::Crash { .. } => "Crash",
+        Telemetry::SubmitToTournament { .. } => "SubmitToTournament",
+        Telemetry::Feedback { .. } => "Feedback",
+        Telemetry::ScenarioComplete { .. } => "ScenarioComplete",
+        Telemetry::Compile { .. } => "Compile",
+    }
+}
+
+/// Counts events between the periodic summaries sent to Discord.
+struct Summary {
+    counts: BTreeMap<&'static str, usize>,
+    last_sent: Instant,
+}
+
+impl Summary {
+    fn new(now: Instant) -> Self {
+        Self {
+            counts: BTreeMap::new(),
+            last_sent: now,
+        }
+    }
+
+    fn record(&mut self, kind: &'static str) {
+        *self.counts.entry(kind).or_default() += 1;
+    }
+
+    fn take_if_due(&mut self, now: Instant) -> Option<String> {
+        if self.counts.is_empty() || now - self.last_sent < SUMMARY_INTERVAL {
+            return None;
+        }
+        self.last_sent = now;
+        let counts = std::mem::take(&mut self.counts);
+        let total: usize = counts.values().sum();
+        let details: Vec<String> = counts
+            .iter()
+            .map(|(kind, count)| format!("{count} {kind}"))
+            .collect();
+        Some(format!(
+            "{} telemetry events in the last {} minutes: {}",
+            total,
+            SUMMARY_INTERVAL.as_secs() / 60,
+            details.join(", ")
+        ))
+    }
+}
+
+/// Batches telemetry events and writes them to a sink, flushing every
+/// `MAX_BATCH_EVENTS` events or `MAX_BATCH_DELAY` after the first unwritten
+/// event. The latest events are also kept in memory for debugging.
+pub struct EventLog {
+    tx: Mutex<Option<mpsc::UnboundedSender<(&'static str, String)>>>,
+    writer: Mutex<Option<JoinHandle<()>>>,
+    recent: Mutex<VecDeque<String>>,
+}
+
+pub type SharedEventLog = Arc<EventLog>;
+
+impl EventLog {
+    pub fn start(sink: Arc<dyn Sink>) -> SharedEventLog {
+        Self::with_limits(sink, MAX_BATCH_EVENTS, MAX_BATCH_DELAY)
+    }
+
+    pub fn with_limits(
+        sink: Arc<dyn Sink>,
+        max_events: usize,
+        max_delay: Duration,
+    ) -> SharedEventLog {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let writer = tokio::spawn(run(rx, sink, max_events, max_delay));
+        Arc::new(Self {
+            tx: Mutex::new(Some(tx)),
+            writer: Mutex::new(Some(writer)),
+            recent: Mutex::new(VecDeque::new()),
+        })
+    }
+
+    pub fn push(&self, msg: &TelemetryMsg) {
+        let line = match serde_json::to_string(msg) {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Failed to serialize telemetry: {}", e);
+                return;
+            }
+        };
+        {
+            let mut recent = self.recent.lock().unwrap();
+            if recent.len() >= MAX_RECENT_EVENTS {
+                recent.pop_front();
+            }
+            recent.push_back(line.clone());
+        }
+        let tx = self.tx.lock().unwrap();
+        match tx.as_ref() {
+            Some(tx) if tx.send((kind(&msg.payload), line)).is_ok() => {}
+            _ => log::warn!("Telemetry writer has stopped"),
+        }
+    }
+
+    /// Stops accepting events and waits for the ones already pushed to be
+    /// written.
+    pub async fn shutdown(&self) {
+        self.tx.lock().unwrap().take();
+        let writer = self.writer.lock().unwrap().take();
+        if let Some(writer) = writer {
+            if let Err(e) = writer.await {
+                log::error!("Telemetry writer failed: {}", e);
+            }
+        }
+    }
+
+    /// Returns up to `limit` of the latest events, oldest first.
+    pub fn recent(&self, limit: usize) -> Vec<String> {
+        let recent = self.recent.lock().unwrap();
+        recent
+            .iter()
+            .skip(recent.len().saturating_sub(limit))
+            .cloned()
+            .collect()
+    }
+}
+
+async fn run(
+    mut rx: mpsc::UnboundedReceiver<(&'static str, String)>,
+    sink: Arc<dyn Sink>,
+    max_events: usize,
+    max_delay: Duration,
+) {
+    let mut batch: Vec<String> = Vec::new();
+    let mut deadline = Instant::now();
+    let mut summary = Summary::new(Instant::now());
+    loop {
+        let flush = tokio::select! {
+            event = rx.recv() => match event {
+                Some((kind, line)) => {
+                    if batch.is_empty() {
+                        deadline = Instant::now() + max_delay;
+                    }
+                    summary.record(kind);
+                    batch.push(line);
+                    batch.len() >= max_events
+                }
+                None => {
+                    flush_batch(sink.as_ref(), &mut batch).await;
+                    return;
+                }
+            },
+            _ = tokio::time::sleep_until(deadline), if !batch.is_empty() => true,
+        };
+        if flush {
+            flush_batch(sink.as_ref(), &mut batch).await;
+            if let Some(msg) = summary.take_if_due(Instant::now()) {
+                discord::send_message(discord::Channel::Telemetry, msg);
+            }
+        }
+    }
+}
+
+async fn flush_batch(sink: &dyn Sink, batch: &mut Vec<String>) {
+    if batch.is_empty() {
+        return;
+    }
+    if let Err(e) = sink.write(batch).await {
+        log::error!("Failed to write {} telemetry events: {:?}", batch.len(), e);
+    }
+    batch.clear();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Utc;
+
+    #[derive(Default)]
+    struct MemorySink {
+        batches: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl Sink for MemorySink {
+        fn write<'a>(&'a self, lines: &'a [String]) -> SinkFuture<'a> {
+            self.batches.lock().unwrap().push(lines.to_vec());
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    impl MemorySink {
+        fn batch_sizes(&self) -> Vec<usize> {
+            self.batches
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|x| x.len())
+                .collect()
+        }
+    }
+
+    fn msg(success: bool) -> TelemetryMsg {
+        TelemetryMsg {
+            payload: Telemetry::Compile { success },
+            build: "test".to_string(),
+            userid: "user".to_string(),
+            username: "name".to_string(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    async fn settle() {
+        for _ in 0..100 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flush_on_size() {
+        let sink = Arc::new(MemorySink::default());
+        let log = EventLog::with_limits(sink.clone(), 3, Duration::from_secs(3600));
+        for _ in 0..7 {
+            log.push(&msg(true));
+        }
+        settle().await;
+        assert_eq!(sink.batch_sizes(), vec![3, 3]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flush_on_timer() {
+        let sink = Arc::new(MemorySink::default());
+        let log = EventLog::with_limits(sink.clone(), 100, Duration::from_secs(10));
+        log.push(&msg(true));
+        log.push(&msg(false));
+        settle().await;
+        assert!(sink.batch_sizes().is_empty());
+
+        tokio::time::advance(Duration::from_secs(5)).await;
+        settle().await;
+        assert!(sink.batch_sizes().is_empty());
+
+        tokio::time::advance(Duration::from_secs(6)).await;
+        settle().await;
+        assert_eq!(sink.batch_sizes(), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_flush_on_shutdown() {
+        let sink = Arc::new(MemorySink::default());
+        let log = EventLog::with_limits(sink.clone(), 100, Duration::from_secs(3600));
+        log.push(&msg(true));
+        log.push(&msg(false));
+        log.shutdown().await;
+        assert_eq!(sink.batch_sizes(), vec![2]);
+
+        // Later events are dropped.
+        log.push(&msg(true));
+        log.shutdown().await;
+        assert_eq!(sink.batch_sizes(), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_recent() {
+        let sink = Arc::new(MemorySink::default());
+        let log = EventLog::start(sink);
+        log.push(&msg(true));
+        log.push(&msg(false));
+        let recent = log.recent(1);
+        assert_eq!(recent.len(), 1);
+        assert!(recent[0].contains("\"success\":false"));
+        assert_eq!(log.recent(10).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_file_sink() {
+        let path = std::env::temp_dir().join(format!("telemetry-{}.ndjson", rand::random::<u32>()));
+        let sink = FileSink { path: path.clone() };
+        sink.write(&["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+        sink.write(&["c".to_string()]).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\nb\nc\n");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_summary() {
+        let start = Instant::now();
+        let mut summary = Summary::new(start);
+        summary.record("Compile");
+        summary.record("Compile");
+        summary.record("Crash");
+        assert_eq!(summary.take_if_due(start + Duration::from_secs(1)), None);
+        assert_eq!(
+            summary.take_if_due(start + SUMMARY_INTERVAL),
+            Some("3 telemetry events in the last 10 minutes: 2 Compile, 1 Crash".to_string())
+        );
+        assert_eq!(summary.take_if_due(start + SUMMARY_INTERVAL * 2), None);
+    }
+}
@@ -1,53 +1,195 @@
 use crate::{discord, error, project_id, Error};
 use axum::debug_handler;
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
 use axum::Json;
 use bytes::Bytes;
-use chrono::Utc;
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use firestore::*;
-use gcloud_sdk::google::firestore::v1::Document;
-use oort_proto::{LeaderboardData, LeaderboardSubmission, TimeLeaderboardRow};
+use gcloud_sdk::google::firestore::v1::value::ValueType;
+use gcloud_sdk::google::firestore::v1::{Document, Value};
+use hmac::{Hmac, Mac};
+use oort_proto::{LeaderboardData, LeaderboardResponse, LeaderboardSubmission, TimeLeaderboardRow};
+use oort_simulator::simulation::PHYSICS_TICK_LENGTH;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashSet;
 
-async fn fetch_leaderboard(
+const TOKEN_LIFETIME_MINUTES: i64 = 30;
+
+// Generous upper bound on how fast a browser can run the simulation.
+const MAX_TICKS_PER_SECOND: f64 = 10000.0;
+
+// Every accepted submission. The "leaderboard" collection only holds each
+// user's best, keyed by "{scenario_name}.{userid}".
+const HISTORY_COLLECTION: &str = "leaderboard_history";
+
+// Nonces of tokens that have been used, so each token is good for one
+// submission.
+const USED_TOKEN_COLLECTION: &str = "leaderboard_used_tokens";
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1000;
+
+// Rows fetched per query. Larger leaderboards are read in several pages.
+const QUERY_PAGE_SIZE: u32 = 500;
+
+// History isn't cached, so each request reads offset + limit rows from
+// Firestore. This bounds how deep a request can page.
+const MAX_HISTORY_OFFSET: usize = 10000;
+
+fn equal_filter(field: &str, value: &str) -> FirestoreQueryFilter {
+    FirestoreQueryFilter::Compare(Some(FirestoreQueryFilterCompare::Equal(
+        field.into(),
+        value.into(),
+    )))
+}
+
+fn rows_filter(scenario_name: &str, userid: Option<&str>) -> FirestoreQueryFilter {
+    let mut filters = vec![equal_filter("scenario_name", scenario_name)];
+    if let Some(userid) = userid {
+        filters.push(equal_filter("userid", userid));
+    }
+    FirestoreQueryFilter::Composite(FirestoreQueryFilterComposite::new(
+        filters,
+        FirestoreQueryFilterCompositeOperator::And,
+    ))
+}
+
+/// Reads the rows for the scenario, optionally only one user's, paging
+/// through the collection with query cursors. Stops after `max_rows` rows in
+/// leaderboard order if given.
+async fn fetch_rows(
     db: &FirestoreDb,
+    collection: &str,
     scenario_name: &str,
-) -> anyhow::Result<LeaderboardData> {
-    let docs: Vec<Document> = db
-        .query_doc(
-            FirestoreQueryParams::new("leaderboard".into())
-                .with_filter(FirestoreQueryFilter::Composite(
-                    FirestoreQueryFilterComposite::new(
-                        vec![FirestoreQueryFilter::Compare(Some(
-                            FirestoreQueryFilterCompare::Equal(
-                                "scenario_name".into(),
-                                scenario_name.into(),
-                            ),
-                        ))],
-                        FirestoreQueryFilterCompositeOperator::And,
-                    ),
-                ))
-                .with_order_by(vec![
-                    FirestoreQueryOrder::new("time".to_owned(), FirestoreQueryDirection::Ascending),
-                    FirestoreQueryOrder::new(
-                        "timestamp".to_owned(),
-                        FirestoreQueryDirection::Ascending,
-                    ),
-                ])
-                .with_limit(1000),
-        )
-        .await?;
+    userid: Option<&str>,
+    max_rows: Option<usize>,
+) -> anyhow::Result<Vec<TimeLeaderboardRow>> {
+    let mut rows = vec![];
+    let mut cursor = None;
+    loop {
+        let page_size = match max_rows {
+            Some(max_rows) => (max_rows - rows.len()).min(QUERY_PAGE_SIZE as usize) as u32,
+            None => QUERY_PAGE_SIZE,
+        };
+        if page_size == 0 {
+            break;
+        }
+        let mut params = FirestoreQueryParams::new(collection.into())
+            .with_filter(rows_filter(scenario_name, userid))
+            .with_order_by(vec![
+                FirestoreQueryOrder::new("time".to_owned(), FirestoreQueryDirection::Ascending),
+                FirestoreQueryOrder::new(
+                    "timestamp".to_owned(),
+                    FirestoreQueryDirection::Ascending,
+                ),
+                // Makes the order total, so the cursor never skips a tie.
+                FirestoreQueryOrder::new("__name__".to_owned(), FirestoreQueryDirection::Ascending),
+            ])
+            .with_limit(page_size);
+        if let Some(cursor) = cursor.take() {
+            params = params.with_start_at(cursor);
+        }
+        let docs: Vec<Document> = db.query_doc(params).await?;
 
-    let mut leaderboard = LeaderboardData::default();
+        for doc in &docs {
+            if let Ok(msg) = FirestoreDb::deserialize_doc_to::<LeaderboardSubmission>(doc) {
+                rows.push(make_row(&msg));
+            } else {
+                log::error!("Failed to deserialize doc {}", doc.name);
+            }
+        }
 
-    for doc in &docs {
-        if let Ok(msg) = FirestoreDb::deserialize_doc_to::<LeaderboardSubmission>(doc) {
-            leaderboard.lowest_time.push(make_row(&msg));
-        } else {
-            log::error!("Failed to deserialize doc {}", doc.name);
+        match docs.last() {
+            Some(last) if docs.len() == page_size as usize => {
+                cursor = Some(cursor_after(last)?);
+            }
+            _ => break,
         }
     }
+    sort_rows(&mut rows);
+    Ok(rows)
+}
+
+/// Counts the rows `fetch_rows` would return without reading them.
+async fn count_rows(
+    db: &FirestoreDb,
+    collection: &str,
+    scenario_name: &str,
+    userid: Option<&str>,
+) -> anyhow::Result<usize> {
+    #[derive(Deserialize)]
+    struct Count {
+        count: usize,
+    }
+    let params = FirestoreAggregatedQueryParams::new(
+        FirestoreQueryParams::new(collection.into())
+            .with_filter(rows_filter(scenario_name, userid)),
+        vec![FirestoreAggregation::new("count".to_owned()).with_operator(
+            FirestoreAggregationOperator::Count(FirestoreAggregationOperatorCount::new()),
+        )],
+    );
+    let counts: Vec<Count> = db.aggregated_query_obj(params).await?;
+    Ok(counts.first().map_or(0, |x| x.count))
+}
+
+/// Starts the next page after `doc`, using the values of the order by fields.
+fn cursor_after(doc: &Document) -> anyhow::Result<FirestoreQueryCursor> {
+    let field = |name: &str| {
+        doc.fields
+            .get(name)
+            .cloned()
+            .map(FirestoreValue::from)
+            .ok_or_else(|| anyhow::anyhow!("Missing {} in {}", name, doc.name))
+    };
+    Ok(FirestoreQueryCursor::AfterValue(vec![
+        field("time")?,
+        field("timestamp")?,
+        FirestoreValue::from(Value {
+            value_type: Some(ValueType::ReferenceValue(doc.name.clone())),
+        }),
+    ]))
+}
+
+async fn fetch_leaderboard(
+    db: &FirestoreDb,
+    scenario_name: &str,
+) -> anyhow::Result<LeaderboardData> {
+    let rows = fetch_rows(db, "leaderboard", scenario_name, None, None).await?;
+    Ok(LeaderboardData {
+        lowest_time: best_per_user(rows),
+    })
+}
+
+/// Fastest first. Ties go to the earlier submission, so a new submission
+/// never moves an existing entry up or down past its equals.
+pub fn sort_rows(rows: &mut [TimeLeaderboardRow]) {
+    rows.sort_by(|a, b| {
+        let key =
+            |x: &TimeLeaderboardRow| ((x.time_float.unwrap_or(1e6) * 1e6) as u64, x.timestamp);
+        key(a).cmp(&key(b)).then_with(|| a.userid.cmp(&b.userid))
+    });
+}
 
-    Ok(leaderboard)
+/// Collapses the rows to each user's best, in leaderboard order.
+pub fn best_per_user(mut rows: Vec<TimeLeaderboardRow>) -> Vec<TimeLeaderboardRow> {
+    sort_rows(&mut rows);
+    let mut seen = HashSet::new();
+    rows.retain(|row| seen.insert(row.userid.clone()));
+    rows
+}
+
+pub fn paginate(rows: &[TimeLeaderboardRow], offset: usize, limit: usize) -> LeaderboardResponse {
+    LeaderboardResponse {
+        entries: rows
+            .iter()
+            .skip(offset)
+            .take(limit.min(MAX_PAGE_SIZE))
+            .cloned()
+            .collect(),
+        total: rows.len(),
+    }
 }
 
 pub fn make_row(submission: &LeaderboardSubmission) -> TimeLeaderboardRow {
@@ -65,6 +207,159 @@ pub fn make_row(submission: &LeaderboardSubmission) -> TimeLeaderboardRow {
     }
 }
 
+type HmacSha256 = Hmac<Sha256>;
+
+/// Reads the key that signs submission tokens. It only exists on the server,
+/// unlike the envelope secret which is compiled into the frontend.
+pub fn token_key() -> Result<Vec<u8>, Error> {
+    match std::env::var("LEADERBOARD_TOKEN_SECRET") {
+        Ok(secret) if !secret.is_empty() => Ok(secret.into_bytes()),
+        _ => Err(error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Leaderboard submissions disabled".into(),
+        )),
+    }
+}
+
+fn sign(key: &[u8], payload: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(payload);
+    mac
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|x| format!("{x:02x}")).collect()
+}
+
+/// A verified submission token.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Token {
+    pub issued: DateTime<Utc>,
+    /// Random per token. Recorded when the token is used so it can't be
+    /// replayed.
+    pub nonce: u64,
+}
+
+/// Returns "{payload}.{mac}", both hex encoded.
+pub fn make_token(key: &[u8], scenario_name: &str, nonce: u64, now: DateTime<Utc>) -> String {
+    let payload = format!(
+        "{}:{:016x}:{}",
+        now.timestamp_millis(),
+        nonce,
+        scenario_name
+    );
+    let mac = sign(key, payload.as_bytes()).finalize().into_bytes();
+    format!("{}.{}", encode_hex(payload.as_bytes()), encode_hex(&mac))
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 || !s.is_ascii() {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..(i + 2)], 16).ok())
+        .collect()
+}
+
+/// Checks the token's signature, scenario and age. Doesn't check whether it
+/// was already used, see [`consume_token`].
+pub fn verify_token(
+    key: &[u8],
+    token: &str,
+    scenario_name: &str,
+    now: DateTime<Utc>,
+) -> Result<Token, Error> {
+    let invalid = || error(StatusCode::BAD_REQUEST, "invalid token".into());
+    let (payload, mac) = token.split_once('.').ok_or_else(invalid)?;
+    let payload = decode_hex(payload).ok_or_else(invalid)?;
+    let mac = decode_hex(mac).ok_or_else(invalid)?;
+    // verify_slice compares in constant time.
+    sign(key, &payload)
+        .verify_slice(&mac)
+        .map_err(|_| invalid())?;
+    let payload = String::from_utf8(payload).map_err(|_| invalid())?;
+    let (millis, rest) = payload.split_once(':').ok_or_else(invalid)?;
+    let (nonce, token_scenario_name) = rest.split_once(':').ok_or_else(invalid)?;
+    let millis: i64 = millis.parse().map_err(|_| invalid())?;
+    let nonce = u64::from_str_radix(nonce, 16).map_err(|_| invalid())?;
+    let issued = Utc
+        .timestamp_millis_opt(millis)
+        .single()
+        .ok_or_else(invalid)?;
+    if token_scenario_name != scenario_name {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "token issued for a different scenario".into(),
+        ));
+    }
+    if issued > now || now - issued > Duration::minutes(TOKEN_LIFETIME_MINUTES) {
+        return Err(error(StatusCode::BAD_REQUEST, "expired token".into()));
+    }
+    Ok(Token { issued, nonce })
+}
+
+pub fn verify_submission(
+    key: &[u8],
+    submission: &LeaderboardSubmission,
+    now: DateTime<Utc>,
+) -> Result<Token, Error> {
+    let token = verify_token(key, &submission.token, &submission.scenario_name, now)?;
+    let issued = token.issued;
+    if oort_envelope::hash_code(&submission.code) != submission.code_hash {
+        return Err(error(StatusCode::BAD_REQUEST, "code hash mismatch".into()));
+    }
+    let ticks = submission.time / PHYSICS_TICK_LENGTH;
+    let elapsed = (now - issued).num_milliseconds() as f64 / 1e3;
+    if ticks > elapsed * MAX_TICKS_PER_SECOND {
+        log::warn!(
+            "Rejecting submission from {} claiming {} ticks after {:.3}s",
+            submission.username,
+            ticks as u64,
+            elapsed
+        );
+        return Err(error(StatusCode::BAD_REQUEST, "implausible time".into()));
+    }
+    Ok(token)
+}
+
+#[derive(Serialize, Deserialize)]
+struct UsedToken {
+    issued: DateTime<Utc>,
+}
+
+/// Records the token's nonce, failing if it was already recorded. Firestore
+/// only creates a document if it doesn't exist, so two racing submissions
+/// can't both use the same token.
+async fn consume_token(db: &FirestoreDb, token: &Token) -> Result<(), Error> {
+    let result: FirestoreResult<UsedToken> = db
+        .create_obj(
+            USED_TOKEN_COLLECTION,
+            Some(format!("{:016x}", token.nonce)),
+            &UsedToken {
+                issued: token.issued,
+            },
+            None,
+        )
+        .await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(errors::FirestoreError::DataConflictError(_)) => {
+            Err(error(StatusCode::BAD_REQUEST, "token already used".into()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn get_token(Path(scenario_name): Path<String>) -> Result<String, Error> {
+    Ok(make_token(
+        &token_key()?,
+        &scenario_name,
+        rand::random(),
+        Utc::now(),
+    ))
+}
+
 pub async fn get(
     Path(scenario_name): Path<String>,
     cache: State<SharedLeaderboardCache>,
@@ -74,6 +369,60 @@ pub async fn get(
     Ok(Json(data))
 }
 
+fn default_page_size() -> usize {
+    DEFAULT_PAGE_SIZE
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LeaderboardQuery {
+    scenario: String,
+    #[serde(default = "default_page_size")]
+    limit: usize,
+    #[serde(default)]
+    offset: usize,
+    /// Nonzero to include every submission instead of each user's best.
+    /// Paging is limited to the first `MAX_HISTORY_OFFSET` submissions.
+    #[serde(default)]
+    all: u32,
+    /// Only return this user's rows.
+    userid: Option<String>,
+}
+
+pub async fn query(
+    Query(params): Query<LeaderboardQuery>,
+    cache: State<SharedLeaderboardCache>,
+) -> Result<Json<LeaderboardResponse>, Error> {
+    let db = FirestoreDb::new(&project_id()).await?;
+    if params.all != 0 {
+        if params.offset > MAX_HISTORY_OFFSET {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                format!("offset is limited to {MAX_HISTORY_OFFSET} when all is set"),
+            ));
+        }
+        let limit = params.limit.min(MAX_PAGE_SIZE);
+        let userid = params.userid.as_deref();
+        let rows = fetch_rows(
+            &db,
+            HISTORY_COLLECTION,
+            &params.scenario,
+            userid,
+            Some(params.offset + limit),
+        )
+        .await?;
+        let total = count_rows(&db, HISTORY_COLLECTION, &params.scenario, userid).await?;
+        return Ok(Json(LeaderboardResponse {
+            total,
+            ..paginate(&rows, params.offset, limit)
+        }));
+    }
+    let mut rows = cache.get(&db, &params.scenario).await?.lowest_time;
+    if let Some(userid) = &params.userid {
+        rows.retain(|row| &row.userid == userid);
+    }
+    Ok(Json(paginate(&rows, params.offset, params.limit)))
+}
+
 #[debug_handler]
 pub async fn post(
     cache: State<SharedLeaderboardCache>,
@@ -84,17 +433,20 @@ pub async fn post(
     let payload = match oort_envelope::remove(payload.as_ref()) {
         Some(x) => x,
         None => {
-            return Err(error(
-                axum::http::StatusCode::BAD_REQUEST,
-                "invalid envelope".into(),
-            ));
+            return Err(error(StatusCode::BAD_REQUEST, "invalid envelope".into()));
         }
     };
     let mut obj: LeaderboardSubmission = serde_json::from_slice(&payload)?;
 
     obj.timestamp = Utc::now();
+    let token = verify_submission(&token_key()?, &obj, obj.timestamp)?;
+    consume_token(&db, &token).await?;
     let path = format!("{}.{}", obj.scenario_name, obj.userid);
 
+    let history_path = format!("{}.{}", path, obj.timestamp.timestamp_millis());
+    db.update_obj(HISTORY_COLLECTION, &history_path, &obj, None, None, None)
+        .await?;
+
     let old_leaderboard = cache.get(&db, &obj.scenario_name).await?;
 
     if let Ok(existing_obj) = db
@@ -218,15 +570,9 @@ impl LeaderboardCache {
 
         let mut scenarios = self.scenarios.lock().await;
         let cached = scenarios.get_mut(scenario_name).unwrap();
-        cached
-            .leaderboard
-            .lowest_time
-            .retain(|x| x.userid != row.userid);
-        cached.leaderboard.lowest_time.push(row);
-        cached
-            .leaderboard
-            .lowest_time
-            .sort_by_key(|x| ((x.time_float.unwrap_or(1e6) * 1e6) as u64, x.timestamp));
+        let rows = &mut cached.leaderboard.lowest_time;
+        rows.push(row);
+        *rows = best_per_user(std::mem::take(rows));
         Ok(())
     }
 }
@@ -236,3 +582,157 @@ impl Default for LeaderboardCache {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const KEY: &[u8] = b"test key";
+
+    fn submission(token: String, time: f64) -> LeaderboardSubmission {
+        let code = "fn tick() {}".to_string();
+        LeaderboardSubmission {
+            scenario_name: "gunnery".into(),
+            userid: "user".into(),
+            username: "name".into(),
+            timestamp: Utc::now(),
+            time,
+            code_size: 0,
+            code_hash: oort_envelope::hash_code(&code),
+            code,
+            seed: Some(0),
+            token,
+        }
+    }
+
+    fn row(userid: &str, time: f64, seconds: i64) -> TimeLeaderboardRow {
+        let mut obj = submission(String::new(), time);
+        obj.userid = userid.into();
+        obj.timestamp = Utc.timestamp_opt(seconds, 0).unwrap();
+        make_row(&obj)
+    }
+
+    fn users(rows: &[TimeLeaderboardRow]) -> Vec<&str> {
+        rows.iter().map(|x| x.userid.as_str()).collect()
+    }
+
+    #[test]
+    fn test_best_per_user_improved_time() {
+        let rows = best_per_user(vec![row("a", 10.0, 0), row("b", 9.0, 1), row("a", 8.0, 2)]);
+        assert_eq!(users(&rows), vec!["a", "b"]);
+        assert_eq!(rows[0].time_float, Some(8.0));
+    }
+
+    #[test]
+    fn test_best_per_user_worse_time() {
+        let rows = best_per_user(vec![row("a", 8.0, 0), row("b", 9.0, 1), row("a", 10.0, 2)]);
+        assert_eq!(users(&rows), vec!["a", "b"]);
+        assert_eq!(rows[0].time_float, Some(8.0));
+        assert_eq!(rows[0].timestamp, Some(Utc.timestamp_opt(0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_pagination() {
+        let mut rows: Vec<_> = (0..10)
+            .map(|i| row(&format!("u{i}"), 10.0 + i as f64, i))
+            .collect();
+        rows = best_per_user(rows);
+
+        let page = paginate(&rows, 0, 4);
+        assert_eq!(page.total, 10);
+        assert_eq!(users(&page.entries), vec!["u0", "u1", "u2", "u3"]);
+        let page = paginate(&rows, 8, 4);
+        assert_eq!(users(&page.entries), vec!["u8", "u9"]);
+        assert!(paginate(&rows, 20, 4).entries.is_empty());
+        assert_eq!(paginate(&rows, 0, usize::MAX).entries.len(), 10);
+    }
+
+    #[test]
+    fn test_pagination_stable_with_new_submissions() {
+        let mut rows: Vec<_> = (0..10)
+            .map(|i| row(&format!("u{i}"), 10.0 + i as f64, i))
+            .collect();
+        rows = best_per_user(rows);
+        let first_page = paginate(&rows, 0, 5);
+
+        // A later tie, a slower time, and a worse resubmission all land after
+        // the first page.
+        rows.push(row("new", 14.0, 100));
+        rows.push(row("slow", 30.0, 101));
+        rows.push(row("u2", 50.0, 102));
+        rows = best_per_user(rows);
+
+        assert_eq!(
+            paginate(&rows, 0, 5),
+            LeaderboardResponse {
+                total: 12,
+                ..first_page
+            }
+        );
+        assert_eq!(users(&paginate(&rows, 5, 2).entries), vec!["new", "u5"]);
+    }
+
+    #[test]
+    fn test_token() {
+        let now = Utc::now();
+        let token = make_token(KEY, "gunnery", 1, now);
+        assert_eq!(
+            verify_token(KEY, &token, "gunnery", now + Duration::minutes(1)).ok(),
+            Some(Token {
+                issued: Utc.timestamp_millis_opt(now.timestamp_millis()).unwrap(),
+                nonce: 1,
+            })
+        );
+        assert!(verify_token(KEY, &token, "tutorial01", now + Duration::minutes(1)).is_err());
+        assert_ne!(token, make_token(KEY, "gunnery", 2, now));
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let now = Utc::now();
+        let token = make_token(KEY, "gunnery", 1, now);
+        let later = now + Duration::minutes(TOKEN_LIFETIME_MINUTES + 1);
+        assert!(verify_token(KEY, &token, "gunnery", later).is_err());
+        let earlier = now - Duration::minutes(1);
+        assert!(verify_token(KEY, &token, "gunnery", earlier).is_err());
+    }
+
+    #[test]
+    fn test_tampered_token() {
+        let now = Utc::now();
+        let token = make_token(KEY, "gunnery", 1, now);
+        let mut bytes = token.into_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] = if bytes[last] == b'0' { b'1' } else { b'0' };
+        let token = String::from_utf8(bytes).unwrap();
+        assert!(verify_token(KEY, &token, "gunnery", now).is_err());
+        assert!(verify_token(KEY, "", "gunnery", now).is_err());
+        assert!(verify_token(KEY, "xyz", "gunnery", now).is_err());
+
+        // Re-signing the payload needs the server's key.
+        let forged = make_token(b"guessed key", "gunnery", 1, now);
+        assert!(verify_token(KEY, &forged, "gunnery", now).is_err());
+        let token = make_token(KEY, "gunnery", 1, now);
+        let (_, mac) = token.split_once('.').unwrap();
+        let earlier = now - Duration::minutes(1);
+        let payload =
+            encode_hex(format!("{}:{:016x}:gunnery", earlier.timestamp_millis(), 1).as_bytes());
+        assert!(verify_token(KEY, &format!("{payload}.{mac}"), "gunnery", now).is_err());
+    }
+
+    #[test]
+    fn test_submission() {
+        let now = Utc::now();
+        let later = now + Duration::minutes(1);
+        let obj = submission(make_token(KEY, "gunnery", 1, now), 10.0);
+        assert!(verify_submission(KEY, &obj, later).is_ok());
+
+        let mut tampered = obj.clone();
+        tampered.code = "fn tick() { cheat(); }".into();
+        assert!(verify_submission(KEY, &tampered, later).is_err());
+
+        // Faster than any browser could simulate.
+        let obj = submission(make_token(KEY, "gunnery", 1, now), 10.0);
+        assert!(verify_submission(KEY, &obj, now + Duration::milliseconds(1)).is_err());
+    }
+}
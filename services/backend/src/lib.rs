@@ -1,13 +1,21 @@
 pub mod discord;
+pub mod event_log;
 pub mod leaderboard;
 pub mod rescore;
 pub mod shortcode;
 pub mod telemetry;
 pub mod tournament;
 
+use axum::extract::FromRef;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 
+#[derive(Clone, FromRef)]
+pub struct AppState {
+    pub leaderboard_cache: leaderboard::SharedLeaderboardCache,
+    pub event_log: event_log::SharedEventLog,
+}
+
 pub fn project_id() -> String {
     std::env::var("PROJECT_ID").expect("missing PROJECT_ID environment variable")
 }
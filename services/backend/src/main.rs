@@ -1,7 +1,9 @@
 use axum::Router;
 use clap::{Parser, Subcommand};
 use http::Method;
-use oort_backend_service::{leaderboard, project_id, rescore, shortcode, telemetry, tournament};
+use oort_backend_service::{
+    event_log, leaderboard, project_id, rescore, shortcode, telemetry, tournament, AppState,
+};
 use tower_http::cors::{Any, CorsLayer};
 
 #[derive(Parser, Debug)]
@@ -53,6 +55,7 @@ async fn serve() -> anyhow::Result<()> {
 
     let leaderboard_cache: leaderboard::SharedLeaderboardCache =
         std::sync::Arc::new(leaderboard::LeaderboardCache::new());
+    let event_log = event_log::EventLog::start(event_log::sink_from_env());
 
     let cors = CorsLayer::new()
         .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
@@ -65,18 +68,43 @@ async fn serve() -> anyhow::Result<()> {
             .route("/shortcode/:id", get(shortcode::get))
             .route("/shortcode", post(shortcode::post))
             .route("/telemetry", post(telemetry::post))
+            .route("/events/recent", get(telemetry::recent))
             .route("/tournament/submit", post(tournament::submit))
             .route("/tournament/results/:id", get(tournament::get_results))
             .route("/leaderboard/:scenario_name", get(leaderboard::get))
-            .route("/leaderboard", post(leaderboard::post))
-            .with_state(leaderboard_cache)
+            .route(
+                "/leaderboard/token/:scenario_name",
+                get(leaderboard::get_token),
+            )
+            .route(
+                "/leaderboard",
+                get(leaderboard::query).post(leaderboard::post),
+            )
+            .with_state(AppState {
+                leaderboard_cache,
+                event_log: event_log.clone(),
+            })
             .layer(cors)
             .layer(tower_http::trace::TraceLayer::new_for_http())
     };
 
     axum::Server::bind(&format!("0.0.0.0:{port}").parse()?)
         .serve(router.into_make_service())
+        .with_graceful_shutdown(shutdown_signal())
         .await?;
+    event_log.shutdown().await;
 
     Ok(())
 }
+
+/// Resolves on Ctrl-C or SIGTERM, which Cloud Run sends before stopping an
+/// instance.
+async fn shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to install SIGTERM handler");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+    log::info!("Shutting down");
+}
@@ -1,8 +1,11 @@
-use crate::{discord, project_id, Error};
-use axum::extract::Json;
+use crate::event_log::{SharedEventLog, MAX_RECENT_EVENTS};
+use crate::{discord, error, project_id, Error};
+use axum::extract::{Json, Query, State};
+use axum::http::{HeaderMap, StatusCode};
 use chrono::prelude::*;
 use firestore::*;
 use oort_proto::{Telemetry, TelemetryMsg};
+use subtle::ConstantTimeEq;
 
 fn generate_docid() -> String {
     use rand::Rng;
@@ -19,9 +22,13 @@ fn generate_docid() -> String {
         .collect()
 }
 
-pub async fn post(Json(mut obj): Json<TelemetryMsg>) -> Result<(), Error> {
+pub async fn post(
+    State(event_log): State<SharedEventLog>,
+    Json(mut obj): Json<TelemetryMsg>,
+) -> Result<(), Error> {
     let db = FirestoreDb::new(&project_id()).await?;
     obj.timestamp = Utc::now();
+    event_log.push(&obj);
     log::debug!("Got request obj {:?}", obj);
     let docid = generate_docid();
     db.create_obj("telemetry", Some(&docid), &obj, None).await?;
@@ -83,6 +90,51 @@ pub async fn post(Json(mut obj): Json<TelemetryMsg>) -> Result<(), Error> {
                 ),
             );
         }
+        Telemetry::ScenarioComplete {
+            scenario_name,
+            ticks,
+            seed,
+        } => {
+            log::info!(
+                "User {} finished scenario {} in {} ticks with seed {}",
+                obj.username,
+                scenario_name,
+                ticks,
+                seed
+            );
+        }
+        Telemetry::Compile { success } => {
+            log::info!("User {} compiled with success={}", obj.username, success);
+        }
     }
     Ok(())
 }
+
+#[derive(serde::Deserialize)]
+pub struct RecentQuery {
+    limit: Option<usize>,
+}
+
+/// Returns the latest telemetry events as newline-delimited JSON. Requires
+/// the ADMIN_TOKEN environment variable as a bearer token.
+pub async fn recent(
+    State(event_log): State<SharedEventLog>,
+    headers: HeaderMap,
+    Query(query): Query<RecentQuery>,
+) -> Result<String, Error> {
+    let token = std::env::var("ADMIN_TOKEN")
+        .map_err(|_| error(StatusCode::FORBIDDEN, "Admin access disabled".to_string()))?;
+    let authorization = headers
+        .get(http::header::AUTHORIZATION)
+        .and_then(|x| x.to_str().ok());
+    let expected = format!("Bearer {token}");
+    // Compared in constant time so the token can't be guessed byte by byte.
+    let authorized = authorization.map_or(false, |x| {
+        bool::from(x.as_bytes().ct_eq(expected.as_bytes()))
+    });
+    if token.is_empty() || !authorized {
+        return Err(error(StatusCode::UNAUTHORIZED, "Unauthorized".to_string()));
+    }
+    let limit = query.limit.unwrap_or(100).min(MAX_RECENT_EVENTS);
+    Ok(event_log.recent(limit).join("\n"))
+}
@@ -0,0 +1,123 @@
+use bytes::Bytes;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+pub type CodeHash = [u8; 32];
+
+/// Hashes source code after normalizing line endings and trailing whitespace.
+pub fn hash(code: &str) -> CodeHash {
+    let normalized = code.replace("\r\n", "\n");
+    let mut hasher = Sha256::new();
+    hasher.update(normalized.trim_end().as_bytes());
+    hasher.finalize().into()
+}
+
+/// In-memory LRU cache of compiled wasm keyed by source hash.
+pub struct CompileCache {
+    capacity: usize,
+    inner: Mutex<Inner>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<CodeHash, Bytes>,
+    order: VecDeque<CodeHash>,
+}
+
+impl CompileCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Inner::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, key: &CodeHash) -> Option<Bytes> {
+        let mut inner = self.inner.lock().unwrap();
+        let result = inner.entries.get(key).cloned();
+        if result.is_some() {
+            inner.order.retain(|x| x != key);
+            inner.order.push_back(*key);
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    pub fn insert(&self, key: CodeHash, wasm: Bytes) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        if inner.entries.insert(key, wasm).is_some() {
+            inner.order.retain(|x| *x != key);
+        }
+        inner.order.push_back(key);
+        while inner.order.len() > self.capacity {
+            if let Some(evicted) = inner.order.pop_front() {
+                inner.entries.remove(&evicted);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_normalization() {
+        assert_eq!(hash("fn main() {}\n"), hash("fn main() {}"));
+        assert_eq!(hash("a\r\nb"), hash("a\nb"));
+        assert_ne!(hash("a"), hash("b"));
+    }
+
+    #[test]
+    fn test_hit_and_miss() {
+        let cache = CompileCache::new(2);
+        let key = hash("foo");
+        assert_eq!(cache.get(&key), None);
+        cache.insert(key, Bytes::from_static(b"wasm"));
+        assert_eq!(cache.get(&key), Some(Bytes::from_static(b"wasm")));
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn test_eviction() {
+        let cache = CompileCache::new(2);
+        let (a, b, c) = (hash("a"), hash("b"), hash("c"));
+        cache.insert(a, Bytes::from_static(b"a"));
+        cache.insert(b, Bytes::from_static(b"b"));
+        // Touch a so that b is the least recently used entry.
+        assert!(cache.get(&a).is_some());
+        cache.insert(c, Bytes::from_static(b"c"));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+    }
+}
@@ -0,0 +1,66 @@
+/// Origins the frontend is served from in production.
+const PRODUCTION_ORIGINS: &[&str] = &["https://oort.rs", "https://www.oort.rs"];
+
+/// Origins allowed to make credentialed cross-origin requests.
+///
+/// Compile jobs need the session affinity cookie, so the service allows
+/// credentials and can't use a wildcard. The frontend's Firebase Hosting
+/// domains are added for the project in `project_id`.
+pub struct AllowedOrigins {
+    origins: Vec<String>,
+}
+
+impl AllowedOrigins {
+    pub fn new(project_id: Option<&str>) -> Self {
+        let mut origins: Vec<String> = PRODUCTION_ORIGINS.iter().map(|x| x.to_string()).collect();
+        if let Some(project_id) = project_id {
+            origins.push(format!("https://{project_id}.web.app"));
+            origins.push(format!("https://{project_id}.firebaseapp.com"));
+        }
+        Self { origins }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("PROJECT_ID").ok().as_deref())
+    }
+
+    /// Returns whether `origin` may make credentialed requests. Local
+    /// development servers are allowed on any port.
+    pub fn contains(&self, origin: &str) -> bool {
+        if self.origins.iter().any(|x| x == origin) {
+            return true;
+        }
+        ["http://localhost", "http://127.0.0.1"].iter().any(|host| {
+            origin.strip_prefix(host).map_or(false, |rest| {
+                rest.is_empty()
+                    || rest
+                        .strip_prefix(':')
+                        .map_or(false, |port| port.parse::<u16>().is_ok())
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_allowed_origins() {
+        let origins = AllowedOrigins::new(Some("oort-dev"));
+        assert!(origins.contains("https://oort.rs"));
+        assert!(origins.contains("https://oort-dev.web.app"));
+        assert!(origins.contains("https://oort-dev.firebaseapp.com"));
+        assert!(origins.contains("http://localhost:8080"));
+        assert!(origins.contains("http://127.0.0.1"));
+
+        assert!(!origins.contains("https://evil.example"));
+        assert!(!origins.contains("https://oort.rs.evil.example"));
+        assert!(!origins.contains("https://other.web.app"));
+        assert!(!origins.contains("http://localhost.evil.example"));
+        assert!(!origins.contains("http://localhost:8080.evil.example"));
+        assert!(!origins.contains("null"));
+
+        assert!(!AllowedOrigins::new(None).contains("https://oort-dev.web.app"));
+    }
+}
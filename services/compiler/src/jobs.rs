@@ -0,0 +1,157 @@
+use bytes::Bytes;
+use oort_proto::{CompileFailure, CompileJobId, CompileStatus};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+const JOB_LIFETIME: Duration = Duration::from_secs(300);
+
+struct Job {
+    status: CompileStatus,
+    wasm: Option<Bytes>,
+    created: Instant,
+}
+
+/// Tracks compiles started through the asynchronous job API.
+pub struct JobTable {
+    jobs: Mutex<HashMap<CompileJobId, Job>>,
+    counter: AtomicU64,
+}
+
+impl JobTable {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    pub fn create(&self) -> CompileJobId {
+        let mut hasher = Sha256::new();
+        hasher.update(self.counter.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+        if let Ok(t) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            hasher.update(t.as_nanos().to_le_bytes());
+        }
+        let id = CompileJobId(
+            hasher.finalize()[0..12]
+                .iter()
+                .map(|x| format!("{x:02x}"))
+                .collect(),
+        );
+
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.retain(|_, job| job.created.elapsed() < JOB_LIFETIME);
+        jobs.insert(
+            id.clone(),
+            Job {
+                status: CompileStatus::Queued,
+                wasm: None,
+                created: Instant::now(),
+            },
+        );
+        id
+    }
+
+    /// Returns false if the job no longer exists, e.g. because it was cancelled.
+    pub fn set_status(&self, id: &CompileJobId, status: CompileStatus) -> bool {
+        match self.jobs.lock().unwrap().get_mut(id) {
+            Some(job) => {
+                job.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn finish(&self, id: &CompileJobId, result: Result<Bytes, CompileFailure>) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            match result {
+                Ok(wasm) => {
+                    job.status = CompileStatus::Done;
+                    job.wasm = Some(wasm);
+                }
+                Err(error) => {
+                    job.status = CompileStatus::Error { error };
+                }
+            }
+        }
+    }
+
+    pub fn status(&self, id: &CompileJobId) -> Option<CompileStatus> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(id)
+            .map(|job| job.status.clone())
+    }
+
+    /// Removes a finished job and returns its compiled wasm.
+    pub fn take_wasm(&self, id: &CompileJobId) -> Option<Bytes> {
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.get(id)?.wasm.as_ref()?;
+        jobs.remove(id).and_then(|job| job.wasm)
+    }
+
+    pub fn cancel(&self, id: &CompileJobId) -> bool {
+        self.jobs.lock().unwrap().remove(id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.jobs.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for JobTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lifecycle() {
+        let jobs = JobTable::new();
+        let id = jobs.create();
+        assert_eq!(jobs.status(&id), Some(CompileStatus::Queued));
+        assert!(jobs.set_status(&id, CompileStatus::Compiling));
+        assert_eq!(jobs.take_wasm(&id), None);
+        jobs.finish(&id, Ok(Bytes::from_static(b"wasm")));
+        assert_eq!(jobs.status(&id), Some(CompileStatus::Done));
+        assert_eq!(jobs.take_wasm(&id), Some(Bytes::from_static(b"wasm")));
+        assert!(jobs.is_empty());
+    }
+
+    #[test]
+    fn test_error() {
+        let jobs = JobTable::new();
+        let id = jobs.create();
+        jobs.finish(&id, Err(CompileFailure::Timeout));
+        assert_eq!(
+            jobs.status(&id),
+            Some(CompileStatus::Error {
+                error: CompileFailure::Timeout
+            })
+        );
+        assert_eq!(jobs.take_wasm(&id), None);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let jobs = JobTable::new();
+        let id = jobs.create();
+        assert_ne!(id, jobs.create());
+        assert!(jobs.cancel(&id));
+        assert!(!jobs.set_status(&id, CompileStatus::Compiling));
+        assert_eq!(jobs.status(&id), None);
+        assert_eq!(jobs.len(), 1);
+    }
+}
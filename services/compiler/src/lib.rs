@@ -1,7 +1,12 @@
+pub mod cache;
+pub mod cors;
+pub mod jobs;
 pub mod sanitizer;
+pub mod status;
 
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
+use oort_proto::CompileFailure;
 
 pub fn error(status_code: StatusCode, msg: String) -> Error {
     Error {
@@ -15,6 +20,26 @@ pub struct Error {
     err: anyhow::Error,
 }
 
+impl Error {
+    pub fn with_status(status_code: StatusCode, err: anyhow::Error) -> Self {
+        Self { status_code, err }
+    }
+
+    /// How the error is reported through the compile job API.
+    pub fn compile_failure(&self) -> CompileFailure {
+        match self.err.downcast_ref::<CompileFailure>() {
+            Some(failure) => failure.clone(),
+            None => CompileFailure::CompileError(self.err.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.err)
+    }
+}
+
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
         if self.status_code == StatusCode::INTERNAL_SERVER_ERROR {
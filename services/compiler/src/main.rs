@@ -1,26 +1,69 @@
-use axum::extract::State;
-use axum::Router;
+use axum::extract::{Path, State};
+use axum::{Json, Router};
 use bytes::Bytes;
 use clap::Parser as _;
 use http::{Method, StatusCode};
 use once_cell::sync::Lazy;
 use oort_compiler::Compiler;
+use oort_compiler_service::cache::{self, CodeHash, CompileCache};
+use oort_compiler_service::cors::AllowedOrigins;
+use oort_compiler_service::jobs::JobTable;
+use oort_compiler_service::status::Metrics;
 use oort_compiler_service::{error, Error};
+use oort_proto::{CompileJobId, CompileStatus, CompilerStatus};
 use std::io::Write;
 use std::sync::{Arc, Mutex};
 use tempfile::NamedTempFile;
 use tokio::process::Command;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
 
 const MAX_CONCURRENCY: usize = 3;
+const CACHE_SIZE: usize = 256;
 static FORMAT_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
 static SEMAPHORE: Lazy<tokio::sync::Semaphore> =
     Lazy::new(|| tokio::sync::Semaphore::new(MAX_CONCURRENCY));
+static CACHE: Lazy<CompileCache> = Lazy::new(|| CompileCache::new(CACHE_SIZE));
+// Jobs are only visible to the instance that created them. The service is
+// deployed with session affinity so a client's polls return here, and with
+// CPU always allocated so jobs keep running after the response is sent.
+// Clients fall back to /compile if a poll reaches another instance.
+static JOBS: Lazy<JobTable> = Lazy::new(JobTable::new);
+static METRICS: Lazy<Metrics> = Lazy::new(|| Metrics::new(MAX_CONCURRENCY));
+
+type SharedCompiler = Arc<Mutex<Compiler>>;
+
+fn decrypt(code: String) -> Result<String, Error> {
+    if oort_code_encryption::is_encrypted(&code) {
+        log::debug!("Encrypted code: {}", code);
+        return Ok(oort_code_encryption::decrypt(&code)?);
+    }
+    Ok(code)
+}
+
+fn lookup_cache(key: &CodeHash) -> Option<Bytes> {
+    let wasm = CACHE.get(key)?;
+    log::info!(
+        "Compile cache hit (hits={} misses={} entries={})",
+        CACHE.hits(),
+        CACHE.misses(),
+        CACHE.len()
+    );
+    Some(wasm)
+}
 
 async fn post_compile(
-    State(compiler): State<Arc<Mutex<Compiler>>>,
-    mut code: String,
+    State(compiler): State<SharedCompiler>,
+    code: String,
 ) -> Result<Bytes, Error> {
+    let code = decrypt(code)?;
+    log::debug!("Code: {}", code);
+
+    let key = cache::hash(&code);
+    if let Some(wasm) = lookup_cache(&key) {
+        return Ok(wasm);
+    }
+
+    let _queued = METRICS.enqueue();
     let permit = SEMAPHORE.try_acquire();
     if permit.is_err() {
         return Err(error(
@@ -29,30 +72,100 @@ async fn post_compile(
         ));
     }
 
-    if oort_code_encryption::is_encrypted(&code) {
-        log::debug!("Encrypted code: {}", code);
-        code = oort_code_encryption::decrypt(&code)?;
-    }
+    compile(compiler, code, key).await
+}
+
+async fn post_compile_job(
+    State(compiler): State<SharedCompiler>,
+    code: String,
+) -> Result<Json<CompileJobId>, Error> {
+    let code = decrypt(code)?;
     log::debug!("Code: {}", code);
+
+    let id = JOBS.create();
+    let key = cache::hash(&code);
+    if let Some(wasm) = lookup_cache(&key) {
+        JOBS.finish(&id, Ok(wasm));
+        return Ok(Json(id));
+    }
+
+    {
+        let id = id.clone();
+        let queued = METRICS.enqueue();
+        tokio::spawn(async move {
+            let _queued = queued;
+            let _permit = SEMAPHORE.acquire().await;
+            if !JOBS.set_status(&id, CompileStatus::Compiling) {
+                log::info!("Compile job {} cancelled before starting", id);
+                return;
+            }
+            let result = compile(compiler, code, key).await;
+            JOBS.finish(&id, result.map_err(|e| e.compile_failure()));
+        });
+    }
+
+    Ok(Json(id))
+}
+
+async fn get_compile_job(Path(id): Path<String>) -> Result<Json<CompileStatus>, Error> {
+    match JOBS.status(&CompileJobId(id)) {
+        Some(status) => Ok(Json(status)),
+        None => Err(error(
+            StatusCode::NOT_FOUND,
+            "Unknown compile job".to_string(),
+        )),
+    }
+}
+
+async fn get_compile_job_wasm(Path(id): Path<String>) -> Result<Bytes, Error> {
+    match JOBS.take_wasm(&CompileJobId(id)) {
+        Some(wasm) => Ok(wasm),
+        None => Err(error(
+            StatusCode::NOT_FOUND,
+            "Unknown compile job".to_string(),
+        )),
+    }
+}
+
+async fn delete_compile_job(Path(id): Path<String>) {
+    let id = CompileJobId(id);
+    if JOBS.cancel(&id) {
+        log::info!("Cancelled compile job {}", id);
+    }
+}
+
+async fn compile(compiler: SharedCompiler, code: String, key: CodeHash) -> Result<Bytes, Error> {
     oort_compiler_service::sanitizer::check(&code)?;
     let start_time = std::time::Instant::now();
     let result = tokio::runtime::Handle::current()
         .spawn_blocking(move || compiler.lock().unwrap().compile(&code))
         .await?;
     let elapsed = std::time::Instant::now() - start_time;
+    METRICS.record_compile(elapsed);
     match result {
         Ok(wasm) => {
-            log::info!("Compile succeeded in {:?}", elapsed);
-            Ok(Bytes::copy_from_slice(&wasm))
+            log::info!(
+                "Compile succeeded in {:?} (cache hits={} misses={})",
+                elapsed,
+                CACHE.hits(),
+                CACHE.misses()
+            );
+            let wasm = Bytes::copy_from_slice(&wasm);
+            CACHE.insert(key, wasm.clone());
+            Ok(wasm)
         }
         Err(e) => {
             log::info!("Compile failed in {:?}", elapsed);
             log::debug!("Compile failed: {}", e);
-            Err(error(StatusCode::BAD_REQUEST, e.to_string()))
+            Err(Error::with_status(StatusCode::BAD_REQUEST, e))
         }
     }
 }
 
+async fn get_status() -> Json<CompilerStatus> {
+    Json(METRICS.status(env!("CARGO_PKG_VERSION")))
+}
+
 async fn post_format(code: String) -> Result<String, Error> {
     let _guard = FORMAT_LOCK.lock().await;
     let mut tmpfile = NamedTempFile::new()?;
@@ -114,15 +227,29 @@ async fn main() {
     log::info!("Starting oort_compiler_service v1");
 
     let cors = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-        .allow_origin(Any)
-        .allow_headers(Any);
+        .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
+        .allow_origin({
+            let origins = AllowedOrigins::from_env();
+            AllowOrigin::predicate(move |origin, _| {
+                origin.to_str().map_or(false, |x| origins.contains(x))
+            })
+        })
+        .allow_headers(AllowHeaders::mirror_request())
+        // Needed for the session affinity cookie used by compile jobs.
+        .allow_credentials(true);
 
     let router = {
-        use axum::routing::post;
+        use axum::routing::{get, post};
         Router::new()
             .route("/compile", post(post_compile))
+            .route("/compile/jobs", post(post_compile_job))
+            .route(
+                "/compile/jobs/:id",
+                get(get_compile_job).delete(delete_compile_job),
+            )
+            .route("/compile/jobs/:id/wasm", get(get_compile_job_wasm))
             .route("/format", post(post_format))
+            .route("/status", get(get_status))
             .layer(cors)
             .layer(tower_http::trace::TraceLayer::new_for_http())
             .with_state(Arc::new(Mutex::new(compiler)))
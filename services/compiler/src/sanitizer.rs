@@ -72,6 +72,25 @@ mod tests {
         assert!(check("... include_str! ...").is_err());
     }
 
+    #[test]
+    fn test_dependencies() {
+        // Only oort_api is passed to rustc, so `use` of other crates fails
+        // to compile.
+        assert!(check("extern crate serde;").is_err());
+    }
+
+    #[test]
+    fn test_include_absolute_path() {
+        assert!(check("const X: &str = include_str!(\"/etc/passwd\");").is_err());
+        assert!(check("include!(\"/etc/passwd\");").is_err());
+    }
+
+    #[test]
+    fn test_no_mangle() {
+        assert!(check("#[no_mangle] pub fn tick() {}").is_err());
+        assert!(check("#[export_name = \"tick\"] pub fn f() {}").is_err());
+    }
+
     #[test]
     fn test_inside_words() {
         assert!(check("... foounsafe {} ...").is_ok());
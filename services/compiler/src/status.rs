@@ -0,0 +1,109 @@
+use oort_proto::CompilerStatus;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+const RECENT_COMPILES: usize = 20;
+
+/// Request accounting reported by the /status endpoint.
+pub struct Metrics {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    queue_depth: usize,
+    recent_compile_ms: VecDeque<f64>,
+}
+
+/// Counts a compile in the queue depth until dropped.
+pub struct QueueGuard<'a> {
+    metrics: &'a Metrics,
+}
+
+impl Drop for QueueGuard<'_> {
+    fn drop(&mut self) {
+        self.metrics.inner.lock().unwrap().queue_depth -= 1;
+    }
+}
+
+impl Metrics {
+    /// `capacity` is the number of compiles that can run at once. The
+    /// service is unhealthy while more than that are queued.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    pub fn enqueue(&self) -> QueueGuard {
+        self.inner.lock().unwrap().queue_depth += 1;
+        QueueGuard { metrics: self }
+    }
+
+    pub fn record_compile(&self, elapsed: Duration) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.recent_compile_ms.len() >= RECENT_COMPILES {
+            inner.recent_compile_ms.pop_front();
+        }
+        inner
+            .recent_compile_ms
+            .push_back(elapsed.as_secs_f64() * 1e3);
+    }
+
+    pub fn status(&self, version: &str) -> CompilerStatus {
+        let inner = self.inner.lock().unwrap();
+        let avg_compile_ms = (!inner.recent_compile_ms.is_empty()).then(|| {
+            inner.recent_compile_ms.iter().sum::<f64>() / inner.recent_compile_ms.len() as f64
+        });
+        CompilerStatus {
+            healthy: inner.queue_depth <= self.capacity,
+            queue_depth: inner.queue_depth,
+            version: version.to_string(),
+            avg_compile_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_depth() {
+        let metrics = Metrics::new(1);
+        assert_eq!(metrics.status("v").queue_depth, 0);
+        assert!(metrics.status("v").healthy);
+
+        let a = metrics.enqueue();
+        assert_eq!(metrics.status("v").queue_depth, 1);
+        assert!(metrics.status("v").healthy);
+
+        let b = metrics.enqueue();
+        assert_eq!(metrics.status("v").queue_depth, 2);
+        assert!(!metrics.status("v").healthy);
+
+        drop(a);
+        drop(b);
+        assert_eq!(metrics.status("v").queue_depth, 0);
+        assert!(metrics.status("v").healthy);
+    }
+
+    #[test]
+    fn test_avg_compile_ms() {
+        let metrics = Metrics::new(1);
+        assert_eq!(metrics.status("v").avg_compile_ms, None);
+        metrics.record_compile(Duration::from_millis(100));
+        metrics.record_compile(Duration::from_millis(300));
+        assert_eq!(metrics.status("v").avg_compile_ms, Some(200.0));
+
+        // Only recent compiles count.
+        for _ in 0..RECENT_COMPILES {
+            metrics.record_compile(Duration::from_millis(50));
+        }
+        assert_eq!(metrics.status("v").avg_compile_ms, Some(50.0));
+        assert_eq!(metrics.status("v").version, "v");
+    }
+}
@@ -3,10 +3,40 @@
 
 #[doc(hidden)]
 pub mod panic;
+#[doc(hidden)]
+pub mod reference;
 mod vec;
 
+/// The current version of the API.
+///
+/// Code can declare the version it was written against with a comment like
+/// `// oort-api: 1` at the top. Functions renamed since then keep working
+/// but print a deprecation warning in the ship's debug text.
+///
+/// - 1: Original API (`aim_gun`, `fire_gun`, `launch_missile`, `debug_line`, ...).
+/// - 2: Weapons unified under `aim`/`fire`, debug drawing renamed to `draw_*`.
+pub const API_VERSION: u32 = 2;
+
+/// Returns the API version declared by an `// oort-api: N` comment before
+/// any code, or `None` if there isn't one.
+#[doc(hidden)]
+pub fn declared_api_version(code: &str) -> Option<u32> {
+    for line in code.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let comment = line.strip_prefix("//")?;
+        if let Some(version) = comment.trim().strip_prefix("oort-api:") {
+            return version.trim().parse().ok();
+        }
+    }
+    None
+}
+
 #[allow(missing_docs)]
 #[derive(Copy, Clone)]
+#[repr(u16)]
 pub enum SystemState {
     Class,
     Seed,
@@ -145,10 +175,53 @@ pub enum SystemState {
 
     Id,
 
+    Team,
+    AllyCount,
+    EnemyCount,
+
+    RadarContactAge,
+
+    ScriptTickLength,
+
+    MuzzleSpeed0,
+    MuzzleSpeed1,
+    MuzzleSpeed2,
+    MuzzleSpeed3,
+
+    Inaccuracy0,
+    Inaccuracy1,
+    Inaccuracy2,
+    Inaccuracy3,
+
+    AutoTarget0,
+    AutoTarget1,
+    AutoTarget2,
+    AutoTarget3,
+
+    RadarCrossSection,
+
+    Shield,
+    ActivateShieldBoost,
+
+    Spread0,
+    Spread1,
+    Spread2,
+    Spread3,
+
+    Ammo0,
+    Ammo1,
+    Ammo2,
+    Ammo3,
+
     Size,
-    MaxSize = 128,
+    // Raised from 128 once Size outgrew it. The guest exports this as
+    // SYSTEM_STATE_SIZE; modules built before that don't, and the simulator
+    // treats them as having 128 slots.
+    MaxSize = 256,
 }
 
+const _: () = assert!(SystemState::Size as usize <= SystemState::MaxSize as usize);
+
 #[allow(missing_docs)]
 pub const MAX_ENVIRONMENT_SIZE: usize = 1024;
 
@@ -297,6 +370,11 @@ pub mod sys {
     pub static mut SYSTEM_STATE: [u64; SystemState::MaxSize as usize] =
         [0; SystemState::MaxSize as usize];
 
+    /// Number of u64 slots in SYSTEM_STATE, read by the simulator so it never
+    /// copies past the end of the array.
+    #[no_mangle]
+    pub static SYSTEM_STATE_SIZE: u32 = SystemState::MaxSize as u32;
+
     pub fn read_system_state_u64(index: SystemState) -> u64 {
         let system_state = unsafe { &SYSTEM_STATE };
         system_state[index as usize]
@@ -343,6 +421,7 @@ pub mod sys {
 }
 
 mod math {
+    use crate::vec::*;
     pub use std::f64::consts::{PI, TAU};
 
     /// Returns the smallest rotation between angles `a` and `b`.
@@ -356,6 +435,72 @@ mod math {
             c
         }
     }
+
+    /// Returns the equivalent angle in the range (-π, π].
+    pub fn normalize_angle(a: f64) -> f64 {
+        angle_diff(0.0, a)
+    }
+
+    /// Returns `x` limited to the range [`lo`, `hi`].
+    ///
+    /// Unlike [`f64::clamp`] this doesn't panic if `lo` is greater than `hi`,
+    /// in which case the result is `hi`.
+    pub fn clamp(x: f64, lo: f64, hi: f64) -> f64 {
+        x.max(lo).min(hi)
+    }
+
+    /// Linearly interpolates between `a` and `b`. `t` = 0 returns `a` and `t`
+    /// = 1 returns `b`.
+    pub fn lerp(a: f64, b: f64, t: f64) -> f64 {
+        a + (b - a) * t
+    }
+
+    /// Returns the time until two objects are closest, given the position
+    /// and velocity of one relative to the other.
+    ///
+    /// The result is never negative: if the objects are moving apart, or not
+    /// moving relative to each other, they're closest now and this returns 0.
+    pub fn cpa_time(rel_pos: Vec2, rel_vel: Vec2) -> f64 {
+        let speed_sq = rel_vel.dot(rel_vel);
+        if speed_sq == 0.0 {
+            return 0.0;
+        }
+        (-rel_pos.dot(rel_vel) / speed_sq).max(0.0)
+    }
+
+    /// Returns the distance between two objects at their closest point of
+    /// approach. See [`cpa_time`].
+    pub fn cpa_distance(rel_pos: Vec2, rel_vel: Vec2) -> f64 {
+        (rel_pos + rel_vel * cpa_time(rel_pos, rel_vel)).length()
+    }
+
+    /// Returns how long a bullet fired now at `bullet_speed` takes to hit a
+    /// target with the given position and velocity relative to the shooter,
+    /// or `None` if it can never catch up.
+    ///
+    /// The target is assumed to keep a constant velocity. Aim at
+    /// `rel_pos + rel_vel * t` to hit it.
+    pub fn intercept_time(rel_pos: Vec2, rel_vel: Vec2, bullet_speed: f64) -> Option<f64> {
+        // Solve |rel_pos + rel_vel * t| = bullet_speed * t for t.
+        let a = rel_vel.dot(rel_vel) - bullet_speed * bullet_speed;
+        let b = 2.0 * rel_pos.dot(rel_vel);
+        let c = rel_pos.dot(rel_pos);
+        if c == 0.0 {
+            return Some(0.0);
+        }
+        if a.abs() < 1e-9 {
+            // Target and bullet speeds match, so the equation is linear.
+            return if b < 0.0 { Some(-c / b) } else { None };
+        }
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let sqrt_discriminant = discriminant.sqrt();
+        let t0 = (-b - sqrt_discriminant) / (2.0 * a);
+        let t1 = (-b + sqrt_discriminant) / (2.0 * a);
+        [t0.min(t1), t0.max(t1)].into_iter().find(|&t| t > 0.0)
+    }
 }
 
 mod rng {
@@ -411,6 +556,27 @@ mod api {
         read_system_state(SystemState::Id) as u32
     }
 
+    /// Returns the team this ship is on.
+    pub fn team() -> i32 {
+        read_system_state(SystemState::Team) as i32
+    }
+
+    /// Returns the number of ships on this team, including this one.
+    ///
+    /// Ships are counted across the whole world, not just within radar range.
+    /// Missiles and torpedoes are included.
+    pub fn ally_count() -> u32 {
+        read_system_state(SystemState::AllyCount) as u32
+    }
+
+    /// Returns the number of ships on other teams.
+    ///
+    /// Ships are counted across the whole world, not just within radar range.
+    /// Missiles and torpedoes are included but asteroids are not.
+    pub fn enemy_count() -> u32 {
+        read_system_state(SystemState::EnemyCount) as u32
+    }
+
     /// Returns the ship [`Class`] (Fighter, Cruiser, etc).
     pub fn class() -> Class {
         Class::from_f64(read_system_state(SystemState::Class))
@@ -434,6 +600,33 @@ mod api {
             .unwrap_or(0.0)
     }
 
+    /// Returns the vector from this ship to the closest point on the walls
+    /// at the edge of the world.
+    ///
+    /// Returns `None` in scenarios without walls, where the world wraps
+    /// around or is open.
+    pub fn nearest_wall() -> Option<Vec2> {
+        if super::sys::getenv("WORLD_TOPOLOGY").unwrap_or("Walled") != "Walled" {
+            return None;
+        }
+        let p = position();
+        let h = world_size() / 2.0;
+        [
+            vec2(h - p.x, 0.0),
+            vec2(-h - p.x, 0.0),
+            vec2(0.0, h - p.y),
+            vec2(0.0, -h - p.y),
+        ]
+        .into_iter()
+        .min_by(|a, b| a.length().total_cmp(&b.length()))
+    }
+
+    /// Returns the distance (in meters) to the nearest wall, or infinity in
+    /// scenarios without walls.
+    pub fn boundary_distance() -> f64 {
+        nearest_wall().map_or(f64::INFINITY, |v| v.length())
+    }
+
     /// Returns the current position (in meters).
     pub fn position() -> Vec2 {
         vec2(
@@ -461,8 +654,21 @@ mod api {
     }
 
     /// Sets the linear acceleration for the next tick (in m/s²).
-    pub fn accelerate(mut acceleration: Vec2) {
-        acceleration = acceleration.rotate(-heading());
+    ///
+    /// The acceleration is in the world frame. Same as `accelerate_world()`.
+    pub fn accelerate(acceleration: Vec2) {
+        accelerate_world(acceleration);
+    }
+
+    /// Sets the linear acceleration for the next tick in the world frame (in m/s²).
+    pub fn accelerate_world(acceleration: Vec2) {
+        accelerate_local(acceleration.rotate(-heading()));
+    }
+
+    /// Sets the linear acceleration for the next tick in the ship's frame (in m/s²).
+    ///
+    /// Positive x is forward and positive y is to the left.
+    pub fn accelerate_local(mut acceleration: Vec2) {
         if acceleration.x > max_forward_acceleration() {
             acceleration *= max_forward_acceleration() / acceleration.x;
         }
@@ -508,7 +714,10 @@ mod api {
 
     /// Fires a weapon.
     ///
-    /// `index` selects the weapon.
+    /// `index` selects the weapon. Guns come first, then missile launchers.
+    /// Lasers start at index 3, so a fighter's laser is `fire(3)`. A laser
+    /// instantly damages the first enemy ship along its beam, out to its
+    /// range, but takes much longer to reload than a gun.
     pub fn fire(index: usize) {
         let state_index = match index {
             0 => SystemState::Fire0,
@@ -534,6 +743,96 @@ mod api {
         read_system_state(state_index) as u32
     }
 
+    /// Returns the speed (in m/s) of a projectile fired from a weapon,
+    /// relative to this ship.
+    ///
+    /// Projectiles inherit the ship's velocity, so in the world frame they
+    /// move at `velocity()` plus this speed along the firing direction.
+    /// Returns 0 if there is no weapon at `index`.
+    pub fn weapon_muzzle_speed(index: usize) -> f64 {
+        let state_index = match index {
+            0 => SystemState::MuzzleSpeed0,
+            1 => SystemState::MuzzleSpeed1,
+            2 => SystemState::MuzzleSpeed2,
+            3 => SystemState::MuzzleSpeed3,
+            _ => return 0.0,
+        };
+        read_system_state(state_index)
+    }
+
+    /// Returns the maximum random deviation (in radians) of a projectile's
+    /// direction from where a weapon is aimed.
+    ///
+    /// Each bullet is fired at a uniformly random angle within this far of
+    /// the aim direction, so hitting a small target gets harder with range.
+    /// Returns 0 for weapons without spread.
+    pub fn weapon_inaccuracy(index: usize) -> f64 {
+        let state_index = match index {
+            0 => SystemState::Inaccuracy0,
+            1 => SystemState::Inaccuracy1,
+            2 => SystemState::Inaccuracy2,
+            3 => SystemState::Inaccuracy3,
+            _ => return 0.0,
+        };
+        read_system_state(state_index)
+    }
+
+    /// Returns the standard deviation, in radians, of the gaussian noise
+    /// added to the direction of each bullet from a weapon.
+    ///
+    /// About two thirds of bullets land within this angle of the aim
+    /// direction. Returns 0 for weapons without spread.
+    pub fn weapon_spread(index: usize) -> f64 {
+        let state_index = match index {
+            0 => SystemState::Spread0,
+            1 => SystemState::Spread1,
+            2 => SystemState::Spread2,
+            3 => SystemState::Spread3,
+            _ => return 0.0,
+        };
+        read_system_state(state_index)
+    }
+
+    /// Returns the number of shots left in a weapon, or `None` if its ammo
+    /// is unlimited.
+    ///
+    /// Guns count bullets. Calling [`fire`] on an empty weapon does nothing.
+    /// Returns `Some(0)` if there is no weapon at `index`.
+    pub fn ammo(index: usize) -> Option<u32> {
+        let state_index = match index {
+            0 => SystemState::Ammo0,
+            1 => SystemState::Ammo1,
+            2 => SystemState::Ammo2,
+            3 => SystemState::Ammo3,
+            _ => return Some(0),
+        };
+        let ammo = read_system_state(state_index);
+        if ammo.is_infinite() {
+            None
+        } else {
+            Some(ammo as u32)
+        }
+    }
+
+    /// Makes a turreted weapon automatically aim and fire at incoming
+    /// enemies of `class`, or turns that off with `None`.
+    ///
+    /// Each tick after your code runs, the weapon fires at the enemy of that
+    /// class it can hit soonest, if one is in range and within the turret's
+    /// traverse. It uses the weapon's normal reload and doesn't need radar.
+    /// Calling [`fire`] on the weapon still works, and takes the reload
+    /// first.
+    pub fn set_weapon_auto(index: usize, class: Option<Class>) {
+        let state_index = match index {
+            0 => SystemState::AutoTarget0,
+            1 => SystemState::AutoTarget1,
+            2 => SystemState::AutoTarget2,
+            3 => SystemState::AutoTarget3,
+            _ => return,
+        };
+        write_system_state(state_index, class.map_or(0.0, |c| c as u32 as f64 + 1.0));
+    }
+
     /// Self-destructs, producing a damaging explosion.
     ///
     /// This is commonly used by missiles.
@@ -551,6 +850,41 @@ mod api {
         read_system_state(SystemState::Fuel)
     }
 
+    /// Returns the remaining shield points.
+    ///
+    /// Shields absorb bullet and explosion damage before health and recharge
+    /// once the ship has gone a while without being hit. Ships without a
+    /// shield always return zero.
+    pub fn shield() -> f64 {
+        read_system_state(SystemState::Shield)
+    }
+
+    /// Doubles the shield's recharge rate for a short time.
+    ///
+    /// The boost has a cooldown; calls while it's reloading are ignored.
+    pub fn activate_shield_boost() {
+        write_system_state(SystemState::ActivateShieldBoost, 1.0);
+    }
+
+    /// Returns the radar cross-section enemy radars see for this ship.
+    pub fn radar_cross_section() -> f64 {
+        read_system_state(SystemState::RadarCrossSection)
+    }
+
+    /// Sets the radar cross-section enemy radars see for this ship.
+    ///
+    /// The detection range of a radar scales with the fourth root of the
+    /// target's cross-section, so a ship at a quarter of its normal
+    /// cross-section is seen at about 70% of the range. The value is clamped
+    /// between a quarter of the ship class's cross-section and the full
+    /// value.
+    ///
+    /// Running quiet isn't free: the ship's acceleration is scaled by the
+    /// same fraction as its cross-section.
+    pub fn set_radar_cross_section(value: f64) {
+        write_system_state(SystemState::RadarCrossSection, value);
+    }
+
     /// Returns the heading the radar is pointed at.
     pub fn radar_heading() -> f64 {
         read_system_state(SystemState::RadarHeading)
@@ -647,6 +981,41 @@ mod api {
         })
     }
 
+    /// Returns the most recent radar contact, even if it wasn't seen this tick.
+    ///
+    /// The position and velocity are as measured when the contact was last
+    /// seen and are not extrapolated. Use [`last_contact_age`] to coast on
+    /// them, e.g. `position + velocity * age as f64 * TICK_LENGTH`.
+    pub fn last_contact() -> Option<ScanResult> {
+        last_contact_age()?;
+        Some(ScanResult {
+            class: Class::from_f64(read_system_state(SystemState::RadarContactClass)),
+            position: vec2(
+                read_system_state(SystemState::RadarContactPositionX),
+                read_system_state(SystemState::RadarContactPositionY),
+            ),
+            velocity: vec2(
+                read_system_state(SystemState::RadarContactVelocityX),
+                read_system_state(SystemState::RadarContactVelocityY),
+            ),
+            rssi: read_system_state(SystemState::RadarContactRssi),
+            snr: read_system_state(SystemState::RadarContactSnr),
+        })
+    }
+
+    /// Returns how many ticks ago [`last_contact`] was seen, or `None` if the
+    /// radar has never seen anything.
+    ///
+    /// An age of 0 means the contact was seen this tick.
+    pub fn last_contact_age() -> Option<u32> {
+        let age = read_system_state(SystemState::RadarContactAge);
+        if age < 0.0 {
+            None
+        } else {
+            Some(age as u32)
+        }
+    }
+
     #[doc(hidden)]
     pub mod radio_internal {
         use super::SystemState;
@@ -664,8 +1033,9 @@ mod api {
             assert!(sel < MAX_RADIOS);
             let stride = 7;
             let offset = stride * sel;
-            let add_offset =
-                |x| unsafe { ::std::mem::transmute::<u8, SystemState>((x as u8) + offset as u8) };
+            let add_offset = |x| unsafe {
+                ::std::mem::transmute::<u16, SystemState>((x as u16) + offset as u16)
+            };
             RadioIndices {
                 channel: add_offset(SystemState::Radio0Channel),
                 send: add_offset(SystemState::Radio0Send),
@@ -780,6 +1150,7 @@ mod api {
     /// Returns the maximum linear acceleration (in m/s²).
     #[deprecated]
     pub fn max_acceleration() -> Vec2 {
+        crate::dbg::warn_deprecated("max_acceleration", "max_forward_acceleration");
         vec2(
             read_system_state(SystemState::MaxForwardAcceleration),
             read_system_state(SystemState::MaxBackwardAcceleration),
@@ -816,6 +1187,18 @@ mod api {
         read_system_state(SystemState::CurrentTick) * TICK_LENGTH
     }
 
+    /// Returns the time (in seconds) between calls to `tick`.
+    ///
+    /// This is usually `TICK_LENGTH`, but the simulation may run scripts less
+    /// often than physics. Commands like `accelerate` and `torque` stay in
+    /// effect until the next call.
+    pub fn tick_length() -> f64 {
+        match read_system_state(SystemState::ScriptTickLength) {
+            x if x > 0.0 => x,
+            _ => TICK_LENGTH,
+        }
+    }
+
     /// Activates a special ability.
     pub fn activate_ability(ability: Ability) {
         let mut active_abilities =
@@ -839,11 +1222,16 @@ mod api {
 
     /// Returns the position of the target set by the scenario.
     /// Only used in tutorials.
+    ///
+    /// Same as `scenario_var_vec2("target")` in scenarios that set that
+    /// variable.
     pub fn target() -> Vec2 {
-        vec2(
-            read_system_state(SystemState::RadarContactPositionX),
-            read_system_state(SystemState::RadarContactPositionY),
-        )
+        scenario_var_vec2("target").unwrap_or_else(|| {
+            vec2(
+                read_system_state(SystemState::RadarContactPositionX),
+                read_system_state(SystemState::RadarContactPositionY),
+            )
+        })
     }
 
     /// Returns the velocity of the target set by the scenario.
@@ -854,6 +1242,85 @@ mod api {
             read_system_state(SystemState::RadarContactVelocityY),
         )
     }
+
+    /// A location defined by the scenario.
+    #[derive(Clone, Debug)]
+    pub struct Marker {
+        /// The marker's center.
+        pub position: Vec2,
+        /// The marker's radius in meters.
+        pub radius: f64,
+    }
+
+    /// Returns the marker with the given name, if the scenario defined one.
+    ///
+    /// Markers are drawn as circles. The one named "target" is also returned
+    /// by [`target`].
+    pub fn get_marker(name: &str) -> Option<Marker> {
+        let value = super::sys::getenv(&format!("MARKER_{}", name))?;
+        let mut parts = value.split(',').map(|x| x.parse::<f64>().ok());
+        let (x, y, radius) = (parts.next()??, parts.next()??, parts.next()??);
+        Some(Marker {
+            position: vec2(x, y),
+            radius,
+        })
+    }
+
+    /// A value passed to scripts by the scenario.
+    #[derive(Copy, Clone, Debug)]
+    pub enum ScenarioVar {
+        /// A vector, written as "x,y".
+        Vec2(Vec2),
+        /// A number.
+        F64(f64),
+        /// "true" or "false".
+        Bool(bool),
+    }
+
+    impl ScenarioVar {
+        fn parse(value: &str) -> Option<ScenarioVar> {
+            match value {
+                "true" => Some(ScenarioVar::Bool(true)),
+                "false" => Some(ScenarioVar::Bool(false)),
+                _ => match value.split_once(',') {
+                    Some((x, y)) => Some(ScenarioVar::Vec2(vec2(x.parse().ok()?, y.parse().ok()?))),
+                    None => value.parse().ok().map(ScenarioVar::F64),
+                },
+            }
+        }
+    }
+
+    /// Returns the scenario variable with the given name, if the scenario
+    /// set one.
+    ///
+    /// The scenario's variables are listed in the Quick Reference.
+    pub fn scenario_var(name: &str) -> Option<ScenarioVar> {
+        ScenarioVar::parse(super::sys::getenv(&format!("VAR_{}", name))?)
+    }
+
+    /// Returns a scenario variable if it's a [`Vec2`].
+    pub fn scenario_var_vec2(name: &str) -> Option<Vec2> {
+        match scenario_var(name)? {
+            ScenarioVar::Vec2(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns a scenario variable if it's a number.
+    pub fn scenario_var_f64(name: &str) -> Option<f64> {
+        match scenario_var(name)? {
+            ScenarioVar::F64(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Returns a scenario variable if it's a boolean.
+    pub fn scenario_var_bool(name: &str) -> Option<bool> {
+        match scenario_var(name)? {
+            ScenarioVar::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -867,6 +1334,7 @@ pub mod dbg {
     static mut TEXT_BUFFER: String = String::new();
     static mut LINE_BUFFER: Vec<Line> = Vec::new();
     static mut DRAWN_TEXT_BUFFER: Vec<Text> = Vec::new();
+    static mut DEPRECATION_WARNINGS: Vec<(&str, String)> = Vec::new();
 
     /// Adds text to be displayed when the ship is selected by clicking on it.
     ///
@@ -916,6 +1384,7 @@ pub mod dbg {
     #[deprecated]
     #[doc(hidden)]
     pub fn debug_line(a: Vec2, b: Vec2, color: u32) {
+        warn_deprecated("debug_line", "draw_line");
         draw_line(a, b, color)
     }
 
@@ -939,6 +1408,7 @@ pub mod dbg {
     #[deprecated]
     #[doc(hidden)]
     pub fn debug_polygon(center: Vec2, radius: f64, sides: i32, angle: f64, color: u32) {
+        warn_deprecated("debug_polygon", "draw_polygon");
         draw_polygon(center, radius, sides, angle, color)
     }
 
@@ -953,6 +1423,7 @@ pub mod dbg {
     #[deprecated]
     #[doc(hidden)]
     pub fn debug_triangle(center: Vec2, radius: f64, color: u32) {
+        warn_deprecated("debug_triangle", "draw_triangle");
         draw_triangle(center, radius, color)
     }
 
@@ -967,6 +1438,7 @@ pub mod dbg {
     #[deprecated]
     #[doc(hidden)]
     pub fn debug_square(center: Vec2, radius: f64, color: u32) {
+        warn_deprecated("debug_square", "draw_square");
         draw_square(center, radius, color)
     }
 
@@ -981,6 +1453,7 @@ pub mod dbg {
     #[deprecated]
     #[doc(hidden)]
     pub fn debug_diamond(center: Vec2, radius: f64, color: u32) {
+        warn_deprecated("debug_diamond", "draw_diamond");
         draw_diamond(center, radius, color)
     }
 
@@ -1059,7 +1532,24 @@ pub mod dbg {
             TEXT_BUFFER.clear();
             LINE_BUFFER.clear();
             DRAWN_TEXT_BUFFER.clear();
+            for (_, warning) in DEPRECATION_WARNINGS.iter() {
+                TEXT_BUFFER.push_str(warning);
+                TEXT_BUFFER.push('\n');
+            }
+        }
+    }
+
+    /// Warns once that a function from an older API version was called. The
+    /// warning stays at the top of the debug text from then on.
+    #[doc(hidden)]
+    pub fn warn_deprecated(old: &'static str, replacement: &str) {
+        let warnings = unsafe { &mut DEPRECATION_WARNINGS };
+        if warnings.iter().any(|(name, _)| *name == old) {
+            return;
         }
+        let warning = format!("Warning: {old} is deprecated, use {replacement} instead");
+        write(format_args!("{warning}"));
+        warnings.push((old, warning));
     }
 }
 
@@ -1071,18 +1561,21 @@ mod deprecated {
     /// TODO Remove this.
     #[deprecated]
     pub fn aim_gun(index: usize, heading: f64) {
+        super::dbg::warn_deprecated("aim_gun", "aim");
         aim(index, heading);
     }
 
     /// TODO Remove this.
     #[deprecated]
     pub fn fire_gun(index: usize) {
+        super::dbg::warn_deprecated("fire_gun", "fire");
         fire(index);
     }
 
     /// TODO Remove this.
     #[deprecated]
     pub fn launch_missile(index: usize, _unused: f64) {
+        super::dbg::warn_deprecated("launch_missile", "fire");
         use super::Class::*;
         let state_index = match (class(), index) {
             (Fighter, 0) => SystemState::Fire1,
@@ -1101,6 +1594,7 @@ mod deprecated {
     /// TODO Remove this.
     #[deprecated]
     pub fn orders() -> f64 {
+        super::dbg::warn_deprecated("orders", "radio messages");
         0.0
     }
 }
@@ -0,0 +1,223 @@
+//! The list of API functions shown in the in-game quick reference.
+
+/// A group of related API functions.
+pub struct Section {
+    pub name: &'static str,
+    pub entries: &'static [Entry],
+}
+
+/// A function (or type) with its signature and a one-line description.
+pub struct Entry {
+    pub signature: &'static str,
+    pub description: &'static str,
+}
+
+impl Entry {
+    /// Returns true if the signature contains `query`, ignoring case.
+    pub fn matches(&self, query: &str) -> bool {
+        self.signature
+            .to_lowercase()
+            .contains(&query.trim().to_lowercase())
+    }
+}
+
+macro_rules! entry {
+    ($signature:expr, $description:expr) => {
+        Entry {
+            signature: $signature,
+            description: $description,
+        }
+    };
+}
+
+pub const SECTIONS: &[Section] = &[
+    Section {
+        name: "Ship Status and Control",
+        entries: &[
+            entry!("class() → Class", "Returns the ship class."),
+            entry!("position() → Vec2", "Get the current position in meters."),
+            entry!("velocity() → Vec2", "Get the current velocity in m/s."),
+            entry!("heading() → f64", "Get the current heading in radians."),
+            entry!("angular_velocity() → f64", "Get the current angular velocity in radians/s."),
+            entry!("health() → f64", "Current health."),
+            entry!("fuel() → f64", "Current fuel (delta-v)."),
+            entry!("shield() → f64", "Remaining shield points. Shields absorb damage before health and recharge when not under fire."),
+            entry!("activate_shield_boost()", "Briefly double the shield's recharge rate. Has a cooldown."),
+            entry!("accelerate(acceleration: Vec2)", "Accelerate the ship. Units are m/s²."),
+            entry!("accelerate_world(acceleration: Vec2)", "Same as accelerate."),
+            entry!("accelerate_local(acceleration: Vec2)", "Accelerate the ship in its own frame, where +x is forward and +y is left."),
+            entry!("turn(speed: f64)", "Rotate the ship. Unit is radians/s."),
+            entry!("torque(acceleration: f64)", "Angular acceleration. Unit is radians/s²."),
+            entry!("max_forward_acceleration() → f64", "Maximum forward acceleration."),
+            entry!("max_backward_acceleration() → f64", "Maximum backward acceleration."),
+            entry!("max_lateral_acceleration() → f64", "Maximum lateral acceleration."),
+            entry!("max_angular_acceleration() → f64", "Maximum angular acceleration."),
+        ],
+    },
+    Section {
+        name: "Weapons",
+        entries: &[
+            entry!("fire(index: usize)", "Fire a weapon (gun, missile launcher or laser)."),
+            entry!("aim(index: usize, angle: f64)", "Aim a weapon (for weapons on a turret)."),
+            entry!("weapon_muzzle_speed(index: usize) → f64", "Get a weapon's projectile speed relative to this ship."),
+            entry!("weapon_inaccuracy(index: usize) → f64", "Get a weapon's maximum random spread in radians."),
+            entry!("weapon_spread(index: usize) → f64", "Get the standard deviation of a weapon's bullet directions in radians."),
+            entry!("reload_ticks(index: usize) → u32", "Get the number of ticks until a weapon is ready to fire."),
+            entry!("ammo(index: usize) → Option<u32>", "Get the number of shots left in a weapon, or None if unlimited."),
+            entry!("set_weapon_auto(index: usize, class: Option<Class>)", "Automatically shoot at incoming enemies of a class, e.g. missiles."),
+            entry!("explode()", "Self-destruct."),
+        ],
+    },
+    Section {
+        name: "Radar",
+        entries: &[
+            entry!("set_radar_heading(angle: f64)", "Point the radar at the given heading."),
+            entry!("radar_heading() → f64", "Get current radar heading."),
+            entry!("set_radar_width(width: f64)", "Adjust the width of the radar beam (in radians)."),
+            entry!("radar_width() → f64", "Get current radar width."),
+            entry!("scan() → Option<ScanResult>", "Find an enemy ship illuminated by the radar."),
+            entry!("struct ScanResult { position: Vec2, velocity: Vec2 }", "Returned by scan."),
+            entry!("last_contact() → Option<ScanResult>", "Returns the most recent radar contact, even if it wasn't seen this tick."),
+            entry!("last_contact_age() → Option<u32>", "Returns how many ticks ago last_contact was seen. 0 means this tick."),
+        ],
+    },
+    Section {
+        name: "Advanced Radar",
+        entries: &[
+            entry!("set_radar_min_distance(dist: f64)", "Set the minimum distance filter."),
+            entry!("radar_min_distance() → f64", "Get current minimum distance filter."),
+            entry!("set_radar_max_distance(dist: f64)", "Set the maximum distance filter."),
+            entry!("radar_max_distance() → f64", "Get current maximum distance filter."),
+            entry!("set_radar_ecm_mode(mode: EcmMode)", "Set the Electronic Counter Measures (ECM) mode."),
+            entry!("radar_ecm_mode() → EcmMode", "Get current ECM mode."),
+            entry!("EcmMode::None", "No ECM, radar will operate normally."),
+            entry!("EcmMode::Noise", "Decrease the enemy radar's signal to noise ratio, making it more difficult to detect targets and reducing accuracy of returned contacts."),
+            entry!("set_radar_cross_section(value: f64)", "Lower this ship's radar cross-section so enemies detect it at shorter range. Costs engine power."),
+            entry!("radar_cross_section() → f64", "Get this ship's current radar cross-section."),
+        ],
+    },
+    Section {
+        name: "Radio",
+        entries: &[
+            entry!("set_radio_channel(channel: usize)", "Change the radio channel (0 to 9). Takes effect next tick."),
+            entry!("get_radio_channel() → usize", "Get the radio channel."),
+            entry!("send(data: [f64; 4])", "Send a message on a channel."),
+            entry!("receive() → Option<[f64; 4]>", "Receive a message from the channel. The message with the strongest signal is returned."),
+            entry!("send_bytes(data: &[u8])", "Send a message on a channel as bytes, the data will be zero-filled or truncated to a length of 32 bytes."),
+            entry!("receive_bytes() → Option<[u8; 32]>", "Just like receive, but instead the message will be returned as a byte array."),
+            entry!("select_radio(index: usize)", "Select the radio to control with subsequent API calls. Frigates have 4 radios and cruisers have 8."),
+        ],
+    },
+    Section {
+        name: "Special Abilities",
+        entries: &[
+            entry!("activate_ability(ability: Ability)", "Activates a ship's special ability."),
+            entry!("deactivate_ability(ability: Ability)", "Deactivates a ship's special ability."),
+            entry!("active_abilities() → ActiveAbilities", "Returns the ship's active abilities."),
+            entry!("Ability::Boost", "Fighter and missile only. Applies a 100 m/s² forward acceleration for 2s. Reloads in 10s."),
+            entry!("Ability::Decoy", "Torpedo only. Mimics the radar signature of a Cruiser for 0.5s. Reloads in 10s."),
+            entry!("Ability::Shield", "Cruiser only. Deflects damage for 1s. Reloads in 5s."),
+        ],
+    },
+    Section {
+        name: "Scalar Math",
+        entries: &[
+            entry!("PI, TAU", "Constants."),
+            entry!("x.abs()", "Absolute value."),
+            entry!("x.sqrt()", "Square root."),
+            entry!("x.sin(), x.cos(), x.tan()", "Trigonometry."),
+        ],
+    },
+    Section {
+        name: "Vector Math",
+        entries: &[
+            entry!("vec2(x, y) → Vec2", "Create a vector."),
+            entry!("v.x, v.y → f64", "Get a component of a vector."),
+            entry!("v1 +- v2 → Vec2", "Basic arithmetic between vectors."),
+            entry!("v */ f64 → Vec2", "Basic arithmetic between vectors and scalars."),
+            entry!("-v → Vec2", "Negate a vector."),
+            entry!("v.length() → f64", "Length."),
+            entry!("v.normalize() → Vec2", "Normalize to a unit vector."),
+            entry!("v.rotate(f64) → Vec2", "Rotate counter-clockwise."),
+            entry!("v.angle() → f64", "Angle of a vector."),
+            entry!("v1.dot(v2: Vec2) → f64", "Dot product."),
+            entry!("v1.distance(v2: Vec2) → f64", "Distance between two points."),
+        ],
+    },
+    Section {
+        name: "Debugging",
+        entries: &[
+            entry!("debug!(...)", "Add text to be displayed when the ship is selected by clicking on it. Works just like println!."),
+            entry!("draw_line(v0: Vec2, v1: Vec2, color: u32)", "Draw a line visible when the ship is selected. Color is 24-bit RGB."),
+            entry!("draw_triangle(center: Vec2, radius: f64, color: u32)", "Draw a triangle visible when the ship is selected."),
+            entry!("draw_square(center: Vec2, radius: f64, color: u32)", "Draw a square visible when the ship is selected."),
+            entry!("draw_diamond(center: Vec2, radius: f64, color: u32)", "Draw a diamond visible when the ship is selected."),
+            entry!("draw_polygon(center: Vec2, radius: f64, sides: i32, angle: f64, color: u32)", "Draw a regular polygon visible when the ship is selected."),
+            entry!("draw_text!(topleft: Vec2, color: u32, ...)", "Draw text. Works like println!."),
+        ],
+    },
+    Section {
+        name: "Miscellaneous",
+        entries: &[
+            entry!("current_tick() → u32", "Returns the number of ticks elapsed since the simulation started."),
+            entry!("current_time() → f64", "Returns the number of seconds elapsed since the simulation started."),
+            entry!("tick_length() → f64", "Returns the number of seconds between calls to tick."),
+            entry!("TICK_LENGTH: f64", "The length of a physics tick in seconds (1/60)."),
+            entry!("angle_diff(a: f64, b: f64) → f64", "Returns the shortest (possibly negative) distance between two angles."),
+            entry!("normalize_angle(a: f64) → f64", "Returns the equivalent angle between -π and π."),
+            entry!("clamp(x: f64, lo: f64, hi: f64) → f64", "Limits x to the range [lo, hi]."),
+            entry!("lerp(a: f64, b: f64, t: f64) → f64", "Linear interpolation from a (t = 0) to b (t = 1)."),
+            entry!("cpa_time(rel_pos: Vec2, rel_vel: Vec2) → f64", "Returns the time until the closest point of approach, or 0 if it's in the past."),
+            entry!("cpa_distance(rel_pos: Vec2, rel_vel: Vec2) → f64", "Returns the distance at the closest point of approach."),
+            entry!("intercept_time(rel_pos: Vec2, rel_vel: Vec2, bullet_speed: f64) → Option<f64>", "Returns the time for a bullet to hit a target moving at a constant velocity, or None if it can't."),
+            entry!("rand(low: f64, high: f64) → f64", "Get a random number."),
+            entry!("target() → Vec2", "Used in some scenarios, returns the position of the target."),
+            entry!("target_velocity() → Vec2", "Used in some scenarios, returns the velocity of the target."),
+            entry!("get_marker(name: &str) → Option<Marker>", "Returns a location (position and radius) defined by the scenario."),
+            entry!("scenario_var(name: &str) → Option<ScenarioVar>", "Returns a variable set by the scenario."),
+            entry!("scenario_var_vec2(name: &str) → Option<Vec2>", "Returns a scenario variable if it's a vector."),
+            entry!("scenario_var_f64(name: &str) → Option<f64>", "Returns a scenario variable if it's a number."),
+            entry!("scenario_var_bool(name: &str) → Option<bool>", "Returns a scenario variable if it's a boolean."),
+            entry!("scenario_name() → &str", "Returns the name of the current scenario."),
+            entry!("world_size() → f64", "Returns the width of the world in meters."),
+            entry!("seed() → u128", "Returns a seed useful for initializing a random number generator."),
+            entry!("team() → i32", "Returns the team this ship is on."),
+            entry!("id() → u32", "Returns a per-ship ID that is unique within a team."),
+            entry!("ally_count() → u32", "Returns the number of ships on your team, including this one."),
+            entry!("enemy_count() → u32", "Returns the number of ships on other teams, anywhere in the world."),
+            entry!("nearest_wall() → Option<Vec2>", "Returns the vector to the closest point on the world's walls, or None if there are no walls."),
+            entry!("boundary_distance() → f64", "Returns the distance to the nearest wall, or infinity if there are no walls."),
+        ],
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use super::SECTIONS;
+
+    // Deprecated functions that are left out of the quick reference.
+    const UNLISTED: &[&str] = &["max_acceleration"];
+
+    #[test]
+    fn test_sections_cover_api() {
+        let lib = include_str!("lib.rs");
+        let start = lib.find("\nmod api {\n").unwrap();
+        let end = start + lib[start..].find("\n}\n").unwrap();
+        let mut missing = vec![];
+        for line in lib[start..end].lines() {
+            let Some(rest) = line.strip_prefix("    pub fn ") else {
+                continue;
+            };
+            let name = &rest[..rest.find('(').unwrap()];
+            let call = format!("{}(", name);
+            let listed = SECTIONS
+                .iter()
+                .flat_map(|section| section.entries)
+                .any(|entry| entry.signature.starts_with(&call));
+            if !listed && !UNLISTED.contains(&name) {
+                missing.push(name);
+            }
+        }
+        assert!(missing.is_empty(), "missing from SECTIONS: {:?}", missing);
+    }
+}
@@ -0,0 +1,18 @@
+// oort-api: 1
+#![allow(deprecated)]
+// Exercises the compatibility shims for code written against the first API.
+use oort_api::prelude::*;
+
+pub struct Ship {}
+
+impl Ship {
+    pub fn new() -> Ship {
+        Ship {}
+    }
+
+    pub fn tick(&mut self) {
+        aim_gun(0, heading());
+        fire_gun(0);
+        debug_line(position(), position() + vec2(100.0, 0.0), 0xffffff);
+    }
+}
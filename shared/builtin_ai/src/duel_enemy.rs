@@ -0,0 +1,49 @@
+use oort_api::prelude::*;
+
+const BULLET_SPEED: f64 = 1000.0; // m/s
+const PREFERRED_RANGE: f64 = 600.0;
+
+pub struct Ship {
+    last_contact: Option<(Vec2, Vec2)>,
+}
+
+impl Ship {
+    pub fn new() -> Ship {
+        set_radar_width(TAU / 8.0);
+        Ship { last_contact: None }
+    }
+
+    pub fn tick(&mut self) {
+        if let Some(contact) = scan() {
+            self.last_contact = Some((contact.position, contact.velocity));
+            set_radar_heading((contact.position - position()).angle());
+            set_radar_width(TAU / 64.0);
+        } else {
+            set_radar_heading(radar_heading() + radar_width());
+            set_radar_width(TAU / 8.0);
+        }
+
+        if let Some((target_position, target_velocity)) = self.last_contact {
+            let dp = target_position - position();
+            let range_error = dp.length() - PREFERRED_RANGE;
+            accelerate(dp.normalize() * range_error + (target_velocity - velocity()));
+
+            let target_heading = lead_target(target_position, target_velocity);
+            let heading_error = angle_diff(heading(), target_heading);
+            turn(20.0 * heading_error);
+            if heading_error.abs() < 0.05 {
+                fire(0);
+            }
+        }
+    }
+}
+
+fn lead_target(target_position: Vec2, target_velocity: Vec2) -> f64 {
+    let dp = target_position - position();
+    let dv = target_velocity - velocity();
+    let mut predicted_dp = dp;
+    for _ in 0..3 {
+        predicted_dp = dp + dv * predicted_dp.length() / BULLET_SPEED;
+    }
+    predicted_dp.angle()
+}
@@ -1,8 +1,11 @@
-#![allow(unused_imports, clippy::new_without_default)]
+#![allow(unused_imports, clippy::new_without_default, clippy::duplicate_mod)]
+pub mod api_v1_test;
+pub mod duel_enemy;
 pub mod empty;
 pub mod fuzz;
 pub mod gunnery;
 pub mod missile;
+pub mod opponent;
 pub mod planetary_defense_enemy;
 pub mod radar_test;
 pub mod radar_test_enemy;
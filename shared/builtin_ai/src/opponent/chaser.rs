@@ -0,0 +1,37 @@
+// Closes to point-blank range at full throttle and fires continuously.
+use oort_api::prelude::*;
+
+#[path = "common.rs"]
+mod common;
+use common::{lead_target, track_target, SEARCH_WIDTH};
+
+pub struct Ship {
+    last_contact: Option<(Vec2, Vec2)>,
+}
+
+impl Ship {
+    pub fn new() -> Ship {
+        set_radar_width(SEARCH_WIDTH);
+        Ship { last_contact: None }
+    }
+
+    pub fn tick(&mut self) {
+        if let Some(contact) = track_target(TAU / 64.0) {
+            self.last_contact = Some((contact.position, contact.velocity));
+        }
+
+        if let Some((target_position, target_velocity)) = self.last_contact {
+            let dp = target_position - position();
+            accelerate(
+                dp.normalize() * max_forward_acceleration() + (target_velocity - velocity()),
+            );
+
+            let target_heading = lead_target(target_position, target_velocity);
+            let heading_error = angle_diff(heading(), target_heading);
+            turn(20.0 * heading_error);
+            if heading_error.abs() < 0.1 {
+                fire(0);
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// Radar and aiming shared by the reference opponents. Each opponent declares
+// this as a module, and build-builtin-ais inlines it so the opponent still
+// compiles as a single file.
+use oort_api::prelude::*;
+
+const BULLET_SPEED: f64 = 1000.0; // m/s
+
+/// Radar beam width used while searching for a target.
+pub const SEARCH_WIDTH: f64 = TAU / 8.0;
+
+/// Sweeps the radar until it finds a contact, then narrows the beam to
+/// `lock_width` and keeps it on the contact.
+pub fn track_target(lock_width: f64) -> Option<ScanResult> {
+    let contact = scan();
+    if let Some(contact) = contact.as_ref() {
+        set_radar_heading((contact.position - position()).angle());
+        set_radar_width(lock_width);
+    } else {
+        set_radar_heading(radar_heading() + radar_width());
+        set_radar_width(SEARCH_WIDTH);
+    }
+    contact
+}
+
+/// Returns the heading to fire at to hit a target moving at constant velocity.
+pub fn lead_target(target_position: Vec2, target_velocity: Vec2) -> f64 {
+    let dp = target_position - position();
+    let dv = target_velocity - velocity();
+    match intercept_time(dp, dv, BULLET_SPEED) {
+        Some(t) => (dp + dv * t).angle(),
+        // Out of reach, so aim where it's closest.
+        None => (dp + dv * cpa_time(dp, dv)).angle(),
+    }
+}
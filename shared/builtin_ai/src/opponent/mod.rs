@@ -0,0 +1,3 @@
+pub mod chaser;
+pub mod orbiter;
+pub mod sniper;
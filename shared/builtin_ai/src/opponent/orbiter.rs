@@ -0,0 +1,42 @@
+// Circles the target at medium range, firing while strafing sideways.
+use oort_api::prelude::*;
+
+#[path = "common.rs"]
+mod common;
+use common::{lead_target, track_target, SEARCH_WIDTH};
+
+const ORBIT_RADIUS: f64 = 500.0;
+const ORBIT_SPEED: f64 = 150.0; // m/s
+
+pub struct Ship {
+    last_contact: Option<(Vec2, Vec2)>,
+}
+
+impl Ship {
+    pub fn new() -> Ship {
+        set_radar_width(SEARCH_WIDTH);
+        Ship { last_contact: None }
+    }
+
+    pub fn tick(&mut self) {
+        if let Some(contact) = track_target(TAU / 64.0) {
+            self.last_contact = Some((contact.position, contact.velocity));
+        }
+
+        if let Some((target_position, target_velocity)) = self.last_contact {
+            let dp = target_position - position();
+            let radial = dp.normalize();
+            let tangent = vec2(-radial.y, radial.x);
+            let desired_velocity =
+                target_velocity + tangent * ORBIT_SPEED + radial * (dp.length() - ORBIT_RADIUS);
+            accelerate((desired_velocity - velocity()) * 2.0);
+
+            let target_heading = lead_target(target_position, target_velocity);
+            let heading_error = angle_diff(heading(), target_heading);
+            turn(20.0 * heading_error);
+            if heading_error.abs() < 0.05 {
+                fire(0);
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+// Holds position at long range and only fires when the shot is lined up.
+use oort_api::prelude::*;
+
+#[path = "common.rs"]
+mod common;
+use common::{lead_target, track_target, SEARCH_WIDTH};
+
+const PREFERRED_RANGE: f64 = 1200.0;
+
+pub struct Ship {
+    last_contact: Option<(Vec2, Vec2)>,
+}
+
+impl Ship {
+    pub fn new() -> Ship {
+        set_radar_width(SEARCH_WIDTH);
+        Ship { last_contact: None }
+    }
+
+    pub fn tick(&mut self) {
+        if let Some(contact) = track_target(TAU / 128.0) {
+            self.last_contact = Some((contact.position, contact.velocity));
+        }
+
+        match self.last_contact {
+            Some((target_position, target_velocity)) => {
+                let dp = target_position - position();
+                let range_error = dp.length() - PREFERRED_RANGE;
+                accelerate(dp.normalize() * range_error - velocity());
+
+                let target_heading = lead_target(target_position, target_velocity);
+                let heading_error = angle_diff(heading(), target_heading);
+                turn(20.0 * heading_error);
+                if heading_error.abs() < 0.01 {
+                    fire(0);
+                }
+            }
+            None => accelerate(-velocity()),
+        }
+    }
+}
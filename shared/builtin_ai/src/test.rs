@@ -14,8 +14,35 @@ impl Ship {
             "scenario_name" => debug!("Scenario: {}", scenario_name()),
             "world_size" => debug!("World size: {}", world_size()),
             "id" => debug!("ID: {}", id()),
+            "team" => debug!(
+                "Team: {} Allies: {} Enemies: {}",
+                team(),
+                ally_count(),
+                enemy_count()
+            ),
+            "accelerate_local" => accelerate_local(vec2(100.0, 0.0)),
+            "accelerate_world" => accelerate_world(vec2(0.0, 100.0)),
             "panic" => panic!("Panic!"),
             "infinite_loop" => loop {},
+            "scenario_var" => debug!(
+                "Vars: {:?} {:?} {:?} {:?} Target: {} {}",
+                scenario_var_vec2("position").map(|v| (v.x, v.y)),
+                scenario_var_f64("speed"),
+                scenario_var_bool("enabled"),
+                scenario_var("missing").is_some(),
+                target().x,
+                target().y
+            ),
+            "current_tick" => debug!(
+                "Tick: {} Time: {}",
+                current_tick(),
+                current_time() / TICK_LENGTH
+            ),
+            "nearest_wall" => debug!(
+                "Nearest wall: {:?} Distance: {}",
+                nearest_wall().map(|v| (v.x, v.y)),
+                boundary_distance()
+            ),
             _ => debug!("Unknown testcase: {:?}", testcase),
         }
     }
@@ -8,6 +8,8 @@ pub mod tutorial_cruiser_solution;
 pub mod tutorial_deflection_enemy;
 pub mod tutorial_deflection_initial;
 pub mod tutorial_deflection_solution;
+pub mod tutorial_formation_initial;
+pub mod tutorial_formation_solution;
 pub mod tutorial_frigate_enemy;
 pub mod tutorial_frigate_initial;
 pub mod tutorial_frigate_solution;
@@ -1,5 +1,8 @@
 // Tutorial: Acceleration
 // Fly through the target circle.
+//
+// Hint: current_time() returns the seconds since the scenario started, which is
+// handy for doing something for a fixed time, like "accelerate for 2 seconds".
 use oort_api::prelude::*;
 
 pub struct Ship {}
@@ -51,7 +51,7 @@ impl Ship {
             set_radar_heading((contact.position - position()).angle());
             set_radar_width((10.0 * TAU / dp.length()).clamp(TAU / 30.0, TAU));
         } else {
-            accelerate(vec2(100.0, 0.0).rotate(heading()));
+            accelerate_local(vec2(100.0, 0.0));
             set_radar_width(TAU / 4.0);
             set_radar_heading(heading());
         }
@@ -0,0 +1,26 @@
+// Tutorial: Formation
+// Fly your three ships in a triangle around the moving marker and hold the
+// formation for five seconds. Each corner of the triangle is 150m from the
+// marker: one straight ahead of it in the direction it's moving, and the other
+// two 120 degrees to either side.
+//
+// Only the ship that starts in the middle can see the marker, using target()
+// and target_velocity(). It will need to tell the others where it is using the
+// radio.
+//
+// Hint: Each ship runs its own copy of this code. Ship::new() can use
+// position() to work out which ship it's running on.
+use oort_api::prelude::*;
+
+pub struct Ship {}
+
+impl Ship {
+    pub fn new() -> Ship {
+        Ship {}
+    }
+
+    pub fn tick(&mut self) {
+        debug!("target: {:?}", target());
+        accelerate(0.1 * (target() - position()) - velocity());
+    }
+}
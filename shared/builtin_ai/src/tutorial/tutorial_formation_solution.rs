@@ -0,0 +1,79 @@
+// Tutorial: Formation (solution)
+// Fly your three ships in a triangle around the moving marker and hold the
+// formation for five seconds. Each corner of the triangle is 150m from the
+// marker: one straight ahead of it in the direction it's moving, and the other
+// two 120 degrees to either side.
+//
+// Only the ship that starts in the middle can see the marker, using target()
+// and target_velocity(). It will need to tell the others where it is using the
+// radio.
+//
+// Hint: Each ship runs its own copy of this code. Ship::new() can use
+// position() to work out which ship it's running on.
+use oort_api::prelude::*;
+
+const SLOT_DISTANCE: f64 = 150.0;
+const MAX_APPROACH_ACCELERATION: f64 = 15.0;
+
+pub struct Ship {
+    // Angle of our corner relative to the marker's direction of travel.
+    slot_angle: f64,
+    is_lead: bool,
+}
+
+impl Ship {
+    pub fn new() -> Ship {
+        let y = position().y;
+        Ship {
+            slot_angle: if y > 100.0 {
+                TAU / 3.0
+            } else if y < -100.0 {
+                -TAU / 3.0
+            } else {
+                0.0
+            },
+            is_lead: y.abs() <= 100.0,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        set_radio_channel(1);
+        let (marker_position, marker_velocity) = if self.is_lead {
+            send([
+                target().x,
+                target().y,
+                target_velocity().x,
+                target_velocity().y,
+            ]);
+            (target(), target_velocity())
+        } else if let Some(msg) = receive() {
+            (vec2(msg[0], msg[1]), vec2(msg[2], msg[3]))
+        } else {
+            accelerate(-velocity());
+            return;
+        };
+
+        let slot_position = marker_position
+            + vec2(SLOT_DISTANCE, 0.0).rotate(marker_velocity.angle() + self.slot_angle);
+        draw_line(position(), slot_position, 0x00ff00);
+
+        // Approach fast enough to stop in time, then slow down near the slot.
+        let dp = slot_position - position();
+        let distance = dp.length();
+        let approach_speed = (2.0 * MAX_APPROACH_ACCELERATION * distance)
+            .sqrt()
+            .min(distance);
+        let desired_velocity = if distance > 0.0 {
+            marker_velocity + dp / distance * approach_speed
+        } else {
+            marker_velocity
+        };
+        accelerate(3.0 * (desired_velocity - velocity()));
+        turn_to(marker_velocity.angle());
+    }
+}
+
+fn turn_to(target_heading: f64) {
+    let heading_error = angle_diff(heading(), target_heading);
+    turn(10.0 * heading_error);
+}
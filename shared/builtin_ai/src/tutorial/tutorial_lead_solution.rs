@@ -33,13 +33,13 @@ fn turn_to(target_heading: f64) {
     turn(50.0 * heading_error);
 }
 
+// Returns the heading that puts a bullet in the target's path at the moment
+// it gets there.
 fn lead_target(target_position: Vec2, target_velocity: Vec2) -> f64 {
     let dp = target_position - position();
     let dv = target_velocity - velocity();
-    let mut predicted_dp = dp;
-    for _ in 0..3 {
-        predicted_dp = dp + dv * predicted_dp.length() / BULLET_SPEED;
-    }
+    let t = intercept_time(dp, dv, BULLET_SPEED).unwrap_or(dp.length() / BULLET_SPEED);
+    let predicted_dp = dp + dv * t;
     draw_line(position(), position() + predicted_dp, 0x00ff00);
     predicted_dp.angle()
 }
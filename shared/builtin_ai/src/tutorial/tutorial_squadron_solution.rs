@@ -32,7 +32,7 @@ impl Ship {
                 set_radar_heading((target_position - position()).angle());
                 set_radar_width(TAU / 360.0);
             } else {
-                accelerate(vec2(100.0, 0.0).rotate(heading()));
+                accelerate_local(vec2(100.0, 0.0));
                 set_radar_width(TAU / 4.0);
             }
         } else {
@@ -1,5 +1,16 @@
 use anyhow::{bail, Result};
+pub use oort_proto::CompileFailure;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+/// Wall-clock limit for compiling user code. Shorter than the frontend's
+/// timeout so the player sees this error instead of a generic one.
+pub const COMPILE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Limit on the heap rustc can allocate while compiling user code.
+pub const COMPILE_MEMORY_LIMIT: u64 = 2 << 30;
 
 pub struct Compiler {
     #[allow(dead_code)]
@@ -7,6 +18,7 @@ pub struct Compiler {
     dir: PathBuf,
     offline: bool,
     rustc: String,
+    prlimit: bool,
 }
 
 #[allow(clippy::new_without_default)]
@@ -19,6 +31,7 @@ impl Compiler {
             dir,
             offline: true,
             rustc: find_rustc(),
+            prlimit: has_prlimit(),
         }
     }
 
@@ -28,6 +41,7 @@ impl Compiler {
             dir: dir.to_path_buf(),
             offline: true,
             rustc: find_rustc(),
+            prlimit: has_prlimit(),
         }
     }
 
@@ -36,10 +50,11 @@ impl Compiler {
     }
 
     pub fn compile(&mut self, code: &str) -> Result<Vec<u8> /* wasm */> {
+        let code = check_api_version(code)?;
         let tmp_path = &self.dir;
 
         if std::fs::metadata(tmp_path.join("Cargo.toml")).is_ok() {
-            return self.compile_fast(code);
+            return self.compile_checked(&code);
         }
 
         std::fs::write(
@@ -68,6 +83,10 @@ impl Compiler {
             tmp_path.join("api/src/panic.rs"),
             include_bytes!("../../api/src/panic.rs"),
         )?;
+        std::fs::write(
+            tmp_path.join("api/src/reference.rs"),
+            include_bytes!("../../api/src/reference.rs"),
+        )?;
 
         std::fs::create_dir_all(tmp_path.join("ai/src"))?;
         std::fs::write(
@@ -80,7 +99,7 @@ impl Compiler {
         )?;
         std::fs::write(tmp_path.join("ai/src/user.rs"), code.as_bytes())?;
 
-        let output = std::process::Command::new("cargo")
+        let output = Command::new("cargo")
             .args([
                 "build",
                 "--manifest-path",
@@ -103,15 +122,34 @@ impl Compiler {
             bail!("cargo failed: {}", std::str::from_utf8(&output.stderr)?);
         }
 
-        self.compile_fast(code)
+        self.compile_checked(&code)
     }
 
     pub fn compile_fast(&mut self, code: &str) -> Result<Vec<u8> /* wasm */> {
+        let code = check_api_version(code)?;
+        self.compile_checked(&code)
+    }
+
+    /// Compiles code that has already been through `check_api_version`.
+    fn compile_checked(&mut self, code: &str) -> Result<Vec<u8> /* wasm */> {
         let tmp_path = &self.dir;
         std::fs::write(tmp_path.join("ai/src/user.rs"), code.as_bytes())?;
         let rustc_bin_dir = Path::new(&self.rustc).parent().unwrap();
 
-        let output = std::process::Command::new(&self.rustc)
+        // The CPU limit backs up the wall-clock timeout if the machine is
+        // overloaded.
+        let mut command = if self.prlimit {
+            let mut command = Command::new("prlimit");
+            command
+                .arg(format!("--data={COMPILE_MEMORY_LIMIT}"))
+                .arg(format!("--cpu={}", COMPILE_TIMEOUT.as_secs()))
+                .arg("--")
+                .arg(&self.rustc);
+            command
+        } else {
+            Command::new(&self.rustc)
+        };
+        command
             .env(
                 "LD_LIBRARY_PATH",
                 &format!("{}/../lib", rustc_bin_dir.display()),
@@ -158,10 +196,10 @@ impl Compiler {
                 "llvm-args=-rng-seed=42",
                 "--remap-path-prefix",
                 &format!("{}=/tmp/oort-ai", tmp_path.display()),
-            ])
-            .output()?;
+            ]);
+        let output = output_with_timeout(&mut command, COMPILE_TIMEOUT)?;
         if !output.status.success() {
-            bail!("rustc failed: {}", std::str::from_utf8(&output.stderr)?);
+            return Err(classify_failure(&output).into());
         }
 
         Ok(std::fs::read(tmp_path.join(
@@ -170,6 +208,77 @@ impl Compiler {
     }
 }
 
+/// Rejects code written for a newer API than this compiler has. Code written
+/// for an older API gets the compiler's deprecation warnings silenced, since
+/// the shims print their own warnings at runtime.
+fn check_api_version(code: &str) -> Result<String> {
+    match oort_api::declared_api_version(code) {
+        Some(version) if version > oort_api::API_VERSION => bail!(
+            "Code requires API version {}, but this compiler supports up to {}",
+            version,
+            oort_api::API_VERSION
+        ),
+        // Same line, so error line numbers still match the editor.
+        Some(version) if version < oort_api::API_VERSION => {
+            Ok(format!("#![allow(deprecated)] {code}"))
+        }
+        _ => Ok(code.to_string()),
+    }
+}
+
+/// prlimit makes the kernel kill rustc with a signal when it hits a limit,
+/// while ordinary compile errors exit with a status code.
+fn classify_failure(output: &Output) -> CompileFailure {
+    if output.status.code().is_none() {
+        CompileFailure::ResourceLimit
+    } else {
+        CompileFailure::CompileError(format!(
+            "rustc failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+}
+
+/// Like `Command::output`, but kills the process if it runs longer than
+/// `timeout`. Fails with [`CompileFailure::Timeout`] in that case.
+fn output_with_timeout(command: &mut Command, timeout: Duration) -> Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drained on other threads so the child can't block on a full pipe.
+    let read_pipe = |mut pipe: Box<dyn Read + Send>| {
+        std::thread::spawn(move || {
+            let mut buf = vec![];
+            let _ = pipe.read_to_end(&mut buf);
+            buf
+        })
+    };
+    let stdout = read_pipe(Box::new(child.stdout.take().unwrap()));
+    let stderr = read_pipe(Box::new(child.stderr.take().unwrap()));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(CompileFailure::Timeout.into());
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap(),
+        stderr: stderr.join().unwrap(),
+    })
+}
+
 fn find_rlib(tmp_path: &Path, crate_name: &str) -> PathBuf {
     if let Some(path) = glob::glob(
         tmp_path
@@ -188,8 +297,21 @@ fn find_rlib(tmp_path: &Path, crate_name: &str) -> PathBuf {
     panic!("{crate_name} rlib not found");
 }
 
+/// prlimit (from util-linux) applies resource limits to rustc. Without it,
+/// as on macOS, only the timeout is enforced.
+fn has_prlimit() -> bool {
+    let found = Command::new("prlimit")
+        .arg("--version")
+        .output()
+        .map_or(false, |output| output.status.success());
+    if !found {
+        log::warn!("prlimit not found, compiler memory usage is unlimited");
+    }
+    found
+}
+
 fn find_rustc() -> String {
-    let output = std::process::Command::new("rustup")
+    let output = Command::new("rustup")
         .args(["which", "rustc"])
         .output()
         .unwrap();
@@ -206,3 +328,73 @@ fn find_rustc() -> String {
         "rustc".to_string()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_output_with_timeout() {
+        let output = output_with_timeout(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+    }
+
+    #[test]
+    fn test_timeout() {
+        let start = Instant::now();
+        let err = output_with_timeout(Command::new("sleep").arg("10"), Duration::from_millis(100))
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<CompileFailure>(),
+            Some(&CompileFailure::Timeout)
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        if !has_prlimit() {
+            return;
+        }
+        // tail buffers the whole 3 GB line.
+        let output = output_with_timeout(
+            Command::new("prlimit")
+                .arg(format!("--data={COMPILE_MEMORY_LIMIT}"))
+                .args([
+                    "--",
+                    "sh",
+                    "-c",
+                    "head -c 3000000000 /dev/zero | tail -n 1 > /dev/null",
+                ]),
+            Duration::from_secs(30),
+        )
+        .unwrap();
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_classify_failure() {
+        let output = output_with_timeout(
+            Command::new("sh").args(["-c", "echo oops >&2; exit 1"]),
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert_eq!(
+            classify_failure(&output),
+            CompileFailure::CompileError("rustc failed: oops\n".to_string())
+        );
+
+        let output = output_with_timeout(
+            Command::new("sh").args(["-c", "kill -KILL $$"]),
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert_eq!(classify_failure(&output), CompileFailure::ResourceLimit);
+    }
+}
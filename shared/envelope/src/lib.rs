@@ -27,6 +27,16 @@ fn digest(data: &[u8]) -> Vec<u8> {
     hasher.finalize().to_vec()
 }
 
+pub fn hash_code(code: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(code.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|x| format!("{x:02x}"))
+        .collect()
+}
+
 pub fn add(data: &[u8]) -> Vec<u8> {
     let mut result: Vec<u8> = Vec::new();
     result.extend_from_slice(&digest(data));
@@ -18,16 +18,21 @@ pub struct CompletionItem {
     pub insertText: String,
     pub insertTextRules: u32,
     pub filterText: String,
+    pub documentation: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Request {
     Diagnostics(String),
     Completion(u32, u32),
+    /// Request id, line and column. The id is echoed in the response.
+    Hover(u64, u32, u32),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Response {
     Diagnostics(Vec<Diagnostic>),
     Completion(Vec<CompletionItem>),
+    /// Request id, and Markdown or None if there's nothing to show.
+    Hover(u64, Option<String>),
 }
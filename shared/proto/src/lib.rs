@@ -41,6 +41,14 @@ pub enum Telemetry {
     Feedback {
         text: String,
     },
+    ScenarioComplete {
+        scenario_name: String,
+        ticks: u32,
+        seed: u32,
+    },
+    Compile {
+        success: bool,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
@@ -48,6 +56,13 @@ pub struct LeaderboardData {
     pub lowest_time: Vec<TimeLeaderboardRow>,
 }
 
+/// One page of a leaderboard. `total` counts every entry, not just this page.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct LeaderboardResponse {
+    pub entries: Vec<TimeLeaderboardRow>,
+    pub total: usize,
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct TimeLeaderboardRow {
     pub userid: String,
@@ -70,6 +85,12 @@ pub struct LeaderboardSubmission {
     pub time: f64,
     pub code_size: usize,
     pub code: String,
+    #[serde(default)]
+    pub code_hash: String,
+    #[serde(default)]
+    pub seed: Option<u32>,
+    #[serde(default)]
+    pub token: String,
 }
 
 impl Eq for LeaderboardSubmission {}
@@ -108,3 +129,58 @@ pub struct TournamentCompetitor {
     pub shortcode: String,
     pub rating: f64,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct CompileJobId(pub String);
+
+impl std::fmt::Display for CompileJobId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CompileStatus {
+    Queued,
+    Compiling,
+    Done,
+    Error { error: CompileFailure },
+}
+
+/// Why compiling user code failed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum CompileFailure {
+    /// rustc ran past the compiler service's time limit.
+    Timeout,
+    /// rustc was killed for using too much memory or CPU time.
+    ResourceLimit,
+    /// The code was rejected. Holds rustc's output or the reason.
+    CompileError(String),
+}
+
+impl std::fmt::Display for CompileFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompileFailure::Timeout => write!(f, "Compilation took too long"),
+            CompileFailure::ResourceLimit => write!(f, "Compilation ran out of memory or CPU time"),
+            CompileFailure::CompileError(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for CompileFailure {}
+
+/// Returned by the compiler service's /status endpoint.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CompilerStatus {
+    /// False when compiles are waiting for a free slot.
+    pub healthy: bool,
+    /// Compiles waiting or running.
+    pub queue_depth: usize,
+    pub version: String,
+    /// Average of recent compile times, or None if nothing has compiled yet.
+    pub avg_compile_ms: Option<f64>,
+}
@@ -1,6 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use oort_simulator::scenario;
 use oort_simulator::simulation;
+use std::time::Duration;
 
 fn fleet() {
     let scenario = scenario::load("fleet");
@@ -10,8 +11,24 @@ fn fleet() {
     }
 }
 
+// Measures only the time spent running ship code. Compare against a run with
+// `--features parallel` to see the speedup from running teams concurrently.
+fn fleet_vm(iters: u64) -> Duration {
+    let mut total = 0.0;
+    for _ in 0..iters {
+        let scenario = scenario::load("fleet");
+        let mut sim = simulation::Simulation::new("fleet", 0, &scenario.solution_codes());
+        while sim.status() == scenario::Status::Running {
+            sim.step();
+            total += sim.timing().vm;
+        }
+    }
+    Duration::from_secs_f64(total)
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("fleet", |b| b.iter(fleet));
+    c.bench_function("fleet_vm", |b| b.iter_custom(fleet_vm));
 }
 
 pub fn criterion_config() -> Criterion {
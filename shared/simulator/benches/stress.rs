@@ -3,9 +3,12 @@ use oort_simulator::scenario;
 use oort_simulator::simulation;
 use oort_simulator::snapshot::Timing;
 
-fn stress(timing: &mut Timing) {
-    let scenario = scenario::load("stress");
-    let mut sim = simulation::Simulation::new("stress", 0, &scenario.solution_codes());
+fn stress(timing: &mut Timing, scenario_name: &str, script_tick_divisor: u32) {
+    // asteroid-stress has no solution of its own, so both scenarios run the
+    // reference AI on every team.
+    let codes = [scenario::reference_ai(), scenario::reference_ai()];
+    let mut sim = simulation::Simulation::new(scenario_name, 0, &codes);
+    sim.script_tick_divisor = script_tick_divisor;
     while sim.status() == scenario::Status::Running && sim.tick() < 60 * 3 {
         sim.step();
         *timing += sim.timing().clone();
@@ -13,21 +16,28 @@ fn stress(timing: &mut Timing) {
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("stress", |b| {
-        let mut timing = Timing::default();
-        let mut c = 0;
-        b.iter(|| {
-            c += 1;
-            stress(&mut timing)
+    for (name, scenario_name, script_tick_divisor) in [
+        ("stress", "stress", 1),
+        ("stress_divisor4", "stress", 4),
+        ("asteroid_stress", "asteroid-stress", 1),
+        ("asteroid_stress_divisor4", "asteroid-stress", 4),
+    ] {
+        c.bench_function(name, |b| {
+            let mut timing = Timing::default();
+            let mut c = 0;
+            b.iter(|| {
+                c += 1;
+                stress(&mut timing, scenario_name, script_tick_divisor)
+            });
+            if timing.total() > 1.0 {
+                println!(
+                    "\nTimings: {:.0?} total: {:.0}ms",
+                    timing.clone() * (1e3 / c as f64),
+                    timing.total() * (1e3 / c as f64)
+                );
+            }
         });
-        if timing.total() > 1.0 {
-            println!(
-                "\nTimings: {:.0?} total: {:.0}ms",
-                timing.clone() * (1e3 / c as f64),
-                timing.total() * (1e3 / c as f64)
-            );
-        }
-    });
+    }
 }
 
 pub fn criterion_config() -> Criterion {
@@ -1,14 +1,17 @@
 use std::collections::HashMap;
 
 use super::index_set::{HasIndex, Index};
-use crate::simulation::{Simulation, MAX_WORLD_SIZE, PHYSICS_TICK_LENGTH};
+use crate::simulation::{Particle, Simulation, MAX_WORLD_SIZE, PHYSICS_TICK_LENGTH};
 use crate::{collision, simulation};
 use bitvec::vec::BitVec;
-use nalgebra::Vector2;
+use nalgebra::{Rotation2, Vector2};
+use rand::Rng;
 use rapier2d_f64::prelude::*;
 use static_aabb2d_index::*;
+use std::f64::consts::TAU;
 
 const COLOR_COLLIDERS: bool = false;
+const RADIUS: f64 = 1.0;
 
 #[derive(Hash, PartialEq, Eq, Copy, Clone, PartialOrd, Ord)]
 pub struct BulletHandle(pub Index);
@@ -105,6 +108,7 @@ pub fn tick(sim: &mut Simulation) {
         let has_collider;
         let coarse_grid_hit;
         let mut needs_collider = false;
+        let mut swept_aabb = None;
         {
             let world_size = sim.world_size();
             let body = sim.bodies.get_mut(RigidBodyHandle(handle.index())).unwrap();
@@ -123,12 +127,13 @@ pub fn tick(sim: &mut Simulation) {
             coarse_grid_hit = coarse_grids_by_team
                 .iter()
                 .any(|(other_team, grid)| *other_team != team && grid.lookup(position));
-            if coarse_grid_hit {
-                let aabb = shape.compute_swept_aabb(
+            if coarse_grid_hit || sim.num_obstacles > 0 {
+                swept_aabb = Some(shape.compute_swept_aabb(
                     body.position(),
                     &body.predict_position_using_velocity_and_forces(dt),
-                );
-
+                ));
+            }
+            if let (true, Some(aabb)) = (coarse_grid_hit, swept_aabb) {
                 for (other_team, index) in indices_by_team.iter() {
                     if team != *other_team {
                         needs_collider = needs_collider
@@ -147,6 +152,11 @@ pub fn tick(sim: &mut Simulation) {
             }
         }
 
+        // Obstacles aren't in the ship indices, so check them separately.
+        if let (false, Some(aabb)) = (needs_collider, swept_aabb) {
+            needs_collider = collision::obstacle_in_aabb(sim, &aabb);
+        }
+
         if needs_collider && !has_collider {
             add_collider(sim, handle);
         } else if has_collider && !needs_collider {
@@ -163,6 +173,103 @@ pub fn tick(sim: &mut Simulation) {
             }
         }
     }
+
+    if sim.projectile_collisions() {
+        handle_projectile_collisions(sim, dt);
+    }
+}
+
+/// Destroys pairs of bullets from different teams that will pass within
+/// contact distance of each other during the next tick.
+///
+/// Bullet colliders are sensors without CCD, so rapier would miss most
+/// head-on crossings. Instead this sweeps each bullet over the tick and checks
+/// the closest approach of each candidate pair.
+fn handle_projectile_collisions(sim: &mut Simulation, dt: f64) {
+    let bullets: Vec<(BulletHandle, i32, Vector2<f64>, Vector2<f64>)> = sim
+        .bullets
+        .iter()
+        .map(|&handle| {
+            let rigid_body = body(sim, handle);
+            let team = data(sim, handle).team;
+            (
+                handle,
+                team,
+                *rigid_body.translation(),
+                *rigid_body.linvel(),
+            )
+        })
+        .collect();
+    if bullets.len() < 2 {
+        return;
+    }
+
+    let mut builder = StaticAABB2DIndexBuilder::new(bullets.len());
+    for (_, _, p, v) in bullets.iter() {
+        let q = p + v * dt;
+        builder.add(
+            p.x.min(q.x) - RADIUS,
+            p.y.min(q.y) - RADIUS,
+            p.x.max(q.x) + RADIUS,
+            p.y.max(q.y) + RADIUS,
+        );
+    }
+    let index = builder.build().unwrap();
+
+    let mut destroyed: BitVec = BitVec::repeat(false, bullets.len());
+    let mut stack = Vec::new();
+    let mut hits = Vec::new();
+    for (i, (_, team, p, v)) in bullets.iter().enumerate() {
+        if destroyed[i] {
+            continue;
+        }
+        let q = p + v * dt;
+        let mut candidates: Vec<usize> = index
+            .query_iter_with_stack(
+                p.x.min(q.x) - RADIUS,
+                p.y.min(q.y) - RADIUS,
+                p.x.max(q.x) + RADIUS,
+                p.y.max(q.y) + RADIUS,
+                &mut stack,
+            )
+            .filter(|&j| j > i && !destroyed[j] && bullets[j].1 != *team)
+            .collect();
+        candidates.sort();
+        for j in candidates {
+            let dp = bullets[j].2 - p;
+            let dv = bullets[j].3 - v;
+            let t = if dv.norm_squared() > 0.0 {
+                (-dp.dot(&dv) / dv.norm_squared()).clamp(0.0, dt)
+            } else {
+                0.0
+            };
+            if (dp + dv * t).norm() < RADIUS * 2.0 {
+                destroyed.set(i, true);
+                destroyed.set(j, true);
+                hits.push(p + v * t);
+                break;
+            }
+        }
+    }
+
+    for position in hits {
+        for _ in 0..5 {
+            let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
+            let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..500.0), 0.0]);
+            sim.events.particles.push(Particle {
+                position,
+                velocity: v,
+                color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
+                lifetime: (PHYSICS_TICK_LENGTH * 20.0) as f32,
+            });
+        }
+    }
+
+    for (i, (handle, _, _, _)) in bullets.into_iter().enumerate() {
+        if destroyed[i] {
+            destroy(sim, handle);
+        }
+    }
 }
 
 fn build_indices(
@@ -200,7 +307,7 @@ fn build_indices(
 
 fn add_collider(sim: &mut Simulation, handle: BulletHandle) {
     let team = data(sim, handle).team;
-    let collider = ColliderBuilder::ball(1.0)
+    let collider = ColliderBuilder::ball(RADIUS)
         .restitution(1.0)
         .collision_groups(collision::bullet_interaction_groups(team))
         .active_events(ActiveEvents::COLLISION_EVENTS)
@@ -1,7 +1,7 @@
 use crate::bullet::{self, BulletHandle};
 use crate::index_set::HasIndex;
 use crate::ship::{ShipClass, ShipHandle};
-use crate::simulation::{Particle, Simulation, PHYSICS_TICK_LENGTH};
+use crate::simulation::{DestructionCause, Particle, SimEvent, Simulation, PHYSICS_TICK_LENGTH};
 use nalgebra::{Rotation2, UnitComplex};
 use oort_api::Ability;
 use rand::Rng;
@@ -9,6 +9,10 @@ use rapier2d_f64::prelude::*;
 use std::f64::consts::TAU;
 
 const DAMAGE_FACTOR: f64 = 0.00014;
+// Impact energy per kilogram of the victim that takes away all of its health.
+const RAM_LETHAL_ENERGY: f64 = 1000.0;
+// Closing speed below which ship-ship contacts do no damage.
+const RAM_MIN_SPEED: f64 = 5.0;
 const WALL_COLLISION_GROUP: Group = Group::GROUP_1;
 const SHIP_COLLISION_GROUP: Group = Group::GROUP_2;
 const PLANET_COLLISION_GROUP: Group = Group::GROUP_3;
@@ -24,6 +28,7 @@ const BULLET_GROUPS: &[Group] = &[
     Group::GROUP_12,
     Group::GROUP_13,
 ];
+const OBSTACLE_COLLISION_GROUP: Group = Group::GROUP_14;
 
 fn bullet_group(team: i32) -> Group {
     BULLET_GROUPS[team as usize]
@@ -38,7 +43,10 @@ fn all_bullet_groups() -> Group {
 pub fn bullet_interaction_groups(team: i32) -> InteractionGroups {
     InteractionGroups::new(
         bullet_group(team),
-        WALL_COLLISION_GROUP | SHIP_COLLISION_GROUP | PLANET_COLLISION_GROUP,
+        WALL_COLLISION_GROUP
+            | SHIP_COLLISION_GROUP
+            | PLANET_COLLISION_GROUP
+            | OBSTACLE_COLLISION_GROUP,
     )
 }
 
@@ -53,7 +61,11 @@ pub fn ship_interaction_groups(team: i32) -> InteractionGroups {
     let bullet_groups = all_bullet_groups() ^ bullet_group(team);
     InteractionGroups::new(
         SHIP_COLLISION_GROUP,
-        WALL_COLLISION_GROUP | SHIP_COLLISION_GROUP | PLANET_COLLISION_GROUP | bullet_groups,
+        WALL_COLLISION_GROUP
+            | SHIP_COLLISION_GROUP
+            | PLANET_COLLISION_GROUP
+            | OBSTACLE_COLLISION_GROUP
+            | bullet_groups,
     )
 }
 
@@ -65,8 +77,123 @@ pub fn planet_interaction_groups() -> InteractionGroups {
     )
 }
 
-pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
-    for event in events {
+pub fn obstacle_interaction_groups() -> InteractionGroups {
+    InteractionGroups::new(
+        OBSTACLE_COLLISION_GROUP,
+        SHIP_COLLISION_GROUP | all_bullet_groups(),
+    )
+}
+
+/// Damages two ships that ran into each other. The impact energy is split so
+/// that the lighter ship takes most of it, and each ship loses a fraction of
+/// its max health proportional to the energy it takes per kilogram.
+fn handle_ram(sim: &mut Simulation, ships: [ShipHandle; 2], impact_speed: f64) {
+    let is_warhead = |ship| {
+        matches!(
+            sim.ship(ship).data().class,
+            ShipClass::Missile | ShipClass::Torpedo
+        )
+    };
+    // Missiles and torpedoes do their damage with their warheads.
+    if impact_speed < RAM_MIN_SPEED || ships.iter().any(|&ship| is_warhead(ship)) {
+        return;
+    }
+    let masses = ships.map(|ship| sim.ship(ship).data().mass);
+    let total_mass = masses[0] + masses[1];
+    let reduced_mass = masses[0] * masses[1] / total_mass;
+    let energy = 0.5 * reduced_mass * impact_speed * impact_speed;
+    for ((ship, mass), other_mass) in ships.into_iter().zip(masses).zip([masses[1], masses[0]]) {
+        let specific_energy = energy * (other_mass / total_mass) / mass;
+        let damage = sim.ship(ship).data().max_health * specific_energy / RAM_LETHAL_ENERGY;
+        apply_damage(sim, ship, damage);
+    }
+}
+
+/// Damages a ship, draining its shield first. Returns whether the damage
+/// destroyed it.
+fn apply_damage(sim: &mut Simulation, ship: ShipHandle, damage: f64) -> bool {
+    let ship_destroyed = {
+        let ship_data = sim.ship_data.get_mut(ship.index()).unwrap();
+        let damage = match ship_data.shield.as_mut() {
+            Some(shield) => shield.absorb(damage),
+            None => damage,
+        };
+        ship_data.health -= damage;
+        ship_data.health <= 0.0
+    };
+    if !ship_destroyed {
+        return false;
+    }
+    for _ in 0..10 {
+        let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
+        let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..200.0), 0.0]);
+        let p =
+            sim.ship(ship).body().position().translation.vector + v * sim.rng.gen_range(0.0..0.1);
+        let lifetime =
+            (sim.ship_data.get(ship.index()).unwrap().mass.log2() * PHYSICS_TICK_LENGTH) as f32;
+        sim.events.particles.push(Particle {
+            position: p,
+            velocity: v,
+            color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
+            lifetime,
+        });
+    }
+    // Several hits can finish off a ship in the same tick.
+    if !sim.ship(ship).data().destroyed {
+        sim.emit_event(SimEvent::Explosion {
+            id: ship.into(),
+            position: sim.ship(ship).body().position().translation.vector,
+            velocity: *sim.ship(ship).body().linvel(),
+            mass: sim.ship(ship).data().mass,
+            class: sim.ship(ship).data().class,
+            cause: DestructionCause::Damage,
+        });
+    }
+    sim.ship_mut(ship).data_mut().destroyed = true;
+    true
+}
+
+/// Casts a laser beam from `origin` and damages the first enemy ship it
+/// hits. Walls, planets and active shields stop the beam. Returns where the
+/// beam ends.
+pub fn fire_laser(
+    sim: &mut Simulation,
+    shooter: ShipHandle,
+    origin: Vector<Real>,
+    direction: Vector<Real>,
+    range: f64,
+    damage: f64,
+) -> Vector<Real> {
+    let team = sim.ship(shooter).data().team;
+    let ray = Ray::new(origin.into(), direction);
+    let filter = QueryFilter::new()
+        .groups(bullet_interaction_groups(team))
+        .exclude_rigid_body(RigidBodyHandle(shooter.index()));
+    let hit = sim
+        .query_pipeline
+        .cast_ray(&sim.bodies, &sim.colliders, &ray, range, true, filter);
+    let (collider, toi) = match hit {
+        Some(hit) => hit,
+        None => return origin + direction * range,
+    };
+    let end = origin + direction * toi;
+    let target = sim
+        .colliders
+        .get(collider)
+        .and_then(|x| x.parent())
+        .map(|x| ShipHandle(x.0))
+        .filter(|&x| sim.ships.contains(x));
+    if let Some(target) = target {
+        if !sim.ship(target).is_ability_active(Ability::Shield) {
+            sim.emit_event(SimEvent::BulletHit { position: end });
+            apply_damage(sim, target, damage);
+        }
+    }
+    end
+}
+
+pub fn handle_collisions(sim: &mut Simulation, events: &[(CollisionEvent, f64)]) {
+    for &(ref event, impact_speed) in events {
         if let CollisionEvent::Started(h1, h2, _flags) = event {
             let get_index = |h| sim.colliders.get(h).and_then(|x| x.parent()).map(|x| x.0);
             let handle_hit = |sim: &mut Simulation, ship, bullet: BulletHandle| {
@@ -96,6 +223,9 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
                 let dv = bullet_velocity - sim.ship(ship).velocity();
                 let energy = 0.5 * bullet::data(sim, bullet).mass as f64 * dv.magnitude_squared();
                 let damage = energy * DAMAGE_FACTOR;
+                sim.emit_event(SimEvent::BulletHit {
+                    position: bullet_position,
+                });
                 for _ in 0..((damage as i32 / 10).clamp(1, 20)) {
                     let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
                     let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..1000.0), 0.0]);
@@ -107,27 +237,7 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
                         lifetime: (PHYSICS_TICK_LENGTH * 30.0) as f32,
                     });
                 }
-                let ship_destroyed = {
-                    let ship_data = sim.ship_data.get_mut(ship.index()).unwrap();
-                    ship_data.health -= damage;
-                    ship_data.health <= 0.0
-                };
-                if ship_destroyed {
-                    for _ in 0..10 {
-                        let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
-                        let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..200.0), 0.0]);
-                        let p = sim.ship(ship).body().position().translation.vector
-                            + v * sim.rng.gen_range(0.0..0.1);
-                        let lifetime = (sim.ship_data.get(ship.index()).unwrap().mass.log2()
-                            * PHYSICS_TICK_LENGTH) as f32;
-                        sim.events.particles.push(Particle {
-                            position: p,
-                            velocity: v,
-                            color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
-                            lifetime,
-                        });
-                    }
-                    sim.ship_mut(ship).data_mut().destroyed = true;
+                if apply_damage(sim, ship, damage) {
                     bullet::data_mut(sim, bullet).mass *= 0.5;
                     let rotation = UnitComplex::new(sim.rng.gen_range(-0.1..0.1));
                     let new_bullet_velocity = rotation.transform_vector(&bullet_velocity);
@@ -141,28 +251,38 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
                 enum Collider {
                     Bullet(BulletHandle),
                     Ship(ShipHandle),
+                    Obstacle,
                     Wall,
                 }
-                let classify_collider = |idx| {
+                let is_obstacle = |h| {
+                    sim.colliders.get(h).map_or(false, |x| {
+                        x.collision_groups().memberships == OBSTACLE_COLLISION_GROUP
+                    })
+                };
+                let classify_collider = |idx, h| {
                     if sim.bullets.contains(BulletHandle(idx)) {
                         Collider::Bullet(BulletHandle(idx))
                     } else if sim.ships.contains(ShipHandle(idx)) {
                         Collider::Ship(ShipHandle(idx))
+                    } else if is_obstacle(h) {
+                        Collider::Obstacle
                     } else {
                         Collider::Wall
                     }
                 };
-                let mut collider_types = [classify_collider(idx1), classify_collider(idx2)];
+                let mut collider_types =
+                    [classify_collider(idx1, *h1), classify_collider(idx2, *h2)];
                 collider_types.sort();
                 match collider_types {
                     [Collider::Bullet(b), Collider::Ship(s)] => {
                         handle_hit(sim, s, b);
                     }
-                    [Collider::Bullet(b), Collider::Wall] => {
+                    [Collider::Bullet(b), Collider::Obstacle | Collider::Wall] => {
                         bullet::destroy(sim, b);
                     }
                     [Collider::Ship(s1), Collider::Ship(s2)] => {
                         if sim.ship(s1).data().team != sim.ship(s2).data().team {
+                            handle_ram(sim, [s1, s2], impact_speed);
                             sim.ship_mut(s1).handle_collision();
                             sim.ship_mut(s2).handle_collision();
                         }
@@ -201,3 +321,67 @@ pub fn add_walls(sim: &mut Simulation) {
     make_edge(world_size / 2.0, 0.0, std::f64::consts::PI / 2.0);
     make_edge(-world_size / 2.0, 0.0, 3.0 * std::f64::consts::PI / 2.0);
 }
+
+/// Adds a fixed convex obstacle with the given corners. Obstacles block
+/// ships, bullets, lasers and radar but, unlike the world's walls, ships
+/// bounce off them instead of being destroyed.
+pub fn add_obstacle(sim: &mut Simulation, vertices: &[Point<Real>]) -> RigidBodyHandle {
+    let rigid_body = RigidBodyBuilder::fixed().build();
+    let body_handle = sim.bodies.insert(rigid_body);
+    let collider = ColliderBuilder::convex_hull(vertices)
+        .expect("obstacle must have at least three vertices")
+        .restitution(0.5)
+        .collision_groups(obstacle_interaction_groups())
+        .build();
+    sim.colliders
+        .insert_with_parent(collider, body_handle, &mut sim.bodies);
+    sim.num_obstacles += 1;
+    body_handle
+}
+
+pub fn remove_obstacle(sim: &mut Simulation, handle: RigidBodyHandle) {
+    if sim
+        .bodies
+        .remove(
+            handle,
+            &mut sim.island_manager,
+            &mut sim.colliders,
+            &mut sim.impulse_joints,
+            &mut sim.multibody_joints,
+            /*remove_attached_colliders=*/ true,
+        )
+        .is_some()
+    {
+        sim.num_obstacles -= 1;
+    }
+}
+
+/// Returns whether any obstacle's bounding box overlaps `aabb`.
+pub fn obstacle_in_aabb(sim: &Simulation, aabb: &Aabb) -> bool {
+    let mut found = false;
+    sim.query_pipeline
+        .colliders_with_aabb_intersecting_aabb(aabb, |&handle| {
+            found = sim.colliders.get(handle).map_or(false, |x| {
+                x.collision_groups().memberships == OBSTACLE_COLLISION_GROUP
+            });
+            !found
+        });
+    found
+}
+
+/// Returns whether no obstacle lies on the segment between two points.
+pub fn line_of_sight(sim: &Simulation, a: Point<Real>, b: Point<Real>) -> bool {
+    let dp = b - a;
+    let distance = dp.magnitude();
+    if distance == 0.0 {
+        return true;
+    }
+    let ray = Ray::new(a, dp / distance);
+    let filter = QueryFilter::new().groups(InteractionGroups::new(
+        SHIP_COLLISION_GROUP,
+        OBSTACLE_COLLISION_GROUP,
+    ));
+    sim.query_pipeline
+        .cast_ray(&sim.bodies, &sim.colliders, &ray, distance, true, filter)
+        .is_none()
+}
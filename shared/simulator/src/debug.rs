@@ -8,6 +8,63 @@ pub struct Line {
     pub a: Point2<f64>,
     pub b: Point2<f64>,
     pub color: Vector4<f32>,
+    /// Width in screen pixels.
+    #[serde(default = "default_width")]
+    pub width: f32,
+    #[serde(default)]
+    pub style: LineStyle,
+}
+
+impl Default for Line {
+    fn default() -> Self {
+        Self {
+            a: Point2::origin(),
+            b: Point2::origin(),
+            color: vector![1.0, 1.0, 1.0, 1.0],
+            width: default_width(),
+            style: LineStyle::Solid,
+        }
+    }
+}
+
+/// Text anchored at a point in the world. Scenarios use these to annotate
+/// tutorials.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Label {
+    pub position: Point2<f64>,
+    pub text: String,
+    pub color: Vector4<f32>,
+}
+
+fn default_width() -> f32 {
+    1.0
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub enum LineStyle {
+    #[default]
+    Solid,
+    /// Alternating dashes and gaps, with lengths in screen pixels.
+    Dashed { dash: f32, gap: f32 },
+    /// Solid with an arrowhead at `b`.
+    Arrow,
+}
+
+/// Approximates a circle with line segments.
+pub fn circle_lines(center: Point2<f64>, radius: f64, color: Vector4<f32>) -> Vec<Line> {
+    let n = 20;
+    (0..n)
+        .map(|i| {
+            let angle_a = std::f64::consts::TAU * (i as f64) / (n as f64);
+            let angle_b = std::f64::consts::TAU * ((i + 1) as f64) / (n as f64);
+            Line {
+                a: center + vector![radius * angle_a.cos(), radius * angle_a.sin()],
+                b: center + vector![radius * angle_b.cos(), radius * angle_b.sin()],
+                color,
+                ..Default::default()
+            }
+        })
+        .collect()
 }
 
 pub fn emit_ship(sim: &mut Simulation, handle: ShipHandle) {
@@ -19,6 +76,7 @@ pub fn emit_ship(sim: &mut Simulation, handle: ShipHandle) {
         a: p,
         b: p + body.linvel(),
         color: vector![0.0, 0.81, 1.0, 1.0],
+        ..Default::default()
     });
     lines.push(Line {
         a: p,
@@ -26,6 +84,7 @@ pub fn emit_ship(sim: &mut Simulation, handle: ShipHandle) {
             .rotation()
             .transform_vector(&sim.ship(handle).data().acceleration),
         color: vector![0.0, 1.0, 0.2, 1.0],
+        ..Default::default()
     });
     for gun in sim.ship(handle).data().guns.iter() {
         if gun.min_angle == gun.max_angle {
@@ -38,6 +97,7 @@ pub fn emit_ship(sim: &mut Simulation, handle: ShipHandle) {
             a: p0,
             b: p1,
             color: vector![1.0, 0.0, 0.0, 1.0],
+            ..Default::default()
         });
     }
     sim.emit_debug_lines(handle, lines);
@@ -3,12 +3,16 @@ pub mod collision;
 pub mod color;
 pub mod debug;
 pub mod index_set;
+pub mod matchup;
 pub mod model;
+pub mod point_defense;
 pub mod radar;
 pub mod radio;
 pub mod rng;
+pub mod rounds;
 pub mod scenario;
 pub mod ship;
 pub mod simulation;
 pub mod snapshot;
+pub mod topology;
 pub mod vm;
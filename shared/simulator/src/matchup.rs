@@ -0,0 +1,86 @@
+use crate::scenario::{self, Status};
+use crate::simulation::{Code, Simulation};
+
+/// Result of a single game from the perspective of the first code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Game {
+    pub seed: u32,
+    /// Whether the first code played as team 1.
+    pub swapped: bool,
+    pub outcome: Outcome,
+    pub time: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Results {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub games: Vec<Game>,
+}
+
+impl Results {
+    pub fn from_games(games: Vec<Game>) -> Self {
+        let mut results = Results::default();
+        for game in games.iter() {
+            match game.outcome {
+                Outcome::Win => results.wins += 1,
+                Outcome::Loss => results.losses += 1,
+                Outcome::Draw => results.draws += 1,
+            }
+        }
+        results.games = games;
+        results
+    }
+}
+
+/// Returns whether the first code plays as team 1 for the i'th seed.
+pub fn swapped(index: usize) -> bool {
+    index % 2 == 1
+}
+
+/// Runs one game with `a` and `b` on opposite teams.
+pub fn run_game(scenario_name: &str, a: &Code, b: &Code, seed: u32, swapped: bool) -> Game {
+    let codes = if swapped {
+        [b.clone(), a.clone()]
+    } else {
+        [a.clone(), b.clone()]
+    };
+    let mut sim = Simulation::new(scenario_name, seed, &codes);
+    while sim.status() == Status::Running && sim.tick() < scenario::MAX_TICKS {
+        sim.step();
+    }
+    let a_team = if swapped { 1 } else { 0 };
+    let outcome = match sim.status() {
+        Status::Victory { team } if team == a_team => Outcome::Win,
+        Status::Victory { .. } => Outcome::Loss,
+        // Failed isn't attributed to either side, and running out of time is a draw.
+        Status::Failed | Status::Draw | Status::Running => Outcome::Draw,
+    };
+    Game {
+        seed,
+        swapped,
+        outcome,
+        time: sim.score_time(),
+    }
+}
+
+/// Runs `a` against `b` once per seed, alternating which team each code plays.
+///
+/// Results only depend on the codes and the seed list.
+pub fn run(scenario_name: &str, a: &Code, b: &Code, seeds: &[u32]) -> Results {
+    Results::from_games(
+        seeds
+            .iter()
+            .enumerate()
+            .map(|(i, &seed)| run_game(scenario_name, a, b, seed, swapped(i)))
+            .collect(),
+    )
+}
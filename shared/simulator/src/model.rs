@@ -61,16 +61,61 @@ pub fn cruiser() -> Vec<Vector2<f32>> {
     )
 }
 
+/// Radius of each asteroid size tier, picked by `variant % 3`.
+const ASTEROID_RADII: [f32; 3] = [30.0, 50.0, 80.0];
+
+/// Returns an irregular convex polygon, the same every time for a given
+/// variant. The physics collider uses the same polygon.
 pub fn asteroid(variant: i32) -> Vec<Vector2<f32>> {
-    let n = 7;
     let mut rng = Rand32::new(variant as u64);
+    let radius = ASTEROID_RADII[variant.rem_euclid(ASTEROID_RADII.len() as i32) as usize];
+    let n = 7 + rng.rand_range(0..5);
     let mut vertices = vec![];
     for i in 0..n {
-        let r = rng.rand_float();
-        let rotation = Rotation2::new(i as f32 * 2.0 * std::f32::consts::PI / n as f32);
-        vertices.push(rotation.transform_vector(&vector![r, 0.0]));
+        let jitter = 0.8 * (rng.rand_float() - 0.5);
+        let angle = (i as f32 + jitter) * std::f32::consts::TAU / n as f32;
+        let r = radius * (0.7 + 0.3 * rng.rand_float());
+        vertices.push(Rotation2::new(angle).transform_vector(&vector![r, 0.0]));
+    }
+    convex_hull(vertices)
+}
+
+/// Returns the convex hull in counterclockwise order (Andrew's monotone
+/// chain).
+fn convex_hull(mut points: Vec<Vector2<f32>>) -> Vec<Vector2<f32>> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    let cross = |o: Vector2<f32>, a: Vector2<f32>, b: Vector2<f32>| {
+        (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+    };
+    let mut hull: Vec<Vector2<f32>> = vec![];
+    for &p in points.iter() {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    let lower_len = hull.len() + 1;
+    for &p in points.iter().rev().skip(1) {
+        while hull.len() >= lower_len && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // The last point is the first point of the lower hull.
+    hull.pop();
+    hull
+}
+
+/// Area of a simple polygon.
+pub fn area(vertices: &[Vector2<f32>]) -> f32 {
+    let mut sum = 0.0;
+    for i in 0..vertices.len() {
+        let a = vertices[i];
+        let b = vertices[(i + 1) % vertices.len()];
+        sum += a.x * b.y - b.x * a.y;
     }
-    scale(50.0, &vertices)
+    sum.abs() / 2.0
 }
 
 pub fn target() -> Vec<Vector2<f32>> {
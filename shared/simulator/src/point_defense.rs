@@ -0,0 +1,146 @@
+use crate::ship::ShipHandle;
+use crate::simulation::Simulation;
+use crate::vm::translate_class;
+use nalgebra::Vector2;
+use oort_api::prelude::angle_diff;
+use oort_api::Class;
+use std::f64::consts::TAU;
+
+// How far outside a gun's traverse a target can be and still be engaged.
+const TRAVERSE_TOLERANCE: f64 = 0.01;
+
+struct Candidate {
+    team: i32,
+    class: Class,
+    position: Vector2<f64>,
+    velocity: Vector2<f64>,
+}
+
+/// Returns the time at which a projectile fired at `speed` from the origin
+/// meets a target at `dp` moving with velocity `dv`.
+fn intercept_time(dp: Vector2<f64>, dv: Vector2<f64>, speed: f64) -> Option<f64> {
+    let a = dv.dot(&dv) - speed * speed;
+    let b = 2.0 * dp.dot(&dv);
+    let c = dp.dot(&dp);
+    if a.abs() < 1e-9 {
+        let t = -c / b;
+        return (t > 0.0).then_some(t);
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let sqrt = discriminant.sqrt();
+    [(-b - sqrt) / (2.0 * a), (-b + sqrt) / (2.0 * a)]
+        .into_iter()
+        .filter(|&t| t > 0.0)
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+/// Aims and fires guns set to auto-target at the enemy of the chosen class
+/// that they can hit soonest. Runs after scripts so it doesn't count against
+/// their budget, and uses the gun's normal reload.
+pub(crate) fn tick(sim: &mut Simulation) {
+    let shooters: Vec<ShipHandle> = sim
+        .ships
+        .iter()
+        .copied()
+        .filter(|&handle| {
+            sim.ship(handle)
+                .data()
+                .guns
+                .iter()
+                .any(|gun| gun.auto_target.is_some())
+        })
+        .collect();
+    if shooters.is_empty() {
+        return;
+    }
+
+    let candidates: Vec<Candidate> = sim
+        .ships
+        .iter()
+        .map(|&handle| {
+            let ship = sim.ship(handle);
+            Candidate {
+                team: ship.data().team,
+                class: translate_class(ship.data().class),
+                position: ship.position().vector,
+                velocity: ship.velocity(),
+            }
+        })
+        .collect();
+
+    for handle in shooters {
+        let mut targets = vec![];
+        {
+            let ship = sim.ship(handle);
+            let team = ship.data().team;
+            let heading = ship.heading();
+            let rotation = ship.body().position().rotation;
+            for (index, gun) in ship.data().guns.iter().enumerate() {
+                let class = match gun.auto_target {
+                    Some(class) if gun.reload_ticks_remaining == 0 => class,
+                    _ => continue,
+                };
+                let origin = ship.position().vector + rotation.transform_vector(&gun.offset);
+                let best = candidates
+                    .iter()
+                    .filter(|c| c.team != team && c.class == class)
+                    .filter_map(|c| {
+                        let dp = c.position - origin;
+                        let dv = c.velocity - ship.velocity();
+                        let t = intercept_time(dp, dv, gun.speed)?;
+                        if t > gun.ttl as f64 {
+                            return None;
+                        }
+                        let aim = dp + dv * t;
+                        let angle = aim.y.atan2(aim.x);
+                        let relative = (angle - heading).rem_euclid(TAU);
+                        let clamped = relative.clamp(gun.min_angle, gun.max_angle);
+                        if angle_diff(relative, clamped).abs() > TRAVERSE_TOLERANCE {
+                            return None;
+                        }
+                        Some((t, angle))
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+                if let Some((_, angle)) = best {
+                    targets.push((index as i64, angle));
+                }
+            }
+        }
+        for (index, angle) in targets {
+            sim.ship_mut(handle).aim(index, angle);
+            sim.ship_mut(handle).fire(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::intercept_time;
+    use nalgebra::vector;
+
+    #[test]
+    fn test_intercept_time() {
+        // Stationary target.
+        let t = intercept_time(vector![1000.0, 0.0], vector![0.0, 0.0], 1000.0).unwrap();
+        approx::assert_abs_diff_eq!(t, 1.0, epsilon = 1e-9);
+
+        // Head-on.
+        let t = intercept_time(vector![1000.0, 0.0], vector![-1000.0, 0.0], 1000.0).unwrap();
+        approx::assert_abs_diff_eq!(t, 0.5, epsilon = 1e-9);
+
+        // Crossing.
+        let dp = vector![1000.0, 0.0];
+        let dv = vector![0.0, 300.0];
+        let t = intercept_time(dp, dv, 1000.0).unwrap();
+        approx::assert_abs_diff_eq!((dp + dv * t).norm(), 1000.0 * t, epsilon = 1e-6);
+
+        // Outrunning the projectile.
+        assert_eq!(
+            intercept_time(vector![1000.0, 0.0], vector![2000.0, 0.0], 1000.0),
+            None
+        );
+    }
+}
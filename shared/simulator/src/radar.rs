@@ -1,5 +1,7 @@
+use crate::collision;
 use crate::ship::{self, ShipClass, ShipHandle};
 use crate::simulation::{Line, Simulation};
+use crate::topology::{self, WorldTopology};
 use crate::{model, rng, simulation};
 use nalgebra::Rotation2;
 use nalgebra::{vector, Point2, Vector2};
@@ -8,6 +10,7 @@ use rand::Rng;
 use rand_distr::StandardNormal;
 use rapier2d_f64::parry;
 use rapier2d_f64::prelude::*;
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::f64::consts::TAU;
 use std::ops::Range;
@@ -20,8 +23,10 @@ const BEARING_NOISE_FACTOR: f64 = 1e1 * (TAU / 360.0);
 const DISTANCE_NOISE_FACTOR: f64 = 1e4;
 const VELOCITY_NOISE_FACTOR: f64 = 1e2;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
 pub struct Radar {
+    #[serde(skip)]
     pub heading: f64,
     pub width: f64,
     pub min_width: f64,
@@ -32,8 +37,13 @@ pub struct Radar {
     pub rx_cross_section: f64,
     pub reliable_rssi: f64,
     pub min_rssi: f64,
+    #[serde(skip)]
     pub ecm_mode: EcmMode,
+    #[serde(skip)]
     pub result: Option<ScanResult>,
+    /// The most recent contact and the number of ticks since it was seen.
+    #[serde(skip)]
+    pub last_contact: Option<(ScanResult, u32)>,
 }
 
 impl Default for Radar {
@@ -51,6 +61,7 @@ impl Default for Radar {
             min_rssi: from_dbm(-100.0),
             ecm_mode: EcmMode::None,
             result: None,
+            last_contact: None,
         }
     }
 }
@@ -95,6 +106,10 @@ impl Radar {
     pub fn scan(&self) -> Option<ScanResult> {
         self.result
     }
+
+    pub fn last_contact(&self) -> Option<(ScanResult, u32)> {
+        self.last_contact
+    }
 }
 
 struct RadarEmitter {
@@ -165,7 +180,7 @@ fn build_reflector_team(sim: &Simulation) -> Vec<ReflectorTeam> {
         let ship_data = ship.data();
 
         let mut class = ship_data.class;
-        let mut radar_cross_section = ship_data.radar_cross_section;
+        let mut radar_cross_section = ship.radar_cross_section();
         if ship.is_ability_active(Ability::Decoy) {
             class = ShipClass::Cruiser;
             radar_cross_section = ship::CRUISER_RADAR_CROSS_SECTION / 2.0;
@@ -237,8 +252,45 @@ fn build_reflector_team(sim: &Simulation) -> Vec<ReflectorTeam> {
     result
 }
 
+/// Outcome of a single radar scan, computed from a read-only view of the simulation.
+struct Scan {
+    emitter: RadarEmitter,
+    reliable_distance: f64,
+    result: Option<ScanResult>,
+    debug_text: Option<String>,
+}
+
 #[inline(never)]
 pub fn tick(sim: &mut Simulation) {
+    let scans = scan(sim);
+    for scan in scans.iter() {
+        {
+            let mut ship = sim.ship_mut(scan.emitter.handle);
+            let ship_data = ship.data_mut();
+            let radar = ship_data.radar.as_mut().unwrap();
+            radar.result = scan.result;
+            radar.last_contact = match (scan.result, radar.last_contact) {
+                (Some(contact), _) => Some((contact, 0)),
+                (None, Some((contact, age))) => Some((contact, age.saturating_add(1))),
+                (None, None) => None,
+            };
+        }
+
+        if let Some(text) = scan.debug_text.as_ref() {
+            sim.emit_debug_text(scan.emitter.handle, text.clone());
+        }
+        draw_emitter(sim, &scan.emitter, scan.reliable_distance);
+        if let Some(contact) = &scan.result {
+            draw_contact(sim, scan.emitter.handle, contact);
+        }
+    }
+}
+
+/// Runs every ship's radar. The simulation is only read here; results are
+/// written back by `tick` afterwards.
+#[inline(never)]
+fn scan(sim: &Simulation) -> Vec<Scan> {
+    let mut scans = Vec::new();
     let handle_snapshot: Vec<ShipHandle> = sim.ships.iter().cloned().collect();
     let reflector_teams = build_reflector_team(sim);
     let mut candidates: Vec<(i32, usize)> = Vec::new();
@@ -248,6 +300,9 @@ pub fn tick(sim: &mut Simulation) {
         .filter(|handle| sim.ship(**handle).data().class == ShipClass::Planet)
         .cloned()
         .collect::<Vec<_>>();
+    let world_size = sim.world_size();
+    let topology = sim.topology();
+    let wrap_size = (topology == WorldTopology::Wrapping).then_some(world_size);
 
     for handle in handle_snapshot.iter().cloned() {
         let ship = sim.ship(handle);
@@ -289,20 +344,21 @@ pub fn tick(sim: &mut Simulation) {
             };
 
             if radar.ecm_mode != EcmMode::None {
-                {
-                    let mut ship = sim.ship_mut(emitter.handle);
-                    let ship_data = ship.data_mut();
-                    let radar = ship_data.radar.as_mut().unwrap();
-                    radar.result = None;
-                }
-                draw_emitter(sim, &emitter, reliable_distance);
+                scans.push(Scan {
+                    emitter,
+                    reliable_distance,
+                    result: None,
+                    debug_text: None,
+                });
                 continue;
             }
 
             let mut rng = rng::new_rng(sim.tick());
 
             let mut best_rssi = emitter.min_rssi;
-            let mut best_reflector: Option<&RadarReflector> = None;
+            // Paired with the reflector's position as seen by this emitter, which
+            // is the nearest copy across the edge when the world wraps.
+            let mut best_reflector: Option<(&RadarReflector, Point2<f64>)> = None;
             let mut received_noise = BACKGROUND_NOISE * 2.0f64.powf(rng.gen_range(-1.0..1.0));
             candidates.clear();
 
@@ -313,24 +369,28 @@ pub fn tick(sim: &mut Simulation) {
                 emitter.square_distance_range.end = planet_distance.powi(2);
             }
 
-            find_candidates(&emitter, &reflector_teams, &mut candidates);
+            find_candidates(&emitter, &reflector_teams, wrap_size, &mut candidates);
 
             for (team, reflector_index) in candidates.iter() {
                 let reflector = &reflector_teams[*team as usize].reflectors[*reflector_index];
+                let position = emitter.center
+                    + topology::world_offset(
+                        emitter.center.coords,
+                        reflector.position.coords,
+                        world_size,
+                        topology,
+                    );
                 if let Some(jammer) = reflector.jammer.as_ref() {
                     match jammer.ecm_mode {
                         EcmMode::None => {}
                         EcmMode::Noise => {
                             if check_inside_beam_raw(
-                                &reflector.position,
+                                &position,
                                 jammer.bearing,
                                 jammer.width,
                                 &emitter.center,
                             ) {
-                                let r_sq = nalgebra::distance_squared(
-                                    &emitter.center,
-                                    &reflector.position,
-                                );
+                                let r_sq = nalgebra::distance_squared(&emitter.center, &position);
                                 received_noise +=
                                     JAMMER_COEFF * jammer.power * emitter.rx_cross_section
                                         / (TAU * jammer.width * r_sq);
@@ -341,15 +401,16 @@ pub fn tick(sim: &mut Simulation) {
 
                 if emitter
                     .square_distance_range
-                    .contains(&nalgebra::distance_squared(
-                        &emitter.center,
-                        &reflector.position,
-                    ))
+                    .contains(&nalgebra::distance_squared(&emitter.center, &position))
                 {
-                    let rssi =
-                        compute_rssi(&emitter, reflector) * 1.2f64.powf(rng.gen_range(-1.0..1.0));
-                    if rssi > best_rssi {
-                        best_reflector = Some(reflector);
+                    let rssi = compute_rssi(&emitter, reflector, &position)
+                        * 1.2f64.powf(rng.gen_range(-1.0..1.0));
+                    // Checked last since it's the most expensive test.
+                    if rssi > best_rssi
+                        && (!sim.radar_occlusion()
+                            || collision::line_of_sight(sim, emitter.center, position))
+                    {
+                        best_reflector = Some((reflector, position));
                         best_rssi = rssi;
                     }
                 }
@@ -359,20 +420,19 @@ pub fn tick(sim: &mut Simulation) {
             let received_noise_dbm = into_dbm(received_noise);
             let signal_db = best_rssi_dbm - received_noise_dbm;
 
-            if DEBUG {
-                if let Some(reflector) = best_reflector {
-                    sim.emit_debug_text(
-                        handle,
-                        format!(
-                            "Radar contact range {:.1} km rssi {:.1} dBm noise {:.1} dBm signal {:.1} dB",
-                            (reflector.position - emitter.center).norm() * 1e-3,
-                            into_dbm(best_rssi),
-                            into_dbm(received_noise),
-                            signal_db,
-                        ),
-                    );
-                }
-            }
+            let debug_text = if DEBUG {
+                best_reflector.map(|(_, position)| {
+                    format!(
+                        "Radar contact range {:.1} km rssi {:.1} dBm noise {:.1} dBm signal {:.1} dB",
+                        (position - emitter.center).norm() * 1e-3,
+                        into_dbm(best_rssi),
+                        into_dbm(received_noise),
+                        signal_db,
+                    )
+                })
+            } else {
+                None
+            };
 
             let result = if signal_db < 3.0
                 || best_rssi < emitter.min_rssi
@@ -381,10 +441,11 @@ pub fn tick(sim: &mut Simulation) {
             {
                 None
             } else {
-                best_reflector.map(|reflector| {
+                best_reflector.map(|(reflector, position)| {
                     make_scan_result(
                         &emitter,
                         reflector,
+                        &position,
                         best_rssi_dbm,
                         received_noise_dbm,
                         &mut rng,
@@ -392,25 +453,23 @@ pub fn tick(sim: &mut Simulation) {
                 })
             };
 
-            {
-                let mut ship = sim.ship_mut(emitter.handle);
-                let ship_data = ship.data_mut();
-                let radar = ship_data.radar.as_mut().unwrap();
-                radar.result = result;
-            }
-
-            draw_emitter(sim, &emitter, reliable_distance);
-            if let Some(contact) = &result {
-                draw_contact(sim, emitter.handle, contact);
-            }
+            scans.push(Scan {
+                emitter,
+                reliable_distance,
+                result,
+                debug_text,
+            });
         }
     }
+
+    scans
 }
 
 #[inline(never)]
 fn find_candidates(
     emitter: &RadarEmitter,
     reflector_teams: &[ReflectorTeam],
+    wrap_size: Option<f64>,
     candidates: &mut Vec<(i32, usize)>,
 ) {
     let rays = [emitter.rays[0].cast::<f32>(), emitter.rays[1].cast::<f32>()];
@@ -431,8 +490,13 @@ fn find_candidates(
 
         let n = reflector_team.reflectors.len();
         for (i, (&wx, &wy)) in reflector_team.xs.iter().zip(&reflector_team.ys).enumerate() {
-            let wdx = wx - wex;
-            let wdy = wy - wey;
+            let mut wdx = wx - wex;
+            let mut wdy = wy - wey;
+            if let Some(size) = wrap_size {
+                let size = f32x4::splat(size as f32);
+                wdx = wdx - (wdx / size).round() * size;
+                wdy = wdy - (wdy / size).round() * size;
+            }
 
             // Positive if true.
             fn is_clockwise(wx0: f32x4, wy0: f32x4, wx1: f32x4, wy1: f32x4) -> f32x4 {
@@ -457,13 +521,14 @@ fn find_candidates(
 fn make_scan_result(
     emitter: &RadarEmitter,
     reflector: &RadarReflector,
+    reflector_position: &Point2<f64>,
     rssi_dbm: f64,
     noise_dbm: f64,
     rng: &mut impl Rng,
 ) -> ScanResult {
     let signal_db = rssi_dbm - noise_dbm;
     let error_factor = 10.0f64.powf(-signal_db / 10.0);
-    let dp = reflector.position - emitter.center;
+    let dp = reflector_position - emitter.center;
     let beam_rot = Rotation2::new(emitter.bearing);
     let reflector_rot = Rotation2::rotation_between(&Vector2::x(), &dp);
     let mut noisy_bearing: f64 = reflector_rot.angle()
@@ -477,7 +542,7 @@ fn make_scan_result(
         }
     }
 
-    let mut distance = (reflector.position - emitter.center).magnitude();
+    let mut distance = dp.magnitude();
     distance += rng.sample::<f64, _>(StandardNormal) * (DISTANCE_NOISE_FACTOR * error_factor);
     distance = distance.clamp(emitter.min_distance, emitter.max_distance);
 
@@ -521,8 +586,12 @@ fn check_inside_beam_raw(
     }
 }
 
-fn compute_rssi(emitter: &RadarEmitter, reflector: &RadarReflector) -> f64 {
-    let r_sq = nalgebra::distance_squared(&emitter.center, &reflector.position);
+fn compute_rssi(
+    emitter: &RadarEmitter,
+    reflector: &RadarReflector,
+    reflector_position: &Point2<f64>,
+) -> f64 {
+    let r_sq = nalgebra::distance_squared(&emitter.center, reflector_position);
     emitter.power * reflector.radar_cross_section * emitter.rx_cross_section
         / (TAU * emitter.width * r_sq * r_sq)
 }
@@ -592,6 +661,7 @@ fn draw_emitter(sim: &mut Simulation, emitter: &RadarEmitter, reliable_distance:
                 a: center + vector![r * angle_a.cos(), r * angle_a.sin()],
                 b: center + vector![r * angle_b.cos(), r * angle_b.sin()],
                 color,
+                ..Default::default()
             });
         }
     };
@@ -605,6 +675,7 @@ fn draw_emitter(sim: &mut Simulation, emitter: &RadarEmitter, reliable_distance:
                 reliable_distance * emitter.start_bearing.sin()
             ],
         color,
+        ..Default::default()
     });
     lines.push(Line {
         a: center,
@@ -614,6 +685,7 @@ fn draw_emitter(sim: &mut Simulation, emitter: &RadarEmitter, reliable_distance:
                 reliable_distance * emitter.end_bearing.sin()
             ],
         color,
+        ..Default::default()
     });
     sim.emit_debug_lines(emitter.handle, lines);
 }
@@ -631,21 +703,25 @@ fn draw_contact(sim: &mut Simulation, emitter_handle: ShipHandle, contact: &Scan
             a: v0,
             b: v1,
             color,
+            ..Default::default()
         },
         Line {
             a: v1,
             b: v2,
             color,
+            ..Default::default()
         },
         Line {
             a: v2,
             b: v3,
             color,
+            ..Default::default()
         },
         Line {
             a: v3,
             b: v0,
             color,
+            ..Default::default()
         },
     ];
     sim.emit_debug_lines(emitter_handle, lines);
@@ -653,11 +729,12 @@ fn draw_contact(sim: &mut Simulation, emitter_handle: ShipHandle, contact: &Scan
 
 #[cfg(test)]
 mod test {
+    use crate::collision;
     use crate::ship;
     use crate::ship::ShipClass;
     use crate::simulation::Code;
     use crate::simulation::Simulation;
-    use nalgebra::{vector, UnitComplex};
+    use nalgebra::{point, vector, UnitComplex};
     use oort_api::EcmMode;
     use rand::Rng;
     use std::f64::consts::{PI, TAU};
@@ -725,6 +802,108 @@ mod test {
         assert_eq!(sim.ship(ship0).radar().unwrap().result.is_some(), false);
     }
 
+    #[test]
+    fn test_last_contact() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let _ship1 = ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        assert!(sim.ship(ship0).radar().unwrap().last_contact().is_none());
+
+        sim.step();
+        let (contact, age) = sim.ship(ship0).radar().unwrap().last_contact().unwrap();
+        assert_eq!(age, 0);
+        assert_eq!(contact.class, ShipClass::Target);
+
+        // Point away from the target and the contact ages.
+        sim.ship_mut(ship0).radar_mut().unwrap().heading = TAU / 2.0;
+        sim.step();
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().scan().is_none());
+        let (last, age) = sim.ship(ship0).radar().unwrap().last_contact().unwrap();
+        assert_eq!(age, 2);
+        assert_eq!(last.position, contact.position);
+
+        sim.ship_mut(ship0).radar_mut().unwrap().heading = 0.0;
+        sim.step();
+        assert_eq!(
+            sim.ship(ship0).radar().unwrap().last_contact().unwrap().1,
+            0
+        );
+    }
+
+    #[test]
+    fn test_obstacle_blocks_line_of_sight() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        let obstacle = collision::add_obstacle(
+            &mut sim,
+            &[
+                point![400.0, -100.0],
+                point![600.0, -100.0],
+                point![600.0, 100.0],
+                point![400.0, 100.0],
+            ],
+        );
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().scan().is_none());
+
+        collision::remove_obstacle(&mut sim, obstacle);
+        assert_eq!(sim.num_obstacles, 0);
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().scan().is_some());
+
+        // Removing a stale handle is a no-op.
+        collision::remove_obstacle(&mut sim, obstacle);
+        assert_eq!(sim.num_obstacles, 0);
+
+        collision::add_obstacle(
+            &mut sim,
+            &[
+                point![400.0, -100.0],
+                point![600.0, -100.0],
+                point![600.0, 100.0],
+                point![400.0, 100.0],
+            ],
+        );
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().scan().is_none());
+
+        // Move the target out from behind the obstacle.
+        sim.ship_mut(ship1)
+            .body()
+            .set_translation(vector![1000.0, 500.0], true);
+        sim.ship_mut(ship0).radar_mut().unwrap().heading = (0.5f64).atan();
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().scan().is_some());
+    }
+
     #[test]
     fn test_distance_filter() {
         let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
@@ -817,6 +996,46 @@ mod test {
         assert!(!check_detection(Fighter, Cruiser, 150e3));
     }
 
+    #[test]
+    fn test_reduced_cross_section() {
+        let check_detection = |radar_cross_section| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            let offset = vector![-90e3, 0.0];
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0] + offset,
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            let ship1 = ship::create(
+                &mut sim,
+                vector![70e3, 0.0] + offset,
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(1),
+            );
+            sim.ship_mut(ship1)
+                .set_radar_cross_section(radar_cross_section);
+            sim.ship_mut(ship0).radar_mut().unwrap().heading = 0.0;
+            sim.ship_mut(ship0).radar_mut().unwrap().width = TAU / 360.0;
+
+            (0..100)
+                .map(|_| {
+                    sim.step();
+                    sim.ship(ship0).radar().unwrap().result.is_some()
+                })
+                .filter(|x| *x)
+                .count()
+                > 50
+        };
+
+        assert!(check_detection(10.0));
+        assert!(!check_detection(2.5));
+        // Clamped to a quarter of the fighter's cross-section.
+        assert!(!check_detection(0.0));
+    }
+
     #[test]
     fn test_jamming() {
         let check_detection = |range| {
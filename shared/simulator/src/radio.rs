@@ -2,21 +2,40 @@ use crate::ship::ShipHandle;
 use crate::simulation::Simulation;
 use nalgebra::Point2;
 use oort_api::Message;
+use serde::Deserialize;
 use std::collections::BTreeMap;
 use std::f64::consts::TAU;
 
 const NUM_CHANNELS: usize = 10;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
 pub struct Radio {
     pub(crate) power: f64,
     pub(crate) rx_cross_section: f64,
     pub(crate) min_rssi: f64,
+    #[serde(skip)]
     pub(crate) channel: usize,
+    #[serde(skip)]
     pub(crate) sent: Option<Message>,
+    #[serde(skip)]
     pub(crate) received: Option<Message>,
 }
 
+impl Default for Radio {
+    fn default() -> Self {
+        // TODO tune this
+        Radio {
+            power: 20e3,
+            rx_cross_section: 5.0,
+            min_rssi: 1e-5,
+            channel: 0,
+            sent: None,
+            received: None,
+        }
+    }
+}
+
 impl Radio {
     pub fn get_channel(&self) -> usize {
         self.channel
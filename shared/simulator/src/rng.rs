@@ -1,5 +1,13 @@
 pub use rand_chacha::ChaCha8Rng as SeededRng;
 
+/// Returns a deterministic RNG. Scenarios use this instead of `thread_rng`.
 pub fn new_rng(seed: u32) -> SeededRng {
     rand_seeder::Seeder::from(seed).make_rng()
 }
+
+/// Returns a deterministic RNG seeded from several values, e.g. the
+/// simulation seed and the tick. Use fixed-size integers, since usize hashes
+/// differently on wasm32.
+pub fn new_rng_from(key: impl std::hash::Hash) -> SeededRng {
+    rand_seeder::Seeder::from(key).make_rng()
+}
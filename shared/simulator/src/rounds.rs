@@ -0,0 +1,202 @@
+use crate::scenario::{self, Status};
+use crate::simulation::{Code, Simulation};
+use crate::snapshot::Snapshot;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Round {
+    pub seed: u32,
+    pub status: Status,
+    pub time: f64,
+}
+
+/// Results so far, from team 0's point of view.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Tally {
+    pub total: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub rounds: Vec<Round>,
+}
+
+impl Tally {
+    fn record(&mut self, round: Round) {
+        match round.status {
+            Status::Victory { team: 0 } => self.wins += 1,
+            Status::Victory { .. } | Status::Failed => self.losses += 1,
+            Status::Draw | Status::Running => self.draws += 1,
+        }
+        self.rounds.push(round);
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.rounds.len() as u32 >= self.total
+    }
+
+    /// Mean time of the rounds team 0 won, or `None` if it won none.
+    pub fn mean_win_time(&self) -> Option<f64> {
+        let times: Vec<f64> = self
+            .rounds
+            .iter()
+            .filter(|round| round.status == Status::Victory { team: 0 })
+            .map(|round| round.time)
+            .collect();
+        if times.is_empty() {
+            None
+        } else {
+            Some(times.iter().sum::<f64>() / times.len() as f64)
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Round {}/{}: {}W {}L {}D",
+            (self.rounds.len() as u32 + 1).min(self.total),
+            self.total,
+            self.wins,
+            self.losses,
+            self.draws
+        )
+    }
+}
+
+/// Returns the seed for a round, so that rerunning a session with the same
+/// base seed replays the same rounds.
+pub fn round_seed(base_seed: u32, round: u32) -> u32 {
+    base_seed.wrapping_add(round)
+}
+
+/// Runs a scenario for `Scenario::rounds` rounds. Whenever a round ends the
+/// result is recorded and the scenario starts over with the next round's
+/// seed.
+pub struct Session {
+    scenario_name: String,
+    base_seed: u32,
+    codes: Vec<Code>,
+    practice: bool,
+    max_ticks: u32,
+    sim: Box<Simulation>,
+    tally: Tally,
+    // Ticks and time from earlier rounds, so snapshots keep moving forward.
+    tick_offset: u32,
+    time_offset: f64,
+}
+
+impl Session {
+    pub fn new(scenario_name: &str, base_seed: u32, codes: &[Code]) -> Self {
+        let rounds = scenario::load(scenario_name).rounds();
+        Self::with_rounds(scenario_name, base_seed, codes, rounds)
+    }
+
+    pub fn with_rounds(scenario_name: &str, base_seed: u32, codes: &[Code], rounds: u32) -> Self {
+        Self {
+            scenario_name: scenario_name.to_string(),
+            base_seed,
+            codes: codes.to_vec(),
+            practice: false,
+            max_ticks: scenario::load(scenario_name).max_ticks(),
+            sim: Simulation::new(scenario_name, round_seed(base_seed, 0), codes),
+            tally: Tally {
+                total: rounds.max(1),
+                ..Default::default()
+            },
+            tick_offset: 0,
+            time_offset: 0.0,
+        }
+    }
+
+    pub fn set_practice(&mut self, practice: bool) {
+        self.practice = practice;
+        self.sim.practice = practice;
+    }
+
+    pub fn sim(&self) -> &Simulation {
+        &self.sim
+    }
+
+    pub fn sim_mut(&mut self) -> &mut Simulation {
+        &mut self.sim
+    }
+
+    pub fn tally(&self) -> &Tally {
+        &self.tally
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.tally.is_complete()
+    }
+
+    /// Steps the current round, starting the next one if it ended.
+    pub fn step(&mut self) {
+        if self.is_complete() {
+            return;
+        }
+        self.sim.step();
+        let status = self.sim.status();
+        if status == Status::Running && self.sim.tick() < self.max_ticks {
+            return;
+        }
+        self.tally.record(Round {
+            seed: self.sim.seed(),
+            status: if status == Status::Running {
+                Status::Draw
+            } else {
+                status
+            },
+            time: self.sim.score_time(),
+        });
+        if !self.is_complete() {
+            self.tick_offset += self.sim.tick();
+            self.time_offset += self.sim.time();
+            let seed = round_seed(self.base_seed, self.tally.rounds.len() as u32);
+            self.sim = Simulation::new(&self.scenario_name, seed, &self.codes);
+            self.sim.practice = self.practice;
+        }
+    }
+
+    /// Steps until every round has been played.
+    pub fn run(&mut self) {
+        while !self.is_complete() {
+            self.step();
+        }
+    }
+
+    /// The current round's status until the last round, then the overall
+    /// result.
+    pub fn status(&self) -> Status {
+        if self.tally.total == 1 {
+            self.sim.status()
+        } else if !self.is_complete() {
+            Status::Running
+        } else if self.tally.wins > self.tally.losses {
+            Status::Victory { team: 0 }
+        } else if self.tally.losses > self.tally.wins {
+            Status::Failed
+        } else {
+            Status::Draw
+        }
+    }
+
+    /// The round's score time for single-round sessions, otherwise the mean
+    /// over the rounds won so far.
+    pub fn score_time(&self) -> f64 {
+        if self.tally.total == 1 {
+            self.sim.score_time()
+        } else {
+            self.tally.mean_win_time().unwrap_or(0.0)
+        }
+    }
+
+    pub fn snapshot(&self, nonce: u32) -> Snapshot {
+        let mut snapshot = self.sim.snapshot(nonce);
+        if self.tally.total > 1 {
+            snapshot.tick += self.tick_offset;
+            snapshot.time += self.time_offset;
+            snapshot.status = self.status();
+            snapshot.score_time = self.score_time();
+            snapshot.rounds = Some(self.tally.clone());
+        }
+        snapshot
+    }
+}
@@ -24,22 +24,26 @@ impl Scenario for AsteroidDuel {
             (sim.world_size() / 2.0) * 0.9
         ];
 
-        ship::create(
-            sim,
-            vector![
+        let ship_positions = [
+            point![
                 rng.gen_range(-bound.x..bound.x),
                 rng.gen_range(-bound.y..bound.y)
             ],
+            point![
+                rng.gen_range(-bound.x..bound.x),
+                rng.gen_range(-bound.y..bound.y)
+            ],
+        ];
+        ship::create(
+            sim,
+            ship_positions[0].coords,
             vector![0.0, 0.0],
             0.0,
             frigate(0),
         );
         ship::create(
             sim,
-            vector![
-                rng.gen_range(-bound.x..bound.x),
-                rng.gen_range(-bound.y..bound.y)
-            ],
+            ship_positions[1].coords,
             vector![0.0, 0.0],
             std::f64::consts::PI,
             frigate(1),
@@ -52,12 +56,13 @@ impl Scenario for AsteroidDuel {
         for _ in 0..200 {
             let mut data = asteroid(rng.gen_range(0..30));
             data.health = 10000.0;
+            let Some(p) = sample_position(&mut rng, bound, &ship_positions, MIN_SPAWN_DISTANCE)
+            else {
+                continue;
+            };
             ship::create(
                 sim,
-                vector![
-                    rng.gen_range(-bound.x..bound.x),
-                    rng.gen_range(-bound.y..bound.y)
-                ],
+                p.coords,
                 vector![rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)] * 10.0,
                 rng.gen_range(0.0..(2.0 * std::f64::consts::PI)),
                 data,
@@ -80,4 +85,8 @@ impl Scenario for AsteroidDuel {
     fn is_tournament(&self) -> bool {
         true
     }
+
+    fn topology(&self) -> WorldTopology {
+        WorldTopology::Wrapping
+    }
 }
@@ -0,0 +1,81 @@
+use super::prelude::*;
+
+/// Reference AIs that can replace the default enemy, e.g. "duel01:sniper".
+pub const OPPONENTS: &[&str] = &["chaser", "sniper", "orbiter"];
+
+pub struct Duel {
+    opponent: Option<String>,
+}
+
+impl Duel {
+    const ROUNDS: u32 = 5;
+
+    pub fn new() -> Self {
+        Self { opponent: None }
+    }
+
+    pub fn with_opponent(opponent: &str) -> Option<Self> {
+        if !OPPONENTS.contains(&opponent) {
+            return None;
+        }
+        Some(Self {
+            opponent: Some(opponent.to_string()),
+        })
+    }
+}
+
+impl Scenario for Duel {
+    fn name(&self) -> String {
+        match &self.opponent {
+            Some(opponent) => format!("duel01:{opponent}"),
+            None => "duel01".into(),
+        }
+    }
+
+    fn human_name(&self) -> String {
+        "Duel".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let s = self.world_size() * 0.4;
+        ship::create(
+            sim,
+            vector![-s, rng.gen_range(-s..s)],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(0),
+        );
+        ship::create(
+            sim,
+            vector![s, rng.gen_range(-s..s)],
+            vector![0.0, 0.0],
+            PI,
+            fighter_without_missiles(1),
+        );
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        check_team_victory(sim, 0, TOURNAMENT_MAX_TICKS)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        let enemy = match &self.opponent {
+            Some(opponent) => opponent_ai(opponent).unwrap(),
+            None => builtin("duel_enemy"),
+        };
+        vec![empty_ai(), enemy]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn world_size(&self) -> f64 {
+        2000.0
+    }
+
+    fn rounds(&self) -> u32 {
+        Self::ROUNDS
+    }
+}
@@ -0,0 +1,115 @@
+use super::prelude::*;
+
+pub struct Escort {
+    rng: SeededRng,
+    frigate: Option<ShipHandle>,
+    waves_spawned: usize,
+    next_wave_tick: Option<u32>,
+}
+
+impl Escort {
+    // Enemy fighters in each wave. They carry no missiles: with missiles the
+    // frigate doesn't survive three waves.
+    const WAVE_SIZES: [usize; 3] = [2, 3, 3];
+    const WAVE_DELAY_TICKS: u32 = 5 * 60;
+    const SPAWN_DISTANCE: f64 = 5000.0;
+
+    pub fn new() -> Self {
+        Self {
+            rng: new_rng(0),
+            frigate: None,
+            waves_spawned: 0,
+            next_wave_tick: None,
+        }
+    }
+
+    fn enemies_alive(sim: &Simulation) -> bool {
+        sim.ships
+            .iter()
+            .any(|&handle| sim.ship(handle).data().team == 1)
+    }
+
+    fn spawn_wave(&mut self, sim: &mut Simulation) {
+        let center = match self.frigate {
+            Some(handle) if sim.ships.contains(handle) => sim.ship(handle).position().vector,
+            _ => return,
+        };
+        let bearing = self.rng.gen_range(0.0..TAU);
+        let n = Self::WAVE_SIZES[self.waves_spawned];
+        for i in 0..n {
+            let angle = bearing + (i as f64 - (n - 1) as f64 / 2.0) * 0.1;
+            let offset =
+                Rotation2::new(angle).transform_vector(&vector![Self::SPAWN_DISTANCE, 0.0]);
+            ship::create(
+                sim,
+                center + offset,
+                vector![0.0, 0.0],
+                angle + PI,
+                fighter_without_missiles(1),
+            );
+        }
+        self.waves_spawned += 1;
+    }
+}
+
+impl Scenario for Escort {
+    fn name(&self) -> String {
+        "fleet01".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Escort".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        self.rng = new_rng(seed);
+        self.frigate = Some(ship::create(
+            sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            frigate(0),
+        ));
+        ship::create(sim, vector![0.0, 200.0], vector![0.0, 0.0], 0.0, fighter(0));
+        self.spawn_wave(sim);
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        if self.waves_spawned >= Self::WAVE_SIZES.len() || Self::enemies_alive(sim) {
+            return;
+        }
+        // Give the player a breather between waves.
+        match self.next_wave_tick {
+            None => self.next_wave_tick = Some(sim.tick() + Self::WAVE_DELAY_TICKS),
+            Some(tick) if sim.tick() >= tick => {
+                self.next_wave_tick = None;
+                self.spawn_wave(sim);
+            }
+            Some(_) => {}
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        let frigate_alive = match self.frigate {
+            Some(handle) => sim.ships.contains(handle),
+            None => false,
+        };
+        if !frigate_alive {
+            Status::Failed
+        } else if self.waves_spawned == Self::WAVE_SIZES.len() && !Self::enemies_alive(sim) {
+            Status::Victory { team: 0 }
+        } else if sim.tick() >= TOURNAMENT_MAX_TICKS - 1 {
+            Status::Draw
+        } else {
+            Status::Running
+        }
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+}
@@ -0,0 +1,147 @@
+use super::prelude::*;
+use crate::simulation::{DestructionCause, SimEvent, PHYSICS_TICK_LENGTH};
+
+/// A frigate at the origin has to survive waves of homing missiles launched
+/// from the edges of the world.
+pub struct MissileDefense {
+    /// (tick, position, velocity) of each missile, in launch order. Generated
+    /// from the seed up front so every run with the same seed sees the same
+    /// waves.
+    launches: Vec<(u32, Vector2<f64>, Vector2<f64>)>,
+    next_launch: usize,
+    /// Missiles in flight.
+    missiles: Vec<ShipHandle>,
+    frigate: Option<ShipHandle>,
+    intercepted: u32,
+}
+
+impl MissileDefense {
+    const SURVIVAL_TIME: f64 = 90.0;
+    const NUM_WAVES: usize = 8;
+    const FIRST_WAVE_TIME: f64 = 5.0;
+    const WAVE_INTERVAL: f64 = 10.0;
+    // A full tank lets missiles cross the world fast enough that point
+    // defense barely gets a shot at them.
+    const MISSILE_FUEL: f64 = 500.0;
+
+    pub fn new() -> Self {
+        Self {
+            launches: vec![],
+            next_launch: 0,
+            missiles: vec![],
+            frigate: None,
+            intercepted: 0,
+        }
+    }
+
+    fn schedule(rng: &mut SeededRng, world_size: f64) -> Vec<(u32, Vector2<f64>, Vector2<f64>)> {
+        let edge = world_size / 2.0 - 500.0;
+        let mut launches = vec![];
+        for wave in 0..Self::NUM_WAVES {
+            let time = Self::FIRST_WAVE_TIME + wave as f64 * Self::WAVE_INTERVAL;
+            let tick = (time / PHYSICS_TICK_LENGTH) as u32;
+            for _ in 0..(2 + wave) {
+                let along = rng.gen_range(-edge..edge);
+                let position = match rng.gen_range(0..4) {
+                    0 => vector![along, edge],
+                    1 => vector![along, -edge],
+                    2 => vector![edge, along],
+                    _ => vector![-edge, along],
+                };
+                let velocity = -position.normalize() * rng.gen_range(300.0..600.0);
+                launches.push((tick, position, velocity));
+            }
+        }
+        launches
+    }
+}
+
+impl Scenario for MissileDefense {
+    fn name(&self) -> String {
+        "missiles01".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Missile Defense".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        self.launches = Self::schedule(&mut rng, sim.world_size());
+        self.frigate = Some(ship::create(
+            sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            TAU / 4.0,
+            frigate(0),
+        ));
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        // Only missiles shot down count. Ones that detonate, whether against
+        // the frigate or a wall, don't.
+        let shot_down: Vec<u64> = sim
+            .events()
+            .sim_events
+            .iter()
+            .filter_map(|event| match event {
+                SimEvent::Explosion {
+                    id,
+                    cause: DestructionCause::Damage,
+                    ..
+                } => Some(*id),
+                _ => None,
+            })
+            .collect();
+        self.missiles.retain(|&handle| {
+            if shot_down.contains(&handle.into()) {
+                self.intercepted += 1;
+            }
+            sim.ships.contains(handle)
+        });
+
+        while let Some(&(tick, position, velocity)) = self.launches.get(self.next_launch) {
+            if tick > sim.tick() {
+                break;
+            }
+            let mut data = missile(1);
+            data.ttl = None;
+            data.fuel = Some(Self::MISSILE_FUEL);
+            let handle = ship::create(sim, position, velocity, velocity.y.atan2(velocity.x), data);
+            self.missiles.push(handle);
+            self.next_launch += 1;
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        if !self
+            .frigate
+            .map_or(false, |handle| sim.ships.contains(handle))
+        {
+            Status::Failed
+        } else if sim.time() >= Self::SURVIVAL_TIME {
+            Status::Victory { team: 0 }
+        } else {
+            Status::Running
+        }
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), builtin("missile")]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn world_size(&self) -> f64 {
+        20000.0
+    }
+
+    // Everyone who wins survives for the same time, so each intercepted
+    // missile takes a second off to rank the leaderboard by missiles
+    // destroyed.
+    fn score_time(&self, sim: &Simulation) -> f64 {
+        sim.time() - self.intercepted as f64
+    }
+}
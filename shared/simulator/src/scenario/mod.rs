@@ -1,15 +1,20 @@
 mod asteroid_duel;
 mod belt;
 mod cruiser_duel;
+mod duel;
+mod escort;
 mod fighter_duel;
 mod fleet;
 mod frigate_duel;
 mod gunnery;
 mod mini_fleet;
+mod missile_defense;
+mod obstacle_duel;
 mod orbit;
 mod planetary_defense;
 mod primitive_duel;
 mod radar_duel;
+pub mod sandbox;
 mod squadrons;
 mod stress;
 mod test;
@@ -17,6 +22,7 @@ mod tutorial_acceleration;
 mod tutorial_acceleration2;
 mod tutorial_cruiser;
 mod tutorial_deflection;
+mod tutorial_formation;
 mod tutorial_frigate;
 mod tutorial_guns;
 mod tutorial_lead;
@@ -26,30 +32,40 @@ mod tutorial_radio;
 mod tutorial_rotation;
 mod tutorial_search;
 mod tutorial_squadron;
+mod versus;
 mod welcome;
 
+pub use duel::OPPONENTS;
+
 use crate::ship::{asteroid, fighter, ShipAccessor, ShipClass, ShipData};
-use crate::simulation::{Code, Line, Simulation};
-use nalgebra::{vector, Vector2};
+use crate::simulation::{Code, Label, Line, LineStyle, Simulation};
+use crate::topology::WorldTopology;
+use nalgebra::{vector, Point2, Vector2};
 use rand::{seq::SliceRandom, Rng, RngCore};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub mod prelude {
+    pub use super::Camera;
     pub use super::Scenario;
     pub use super::Status;
-    pub use super::{builtin, empty_ai, reference_ai};
+    pub use super::{builtin, empty_ai, opponent_ai, reference_ai};
     pub use super::{
-        check_capital_ship_tournament_victory, check_tournament_victory, check_tutorial_victory,
+        check_capital_ship_tournament_victory, check_team_victory, check_tournament_victory,
+        check_tutorial_victory,
     };
     pub use super::{fighter_without_missiles, fighter_without_missiles_or_radar, target_asteroid};
-    pub use super::{place_teams, Placement};
+    pub use super::{place_teams, target_annotation, Placement};
+    pub use super::{sample_position, MIN_SPAWN_DISTANCE};
     pub use super::{DEFAULT_TUTORIAL_MAX_TICKS, TOURNAMENT_MAX_TICKS};
     pub use crate::rng::{new_rng, SeededRng};
     pub use crate::ship::{
         self, asteroid, cruiser, fighter, frigate, missile, target, torpedo, ShipHandle,
     };
-    pub use crate::simulation::{Code, Line, Simulation};
+    pub use crate::simulation::{
+        Code, Label, Line, LineStyle, Marker, ScenarioVar, Simulation, TARGET_MARKER,
+    };
+    pub use crate::topology::WorldTopology;
     pub use nalgebra::{point, vector, Point2, Rotation2, Vector2};
     pub use rand::Rng;
     pub use std::f64::consts::{PI, TAU};
@@ -74,6 +90,9 @@ pub trait Scenario {
         self.name()
     }
 
+    /// Creates the initial ships. Any randomness must come from an RNG
+    /// created with `new_rng(seed)` so that a seed always reproduces the same
+    /// scenario.
     fn init(&mut self, sim: &mut Simulation, seed: u32);
 
     fn tick(&mut self, _: &mut Simulation) {}
@@ -101,10 +120,21 @@ pub trait Scenario {
         None
     }
 
+    // Scenario that must be completed before this one is unlocked.
+    fn prerequisite(&self) -> Option<String> {
+        None
+    }
+
     fn lines(&self) -> Vec<Line> {
         vec![]
     }
 
+    // Text drawn in the world alongside the lines. Only computed for
+    // snapshots, so headless runs never pay for it.
+    fn labels(&self) -> Vec<Label> {
+        vec![]
+    }
+
     fn is_tournament(&self) -> bool {
         false
     }
@@ -120,9 +150,98 @@ pub trait Scenario {
     fn world_size(&self) -> f64 {
         40000.0
     }
+
+    fn topology(&self) -> WorldTopology {
+        WorldTopology::Walled
+    }
+
+    // Whether bullets from different teams destroy each other on contact.
+    fn projectile_collisions(&self) -> bool {
+        false
+    }
+
+    // Whether obstacles hide ships from radar.
+    fn radar_occlusion(&self) -> bool {
+        true
+    }
+
+    // Number of rounds to play in one session, see rounds::Session.
+    fn rounds(&self) -> u32 {
+        1
+    }
+
+    // Variables passed to scripts with Simulation::write_var, as (name, description).
+    // Shown in the Quick Reference.
+    fn variables(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    // Ticks after which a round ends in a draw.
+    fn max_ticks(&self) -> u32 {
+        MAX_TICKS
+    }
+
+    // Where the UI points the camera at the start. None zooms to fit the
+    // ships and scenario lines.
+    fn initial_camera(&self) -> Option<Camera> {
+        None
+    }
+
+    /// Maximum ticks to earn (3, 2, 1) stars. Use tools/src/bin/par-times.rs
+    /// to calibrate.
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        None
+    }
+}
+
+/// Initial camera position, see `Scenario::initial_camera`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub target: Point2<f64>,
+    /// Width and height in meters of the area to show around the target.
+    pub extent: f64,
+}
+
+/// Returns the number of stars (0-3) earned by winning in the given number of ticks.
+pub fn stars(par_ticks: (u32, u32, u32), ticks: u32) -> u32 {
+    let (three, two, one) = par_ticks;
+    if ticks <= three {
+        3
+    } else if ticks <= two {
+        2
+    } else if ticks <= one {
+        1
+    } else {
+        0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScenarioError {
+    UnknownScenario(String),
 }
 
-pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
+impl std::fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScenarioError::UnknownScenario(name) => write!(f, "Unknown scenario {:?}", name),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+/// Loads a scenario by name, for names from user input like URLs or saved
+/// state.
+pub fn load_safe(name: &str) -> Result<Box<dyn Scenario>, ScenarioError> {
+    // Some scenarios take a parameter after a colon, like "duel01:sniper".
+    if let Some((base, param)) = name.split_once(':') {
+        let scenario: Option<Box<dyn Scenario>> = match base {
+            "duel01" => duel::Duel::with_opponent(param).map(|x| Box::new(x) as _),
+            _ => None,
+        };
+        return scenario.ok_or_else(|| ScenarioError::UnknownScenario(name.to_string()));
+    }
     let scenario: Option<Box<dyn Scenario>> = match name {
         // Tutorials
         "tutorial_guns" => Some(Box::new(tutorial_guns::TutorialGuns {})),
@@ -140,6 +259,7 @@ pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
         "tutorial_radio" => Some(Box::new(tutorial_radio::TutorialRadio::new())),
         "tutorial_missiles" => Some(Box::new(tutorial_missiles::TutorialMissiles::new())),
         "tutorial_squadron" => Some(Box::new(tutorial_squadron::TutorialSquadron::new())),
+        "tutorial_formation" => Some(Box::new(tutorial_formation::TutorialFormation::new())),
         "tutorial_frigate" => Some(Box::new(tutorial_frigate::TutorialFrigate::new())),
         "tutorial_cruiser" => Some(Box::new(tutorial_cruiser::TutorialCruiser::new())),
         // Tournament
@@ -149,6 +269,7 @@ pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
         "frigate_duel" => Some(Box::new(frigate_duel::FrigateDuel::new())),
         "cruiser_duel" => Some(Box::new(cruiser_duel::CruiserDuel::new())),
         "asteroid_duel" => Some(Box::new(asteroid_duel::AsteroidDuel::new())),
+        "obstacle_duel" => Some(Box::new(obstacle_duel::ObstacleDuel::new())),
         "squadrons" => Some(Box::new(squadrons::Squadrons::new())),
         "mini_fleet" => Some(Box::new(mini_fleet::MiniFleet::new())),
         "fleet" => Some(Box::new(fleet::Fleet::new())),
@@ -157,6 +278,10 @@ pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
         // Challenge
         "gunnery" => Some(Box::new(gunnery::GunneryScenario {})),
         "planetary_defense" => Some(Box::new(planetary_defense::PlanetaryDefense::new())),
+        "duel01" => Some(Box::new(duel::Duel::new())),
+        "fleet01" => Some(Box::new(escort::Escort::new())),
+        "missiles01" => Some(Box::new(missile_defense::MissileDefense::new())),
+        "versus01" => Some(Box::new(versus::Versus::new())),
         // Testing
         "test" => Some(Box::new(test::TestScenario {})),
         "basic" => Some(Box::new(test::BasicScenario {})),
@@ -164,7 +289,10 @@ pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
         "frigate_vs_cruiser" => Some(Box::new(test::FrigateVsCruiser::new())),
         "cruiser_vs_frigate" => Some(Box::new(test::CruiserVsFrigate::new())),
         "frigate_point_defense" => Some(Box::new(test::FrigatePointDefense {})),
+        "cruiser_point_defense" => Some(Box::new(test::CruiserPointDefense {})),
         "radar_test" => Some(Box::new(test::RadarTest {})),
+        "projectile_collision_test" => Some(Box::new(test::ProjectileCollisionTest {})),
+        "wrapping_test" => Some(Box::new(test::WrappingTest {})),
         // Stress
         "stress" => Some(Box::new(stress::StressScenario {})),
         "asteroid-stress" => Some(Box::new(stress::AsteroidStressScenario {})),
@@ -172,18 +300,31 @@ pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
         "missile-stress" => Some(Box::new(stress::MissileStressScenario {})),
         // Miscellaneous
         "welcome" => Some(Box::new(welcome::Welcome::new())),
+        "sandbox" => Some(Box::new(sandbox::Sandbox::new())),
         _ => None,
     };
-    if let Some(scenario) = scenario.as_ref() {
-        assert_eq!(scenario.name(), name);
+    let scenario = scenario.ok_or_else(|| ScenarioError::UnknownScenario(name.to_string()))?;
+    // A mismatch is a bug in the table above, not bad input.
+    assert_eq!(scenario.name(), name);
+    Ok(scenario)
+}
+
+/// Maps a current or previous scenario name to the current name.
+pub fn resolve_name(name: &str) -> Option<String> {
+    if load_safe(name).is_ok() {
+        return Some(name.to_string());
     }
-    scenario
+    list()
+        .into_iter()
+        .flat_map(|(_, names)| names)
+        .find(|x| load(x).previous_names().iter().any(|y| y == name))
 }
 
+/// Loads a scenario that's known to exist, panicking otherwise.
 pub fn load(name: &str) -> Box<dyn Scenario> {
     match load_safe(name) {
-        Some(scenario) => scenario,
-        None => panic!("Unknown scenario"),
+        Ok(scenario) => scenario,
+        Err(e) => panic!("{}", e),
     }
 }
 
@@ -204,11 +345,21 @@ pub fn list() -> Vec<(String, Vec<String>)> {
                 "tutorial_radio",
                 "tutorial_missiles",
                 "tutorial_squadron",
+                "tutorial_formation",
                 "tutorial_frigate",
                 "tutorial_cruiser",
             ],
         ),
-        ("Challenge", vec!["gunnery", "planetary_defense"]),
+        (
+            "Challenge",
+            vec![
+                "gunnery",
+                "planetary_defense",
+                "duel01",
+                "fleet01",
+                "missiles01",
+            ],
+        ),
         ("Tournament", vec!["fighter_duel", "mini_fleet"]),
         (
             "Future Tournaments",
@@ -216,12 +367,15 @@ pub fn list() -> Vec<(String, Vec<String>)> {
                 "frigate_duel",
                 "cruiser_duel",
                 "asteroid_duel",
+                "obstacle_duel",
                 "squadrons",
                 "fleet",
                 "belt",
                 "orbit",
             ],
         ),
+        ("Versus", vec!["versus01"]),
+        ("Sandbox", vec!["sandbox"]),
     ]
     .iter()
     .map(|(category, scenario_names)| {
@@ -233,6 +387,67 @@ pub fn list() -> Vec<(String, Vec<String>)> {
     .collect()
 }
 
+/// Scenario metadata used to populate scenario selection UI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScenarioInfo {
+    pub name: String,
+    pub human_name: String,
+    pub category: String,
+    /// Position in the overall list, following the order returned by `list()`.
+    pub order: usize,
+}
+
+/// Returns metadata for every scenario in `list()`.
+///
+/// Each scenario is constructed to read its human name, but `init` is not
+/// called, so no ships or code are created.
+pub fn list_with_metadata() -> Vec<ScenarioInfo> {
+    list()
+        .into_iter()
+        .flat_map(|(category, scenario_names)| {
+            scenario_names
+                .into_iter()
+                .map(move |name| (category.clone(), name))
+        })
+        .enumerate()
+        .map(|(order, (category, name))| ScenarioInfo {
+            human_name: load(&name).human_name(),
+            name,
+            category,
+            order,
+        })
+        .collect()
+}
+
+/// Minimum distance between a randomly placed object and the ships it should avoid.
+pub const MIN_SPAWN_DISTANCE: f64 = 200.0;
+
+/// Returns a random position within `bounds` (half-extents around the origin) that is at
+/// least `min_distance` away from every point in `min_dist_from`.
+///
+/// Uses rejection sampling, and returns `None` if no valid position is found after many
+/// attempts.
+pub fn sample_position<R: Rng>(
+    rng: &mut R,
+    bounds: Vector2<f64>,
+    min_dist_from: &[Point2<f64>],
+    min_distance: f64,
+) -> Option<Point2<f64>> {
+    const MAX_ATTEMPTS: usize = 1000;
+    (0..MAX_ATTEMPTS)
+        .map(|_| {
+            Point2::new(
+                rng.gen_range(-bounds.x..bounds.x),
+                rng.gen_range(-bounds.y..bounds.y),
+            )
+        })
+        .find(|p| {
+            min_dist_from
+                .iter()
+                .all(|q| (p - q).magnitude() >= min_distance)
+        })
+}
+
 pub fn builtin(name: &str) -> Code {
     Code::Builtin(name.to_string())
 }
@@ -245,6 +460,15 @@ pub fn empty_ai() -> Code {
     builtin("empty")
 }
 
+/// Loads one of the reference opponents listed in [`OPPONENTS`].
+pub fn opponent_ai(name: &str) -> Option<Code> {
+    if OPPONENTS.contains(&name) {
+        Some(builtin(&format!("opponent/{name}")))
+    } else {
+        None
+    }
+}
+
 pub fn check_victory_with_filter(
     sim: &Simulation,
     max_ticks: u32,
@@ -270,16 +494,24 @@ pub fn check_victory_with_filter(
     }
 }
 
-pub fn check_tutorial_victory(sim: &Simulation, max_ticks: u32) -> Status {
+/// Scores the game from the point of view of `player_team`: Victory when only
+/// its ships are left, Failed when another team is the only one left, and
+/// Draw when nobody is left or the tick limit expires with several teams
+/// alive. Missiles and torpedoes don't count.
+pub fn check_team_victory(sim: &Simulation, player_team: i32, max_ticks: u32) -> Status {
     match check_victory_with_filter(sim, max_ticks, |ship| {
         ![ShipClass::Missile, ShipClass::Torpedo].contains(&ship.data().class)
     }) {
-        x @ Status::Victory { team: 0 } => x,
+        Status::Victory { team } if team == player_team => Status::Victory { team },
         Status::Victory { .. } => Status::Failed,
         x => x,
     }
 }
 
+pub fn check_tutorial_victory(sim: &Simulation, max_ticks: u32) -> Status {
+    check_team_victory(sim, 0, max_ticks)
+}
+
 pub fn check_tournament_victory(sim: &Simulation) -> Status {
     check_victory_with_filter(sim, TOURNAMENT_MAX_TICKS, |ship| {
         [ShipClass::Fighter, ShipClass::Frigate, ShipClass::Cruiser].contains(&ship.data().class)
@@ -313,6 +545,26 @@ pub fn target_asteroid(variant: i32) -> ShipData {
     asteroid
 }
 
+/// An arrow pointing down at a target circle with instructions above it,
+/// for the early tutorials.
+pub fn target_annotation(target: Point2<f64>, radius: f64, text: &str) -> (Line, Label) {
+    let color = vector![1.0, 1.0, 1.0, 0.8];
+    let tail = target + vector![0.0, radius + 60.0];
+    let arrow = Line {
+        a: tail,
+        b: target + vector![0.0, radius + 10.0],
+        color,
+        style: LineStyle::Arrow,
+        ..Default::default()
+    };
+    let label = Label {
+        position: tail + vector![-radius, 30.0],
+        text: text.to_string(),
+        color,
+    };
+    (arrow, label)
+}
+
 pub struct Placement {
     pub position: Vector2<f64>,
     pub heading: f64,
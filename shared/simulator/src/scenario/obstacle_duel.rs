@@ -0,0 +1,109 @@
+use super::prelude::*;
+use crate::collision;
+
+/// A fighter duel with rocks to hide behind. Obstacles block movement,
+/// bullets and radar.
+pub struct ObstacleDuel {
+    obstacles: Vec<Vec<Point2<f64>>>,
+}
+
+impl ObstacleDuel {
+    const NUM_OBSTACLE_PAIRS: usize = 6;
+
+    pub fn new() -> Self {
+        Self { obstacles: vec![] }
+    }
+}
+
+impl Scenario for ObstacleDuel {
+    fn name(&self) -> String {
+        "obstacle_duel".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Obstacle Duel".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let placements = place_teams(&mut rng, self.world_size());
+        // Keep both an obstacle and its mirror image away from the spawns.
+        let spawns: Vec<Point2<f64>> = placements
+            .iter()
+            .flat_map(|x| [Point2::from(x.position), Point2::from(-x.position)])
+            .collect();
+
+        // Obstacles come in pairs mirrored through the origin so neither
+        // side gets more cover.
+        let bounds = vector![1.0, 1.0] * self.world_size() * 0.35;
+        for _ in 0..Self::NUM_OBSTACLE_PAIRS {
+            let Some(center) = sample_position(&mut rng, bounds, &spawns, 3000.0) else {
+                continue;
+            };
+            let radius = rng.gen_range(300.0..1000.0);
+            let sides = rng.gen_range(5..9);
+            let rotation = rng.gen_range(0.0..TAU);
+            let vertices: Vec<Point2<f64>> = (0..sides)
+                .map(|i| {
+                    center
+                        + Rotation2::new(rotation + TAU * i as f64 / sides as f64)
+                            .transform_vector(&vector![radius, 0.0])
+                })
+                .collect();
+            let mirrored: Vec<Point2<f64>> = vertices
+                .iter()
+                .map(|p| Point2::origin() - p.coords)
+                .collect();
+            self.obstacles.push(vertices);
+            self.obstacles.push(mirrored);
+        }
+        for vertices in self.obstacles.iter() {
+            collision::add_obstacle(sim, vertices);
+        }
+
+        for (team, placement) in placements.into_iter().enumerate() {
+            let Placement { position, heading } = placement;
+            ship::create(
+                sim,
+                position,
+                vector![0.0, 0.0],
+                heading,
+                fighter(team as i32),
+            );
+        }
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        let color = vector![0.5, 0.5, 0.5, 1.0];
+        self.obstacles
+            .iter()
+            .flat_map(|vertices| {
+                vertices
+                    .iter()
+                    .zip(vertices.iter().cycle().skip(1))
+                    .map(move |(&a, &b)| Line {
+                        a,
+                        b,
+                        color,
+                        ..Default::default()
+                    })
+            })
+            .collect()
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        check_tournament_victory(sim)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn is_tournament(&self) -> bool {
+        true
+    }
+}
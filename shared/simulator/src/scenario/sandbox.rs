@@ -0,0 +1,142 @@
+use super::prelude::*;
+use crate::bullet::{self, BulletData};
+use crate::color;
+use serde::{Deserialize, Serialize};
+
+/// An empty world for experimenting with physics and weapons. Entities are
+/// added and removed from the UI with `SandboxCommand`.
+pub struct Sandbox {}
+
+impl Sandbox {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario for Sandbox {
+    fn name(&self) -> String {
+        "sandbox".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Sandbox".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, _seed: u32) {
+        ship::create(sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, fighter(0));
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), Code::None]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn max_ticks(&self) -> u32 {
+        u32::MAX
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnKind {
+    Fighter,
+    Frigate,
+    Cruiser,
+    Missile,
+    Torpedo,
+    Asteroid,
+    Target,
+    Bullet,
+}
+
+impl SpawnKind {
+    pub const ALL: [SpawnKind; 8] = [
+        SpawnKind::Fighter,
+        SpawnKind::Frigate,
+        SpawnKind::Cruiser,
+        SpawnKind::Missile,
+        SpawnKind::Torpedo,
+        SpawnKind::Asteroid,
+        SpawnKind::Target,
+        SpawnKind::Bullet,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpawnKind::Fighter => "fighter",
+            SpawnKind::Frigate => "frigate",
+            SpawnKind::Cruiser => "cruiser",
+            SpawnKind::Missile => "missile",
+            SpawnKind::Torpedo => "torpedo",
+            SpawnKind::Asteroid => "asteroid",
+            SpawnKind::Target => "target",
+            SpawnKind::Bullet => "bullet",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum SandboxCommand {
+    Spawn {
+        kind: SpawnKind,
+        team: i32,
+        position: Point2<f64>,
+    },
+    /// Removes every ship and bullet.
+    Clear,
+}
+
+/// Applies a command between steps. Works in any scenario, but only the
+/// sandbox UI sends them.
+pub fn apply(sim: &mut Simulation, command: &SandboxCommand) {
+    match *command {
+        SandboxCommand::Spawn {
+            kind,
+            team,
+            position,
+        } => {
+            let data = match kind {
+                SpawnKind::Fighter => fighter(team),
+                SpawnKind::Frigate => frigate(team),
+                SpawnKind::Cruiser => cruiser(team),
+                SpawnKind::Missile => missile(team),
+                SpawnKind::Torpedo => torpedo(team),
+                // Cycle through the asteroid sizes.
+                SpawnKind::Asteroid => asteroid(sim.ships.len() as i32),
+                SpawnKind::Target => target(team),
+                SpawnKind::Bullet => {
+                    bullet::create(
+                        sim,
+                        position.coords,
+                        vector![0.0, 0.0],
+                        BulletData {
+                            mass: 0.1,
+                            team,
+                            color: color::to_u32(vector![1.00, 0.63, 0.00, 0.30]),
+                            ttl: 60.0,
+                        },
+                    );
+                    return;
+                }
+            };
+            ship::create(sim, position.coords, vector![0.0, 0.0], 0.0, data);
+        }
+        SandboxCommand::Clear => {
+            // Ships are removed at the end of their next tick.
+            for handle in sim.ships.iter().cloned().collect::<Vec<_>>() {
+                sim.ship_mut(handle).data_mut().destroyed = true;
+            }
+            for handle in sim.bullets.iter().cloned().collect::<Vec<_>>() {
+                bullet::destroy(sim, handle);
+            }
+        }
+    }
+}
@@ -265,6 +265,74 @@ impl Scenario for FrigatePointDefense {
     }
 }
 
+/// A stationary cruiser and four unguided missiles flying straight at it from
+/// different directions, arriving a few seconds apart. Tests turn on point
+/// defense themselves.
+pub struct CruiserPointDefense {}
+
+impl Scenario for CruiserPointDefense {
+    fn name(&self) -> String {
+        "cruiser_point_defense".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, _seed: u32) {
+        ship::create(sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, cruiser(0));
+
+        for i in 0..4 {
+            let angle = i as f64 * TAU / 4.0;
+            let direction = vector![angle.cos(), angle.sin()];
+            let distance = 2000.0 + 1000.0 * i as f64;
+            ship::create(
+                sim,
+                direction * distance,
+                -direction * 300.0,
+                angle + PI,
+                missile(1),
+            );
+        }
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![Code::None, Code::None]
+    }
+}
+
+pub struct ProjectileCollisionTest {}
+
+impl Scenario for ProjectileCollisionTest {
+    fn name(&self) -> String {
+        "projectile_collision_test".into()
+    }
+
+    fn init(&mut self, _sim: &mut Simulation, _seed: u32) {}
+
+    fn world_size(&self) -> f64 {
+        simulation::MAX_WORLD_SIZE
+    }
+
+    fn projectile_collisions(&self) -> bool {
+        true
+    }
+}
+
+pub struct WrappingTest {}
+
+impl Scenario for WrappingTest {
+    fn name(&self) -> String {
+        "wrapping_test".into()
+    }
+
+    fn init(&mut self, _sim: &mut Simulation, _seed: u32) {}
+
+    fn world_size(&self) -> f64 {
+        10e3
+    }
+
+    fn topology(&self) -> WorldTopology {
+        WorldTopology::Wrapping
+    }
+}
+
 pub struct RadarTest {}
 
 impl Scenario for RadarTest {
@@ -6,6 +6,7 @@ pub struct TutorialAcceleration {
 
 impl TutorialAcceleration {
     const TARGET: Vector2<f64> = vector![250.0, 0.0];
+    const INSTRUCTIONS: &str = "Fly here";
 
     pub fn new() -> Self {
         Self { hit_target: false }
@@ -22,14 +23,43 @@ impl Scenario for TutorialAcceleration {
     }
 
     fn init(&mut self, sim: &mut Simulation, _seed: u32) {
-        let handle = ship::create(
+        ship::create(
             sim,
             vector![-250.0, 0.0],
             vector![0.0, 0.0],
             0.0,
             fighter_without_missiles_or_radar(0),
         );
-        sim.write_target(handle, Self::TARGET, vector![0.0, 0.0]);
+        sim.set_marker(
+            TARGET_MARKER,
+            Marker {
+                position: Self::TARGET,
+                radius: 50.0,
+                color: vector![1.0, 0.0, 0.0, 1.0],
+            },
+        );
+        sim.write_var(0, "target", ScenarioVar::Vec2(Self::TARGET));
+    }
+
+    fn initial_camera(&self) -> Option<Camera> {
+        Some(Camera {
+            target: point![0.0, 0.0],
+            extent: 800.0,
+        })
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        if self.hit_target {
+            return vec![];
+        }
+        vec![target_annotation(Self::TARGET.into(), 50.0, Self::INSTRUCTIONS).0]
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        if self.hit_target {
+            return vec![];
+        }
+        vec![target_annotation(Self::TARGET.into(), 50.0, Self::INSTRUCTIONS).1]
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
@@ -39,29 +69,16 @@ impl Scenario for TutorialAcceleration {
                 self.hit_target = true;
             }
         }
-    }
-
-    fn lines(&self) -> Vec<Line> {
-        let mut lines = vec![];
-        let center: Point2<f64> = Self::TARGET.into();
-        let n = 20;
-        let r = 50.0;
-        let color = if self.hit_target {
-            vector![0.0, 1.0, 0.0, 1.0]
-        } else {
-            vector![1.0, 0.0, 0.0, 1.0]
-        };
-        for i in 0..n {
-            let frac = (i as f64) / (n as f64);
-            let angle_a = std::f64::consts::TAU * frac;
-            let angle_b = std::f64::consts::TAU * (frac + 1.0 / n as f64);
-            lines.push(Line {
-                a: center + vector![r * angle_a.cos(), r * angle_a.sin()],
-                b: center + vector![r * angle_b.cos(), r * angle_b.sin()],
-                color,
-            });
+        if self.hit_target {
+            sim.set_marker(
+                TARGET_MARKER,
+                Marker {
+                    position: Self::TARGET,
+                    radius: 50.0,
+                    color: vector![0.0, 1.0, 0.0, 1.0],
+                },
+            );
         }
-        lines
     }
 
     fn status(&self, _: &Simulation) -> Status {
@@ -84,7 +101,22 @@ impl Scenario for TutorialAcceleration {
         Some("tutorial_acceleration2".to_string())
     }
 
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_guns".to_string())
+    }
+
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial02".into()]
     }
+
+    fn variables(&self) -> Vec<(String, String)> {
+        vec![(
+            "target".to_string(),
+            "Center of the target circle. Also returned by target().".to_string(),
+        )]
+    }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((300, 450, 600))
+    }
 }
@@ -6,6 +6,8 @@ pub struct TutorialAcceleration2 {
 }
 
 impl TutorialAcceleration2 {
+    const INSTRUCTIONS: &str = "Random position: use target()";
+
     pub fn new() -> Self {
         Self {
             hit_target: false,
@@ -25,19 +27,59 @@ impl Scenario for TutorialAcceleration2 {
 
     fn init(&mut self, sim: &mut Simulation, seed: u32) {
         let mut rng = new_rng(seed);
+        let ship_position = Rotation2::new(rng.gen_range(0.0..std::f64::consts::TAU))
+            .transform_point(&point![rng.gen_range(100.0..200.0), 0.0]);
         self.target = Some(
-            Rotation2::new(rng.gen_range(0.0..std::f64::consts::TAU))
-                .transform_point(&point![rng.gen_range(400.0..500.0), 0.0]),
+            sample_position(
+                &mut rng,
+                vector![500.0, 500.0],
+                &[point![0.0, 0.0], ship_position],
+                MIN_SPAWN_DISTANCE,
+            )
+            .expect("target position"),
         );
-        let handle = ship::create(
+        ship::create(
             sim,
-            Rotation2::new(rng.gen_range(0.0..std::f64::consts::TAU))
-                .transform_vector(&vector![rng.gen_range(100.0..200.0), 0.0]),
+            ship_position.coords,
             vector![0.0, 0.0],
             0.0,
             fighter_without_missiles_or_radar(0),
         );
-        sim.write_target(handle, self.target.unwrap().coords, vector![0.0, 0.0]);
+        sim.set_marker(
+            TARGET_MARKER,
+            Marker {
+                position: self.target.unwrap().coords,
+                radius: 50.0,
+                color: vector![1.0, 0.0, 0.0, 1.0],
+            },
+        );
+        sim.write_var(0, "target", ScenarioVar::Vec2(self.target.unwrap().coords));
+    }
+
+    // Covers every possible target position.
+    fn initial_camera(&self) -> Option<Camera> {
+        Some(Camera {
+            target: point![0.0, 0.0],
+            extent: 1200.0,
+        })
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        match self.target {
+            Some(target) if !self.hit_target => {
+                vec![target_annotation(target, 50.0, Self::INSTRUCTIONS).0]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        match self.target {
+            Some(target) if !self.hit_target => {
+                vec![target_annotation(target, 50.0, Self::INSTRUCTIONS).1]
+            }
+            _ => vec![],
+        }
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
@@ -47,29 +89,16 @@ impl Scenario for TutorialAcceleration2 {
                 self.hit_target = true;
             }
         }
-    }
-
-    fn lines(&self) -> Vec<Line> {
-        let mut lines = vec![];
-        let center: Point2<f64> = self.target.unwrap();
-        let n = 20;
-        let r = 50.0;
-        let color = if self.hit_target {
-            vector![0.0, 1.0, 0.0, 1.0]
-        } else {
-            vector![1.0, 0.0, 0.0, 1.0]
-        };
-        for i in 0..n {
-            let frac = (i as f64) / (n as f64);
-            let angle_a = std::f64::consts::TAU * frac;
-            let angle_b = std::f64::consts::TAU * (frac + 1.0 / n as f64);
-            lines.push(Line {
-                a: center + vector![r * angle_a.cos(), r * angle_a.sin()],
-                b: center + vector![r * angle_b.cos(), r * angle_b.sin()],
-                color,
-            });
+        if self.hit_target {
+            sim.set_marker(
+                TARGET_MARKER,
+                Marker {
+                    position: self.target.unwrap().coords,
+                    radius: 50.0,
+                    color: vector![0.0, 1.0, 0.0, 1.0],
+                },
+            );
         }
-        lines
     }
 
     fn status(&self, _: &Simulation) -> Status {
@@ -92,7 +121,22 @@ impl Scenario for TutorialAcceleration2 {
         Some("tutorial_rotation".to_string())
     }
 
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_acceleration".to_string())
+    }
+
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial03".into()]
     }
+
+    fn variables(&self) -> Vec<(String, String)> {
+        vec![(
+            "target".to_string(),
+            "Center of the target circle. Also returned by target().".to_string(),
+        )]
+    }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((480, 720, 960))
+    }
 }
@@ -14,7 +14,7 @@ impl Scenario for TutorialCruiser {
     }
 
     fn human_name(&self) -> String {
-        "Tutorial 13: Cruiser".into()
+        "Tutorial 14: Cruiser".into()
     }
 
     fn init(&mut self, sim: &mut Simulation, seed: u32) {
@@ -48,4 +48,12 @@ impl Scenario for TutorialCruiser {
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial11".into()]
     }
+
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_frigate".to_string())
+    }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((1440, 2160, 2880))
+    }
 }
@@ -83,7 +83,15 @@ impl Scenario for TutorialDeflection {
         Some("tutorial_radar".to_string())
     }
 
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_lead".to_string())
+    }
+
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial05".into()]
     }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((1440, 1620, 1800))
+    }
 }
@@ -0,0 +1,180 @@
+use super::prelude::*;
+
+pub struct TutorialFormation {
+    ship_handles: Vec<ShipHandle>,
+    start_angle: f64,
+    leader_position: Point2<f64>,
+    leader_velocity: Vector2<f64>,
+    occupied: [bool; 3],
+    on_target_ticks: u32,
+}
+
+impl TutorialFormation {
+    const ORBIT_RADIUS: f64 = 1000.0;
+    const LEADER_SPEED: f64 = 100.0;
+    const SLOT_DISTANCE: f64 = 150.0;
+    const TOLERANCE: f64 = 50.0;
+    const HOLD_TICKS: u32 = 5 * 60;
+
+    pub fn new() -> Self {
+        Self {
+            ship_handles: vec![],
+            start_angle: 0.0,
+            leader_position: point![0.0, 0.0],
+            leader_velocity: vector![0.0, 0.0],
+            occupied: [false; 3],
+            on_target_ticks: 0,
+        }
+    }
+
+    fn update_leader(&mut self, sim: &Simulation) {
+        let angular_velocity = Self::LEADER_SPEED / Self::ORBIT_RADIUS;
+        let angle = self.start_angle + angular_velocity * sim.time();
+        let rotation = Rotation2::new(angle);
+        self.leader_position = rotation.transform_point(&point![Self::ORBIT_RADIUS, 0.0]);
+        self.leader_velocity = rotation.transform_vector(&vector![0.0, Self::LEADER_SPEED]);
+    }
+
+    // Ahead of the leader, then 120 degrees to the left and right.
+    fn slots(&self) -> [Point2<f64>; 3] {
+        let heading = self.leader_velocity.y.atan2(self.leader_velocity.x);
+        [0.0, TAU / 3.0, -TAU / 3.0].map(|angle| {
+            self.leader_position
+                + Rotation2::new(heading + angle)
+                    .transform_vector(&vector![Self::SLOT_DISTANCE, 0.0])
+        })
+    }
+}
+
+impl Scenario for TutorialFormation {
+    fn name(&self) -> String {
+        "tutorial_formation".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Tutorial 12: Formation".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        self.start_angle = rng.gen_range(0.0..TAU);
+
+        // The middle ship is the only one told where the leader is.
+        for y in [0.0, 200.0, -200.0] {
+            self.ship_handles.push(ship::create(
+                sim,
+                vector![0.0, y],
+                vector![0.0, 0.0],
+                0.0,
+                fighter_without_missiles_or_radar(0),
+            ));
+        }
+
+        self.update_leader(sim);
+        sim.write_target(
+            self.ship_handles[0],
+            self.leader_position.coords,
+            self.leader_velocity,
+        );
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        self.update_leader(sim);
+        if sim.ships.contains(self.ship_handles[0]) {
+            sim.write_target(
+                self.ship_handles[0],
+                self.leader_position.coords,
+                self.leader_velocity,
+            );
+        }
+
+        let positions: Vec<Point2<f64>> = self
+            .ship_handles
+            .iter()
+            .filter(|&&handle| sim.ships.contains(handle))
+            .map(|&handle| sim.ship(handle).position().vector.into())
+            .collect();
+        let slots = self.slots();
+        for (occupied, slot) in self.occupied.iter_mut().zip(slots) {
+            *occupied = positions
+                .iter()
+                .any(|p| nalgebra::distance(p, &slot) < Self::TOLERANCE);
+        }
+
+        if self.occupied.iter().all(|&x| x) {
+            self.on_target_ticks += 1;
+        } else {
+            self.on_target_ticks = 0;
+        }
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        let mut lines = vec![];
+        let leader_color = vector![1.0, 1.0, 1.0, 1.0];
+        let r = 10.0;
+        lines.push(Line {
+            a: self.leader_position + vector![-r, 0.0],
+            b: self.leader_position + vector![r, 0.0],
+            color: leader_color,
+            ..Default::default()
+        });
+        lines.push(Line {
+            a: self.leader_position + vector![0.0, -r],
+            b: self.leader_position + vector![0.0, r],
+            color: leader_color,
+            ..Default::default()
+        });
+
+        let n = 20;
+        let r = Self::TOLERANCE;
+        for (&occupied, center) in self.occupied.iter().zip(self.slots()) {
+            let color = if occupied {
+                vector![0.0, 1.0, 0.0, 1.0]
+            } else {
+                vector![1.0, 0.0, 0.0, 1.0]
+            };
+            for i in 0..n {
+                let frac = (i as f64) / (n as f64);
+                let angle_a = TAU * frac;
+                let angle_b = TAU * (frac + 1.0 / n as f64);
+                lines.push(Line {
+                    a: center + vector![r * angle_a.cos(), r * angle_a.sin()],
+                    b: center + vector![r * angle_b.cos(), r * angle_b.sin()],
+                    color,
+                    ..Default::default()
+                });
+            }
+        }
+        lines
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        if self.on_target_ticks >= Self::HOLD_TICKS {
+            Status::Victory { team: 0 }
+        } else if sim.tick() >= DEFAULT_TUTORIAL_MAX_TICKS * 3 - 1 {
+            Status::Draw
+        } else {
+            Status::Running
+        }
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![builtin("tutorial/tutorial_formation_initial")]
+    }
+
+    fn solution(&self) -> Code {
+        builtin("tutorial/tutorial_formation_solution")
+    }
+
+    fn next_scenario(&self) -> Option<String> {
+        Some("tutorial_frigate".to_string())
+    }
+
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_squadron".to_string())
+    }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((1380, 2070, 2760))
+    }
+}
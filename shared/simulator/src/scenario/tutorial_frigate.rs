@@ -14,7 +14,7 @@ impl Scenario for TutorialFrigate {
     }
 
     fn human_name(&self) -> String {
-        "Tutorial 12: Frigate".into()
+        "Tutorial 13: Frigate".into()
     }
 
     fn init(&mut self, sim: &mut Simulation, seed: u32) {
@@ -49,7 +49,15 @@ impl Scenario for TutorialFrigate {
         Some("tutorial_cruiser".to_string())
     }
 
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_formation".to_string())
+    }
+
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial10".into()]
     }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((1380, 2070, 2760))
+    }
 }
@@ -47,4 +47,8 @@ impl Scenario for TutorialGuns {
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial01".into()]
     }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((180, 270, 360))
+    }
 }
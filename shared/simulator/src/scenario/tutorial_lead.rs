@@ -83,4 +83,12 @@ impl Scenario for TutorialLead {
     fn next_scenario(&self) -> Option<String> {
         Some("tutorial_deflection".to_string())
     }
+
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_rotation".to_string())
+    }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((540, 810, 1080))
+    }
 }
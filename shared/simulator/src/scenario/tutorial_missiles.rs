@@ -51,7 +51,15 @@ impl Scenario for TutorialMissiles {
         Some("tutorial_squadron".to_string())
     }
 
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_radio".to_string())
+    }
+
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial09".into()]
     }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((720, 1080, 1440))
+    }
 }
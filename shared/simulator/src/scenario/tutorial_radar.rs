@@ -63,7 +63,15 @@ impl Scenario for TutorialRadar {
         Some("tutorial_search".to_string())
     }
 
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_deflection".to_string())
+    }
+
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial06".into()]
     }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((2700, 3150, 3600))
+    }
 }
@@ -61,4 +61,12 @@ impl Scenario for TutorialRadio {
     fn next_scenario(&self) -> Option<String> {
         Some("tutorial_missiles".to_string())
     }
+
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_search".to_string())
+    }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((2940, 3270, 3600))
+    }
 }
@@ -19,17 +19,23 @@ impl Scenario for TutorialRotation {
 
     fn init(&mut self, sim: &mut Simulation, seed: u32) {
         let mut rng = new_rng(seed);
-        let target = Rotation2::new(rng.gen_range(0.0..std::f64::consts::TAU))
-            .transform_point(&point![rng.gen_range(600.0..1000.0), 0.0]);
-        let handle = ship::create(
+        let ship_position = Rotation2::new(rng.gen_range(0.0..std::f64::consts::TAU))
+            .transform_point(&point![rng.gen_range(100.0..500.0), 0.0]);
+        let target = sample_position(
+            &mut rng,
+            vector![1000.0, 1000.0],
+            &[point![0.0, 0.0], ship_position],
+            MIN_SPAWN_DISTANCE,
+        )
+        .expect("target position");
+        ship::create(
             sim,
-            Rotation2::new(rng.gen_range(0.0..std::f64::consts::TAU))
-                .transform_vector(&vector![rng.gen_range(100.0..500.0), 0.0]),
+            ship_position.coords,
             vector![0.0, 0.0],
             0.0,
             fighter_without_missiles_or_radar(0),
         );
-        sim.write_target(handle, target.coords, vector![0.0, 0.0]);
+        sim.write_var(0, "target", ScenarioVar::Vec2(target.coords));
         ship::create(
             sim,
             target.coords,
@@ -55,7 +61,22 @@ impl Scenario for TutorialRotation {
         Some("tutorial_lead".to_string())
     }
 
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_acceleration2".to_string())
+    }
+
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial04".into()]
     }
+
+    fn variables(&self) -> Vec<(String, String)> {
+        vec![(
+            "target".to_string(),
+            "Position of the asteroid. Also returned by target().".to_string(),
+        )]
+    }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((660, 990, 1320))
+    }
 }
@@ -62,7 +62,15 @@ impl Scenario for TutorialSearch {
         Some("tutorial_radio".to_string())
     }
 
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_radar".to_string())
+    }
+
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial08".into()]
     }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((3120, 4260, 5400))
+    }
 }
@@ -54,10 +54,18 @@ impl Scenario for TutorialSquadron {
     }
 
     fn next_scenario(&self) -> Option<String> {
-        Some("tutorial_frigate".to_string())
+        Some("tutorial_formation".to_string())
+    }
+
+    fn prerequisite(&self) -> Option<String> {
+        Some("tutorial_missiles".to_string())
     }
 
     fn previous_names(&self) -> Vec<String> {
         vec!["tutorial07".into()]
     }
+
+    fn par_ticks(&self) -> Option<(u32, u32, u32)> {
+        Some((4380, 4890, 5400))
+    }
 }
@@ -0,0 +1,44 @@
+use super::prelude::*;
+
+/// Two players' code against each other. Spawns are point-symmetric around
+/// the origin so neither team has a positional advantage.
+pub struct Versus {}
+
+impl Versus {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Scenario for Versus {
+    fn name(&self) -> String {
+        "versus01".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Versus".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let s = self.world_size() * 0.4;
+        let center = vector![-s, rng.gen_range(-s..s)];
+        for offset in [-200.0, 200.0] {
+            let position = center + vector![0.0, offset];
+            ship::create(sim, position, vector![0.0, 0.0], 0.0, fighter(0));
+            ship::create(sim, -position, vector![0.0, 0.0], PI, fighter(1));
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        check_tournament_victory(sim)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+}
@@ -48,6 +48,14 @@ impl Scenario for Welcome {
         );
     }
 
+    fn initial_camera(&self) -> Option<Camera> {
+        // Asteroids spawn within 2 km of the origin.
+        Some(Camera {
+            target: point![0.0, 0.0],
+            extent: 4000.0,
+        })
+    }
+
     fn tick(&mut self, sim: &mut Simulation) {
         let rng = self.rng.as_mut().unwrap();
         let asteroid_variants = [1, 6, 14];
@@ -56,9 +64,18 @@ impl Scenario for Welcome {
             .iter()
             .filter(|s| matches!(sim.ship(**s).data().class, ShipClass::Asteroid { .. }))
             .count();
+        // Keep new asteroids away from the origin and the player's ship.
+        let mut avoid = vec![point![0.0, 0.0]];
+        avoid.extend(
+            sim.ships
+                .iter()
+                .filter(|&&s| sim.ship(s).data().team == 0)
+                .map(|&s| Point2::from(sim.ship(s).position().vector)),
+        );
         for _ in num_asteroids..20 {
-            let p = Rotation2::new(rng.gen_range(0.0..std::f64::consts::TAU))
-                .transform_point(&point![rng.gen_range(500.0..2000.0), 0.0]);
+            let Some(p) = sample_position(rng, vector![2000.0, 2000.0], &avoid, 500.0) else {
+                continue;
+            };
             ship::create(
                 sim,
                 vector![p.x, p.y],
@@ -92,6 +109,7 @@ impl Scenario for Welcome {
             a: point![x, x],
             b: point![x, x],
             color: vector![0.0, 0.0, 0.0, 0.0],
+            ..Default::default()
         }]
     }
 }
@@ -6,15 +6,18 @@ use crate::radar::Radar;
 use crate::radio::Radio;
 use crate::rng;
 use crate::simulation::{self, PHYSICS_TICK_LENGTH};
-use crate::simulation::{Particle, Simulation};
+use crate::simulation::{DestructionCause, Particle, SimEvent, Simulation};
 use crate::{bullet, collision};
 use bullet::BulletData;
 use nalgebra::{vector, Rotation2, UnitComplex, Vector2};
-use oort_api::Ability;
+use oort_api::{Ability, Class};
 use rand::Rng;
+use rand_distr::StandardNormal;
 use rapier2d_f64::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::f64::consts::TAU;
+use std::sync::OnceLock;
 
 #[derive(Hash, PartialEq, Eq, Copy, Clone, Debug, Ord, PartialOrd)]
 pub struct ShipHandle(pub Index);
@@ -59,45 +62,84 @@ impl ShipClass {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct Gun {
     pub magazine_size: i32,
+    #[serde(skip)]
     pub magazine_remaining: i32,
     pub magazine_reload_ticks: u32,
     pub reload_ticks: u32,
+    #[serde(skip)]
     pub reload_ticks_remaining: u32,
     pub speed: f64,
     pub speed_error: f64,
     pub offset: Vector2<f64>,
+    #[serde(skip)]
     pub heading: f64,
     pub min_angle: f64,
     pub max_angle: f64,
     pub inaccuracy: f64,
+    /// Standard deviation of the gaussian noise added to each bullet's
+    /// direction, in radians.
+    pub spread: f64,
+    /// Impulse applied to the ship opposite the firing direction, in N·s.
+    pub recoil_impulse: f64,
     pub burst_size: i32,
     pub ttl: f32,
     pub bullet_mass: f64,
+    /// Bullets left, or None for unlimited.
+    pub ammo: Option<i32>,
+    /// Set by `set_weapon_auto`. See point_defense.rs.
+    #[serde(skip)]
+    pub auto_target: Option<Class>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct MissileLauncher {
     pub class: ShipClass,
     pub reload_ticks: u32,
+    #[serde(skip)]
     pub reload_ticks_remaining: u32,
     pub initial_speed: f64,
     pub offset: Vector2<f64>,
     pub angle: f64,
+    /// Missiles left, or None for unlimited.
+    pub ammo: Option<i32>,
 }
 
-#[derive(Debug, Clone)]
+/// A hitscan weapon that damages the first ship along its beam instantly.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Laser {
+    pub damage: f64,
+    pub range: f64,
+    pub reload_ticks: u32,
+    #[serde(skip)]
+    pub reload_ticks_remaining: u32,
+    pub offset: Vector2<f64>,
+    #[serde(skip)]
+    pub heading: f64,
+    pub min_angle: f64,
+    pub max_angle: f64,
+    /// Shots left, or None for unlimited.
+    pub ammo: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct ShipAbility {
     pub ability: Ability,
     pub active_time: f64,
     pub reload_time: f64,
+    #[serde(skip)]
     pub active_time_remaining: f64,
+    #[serde(skip)]
     pub reload_time_remaining: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct Warhead {
     pub count: i32,
     pub mass: f32,
@@ -106,31 +148,107 @@ pub struct Warhead {
     pub ttl: f32,
 }
 
-#[derive(Debug, Clone)]
+/// A regenerating barrier that absorbs bullet damage before the hull does.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Shield {
+    pub max: f64,
+    /// Points recharged per second.
+    pub recharge_rate: f64,
+    /// Ticks without a hit before recharging starts.
+    pub recharge_delay_ticks: u32,
+    /// How long a boost doubles the recharge rate, in seconds.
+    pub boost_time: f64,
+    pub boost_reload_time: f64,
+    #[serde(skip)]
+    pub value: f64,
+    #[serde(skip)]
+    pub ticks_since_hit: u32,
+    #[serde(skip)]
+    pub boost_time_remaining: f64,
+    #[serde(skip)]
+    pub boost_reload_time_remaining: f64,
+}
+
+impl Shield {
+    /// Soaks up as much of the damage as possible and returns the rest.
+    pub fn absorb(&mut self, damage: f64) -> f64 {
+        self.ticks_since_hit = 0;
+        let absorbed = damage.min(self.value);
+        self.value -= absorbed;
+        damage - absorbed
+    }
+
+    pub fn activate_boost(&mut self) {
+        if self.boost_reload_time_remaining > 0.0 {
+            return;
+        }
+        self.boost_time_remaining = self.boost_time;
+        self.boost_reload_time_remaining = self.boost_reload_time;
+    }
+
+    fn tick(&mut self) {
+        let boosted = self.boost_time_remaining > 0.0;
+        self.boost_time_remaining = (self.boost_time_remaining - PHYSICS_TICK_LENGTH).max(0.0);
+        self.boost_reload_time_remaining =
+            (self.boost_reload_time_remaining - PHYSICS_TICK_LENGTH).max(0.0);
+        if self.ticks_since_hit < self.recharge_delay_ticks {
+            self.ticks_since_hit += 1;
+            return;
+        }
+        let rate = if boosted {
+            self.recharge_rate * 2.0
+        } else {
+            self.recharge_rate
+        };
+        self.value = (self.value + rate * PHYSICS_TICK_LENGTH).min(self.max);
+    }
+}
+
+/// Per-ship state. Class definitions are loaded from ship_classes.json, which
+/// only contains the fields that aren't updated during the simulation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct ShipData {
     pub class: ShipClass,
+    #[serde(skip)]
     pub team: i32,
     pub health: f64,
+    /// Health the ship was created with.
+    #[serde(skip)]
+    pub max_health: f64,
     pub mass: f64,
+    #[serde(skip)]
     pub acceleration: Vector2<f64>,
+    #[serde(skip)]
     pub last_acceleration: Vector2<f64>,
+    #[serde(skip)]
     pub angular_acceleration: f64,
     pub max_forward_acceleration: f64,
     pub max_backward_acceleration: f64,
     pub max_lateral_acceleration: f64,
     pub max_angular_acceleration: f64,
+    #[serde(skip)]
     pub destroyed: bool,
+    #[serde(skip)]
     pub crash_message: Option<String>,
     pub ttl: Option<u64>,
     pub fuel: Option<f64>,
     pub guns: Vec<Gun>,
     pub missile_launchers: Vec<MissileLauncher>,
+    /// Weapon indices for lasers start at `first_laser_index()`.
+    pub lasers: Vec<Laser>,
     pub radar: Option<Radar>,
     pub radar_cross_section: f64,
+    /// Set by the ship's script to lower its cross-section below the class's.
+    #[serde(skip)]
+    pub reduced_radar_cross_section: Option<f64>,
     pub radios: Vec<Radio>,
     pub abilities: Vec<ShipAbility>,
+    #[serde(skip)]
     pub target: Option<Box<Target>>,
     pub warhead: Warhead,
+    pub shield: Option<Shield>,
 }
 
 #[derive(Debug, Clone)]
@@ -145,6 +263,7 @@ impl Default for ShipData {
             class: ShipClass::Fighter,
             team: 0,
             health: 100.0,
+            max_health: 100.0,
             mass: 1000.0,
             acceleration: vector![0.0, 0.0],
             last_acceleration: vector![0.0, 0.0],
@@ -159,16 +278,32 @@ impl Default for ShipData {
             fuel: None,
             guns: vec![],
             missile_launchers: vec![],
+            lasers: vec![],
             radar: None,
             radar_cross_section: 10.0,
+            reduced_radar_cross_section: None,
             radios: vec![],
             abilities: vec![],
             target: None,
             warhead: Default::default(),
+            shield: None,
         }
     }
 }
 
+impl ShipData {
+    /// Lasers start at weapon index 3, or after the missile launchers on
+    /// classes with more guns and launchers than that. A fighter's laser is
+    /// weapon 3 and index 2 is unused.
+    pub fn first_laser_index(&self) -> usize {
+        (self.guns.len() + self.missile_launchers.len()).max(FIRST_LASER_INDEX)
+    }
+
+    fn laser_index(&self, idx: usize) -> Option<usize> {
+        idx.checked_sub(self.first_laser_index())
+    }
+}
+
 impl Default for Gun {
     fn default() -> Gun {
         Gun {
@@ -184,9 +319,29 @@ impl Default for Gun {
             min_angle: 0.0,
             max_angle: 0.0,
             inaccuracy: 0.0,
+            spread: 0.0,
+            recoil_impulse: 0.0,
             burst_size: 1,
             ttl: 10.0,
             bullet_mass: 1.0,
+            ammo: None,
+            auto_target: None,
+        }
+    }
+}
+
+impl Default for Laser {
+    fn default() -> Laser {
+        Laser {
+            damage: 50.0,
+            range: 2000.0,
+            reload_ticks: 120,
+            reload_ticks_remaining: 0,
+            offset: vector![0.0, 0.0],
+            heading: 0.0,
+            min_angle: 0.0,
+            max_angle: 0.0,
+            ammo: None,
         }
     }
 }
@@ -203,6 +358,22 @@ impl Default for ShipAbility {
     }
 }
 
+impl Default for Shield {
+    fn default() -> Self {
+        Self {
+            max: 0.0,
+            recharge_rate: 0.0,
+            recharge_delay_ticks: 120,
+            boost_time: 1.0,
+            boost_reload_time: 10.0,
+            value: 0.0,
+            ticks_since_hit: 0,
+            boost_time_remaining: 0.0,
+            boost_reload_time_remaining: 0.0,
+        }
+    }
+}
+
 impl Default for Warhead {
     fn default() -> Self {
         Self {
@@ -215,293 +386,97 @@ impl Default for Warhead {
     }
 }
 
-fn radio() -> Radio {
-    // TODO tune this
-    Radio {
-        power: 20e3,
-        rx_cross_section: 5.0,
-        min_rssi: 1e-5,
-        channel: 0,
-        sent: None,
-        received: None,
+static SHIP_CLASSES: OnceLock<BTreeMap<String, ShipData>> = OnceLock::new();
+
+/// Parses and validates a map from class name to ship definition.
+pub fn parse_ship_classes(text: &str) -> Result<BTreeMap<String, ShipData>, String> {
+    let classes: BTreeMap<String, ShipData> =
+        serde_json::from_str(text).map_err(|e| format!("Failed to parse ship classes: {}", e))?;
+    for (name, data) in classes.iter() {
+        if !(data.mass.is_finite() && data.mass > 0.0) {
+            return Err(format!(
+                "Ship class {:?} has invalid mass {}",
+                name, data.mass
+            ));
+        }
+        if model::load(data.class).len() < 3 {
+            return Err(format!("Ship class {:?} has no model", name));
+        }
     }
+    Ok(classes)
 }
 
-pub fn vulcan_gun() -> Gun {
-    Gun {
-        magazine_size: 30,
-        reload_ticks: 4,
-        magazine_reload_ticks: 60,
-        speed: 1000.0,
-        inaccuracy: 0.0025,
-        bullet_mass: 0.1,
-        ..Default::default()
+/// Returns the definition of a built-in ship class, e.g. "fighter".
+pub fn ship_class(name: &str) -> ShipData {
+    let classes = SHIP_CLASSES.get_or_init(|| {
+        parse_ship_classes(include_str!("ship_classes.json")).expect("Invalid ship_classes.json")
+    });
+    match classes.get(name) {
+        Some(data) => data.clone(),
+        None => panic!("Unknown ship class {:?}", name),
     }
 }
 
 pub fn fighter(team: i32) -> ShipData {
     ShipData {
-        class: ShipClass::Fighter,
         team,
-        health: 100.0,
-        mass: 15000.0,
-        max_forward_acceleration: 60.0,
-        max_backward_acceleration: 30.0,
-        max_lateral_acceleration: 30.0,
-        max_angular_acceleration: TAU,
-        guns: vec![Gun {
-            offset: vector![20.0, 0.0],
-            ..vulcan_gun()
-        }],
-        missile_launchers: vec![MissileLauncher {
-            class: ShipClass::Missile,
-            reload_ticks: 5 * 60,
-            reload_ticks_remaining: 0,
-            initial_speed: 100.0,
-            offset: vector![20.0, 0.0],
-            angle: 0.0,
-        }],
-        radar: Some(Radar {
-            power: 20e3,
-            rx_cross_section: 5.0,
-            min_width: TAU / 720.0,
-            ..Default::default()
-        }),
-        radar_cross_section: 10.0,
-        radios: vec![radio(), radio()],
-        abilities: vec![ShipAbility {
-            ability: Ability::Boost,
-            active_time: 2.0,
-            reload_time: 10.0,
-            ..Default::default()
-        }],
-        ..Default::default()
+        ..ship_class("fighter")
     }
 }
 
 pub fn frigate(team: i32) -> ShipData {
     ShipData {
-        class: ShipClass::Frigate,
         team,
-        health: 10000.0,
-        mass: 4e6,
-        max_forward_acceleration: 10.0,
-        max_backward_acceleration: 5.0,
-        max_lateral_acceleration: 5.0,
-        max_angular_acceleration: TAU / 8.0,
-        guns: vec![
-            Gun {
-                magazine_size: 1,
-                magazine_reload_ticks: 0,
-                reload_ticks: 2 * 60,
-                speed: 4000.0,
-                offset: vector![40.0, 0.0],
-                bullet_mass: 1.0,
-                ttl: 60.0,
-                ..Default::default()
-            },
-            Gun {
-                offset: vector![0.0, 15.0],
-                max_angle: TAU,
-                ..vulcan_gun()
-            },
-            Gun {
-                offset: vector![0.0, -15.0],
-                max_angle: TAU,
-                ..vulcan_gun()
-            },
-        ],
-        missile_launchers: vec![MissileLauncher {
-            class: ShipClass::Missile,
-            reload_ticks: 2 * 60,
-            reload_ticks_remaining: 0,
-            initial_speed: 100.0,
-            offset: vector![60.0, 0.0],
-            angle: 0.0,
-        }],
-        radar: Some(Radar {
-            power: 100e3,
-            rx_cross_section: 10.0,
-            ..Default::default()
-        }),
-        radar_cross_section: 30.0,
-        radios: vec![radio(), radio(), radio(), radio()],
-        ..Default::default()
+        ..ship_class("frigate")
     }
 }
 
+/// The lowest weapon index a laser can have.
+pub const FIRST_LASER_INDEX: usize = 3;
+
 pub const CRUISER_RADAR_CROSS_SECTION: f64 = 40.0;
 
+/// The lowest radar cross-section a ship can set, as a fraction of its
+/// class's.
+pub const MIN_RADAR_CROSS_SECTION_FRACTION: f64 = 0.25;
+
 pub fn cruiser(team: i32) -> ShipData {
-    let missile_launcher = MissileLauncher {
-        class: ShipClass::Missile,
-        reload_ticks: 72,
-        reload_ticks_remaining: 0,
-        initial_speed: 100.0,
-        offset: vector![0.0, 0.0],
-        angle: 0.0,
-    };
     ShipData {
-        class: ShipClass::Cruiser,
         team,
-        health: 20000.0,
-        mass: 9e6,
-        max_forward_acceleration: 5.0,
-        max_backward_acceleration: 2.5,
-        max_lateral_acceleration: 2.5,
-        max_angular_acceleration: TAU / 16.0,
-        guns: vec![Gun {
-            magazine_size: 30,
-            magazine_reload_ticks: 60,
-            reload_ticks: 24,
-            speed: 1000.0,
-            speed_error: 50.0,
-            offset: vector![0.0, 0.0],
-            max_angle: TAU,
-            inaccuracy: 0.02,
-            burst_size: 6,
-            ttl: 1.0,
-            bullet_mass: 0.1,
-            ..Default::default()
-        }],
-        missile_launchers: vec![
-            MissileLauncher {
-                offset: vector![0.0, 50.0],
-                angle: TAU / 4.0,
-                ..missile_launcher
-            },
-            MissileLauncher {
-                offset: vector![0.0, -50.0],
-                angle: -TAU / 4.0,
-                ..missile_launcher
-            },
-            MissileLauncher {
-                class: ShipClass::Torpedo,
-                reload_ticks: 180,
-                reload_ticks_remaining: 0,
-                initial_speed: 100.0,
-                offset: vector![140.0, 0.0],
-                angle: 0.0,
-            },
-        ],
-        radar: Some(Radar {
-            power: 200e3,
-            rx_cross_section: 20.0,
-            ..Default::default()
-        }),
-        radar_cross_section: CRUISER_RADAR_CROSS_SECTION,
-        radios: vec![
-            radio(),
-            radio(),
-            radio(),
-            radio(),
-            radio(),
-            radio(),
-            radio(),
-            radio(),
-        ],
-        abilities: vec![ShipAbility {
-            ability: Ability::Shield,
-            active_time: 1.0,
-            reload_time: 5.0,
-            ..Default::default()
-        }],
-        ..Default::default()
+        ..ship_class("cruiser")
     }
 }
 
+/// Asteroid mass per square meter, so bigger rocks are harder to push around.
+pub const ASTEROID_DENSITY: f64 = 4000.0;
+
 pub fn asteroid(variant: i32) -> ShipData {
     ShipData {
         class: ShipClass::Asteroid { variant },
         team: 9,
-        health: 200.0,
-        mass: 20e6,
-        radar_cross_section: 50.0,
-        ..Default::default()
+        mass: ASTEROID_DENSITY * model::area(&model::asteroid(variant)) as f64,
+        ..ship_class("asteroid")
     }
 }
 
 pub fn target(team: i32) -> ShipData {
     ShipData {
-        class: ShipClass::Target,
         team,
-        health: 1.0,
-        mass: 10.0,
-        ..Default::default()
+        ..ship_class("target")
     }
 }
 
 pub fn missile(team: i32) -> ShipData {
     ShipData {
-        class: ShipClass::Missile,
         team,
-        health: 20.0,
-        mass: 150.0,
-        max_forward_acceleration: 300.0,
-        max_backward_acceleration: 0.0,
-        max_lateral_acceleration: 100.0,
-        max_angular_acceleration: 4.0 * TAU,
-        radar: Some(Radar {
-            power: 1e3,
-            rx_cross_section: 3.0,
-            min_width: TAU / 720.0,
-            ..Default::default()
-        }),
-        radar_cross_section: 0.1,
-        radios: vec![radio()],
-        ttl: Some(60 * 60),
-        fuel: Some(2000.0),
-        abilities: vec![ShipAbility {
-            ability: Ability::Boost,
-            active_time: 2.0,
-            reload_time: 10.0,
-            ..Default::default()
-        }],
-        warhead: Warhead {
-            count: 20,
-            mass: 0.05,
-            width: 0.4,
-            speed: 1e3,
-            ttl: 0.2,
-        },
-        ..Default::default()
+        ..ship_class("missile")
     }
 }
 
 pub fn torpedo(team: i32) -> ShipData {
     ShipData {
-        class: ShipClass::Torpedo,
         team,
-        health: 100.0,
-        mass: 500.0,
-        max_forward_acceleration: 70.0,
-        max_backward_acceleration: 0.0,
-        max_lateral_acceleration: 20.0,
-        max_angular_acceleration: 2.0 * TAU,
-        radar: Some(Radar {
-            power: 10e3,
-            rx_cross_section: 3.0,
-            min_width: TAU / 720.0,
-            ..Default::default()
-        }),
-        radar_cross_section: 0.3,
-        radios: vec![radio()],
-        ttl: Some(60 * 60),
-        fuel: Some(3000.0),
-        abilities: vec![ShipAbility {
-            ability: Ability::Decoy,
-            active_time: 0.5,
-            reload_time: 10.0,
-            ..Default::default()
-        }],
-        warhead: Warhead {
-            count: 50,
-            mass: 0.25,
-            width: 0.5,
-            speed: 1e3,
-            ttl: 0.2,
-        },
-        ..Default::default()
+        ..ship_class("torpedo")
     }
 }
 
@@ -548,9 +523,14 @@ pub fn create(
     sim.colliders
         .insert_with_parent(collider, body_handle, &mut sim.bodies);
 
+    data.max_health = data.health;
     for gun in data.guns.iter_mut() {
         gun.magazine_remaining = gun.magazine_size;
     }
+    if let Some(shield) = data.shield.as_mut() {
+        shield.value = shield.max;
+        shield.ticks_since_hit = shield.recharge_delay_ticks;
+    }
 
     sim.ships.insert(handle);
     sim.new_ships.push((data.team, handle));
@@ -622,6 +602,15 @@ impl<'a> ShipAccessor<'a> {
             .collect()
     }
 
+    /// Remaining shield points, or zero for ships without a shield.
+    pub fn shield(&self) -> f64 {
+        self.data().shield.as_ref().map_or(0.0, |x| x.value)
+    }
+
+    pub fn auto_target(&self, index: usize) -> Option<Class> {
+        self.data().guns.get(index).and_then(|gun| gun.auto_target)
+    }
+
     pub fn get_reload_ticks(&self, idx: usize) -> u32 {
         if let Some(gun) = self.data().guns.get(idx) {
             gun.reload_ticks_remaining
@@ -631,10 +620,65 @@ impl<'a> ShipAccessor<'a> {
             .get(idx - self.data().guns.len())
         {
             missile.reload_ticks_remaining
+        } else if let Some(laser) = self
+            .data()
+            .laser_index(idx)
+            .and_then(|i| self.data().lasers.get(i))
+        {
+            laser.reload_ticks_remaining
         } else {
             0
         }
     }
+
+    /// Shots left in a weapon, or None if it has unlimited ammo. Guns count
+    /// bullets.
+    pub fn get_ammo(&self, idx: usize) -> Option<i32> {
+        let data = self.data();
+        if let Some(gun) = data.guns.get(idx) {
+            gun.ammo
+        } else if let Some(missile) = data.missile_launchers.get(idx - data.guns.len()) {
+            missile.ammo
+        } else if let Some(laser) = data.laser_index(idx).and_then(|i| data.lasers.get(i)) {
+            laser.ammo
+        } else {
+            Some(0)
+        }
+    }
+
+    /// Speed of a projectile relative to the ship when fired from a weapon.
+    pub fn get_muzzle_speed(&self, idx: usize) -> f64 {
+        if let Some(gun) = self.data().guns.get(idx) {
+            gun.speed
+        } else if let Some(missile) = self
+            .data()
+            .missile_launchers
+            .get(idx - self.data().guns.len())
+        {
+            missile.initial_speed
+        } else {
+            0.0
+        }
+    }
+
+    /// Maximum angle between a bullet and the direction its gun is aimed.
+    /// Missile launchers have no spread.
+    pub fn get_inaccuracy(&self, idx: usize) -> f64 {
+        self.data().guns.get(idx).map_or(0.0, |gun| gun.inaccuracy)
+    }
+
+    /// Standard deviation of a weapon's bullet directions. Missile launchers
+    /// have no spread.
+    pub fn get_spread(&self, idx: usize) -> f64 {
+        self.data().guns.get(idx).map_or(0.0, |gun| gun.spread)
+    }
+
+    /// The cross-section other ships' radars see.
+    pub fn radar_cross_section(&self) -> f64 {
+        let data = self.data();
+        data.reduced_radar_cross_section
+            .unwrap_or(data.radar_cross_section)
+    }
 }
 
 pub struct ShipAccessorMut<'a> {
@@ -698,11 +742,18 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
     }
 
     pub fn fire(&mut self, index: i64) {
+        if self.simulation.practice && self.data().team != 0 {
+            return;
+        }
         let num_guns = self.data().guns.len() as i64;
-        if index >= num_guns {
-            self.launch_missile(index - num_guns);
-        } else {
+        let num_missile_launchers = self.data().missile_launchers.len() as i64;
+        let first_laser_index = self.data().first_laser_index() as i64;
+        if index < num_guns {
             self.fire_gun(index);
+        } else if index < num_guns + num_missile_launchers {
+            self.launch_missile(index - num_guns);
+        } else if index >= first_laser_index {
+            self.fire_laser(index - first_laser_index);
         }
     }
 
@@ -712,9 +763,9 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             return;
         }
         let team = ship_data.team;
-        let gun = {
+        let (gun, num_bullets) = {
             let gun = &mut ship_data.guns[index as usize];
-            if gun.reload_ticks_remaining > 0 {
+            if gun.reload_ticks_remaining > 0 || gun.ammo.map_or(false, |ammo| ammo <= 0) {
                 return;
             }
             gun.reload_ticks_remaining = gun.reload_ticks;
@@ -723,11 +774,26 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
                 gun.magazine_remaining = gun.magazine_size;
                 gun.reload_ticks_remaining += gun.magazine_reload_ticks;
             }
-            gun.clone()
+            // The last burst may be partial.
+            let num_bullets = gun
+                .ammo
+                .map_or(gun.burst_size, |ammo| ammo.min(gun.burst_size));
+            if let Some(ammo) = gun.ammo.as_mut() {
+                *ammo -= num_bullets;
+            }
+            (gun.clone(), num_bullets)
         };
 
         let mut rng =
             rng::new_rng(self.simulation.tick() ^ u64::from(self.handle) as u32 ^ index as u32);
+        // Spread has its own RNG so that it varies with the simulation seed
+        // without changing the draws for inaccuracy and speed error.
+        let mut spread_rng = rng::new_rng_from((
+            self.simulation.seed(),
+            self.simulation.tick(),
+            u64::from(self.handle),
+            index as u32,
+        ));
         let alpha = (gun.bullet_mass as f32).clamp(0.7, 1.0);
         let color = color::to_u32(vector![1.0, 1.0, 1.0, alpha]);
         let mut t = 0.0;
@@ -737,12 +803,29 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             .rem_euclid(TAU)
             .clamp(gun.min_angle, gun.max_angle);
 
-        for _ in 0..gun.burst_size {
+        let position = self.body().position().translation.vector;
+        self.simulation.emit_event(SimEvent::GunFired { position });
+
+        if gun.recoil_impulse > 0.0 {
+            let body = self.body();
+            let rot = body.position().rotation * UnitComplex::new(relative_heading);
+            body.apply_impulse(
+                rot.transform_vector(&vector![-gun.recoil_impulse, 0.0]),
+                true,
+            );
+        }
+
+        for _ in 0..num_bullets {
             let relative_heading = if gun.inaccuracy > 0.0 {
                 relative_heading + rng.gen_range(-gun.inaccuracy..gun.inaccuracy)
             } else {
                 relative_heading
             };
+            let relative_heading = if gun.spread > 0.0 {
+                relative_heading + spread_rng.sample::<f64, _>(StandardNormal) * gun.spread
+            } else {
+                relative_heading
+            };
             let speed = if gun.speed_error > 0.0 {
                 gun.speed + rng.gen_range(-gun.speed_error..gun.speed_error)
             } else {
@@ -775,10 +858,15 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             if let Some(missile_launcher) =
                 ship_data.missile_launchers.get_mut(index as usize).as_mut()
             {
-                if missile_launcher.reload_ticks_remaining > 0 {
+                if missile_launcher.reload_ticks_remaining > 0
+                    || missile_launcher.ammo.map_or(false, |ammo| ammo <= 0)
+                {
                     return;
                 }
                 missile_launcher.reload_ticks_remaining = missile_launcher.reload_ticks;
+                if let Some(ammo) = missile_launcher.ammo.as_mut() {
+                    *ammo -= 1;
+                }
                 missile_launcher.clone()
             } else {
                 return;
@@ -793,6 +881,8 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         let rot2 = rot * UnitComplex::new(missile_launcher.angle);
         let v = body.linvel() + rot2.transform_vector(&vector![speed, 0.0]);
         let team = self.data().team;
+        self.simulation
+            .emit_event(SimEvent::MissileLaunched { position: p });
         create(
             self.simulation,
             p,
@@ -806,13 +896,72 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         );
     }
 
+    pub fn fire_laser(&mut self, index: i64) {
+        let laser = {
+            let ship_data = self.data_mut();
+            match ship_data.lasers.get_mut(index as usize) {
+                Some(laser)
+                    if laser.reload_ticks_remaining == 0
+                        && laser.ammo.map_or(true, |ammo| ammo > 0) =>
+                {
+                    laser.reload_ticks_remaining = laser.reload_ticks;
+                    if let Some(ammo) = laser.ammo.as_mut() {
+                        *ammo -= 1;
+                    }
+                    laser.clone()
+                }
+                _ => return,
+            }
+        };
+
+        let relative_heading = (laser.heading - self.readonly().heading())
+            .rem_euclid(TAU)
+            .clamp(laser.min_angle, laser.max_angle);
+        let body = self.body();
+        let rot = body.position().rotation * UnitComplex::new(relative_heading);
+        let start = body.position().translation.vector
+            + body.position().rotation.transform_vector(&laser.offset);
+        let direction = rot.transform_vector(&vector![1.0, 0.0]);
+        let end = collision::fire_laser(
+            self.simulation,
+            self.handle,
+            start,
+            direction,
+            laser.range,
+            laser.damage,
+        );
+        self.simulation
+            .emit_event(SimEvent::LaserFired { start, end });
+    }
+
     pub fn aim(&mut self, index: i64, heading: f64) {
         let ship_data = self.data_mut();
-        if index as usize >= ship_data.guns.len() {
-            return;
+        let laser_index = ship_data.laser_index(index as usize);
+        if let Some(gun) = ship_data.guns.get_mut(index as usize) {
+            gun.heading = heading;
+        } else if let Some(laser) = laser_index.and_then(|i| ship_data.lasers.get_mut(i)) {
+            laser.heading = heading;
         }
-        let gun = &mut ship_data.guns[index as usize];
-        gun.heading = heading;
+    }
+
+    pub fn set_auto_target(&mut self, index: usize, class: Option<Class>) {
+        if let Some(gun) = self.data_mut().guns.get_mut(index) {
+            gun.auto_target = class;
+        }
+    }
+
+    /// Lowers the ship's radar cross-section, down to
+    /// `MIN_RADAR_CROSS_SECTION_FRACTION` of its class's. Engine power is cut
+    /// by the same fraction.
+    pub fn set_radar_cross_section(&mut self, value: f64) {
+        let max = self.data().radar_cross_section;
+        let min = max * MIN_RADAR_CROSS_SECTION_FRACTION;
+        self.data_mut().reduced_radar_cross_section =
+            if max <= 0.0 || value.is_nan() || value >= max {
+                None
+            } else {
+                Some(value.max(min))
+            };
     }
 
     pub fn explode(&mut self) {
@@ -825,6 +974,15 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         let team = self.data().team;
         let p =
             self.body().position().translation.vector - self.body().linvel() * PHYSICS_TICK_LENGTH;
+        let velocity = *self.body().linvel();
+        self.simulation.emit_event(SimEvent::Explosion {
+            id: self.handle.into(),
+            position: p,
+            velocity,
+            mass: self.data().mass,
+            class: self.data().class,
+            cause: DestructionCause::Exploded,
+        });
         let mut rng = new_rng(0);
         for _ in 0..warhead.count {
             let color = vector![rng.gen_range(0.7..1.0), 0.5, 0.5, rng.gen_range(0.5..1.0)];
@@ -868,6 +1026,14 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         }
     }
 
+    /// Doubles the shield's recharge rate for a short time. Does nothing if
+    /// the boost is reloading or the ship has no shield.
+    pub fn activate_shield_boost(&mut self) {
+        if let Some(shield) = self.data_mut().shield.as_mut() {
+            shield.activate_boost();
+        }
+    }
+
     pub fn deactivate_ability(&mut self, ability: oort_api::Ability) {
         if let Some(ship_ability) = self
             .data_mut()
@@ -898,6 +1064,12 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
                     missile_launcher.reload_ticks_remaining -= 1;
                 }
             }
+
+            for laser in ship_data.lasers.iter_mut() {
+                if laser.reload_ticks_remaining > 0 {
+                    laser.reload_ticks_remaining -= 1;
+                }
+            }
         }
 
         // Acceleration.
@@ -906,6 +1078,9 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             if self.readonly().is_ability_active(Ability::Boost) {
                 acceleration += vector![100.0, 0.0];
             }
+            if let Some(reduced) = self.data().reduced_radar_cross_section {
+                acceleration *= reduced / self.data().radar_cross_section;
+            }
             let fuel_consumption = (acceleration * PHYSICS_TICK_LENGTH).norm();
             if let Some(fuel) = self.data_mut().fuel {
                 if fuel < fuel_consumption {
@@ -921,7 +1096,10 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             self.body().reset_forces(false);
             self.body().add_force(inertial_acceleration * mass, true);
             self.data_mut().last_acceleration = inertial_acceleration;
-            self.data_mut().acceleration = vector![0.0, 0.0];
+            // Commands last until the script runs again.
+            if self.simulation.is_script_tick(self.simulation.tick() + 1) {
+                self.data_mut().acceleration = vector![0.0, 0.0];
+            }
         }
 
         // Torque.
@@ -935,7 +1113,9 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             let torque = self.data().angular_acceleration * inertia_sqrt * inertia_sqrt;
             self.body().reset_torques(false);
             self.body().add_torque(torque, true);
-            self.data_mut().angular_acceleration = 0.0;
+            if self.simulation.is_script_tick(self.simulation.tick() + 1) {
+                self.data_mut().angular_acceleration = 0.0;
+            }
         }
 
         // TTL
@@ -958,10 +1138,16 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             }
         }
 
+        // Shield.
+        if let Some(shield) = self.data_mut().shield.as_mut() {
+            shield.tick();
+        }
+
         // Destruction.
         if self.data().destroyed {
-            if let Some(team_ctrl) = self.simulation.get_team_controller(self.data().team) {
-                team_ctrl.borrow_mut().remove_ship(self.handle);
+            let team = self.data().team;
+            if let Some(team_ctrl) = self.simulation.get_team_controller(team) {
+                team_ctrl.remove_ship(self.handle);
             }
             self.simulation.ships.remove(self.handle);
             self.simulation.bodies.remove(
@@ -987,6 +1173,7 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
 
 #[cfg(test)]
 mod test {
+    use crate::model;
     use crate::ship;
     use crate::simulation::Code;
     use crate::simulation::Simulation;
@@ -1049,4 +1236,120 @@ mod test {
         sim.ship_mut(ship0).fire(1);
         assert_eq!(sim.ships.len(), 3);
     }
+
+    #[test]
+    fn test_radar_cross_section() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        assert_eq!(sim.ship(ship0).radar_cross_section(), 10.0);
+
+        sim.ship_mut(ship0).set_radar_cross_section(5.0);
+        assert_eq!(sim.ship(ship0).radar_cross_section(), 5.0);
+        sim.ship_mut(ship0).set_radar_cross_section(0.0);
+        assert_eq!(sim.ship(ship0).radar_cross_section(), 2.5);
+        sim.ship_mut(ship0).set_radar_cross_section(100.0);
+        assert_eq!(sim.ship(ship0).radar_cross_section(), 10.0);
+
+        // Engines run at the same fraction as the cross-section.
+        let ship1 = ship::create(
+            &mut sim,
+            vector![0.0, 1000.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        sim.ship_mut(ship0).set_radar_cross_section(5.0);
+        for _ in 0..10 {
+            sim.ship_mut(ship0).accelerate(vector![60.0, 0.0]);
+            sim.ship_mut(ship1).accelerate(vector![60.0, 0.0]);
+            sim.step();
+        }
+        let v0 = sim.ship(ship0).velocity().x;
+        let v1 = sim.ship(ship1).velocity().x;
+        assert!(v1 > 0.0);
+        assert!((v0 - v1 / 2.0).abs() < 1e-6, "{v0} {v1}");
+    }
+
+    #[test]
+    fn test_asteroid_colliders() {
+        let mut sim = Simulation::new("test", 0, &[Code::None]);
+        let mut areas = vec![];
+        for variant in [1, 6, 14] {
+            let model = model::asteroid(variant);
+            assert_eq!(model, model::asteroid(variant));
+
+            let handle = ship::create(
+                &mut sim,
+                vector![variant as f64 * 1000.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::asteroid(variant),
+            );
+            let body = sim.ship(handle).body();
+            let collider = &sim.colliders[body.colliders()[0]];
+            let polygon = collider.shape().as_convex_polygon().unwrap();
+            assert_eq!(polygon.points().len(), model.len());
+
+            let area = model::area(&model) as f64;
+            let mass = ship::ASTEROID_DENSITY * area;
+            assert!((body.mass() - mass).abs() < mass * 1e-6);
+            areas.push(area);
+        }
+        // One of each size tier.
+        assert!(areas[1] < areas[0] && areas[0] < areas[2], "{areas:?}");
+    }
+
+    #[test]
+    fn test_asteroid_shapes() {
+        // Vertex count and area of every variant the scenarios spawn. The
+        // convex hull can drop some of the 7 to 11 sampled points, as in
+        // variant 13.
+        let expected: [(usize, f32); 30] = [
+            (8, 1744.0665),
+            (9, 5042.8354),
+            (8, 12462.323),
+            (8, 1838.2595),
+            (9, 5471.5674),
+            (10, 14095.4375),
+            (10, 1951.4733),
+            (8, 5495.3213),
+            (9, 13636.98),
+            (8, 1851.4081),
+            (9, 5397.634),
+            (10, 12946.837),
+            (9, 1584.6787),
+            (6, 4956.9287),
+            (11, 14081.849),
+            (8, 1804.0767),
+            (9, 5253.0654),
+            (7, 11850.059),
+            (11, 1975.8163),
+            (10, 5598.2783),
+            (9, 14195.248),
+            (9, 2176.4326),
+            (8, 5183.9497),
+            (10, 13423.767),
+            (9, 1806.938),
+            (10, 4956.8843),
+            (10, 12776.322),
+            (7, 1876.9231),
+            (7, 4493.46),
+            (9, 11577.0),
+        ];
+        for (variant, &(len, area)) in expected.iter().enumerate() {
+            let model = model::asteroid(variant as i32);
+            assert_eq!(model.len(), len, "variant {variant}");
+            assert!(
+                (model::area(&model) - area).abs() < 0.01,
+                "variant {variant} has area {}",
+                model::area(&model)
+            );
+        }
+    }
 }
@@ -1,14 +1,16 @@
 use crate::bullet::{self, BulletData, BulletHandle};
 use crate::collision;
 use crate::debug;
-pub use crate::debug::Line;
+pub use crate::debug::{Label, Line, LineStyle};
 use crate::index_set::{HasIndex, IndexSet};
+use crate::point_defense;
 use crate::radar;
 use crate::radio;
 use crate::scenario;
 use crate::scenario::Scenario;
-use crate::ship::{ShipAccessor, ShipAccessorMut, ShipData, ShipHandle, Target};
+use crate::ship::{ShipAccessor, ShipAccessorMut, ShipClass, ShipData, ShipHandle, Target};
 use crate::snapshot::*;
+use crate::topology::{self, WorldTopology};
 use crate::vm;
 use crate::vm::TeamController;
 use crossbeam::channel::Sender;
@@ -19,9 +21,7 @@ use rand_chacha::ChaCha8Rng;
 use rapier2d_f64::data::Coarena;
 use rapier2d_f64::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::cell::RefCell;
-use std::collections::{BTreeMap, HashMap};
-use std::rc::Rc;
+use std::collections::{BTreeMap, VecDeque};
 
 pub const MAX_WORLD_SIZE: f64 = 200000.0;
 pub const PHYSICS_TICK_LENGTH: f64 = 1.0 / 60.0;
@@ -36,11 +36,44 @@ pub enum Code {
     Precompiled(bytes::Bytes),
 }
 
+/// A named location set by the scenario. Scripts can look it up with
+/// `get_marker` and it's drawn as a circle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marker {
+    pub position: Vector2<f64>,
+    pub radius: f64,
+    pub color: Vector4<f32>,
+}
+
+/// The marker that `target()` falls back to for ships without radar.
+pub const TARGET_MARKER: &str = "target";
+
+const MARKER_LINE_WIDTH: f32 = 2.0;
+
+/// A value the scenario passes to a team's scripts, which read it with
+/// `scenario_var`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ScenarioVar {
+    Vec2(Vector2<f64>),
+    F64(f64),
+    Bool(bool),
+}
+
+impl ScenarioVar {
+    fn encode(&self) -> String {
+        match self {
+            ScenarioVar::Vec2(v) => format!("{},{}", v.x, v.y),
+            ScenarioVar::F64(x) => format!("{}", x),
+            ScenarioVar::Bool(b) => format!("{}", b),
+        }
+    }
+}
+
 pub struct Simulation {
     scenario: Option<Box<dyn Scenario>>,
     pub ships: IndexSet<ShipHandle>,
     pub(crate) ship_data: Coarena<ShipData>,
-    team_controllers: HashMap<i32, Rc<RefCell<Box<TeamController>>>>,
+    team_controllers: BTreeMap<i32, Box<TeamController>>,
     pub new_ships: Vec<(/*team*/ i32, ShipHandle)>,
     pub bullets: IndexSet<BulletHandle>,
     pub(crate) bullet_data: Coarena<BulletData>,
@@ -54,15 +87,33 @@ pub struct Simulation {
     broad_phase: BroadPhase,
     narrow_phase: NarrowPhase,
     ccd_solver: CCDSolver,
+    /// Updated by the physics step. Used to ray cast lasers.
+    pub(crate) query_pipeline: QueryPipeline,
     event_collector: CollisionEventHandler,
-    contact_recv: crossbeam::channel::Receiver<CollisionEvent>,
+    contact_recv: crossbeam::channel::Receiver<(CollisionEvent, /*impact_speed*/ f64)>,
     pub(crate) events: SimEvents,
     tick: u32,
     pub cheats: bool,
+    /// Freezes every team except the player's: their scripts don't run and
+    /// their weapons don't fire, but physics still moves them.
+    pub practice: bool,
+    /// Scripts run every `script_tick_divisor` physics ticks. Ships keep
+    /// accelerating and turning as commanded in between.
+    pub script_tick_divisor: u32,
     seed: u32,
     timing: Timing,
     pub(crate) rng: ChaCha8Rng,
     world_size: f64,
+    topology: WorldTopology,
+    projectile_collisions: bool,
+    radar_occlusion: bool,
+    /// Bullets only check for obstacles when there are any.
+    pub(crate) num_obstacles: usize,
+    markers: BTreeMap<String, Marker>,
+    /// Marker names from least to most recently set.
+    marker_order: VecDeque<String>,
+    environments: BTreeMap<i32, BTreeMap<String, String>>,
+    vars: BTreeMap<i32, BTreeMap<String, ScenarioVar>>,
 }
 
 impl Simulation {
@@ -75,7 +126,7 @@ impl Simulation {
             scenario: None,
             ships: IndexSet::new(),
             ship_data: Coarena::new(),
-            team_controllers: HashMap::new(),
+            team_controllers: BTreeMap::new(),
             new_ships: Vec::new(),
             bullets: IndexSet::new(),
             bullet_data: Coarena::new(),
@@ -93,24 +144,38 @@ impl Simulation {
             broad_phase: BroadPhase::new(),
             narrow_phase: NarrowPhase::new(),
             ccd_solver: CCDSolver::new(),
+            query_pipeline: QueryPipeline::new(),
             event_collector: CollisionEventHandler::new(contact_send),
             contact_recv,
             events: SimEvents::new(),
             tick: 0,
             cheats: false,
+            practice: false,
+            script_tick_divisor: 1,
             seed,
             timing: Default::default(),
             rng: crate::rng::new_rng(seed),
             world_size: scenario.world_size(),
+            topology: scenario.topology(),
+            projectile_collisions: scenario.projectile_collisions(),
+            radar_occlusion: scenario.radar_occlusion(),
+            num_obstacles: 0,
+            markers: BTreeMap::new(),
+            marker_order: VecDeque::new(),
+            environments: BTreeMap::new(),
+            vars: BTreeMap::new(),
         });
 
         for (team, code) in codes.iter().enumerate() {
             if !matches!(code, Code::None) {
-                sim.upload_code(team as i32, code);
+                // Failures are reported through events.errors.
+                let _ = sim.upload_code(team as i32, code);
             }
         }
 
-        collision::add_walls(&mut sim);
+        if sim.topology == WorldTopology::Walled {
+            collision::add_walls(&mut sim);
+        }
 
         scenario.init(&mut sim, seed);
         sim.scenario = Some(scenario);
@@ -142,6 +207,18 @@ impl Simulation {
         self.world_size
     }
 
+    pub fn topology(&self) -> WorldTopology {
+        self.topology
+    }
+
+    pub fn projectile_collisions(&self) -> bool {
+        self.projectile_collisions
+    }
+
+    pub fn radar_occlusion(&self) -> bool {
+        self.radar_occlusion
+    }
+
     pub fn status(&self) -> scenario::Status {
         self.scenario.as_ref().unwrap().status(self)
     }
@@ -165,15 +242,17 @@ impl Simulation {
         self.events.clear();
         self.timing = Default::default();
 
+        let mut team_controllers = std::mem::take(&mut self.team_controllers);
         let new_ships = std::mem::take(&mut self.new_ships);
         for (team, handle) in new_ships.iter() {
-            if let Some(team_ctrl) = self.get_team_controller(*team) {
-                if let Err(e) = team_ctrl.borrow_mut().add_ship(*handle, self) {
+            if let Some(team_ctrl) = team_controllers.get_mut(team) {
+                if let Err(e) = team_ctrl.add_ship(*handle, self) {
                     log::warn!("Ship creation error: {:?}", e);
                     self.events.errors.push(e);
                 }
             }
         }
+        self.team_controllers = team_controllers;
 
         let physics_timer = Timer::new();
         let gravity = vector![0.0, 0.0];
@@ -189,10 +268,11 @@ impl Simulation {
             &mut self.impulse_joints,
             &mut self.multibody_joints,
             &mut self.ccd_solver,
-            None,
+            Some(&mut self.query_pipeline),
             &physics_hooks,
             &self.event_collector,
         );
+        topology::tick(self);
         self.timing.physics = physics_timer.elapsed();
 
         let collision_timer = Timer::new();
@@ -208,18 +288,27 @@ impl Simulation {
         radio::tick(self);
         self.timing.radio += radio_timer.elapsed();
 
-        let vm_timer = Timer::new();
-        let mut teams: Vec<_> = self
-            .team_controllers
-            .iter()
-            .map(|(k, v)| (*k, Rc::clone(v)))
-            .collect();
-        teams.sort_by_key(|(k, _)| *k);
-
-        for (_, team_controller) in teams.iter() {
-            team_controller.borrow_mut().tick(self);
+        if self.is_script_tick(self.tick) {
+            let vm_timer = Timer::new();
+            let mut team_controllers = std::mem::take(&mut self.team_controllers);
+            let frozen_team_controllers = if self.practice {
+                team_controllers.split_off(&1)
+            } else {
+                BTreeMap::new()
+            };
+            for team_controller in team_controllers.values_mut() {
+                team_controller.prepare(self);
+            }
+            run_team_controllers(&mut team_controllers);
+            for team_controller in team_controllers.values_mut() {
+                team_controller.apply(self);
+            }
+            team_controllers.extend(frozen_team_controllers);
+            self.team_controllers = team_controllers;
+            self.timing.vm += vm_timer.elapsed();
         }
-        self.timing.vm += vm_timer.elapsed();
+
+        point_defense::tick(self);
 
         let ship_timer = Timer::new();
         let handle_snapshot: Vec<ShipHandle> = self.ships.iter().cloned().collect();
@@ -242,15 +331,45 @@ impl Simulation {
         self.tick += 1;
     }
 
-    pub fn upload_code(&mut self, team: i32, code: &Code) {
-        match vm::new_team_controller(code) {
+    pub(crate) fn is_script_tick(&self, tick: u32) -> bool {
+        tick % self.script_tick_divisor.max(1) == 0
+    }
+
+    /// The time between script ticks, in seconds.
+    pub fn script_tick_length(&self) -> f64 {
+        PHYSICS_TICK_LENGTH * self.script_tick_divisor.max(1) as f64
+    }
+
+    /// Replaces a team's code. The new controller is given the team's existing
+    /// ships before it is swapped in, so if anything fails the previous code
+    /// keeps running.
+    pub fn upload_code(&mut self, team: i32, code: &Code) -> Result<(), vm::Error> {
+        let result = vm::new_team_controller(code).and_then(|mut team_ctrl| {
+            // Ships still in new_ships are added at the start of the next step.
+            let existing_ships: Vec<ShipHandle> = self
+                .ships
+                .iter()
+                .copied()
+                .filter(|&handle| self.ship(handle).data().team == team)
+                .filter(|handle| !self.new_ships.iter().any(|(_, h)| h == handle))
+                .collect();
+            for handle in existing_ships {
+                team_ctrl.add_ship(handle, self)?;
+            }
+            Ok(team_ctrl)
+        });
+        match result {
             Ok(team_ctrl) => {
-                self.team_controllers
-                    .insert(team, Rc::new(RefCell::new(team_ctrl)));
+                self.team_controllers.insert(team, team_ctrl);
+                if self.scenario.is_some() {
+                    self.send_environment(team);
+                }
+                Ok(())
             }
             Err(e) => {
                 log::warn!("Creating team controller failed: {:?}", e);
-                self.events.errors.push(e);
+                self.events.errors.push(e.clone());
+                Err(e)
             }
         }
     }
@@ -263,6 +382,10 @@ impl Simulation {
         &self.timing
     }
 
+    pub fn emit_event(&mut self, event: SimEvent) {
+        self.events.sim_events.push(event);
+    }
+
     pub fn emit_debug_lines(&mut self, ship: ShipHandle, lines: Vec<Line>) {
         self.events.debug_lines.push((ship.into(), lines));
     }
@@ -287,6 +410,55 @@ impl Simulation {
             .extend(texts.iter().cloned());
     }
 
+    /// Adds or updates a marker. Names can't contain '=' or newlines.
+    pub fn set_marker(&mut self, name: &str, marker: Marker) {
+        assert!(
+            !name.contains(['=', '\n']),
+            "Invalid marker name {:?}",
+            name
+        );
+        let moved = match self.markers.get(name) {
+            Some(old) => old.position != marker.position || old.radius != marker.radius,
+            None => true,
+        };
+        self.markers.insert(name.to_string(), marker);
+        self.marker_order.retain(|x| x != name);
+        self.marker_order.push_back(name.to_string());
+        // Scripts only see the position and radius. The environment is sent
+        // after the scenario is initialized.
+        if moved && self.scenario.is_some() {
+            for team in self.team_controllers.keys().copied().collect::<Vec<_>>() {
+                self.send_environment(team);
+            }
+        }
+    }
+
+    pub fn marker(&self, name: &str) -> Option<&Marker> {
+        self.markers.get(name)
+    }
+
+    /// Sets a variable for a team's scripts. Names can't contain '=' or
+    /// newlines.
+    pub fn write_var(&mut self, team: i32, name: &str, value: ScenarioVar) {
+        assert!(
+            !name.contains(['=', '\n']),
+            "Invalid variable name {:?}",
+            name
+        );
+        let vars = self.vars.entry(team).or_default();
+        if vars.get(name) == Some(&value) {
+            return;
+        }
+        vars.insert(name.to_string(), value);
+        if self.scenario.is_some() {
+            self.send_environment(team);
+        }
+    }
+
+    pub fn var(&self, team: i32, name: &str) -> Option<&ScenarioVar> {
+        self.vars.get(&team)?.get(name)
+    }
+
     pub fn write_target(&mut self, ship: ShipHandle, p: Vector2<f64>, v: Vector2<f64>) {
         self.ship_mut(ship).data_mut().target = Some(Box::new(Target {
             position: p,
@@ -319,23 +491,62 @@ impl Simulation {
         s.finish()
     }
 
+    /// Hashes the exact position, velocity and heading of every ship and
+    /// bullet, for regression tests that pin down physics behavior. Unlike
+    /// `hash`, this uses FNV-1a rather than `DefaultHasher`, so the value
+    /// doesn't depend on the Rust version.
+    pub fn state_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut write = |v: f64| {
+            for byte in v.to_bits().to_le_bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        };
+        for handle in self.ships.iter() {
+            let ship = self.ship(*handle);
+            write(ship.position().x);
+            write(ship.position().y);
+            write(ship.heading());
+            write(ship.velocity().x);
+            write(ship.velocity().y);
+            write(ship.angular_velocity());
+            write(ship.data().health);
+        }
+        for handle in self.bullets.iter() {
+            let body = bullet::body(self, *handle);
+            write(body.translation().x);
+            write(body.translation().y);
+            write(body.linvel().x);
+            write(body.linvel().y);
+        }
+        hash
+    }
+
     pub fn snapshot(&self, nonce: u32) -> Snapshot {
         let mut snapshot = Snapshot {
             nonce,
+            tick: self.tick(),
             time: self.time(),
             score_time: self.score_time(),
             status: self.status(),
             ships: vec![],
             bullets: vec![],
-            scenario_lines: self.scenario.as_ref().unwrap().lines(),
+            scenario_lines: self.scenario_lines(),
+            scenario_labels: self.scenario.as_ref().unwrap().labels(),
             debug_lines: self.events.debug_lines.clone(),
             debug_text: self.events.debug_text.clone(),
             drawn_text: self.events.drawn_text.clone(),
             particles: self.events.particles.clone(),
+            events: self.events.sim_events.clone(),
             errors: self.events.errors.clone(),
             cheats: self.cheats,
             timing: self.timing.clone(),
             world_size: self.world_size,
+            topology: self.topology,
+            rounds: None,
         };
 
         for &handle in self.ships.iter() {
@@ -347,6 +558,21 @@ impl Simulation {
             let class = ship.data().class;
             let health = ship.data().health;
             let fuel = ship.data().fuel;
+            let shield = ship
+                .data()
+                .shield
+                .as_ref()
+                .filter(|x| x.max > 0.0)
+                .map(|x| x.value / x.max);
+            let script_status = match ship.data().crash_message.as_ref() {
+                Some(msg) => ScriptStatus::Crashed(msg.clone()),
+                None if !self.team_controllers.contains_key(&team)
+                    || (self.practice && team != 0) =>
+                {
+                    ScriptStatus::Disabled
+                }
+                None => ScriptStatus::Ok,
+            };
             snapshot.ships.push(ShipSnapshot {
                 id,
                 position,
@@ -358,7 +584,9 @@ impl Simulation {
                 class,
                 health,
                 fuel,
+                shield,
                 active_abilities: ship.active_abilities(),
+                script_status,
             });
         }
 
@@ -376,31 +604,111 @@ impl Simulation {
         snapshot
     }
 
-    pub fn get_team_controller(&mut self, team: i32) -> Option<Rc<RefCell<Box<TeamController>>>> {
-        self.team_controllers.get_mut(&team).map(|x| x.clone())
+    pub fn get_team_controller(&mut self, team: i32) -> Option<&mut TeamController> {
+        self.team_controllers.get_mut(&team).map(|x| x.as_mut())
     }
 
-    pub fn update_environment(&mut self, team: i32, mut environment: BTreeMap<String, String>) {
+    pub fn update_environment(&mut self, team: i32, environment: BTreeMap<String, String>) {
+        self.environments.insert(team, environment);
+        self.send_environment(team);
+    }
+
+    fn environment(&self, team: i32) -> vm::Environment {
+        let mut environment = self.environments.get(&team).cloned().unwrap_or_default();
         environment.insert(
             "SCENARIO_NAME".to_string(),
             self.scenario.as_ref().unwrap().name(),
         );
         environment.insert("WORLD_SIZE".to_string(), format!("{}", self.world_size));
-        if let Some(team_ctrl) = self.get_team_controller(team) {
-            team_ctrl
-                .borrow_mut()
-                .update_environment(&environment)
-                .unwrap();
+        environment.insert("WORLD_TOPOLOGY".to_string(), format!("{:?}", self.topology));
+        for (name, marker) in self.markers.iter() {
+            environment.insert(
+                format!("MARKER_{}", name),
+                format!(
+                    "{},{},{}",
+                    marker.position.x, marker.position.y, marker.radius
+                ),
+            );
+        }
+        for (name, value) in self.vars.get(&team).into_iter().flatten() {
+            environment.insert(format!("VAR_{}", name), value.encode());
+        }
+        environment
+    }
+
+    fn send_environment(&mut self, team: i32) {
+        if !self.team_controllers.contains_key(&team) {
+            return;
+        }
+        // The environment has a fixed size in the guest. Markers are the
+        // only entries that can pile up, so the oldest ones are dropped
+        // until it fits.
+        let mut environment = self.environment(team);
+        while vm::encoded_environment_len(&environment) > oort_api::MAX_ENVIRONMENT_SIZE {
+            let Some(oldest) = self.marker_order.pop_front() else {
+                break;
+            };
+            log::warn!("Dropping marker {:?} to fit the script environment", oldest);
+            self.markers.remove(&oldest);
+            environment.remove(&format!("MARKER_{}", oldest));
+        }
+        if let Err(e) = self
+            .get_team_controller(team)
+            .unwrap()
+            .update_environment(&environment)
+        {
+            log::warn!("Failed to update environment for team {}: {:?}", team, e);
+            self.events.errors.push(e);
+        }
+    }
+
+    fn scenario_lines(&self) -> Vec<Line> {
+        let mut lines = self.scenario.as_ref().unwrap().lines();
+        for marker in self.markers.values() {
+            lines.extend(
+                debug::circle_lines(marker.position.into(), marker.radius, marker.color)
+                    .into_iter()
+                    .map(|line| Line {
+                        width: MARKER_LINE_WIDTH,
+                        ..line
+                    }),
+            );
         }
+        lines
+    }
+}
+
+/// Runs the VMs for each team. Teams don't share any state while running, so
+/// with the "parallel" feature they run on the rayon thread pool.
+///
+/// Ships on a team share one wasm instance, so they still run serially.
+/// Splitting a team across instances would make a ship's behavior depend on
+/// which ships it shares an instance with (for example, the guest's std
+/// HashMap keys come from a per-instance counter), so the browser and native
+/// builds could diverge on the same seed.
+#[cfg(feature = "parallel")]
+fn run_team_controllers(team_controllers: &mut BTreeMap<i32, Box<TeamController>>) {
+    use rayon::prelude::*;
+    team_controllers
+        .par_iter_mut()
+        .for_each(|(_, team_controller)| team_controller.run());
+}
+
+#[cfg(not(feature = "parallel"))]
+fn run_team_controllers(team_controllers: &mut BTreeMap<i32, Box<TeamController>>) {
+    for team_controller in team_controllers.values_mut() {
+        team_controller.run();
     }
 }
 
 pub struct CollisionEventHandler {
-    collision_event_sender: Sender<CollisionEvent>,
+    collision_event_sender: Sender<(CollisionEvent, /*impact_speed*/ f64)>,
 }
 
 impl CollisionEventHandler {
-    pub fn new(collision_event_sender: Sender<CollisionEvent>) -> CollisionEventHandler {
+    pub fn new(
+        collision_event_sender: Sender<(CollisionEvent, /*impact_speed*/ f64)>,
+    ) -> CollisionEventHandler {
         CollisionEventHandler {
             collision_event_sender,
         }
@@ -408,14 +716,33 @@ impl CollisionEventHandler {
 }
 
 impl EventHandler for CollisionEventHandler {
+    /// Called during collision detection, before the solver has pushed the
+    /// bodies apart, so this is where the closing speed can be measured.
     fn handle_collision_event(
         &self,
-        _bodies: &RigidBodySet,
-        _colliders: &ColliderSet,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
         event: CollisionEvent,
-        _contact_pair: Option<&rapier2d_f64::geometry::ContactPair>,
+        contact_pair: Option<&rapier2d_f64::geometry::ContactPair>,
     ) {
-        let _ = self.collision_event_sender.send(event);
+        let impact_speed = contact_pair
+            .and_then(|pair| {
+                let normal = pair.manifolds.first()?.data.normal;
+                let velocity = |handle| {
+                    colliders
+                        .get(handle)
+                        .and_then(|collider| collider.parent())
+                        .and_then(|body| bodies.get(body))
+                        .map_or(vector![0.0, 0.0], |body| *body.linvel())
+                };
+                Some(
+                    (velocity(pair.collider1) - velocity(pair.collider2))
+                        .dot(&normal)
+                        .abs(),
+                )
+            })
+            .unwrap_or(0.0);
+        let _ = self.collision_event_sender.send((event, impact_speed));
     }
 
     fn handle_contact_force_event(
@@ -438,9 +765,49 @@ pub struct Particle {
     pub lifetime: f32,
 }
 
+/// Something audible happened. Only used for presentation, never read by the
+/// simulation itself.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum SimEvent {
+    GunFired {
+        position: Vector2<f64>,
+    },
+    MissileLaunched {
+        position: Vector2<f64>,
+    },
+    BulletHit {
+        position: Vector2<f64>,
+    },
+    /// A ship was destroyed.
+    Explosion {
+        /// The ship's id, as in [`ShipSnapshot`].
+        id: u64,
+        position: Vector2<f64>,
+        velocity: Vector2<f64>,
+        mass: f64,
+        class: ShipClass,
+        cause: DestructionCause,
+    },
+    LaserFired {
+        start: Vector2<f64>,
+        end: Vector2<f64>,
+    },
+}
+
+/// Why a ship was destroyed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DestructionCause {
+    /// Bullets, lasers or a collision used up its health.
+    Damage,
+    /// It went off by itself: its warhead detonated, it ran out of time, or
+    /// it hit a wall.
+    Exploded,
+}
+
 pub struct SimEvents {
     pub errors: Vec<vm::Error>,
     pub particles: Vec<Particle>,
+    pub sim_events: Vec<SimEvent>,
     pub debug_lines: Vec<(u64, Vec<Line>)>,
     pub debug_text: BTreeMap<u64, String>,
     pub drawn_text: BTreeMap<Option<u64>, Vec<Text>>,
@@ -451,6 +818,7 @@ impl SimEvents {
         Self {
             errors: vec![],
             particles: vec![],
+            sim_events: vec![],
             debug_lines: Vec::new(),
             debug_text: BTreeMap::new(),
             drawn_text: BTreeMap::new(),
@@ -460,6 +828,7 @@ impl SimEvents {
     pub fn clear(&mut self) {
         self.errors.clear();
         self.particles.clear();
+        self.sim_events.clear();
         self.debug_lines.clear();
         self.debug_text.clear();
         self.drawn_text.clear();
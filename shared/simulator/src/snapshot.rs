@@ -1,6 +1,8 @@
+use crate::rounds::Tally;
 use crate::scenario::Status;
 use crate::ship::ShipClass;
-use crate::simulation::{Line, Particle};
+use crate::simulation::{Label, Line, Particle, SimEvent};
+use crate::topology::WorldTopology;
 use crate::vm;
 use nalgebra::{Point2, Vector2};
 use oort_api::{Ability, Text};
@@ -10,13 +12,17 @@ use std::collections::BTreeMap;
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Snapshot {
     pub nonce: u32,
+    pub tick: u32,
     pub time: f64,
     pub score_time: f64,
     pub status: Status,
     pub ships: Vec<ShipSnapshot>,
     pub bullets: Vec<BulletSnapshot>,
     pub scenario_lines: Vec<Line>,
+    #[serde(default)]
+    pub scenario_labels: Vec<Label>,
     pub particles: Vec<Particle>,
+    pub events: Vec<SimEvent>,
     pub errors: Vec<vm::Error>,
     pub cheats: bool,
     pub debug_lines: Vec<(u64, Vec<Line>)>,
@@ -24,6 +30,9 @@ pub struct Snapshot {
     pub drawn_text: BTreeMap<Option<u64>, Vec<Text>>,
     pub timing: Timing,
     pub world_size: f64,
+    #[serde(default)]
+    pub topology: WorldTopology,
+    pub rounds: Option<Tally>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -38,7 +47,19 @@ pub struct ShipSnapshot {
     pub class: ShipClass,
     pub health: f64,
     pub fuel: Option<f64>,
+    /// Fraction of the shield remaining, for ships that have one.
+    pub shield: Option<f64>,
     pub active_abilities: Vec<Ability>,
+    pub script_status: ScriptStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ScriptStatus {
+    Ok,
+    /// The script panicked or ran out of instructions.
+    Crashed(String),
+    /// No script controls the ship, or it's frozen in practice mode.
+    Disabled,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -0,0 +1,86 @@
+use crate::simulation::Simulation;
+use nalgebra::{vector, Point2, Vector2};
+use rapier2d_f64::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What happens at the edge of the world.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum WorldTopology {
+    /// Ships bounce off walls at the edge.
+    #[default]
+    Walled,
+    /// Anything leaving one side reappears on the opposite side.
+    Wrapping,
+    /// No walls. Ships can fly off forever.
+    Open,
+}
+
+/// Returns the shortest vector from `a` to `b`, which may cross the edge of
+/// the world when it wraps.
+pub fn world_offset(
+    a: Vector2<f64>,
+    b: Vector2<f64>,
+    world_size: f64,
+    topology: WorldTopology,
+) -> Vector2<f64> {
+    let d = b - a;
+    match topology {
+        WorldTopology::Wrapping => d.map(|x| x - world_size * (x / world_size).round()),
+        WorldTopology::Walled | WorldTopology::Open => d,
+    }
+}
+
+pub fn world_distance(
+    a: Vector2<f64>,
+    b: Vector2<f64>,
+    world_size: f64,
+    topology: WorldTopology,
+) -> f64 {
+    world_offset(a, b, world_size, topology).norm()
+}
+
+/// Moves a position that has left the world to the opposite side.
+pub fn wrap_position(p: Vector2<f64>, world_size: f64) -> Vector2<f64> {
+    let h = world_size / 2.0;
+    p.map(|x| (x + h).rem_euclid(world_size) - h)
+}
+
+/// Offsets at which an object within `margin` of the edge should also be
+/// drawn so that it looks continuous across the seam.
+pub fn seam_offsets(p: Point2<f64>, world_size: f64, margin: f64) -> Vec<Vector2<f64>> {
+    let h = world_size / 2.0;
+    let shift = |x: f64| {
+        if x > h - margin {
+            vec![0.0, -world_size]
+        } else if x < -h + margin {
+            vec![0.0, world_size]
+        } else {
+            vec![0.0]
+        }
+    };
+    let mut offsets = vec![];
+    for dx in shift(p.x) {
+        for dy in shift(p.y) {
+            if dx != 0.0 || dy != 0.0 {
+                offsets.push(vector![dx, dy]);
+            }
+        }
+    }
+    offsets
+}
+
+/// Teleports every body outside the world to the opposite side, keeping its
+/// velocity.
+pub(crate) fn tick(sim: &mut Simulation) {
+    if sim.topology() != WorldTopology::Wrapping {
+        return;
+    }
+    let world_size = sim.world_size();
+    let h = world_size / 2.0;
+    for (_, body) in sim.bodies.iter_mut() {
+        let p = *body.translation();
+        if p.x.abs() > h || p.y.abs() > h {
+            body.set_translation(wrap_position(p, world_size), true);
+        }
+    }
+}
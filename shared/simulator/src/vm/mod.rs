@@ -5,17 +5,14 @@ use crate::color;
 use crate::debug;
 use crate::rng::new_rng;
 use crate::ship::{ShipClass, ShipHandle};
-use crate::simulation::{Code, Particle, Simulation, PHYSICS_TICK_LENGTH};
+use crate::simulation::{Code, Particle, Simulation, PHYSICS_TICK_LENGTH, TARGET_MARKER};
 use nalgebra::{point, vector, Rotation2};
 use oort_api::{ActiveAbilities, Class, EcmMode, Line, SystemState, Text};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::cell::{Ref, RefCell, RefMut};
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::f64::consts::TAU;
-use std::ops::{Deref, DerefMut};
-use std::rc::Rc;
 use wasmer::{imports, Instance, MemoryView, Module, Store, WasmPtr};
 
 pub type Vec2 = nalgebra::Vector2<f64>;
@@ -24,6 +21,8 @@ pub type Environment = BTreeMap<String, String>;
 const GAS_PER_TICK: i32 = 1_000_000;
 const MAX_DEBUG_LINES: u32 = 1024;
 const MAX_DRAWN_TEXT: u32 = 128;
+/// Size of SYSTEM_STATE in guests that predate SYSTEM_STATE_SIZE.
+const LEGACY_SYSTEM_STATE_SIZE: usize = 128;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Error {
@@ -55,14 +54,38 @@ pub fn new_team_controller(code: &Code) -> Result<Box<TeamController>, Error> {
             Ok(code) => new_team_controller(&code),
             Err(e) => Err(Error { msg: e }),
         },
-        _ => unreachable!(),
+        Code::Rust(_) => Err(Error {
+            msg: "Rust code must be compiled to wasm before it is uploaded".to_string(),
+        }),
+        Code::None => Err(Error {
+            msg: "No code to upload".to_string(),
+        }),
     }
 }
 
+/// Output of running a ship's tick, buffered until it is applied to the simulation.
+struct ShipTickResult {
+    handle: ShipHandle,
+    result: Result<(), Error>,
+    debug_text: Option<String>,
+    debug_lines: Option<Vec<debug::Line>>,
+    drawn_text: Option<Vec<Text>>,
+}
+
+/// Runs the code for all ships on a team.
+///
+/// A tick is split into three phases so that different teams can run their
+/// VMs in parallel:
+///
+/// 1. `prepare` snapshots each ship's inputs from the simulation.
+/// 2. `run` executes the VM. It does not touch the simulation.
+/// 3. `apply` writes the buffered outputs back to the simulation in handle order.
 pub struct TeamController {
     vm: WasmVm,
     states: HashMap<ShipHandle, LocalSystemState>,
     next_id: u32,
+    pending: Vec<ShipHandle>,
+    results: Vec<ShipTickResult>,
 }
 
 impl TeamController {
@@ -71,6 +94,8 @@ impl TeamController {
             vm: WasmVm::create(code)?,
             states: HashMap::new(),
             next_id: 1,
+            pending: Vec::new(),
+            results: Vec::new(),
         }))
     }
 
@@ -83,6 +108,11 @@ impl TeamController {
         );
         state.set(SystemState::Id, self.next_id as f64);
         self.next_id += 1;
+        state.set(SystemState::Team, sim.ship(handle).data().team as f64);
+        state.set(
+            SystemState::RadarCrossSection,
+            sim.ship(handle).radar_cross_section(),
+        );
         if let Some(radar) = sim.ship(handle).data().radar.as_ref() {
             state.set(SystemState::RadarHeading, radar.heading);
             state.set(SystemState::RadarWidth, radar.width);
@@ -99,81 +129,111 @@ impl TeamController {
         self.states.remove(&handle);
         let (index, _) = handle.0.into_raw_parts();
         let index = index as i32;
-        self.vm
-            .reset_gas
-            .call(&mut self.vm.store_mut(), &[GAS_PER_TICK.into()])
+        let vm = &mut self.vm;
+        vm.reset_gas
+            .call(&mut vm.store, &[GAS_PER_TICK.into()])
             .unwrap();
-        if let Err(e) = translate_runtime_error(
-            self.vm
-                .delete_ship
-                .call(self.vm.store_mut().deref_mut(), &[index.into()]),
-        ) {
+        if let Err(e) = translate_runtime_error(vm.delete_ship.call(&mut vm.store, &[index.into()]))
+        {
             log::warn!("Failed to delete ship: {:?}", e);
         }
     }
 
-    pub fn tick(&mut self, sim: &mut Simulation) {
+    /// Generates the system state for each ship that will run this tick.
+    pub fn prepare(&mut self, sim: &mut Simulation) {
         let mut handles: Vec<_> = self.states.keys().cloned().collect();
         handles.sort_by_key(|x| x.0);
 
+        let (ally_count, enemy_count) = match handles.first() {
+            Some(&handle) => count_ships(sim, sim.ship(handle).data().team),
+            None => (0, 0),
+        };
+
+        self.pending.clear();
         for handle in handles {
-            if let Err(e) = self.tick_ship(sim, handle) {
+            if let Some(msg) = sim.ship(handle).data().crash_message.as_ref() {
+                sim.emit_debug_text(handle, format!("Crashed: {}", msg.clone()));
+                emit_crash_particles(sim, handle);
+                continue;
+            }
+            let state = self.states.get_mut(&handle).unwrap();
+            generate_system_state(sim, handle, state);
+            state.set(SystemState::AllyCount, ally_count as f64);
+            state.set(SystemState::EnemyCount, enemy_count as f64);
+            self.pending.push(handle);
+        }
+    }
+
+    /// Runs the VM for each prepared ship, buffering the results.
+    pub fn run(&mut self) {
+        self.results.clear();
+        for handle in std::mem::take(&mut self.pending) {
+            let result = self.run_ship(handle);
+            self.results.push(result);
+        }
+    }
+
+    /// Applies the buffered results to the simulation.
+    pub fn apply(&mut self, sim: &mut Simulation) {
+        for result in std::mem::take(&mut self.results) {
+            let handle = result.handle;
+            if let Err(e) = result.result {
                 log::warn!("{}", e.msg);
                 sim.emit_debug_text(handle, format!("Crashed: {}", e.msg.clone()));
                 sim.ship_mut(handle).data_mut().crash_message = Some(e.msg);
+                continue;
+            }
+
+            apply_system_state(sim, handle, self.states.get_mut(&handle).unwrap());
+
+            if let Some(s) = result.debug_text {
+                sim.emit_debug_text(handle, s);
+            }
+            if let Some(lines) = result.debug_lines {
+                sim.emit_debug_lines(handle, lines);
+            }
+            if let Some(texts) = result.drawn_text {
+                sim.emit_drawn_text(Some(handle), &texts);
             }
         }
     }
 
-    fn tick_ship(&mut self, sim: &mut Simulation, handle: ShipHandle) -> Result<(), Error> {
-        if let Some(msg) = sim.ship(handle).data().crash_message.as_ref() {
-            sim.emit_debug_text(handle, format!("Crashed: {}", msg.clone()));
-            let mut rng = new_rng(sim.tick());
-            if rng.gen_range(0.0..1.0) < 0.2 {
-                let color = vector![0.5, 0.5, 0.9, rng.gen_range(0.5..1.0)];
-                let rot = Rotation2::new(rng.gen_range(0.0..TAU));
-                let speed = 300.0 * rng.gen_range(0.0..1.0);
-                let p = sim.ship(handle).position().vector;
-                let v =
-                    sim.ship(handle).body().linvel() + rot.transform_vector(&vector![speed, 0.0]);
-                let offset = v * rng.gen_range(0.0..PHYSICS_TICK_LENGTH);
-                sim.events.particles.push(Particle {
-                    position: p + offset,
-                    velocity: v,
-                    color,
-                    lifetime: 1.0,
-                });
-            }
-            return Ok(());
+    fn run_ship(&mut self, handle: ShipHandle) -> ShipTickResult {
+        let mut result = ShipTickResult {
+            handle,
+            result: Ok(()),
+            debug_text: None,
+            debug_lines: None,
+            drawn_text: None,
+        };
+        if let Err(e) = self.run_ship_inner(&mut result) {
+            result.result = Err(e);
         }
+        result
+    }
 
+    fn run_ship_inner(&mut self, result: &mut ShipTickResult) -> Result<(), Error> {
         let vm = &mut self.vm;
-        let state = self.states.get_mut(&handle).unwrap();
+        let state = self.states.get_mut(&result.handle).unwrap();
 
         {
-            translate_runtime_error(
-                vm.reset_gas
-                    .call(vm.store_mut().deref_mut(), &[GAS_PER_TICK.into()]),
-            )?;
-
-            generate_system_state(sim, handle, state);
+            translate_runtime_error(vm.reset_gas.call(&mut vm.store, &[GAS_PER_TICK.into()]))?;
 
-            let store = vm.store();
-            let memory_view = vm.memory.view(store.deref());
+            let memory_view = vm.memory.view(&vm.store);
             let slice = vm
                 .system_state_ptr
-                .slice(&memory_view, SystemState::Size as u32)
+                .slice(&memory_view, vm.system_state_len as u32)
+                .expect("system state write");
+            slice
+                .write_slice(&state.state[..vm.system_state_len])
                 .expect("system state write");
-            slice.write_slice(&state.state).expect("system state write");
         }
 
-        let (index, _) = handle.0.into_raw_parts();
+        let (index, _) = result.handle.0.into_raw_parts();
         let index = index as i32;
-        let result = vm
-            .tick_ship
-            .call(vm.store_mut().deref_mut(), &[index.into()]);
-        if let Err(e) = result {
-            if let Ok(ret) = vm.get_gas.call(vm.store_mut().deref_mut(), &[]) {
+        let call_result = vm.tick_ship.call(&mut vm.store, &[index.into()]);
+        if let Err(e) = call_result {
+            if let Ok(ret) = vm.get_gas.call(&mut vm.store, &[]) {
                 if !ret.is_empty() {
                     let gas: i32 = ret[0].i32().unwrap();
                     if gas <= 0 {
@@ -185,8 +245,7 @@ impl TeamController {
             }
 
             {
-                let store = vm.store();
-                let memory_view = vm.memory.view(store.deref());
+                let memory_view = vm.memory.view(&vm.store);
                 if let Some(vec) = WasmVm::read_vec(
                     &memory_view,
                     vm.panic_buffer_ptr.offset(),
@@ -208,23 +267,19 @@ impl TeamController {
         }
 
         {
-            let store = vm.store();
-            let memory_view = vm.memory.view(store.deref());
+            let memory_view = vm.memory.view(&vm.store);
             let slice = vm
                 .system_state_ptr
-                .slice(&memory_view, SystemState::Size as u32)
+                .slice(&memory_view, vm.system_state_len as u32)
                 .expect("system state read");
             slice
-                .read_slice(&mut state.state)
+                .read_slice(&mut state.state[..vm.system_state_len])
                 .expect("system state read");
-            apply_system_state(sim, handle, state);
 
             if state.get(SystemState::DebugTextLength) > 0.0 {
                 let offset = state.get(SystemState::DebugTextPointer) as u32;
                 let length = state.get(SystemState::DebugTextLength) as u32;
-                if let Some(s) = WasmVm::read_string(&memory_view, offset, length) {
-                    sim.emit_debug_text(handle, s);
-                }
+                result.debug_text = WasmVm::read_string(&memory_view, offset, length);
             }
 
             if state.get(SystemState::DebugLinesLength) > 0.0 {
@@ -233,14 +288,14 @@ impl TeamController {
                 if length <= MAX_DEBUG_LINES {
                     if let Some(lines) = WasmVm::read_vec::<Line>(&memory_view, offset, length) {
                         if validate_lines(&lines) {
-                            sim.emit_debug_lines(
-                                handle,
+                            result.debug_lines = Some(
                                 lines
                                     .iter()
                                     .map(|v| crate::debug::Line {
                                         a: point![v.x0, v.y0],
                                         b: point![v.x1, v.y1],
                                         color: color::from_u24(v.color),
+                                        ..Default::default()
                                     })
                                     .collect::<Vec<debug::Line>>(),
                             );
@@ -255,7 +310,7 @@ impl TeamController {
                 if length <= MAX_DRAWN_TEXT {
                     if let Some(texts) = WasmVm::read_vec::<Text>(&memory_view, offset, length) {
                         if validate_texts(&texts) {
-                            sim.emit_drawn_text(Some(handle), &texts);
+                            result.drawn_text = Some(texts);
                         }
                     }
                 }
@@ -270,11 +325,13 @@ impl TeamController {
     }
 }
 
-#[derive(Clone)]
 pub struct WasmVm {
-    store: Rc<RefCell<wasmer::Store>>,
+    store: wasmer::Store,
     memory: wasmer::Memory,
     system_state_ptr: WasmPtr<u64>,
+    // Number of system state slots shared with the guest. Older guests have
+    // a smaller array than SystemState::Size.
+    system_state_len: usize,
     environment_ptr: WasmPtr<u8>,
     panic_buffer_ptr: WasmPtr<u8>,
     tick_ship: wasmer::Function,
@@ -310,6 +367,15 @@ impl WasmVm {
                 .i32()
                 .unwrap();
         let system_state_ptr: WasmPtr<u64> = WasmPtr::new(system_state_offset as u32);
+        let guest_system_state_size = match instance.exports.get_global("SYSTEM_STATE_SIZE") {
+            Ok(global) => {
+                let offset = global.get(&mut store).i32().unwrap();
+                let ptr: WasmPtr<u32> = WasmPtr::new(offset as u32);
+                translate_error(ptr.read(&memory.view(&store)))? as usize
+            }
+            Err(_) => LEGACY_SYSTEM_STATE_SIZE,
+        };
+        let system_state_len = guest_system_state_size.min(SystemState::Size as usize);
         let environment_offset: i32 = translate_error(instance.exports.get_global("ENVIRONMENT"))?
             .get(&mut store)
             .i32()
@@ -334,9 +400,10 @@ impl WasmVm {
         translate_runtime_error(initialize.call(&mut store, &[]))?;
 
         Ok(WasmVm {
-            store: Rc::new(RefCell::new(store)),
+            store,
             memory,
             system_state_ptr,
+            system_state_len,
             environment_ptr,
             panic_buffer_ptr,
             tick_ship,
@@ -346,14 +413,6 @@ impl WasmVm {
         })
     }
 
-    fn store(&self) -> Ref<'_, Store> {
-        self.store.borrow()
-    }
-
-    fn store_mut(&self) -> RefMut<'_, Store> {
-        self.store.borrow_mut()
-    }
-
     fn read_string(memory_view: &MemoryView, offset: u32, length: u32) -> Option<String> {
         let ptr: WasmPtr<u8> = WasmPtr::new(offset);
         let mut bytes: Vec<u8> = Vec::new();
@@ -377,19 +436,18 @@ impl WasmVm {
         Some(src_slice.to_vec())
     }
 
-    fn update_environment(&self, environment: &Environment) -> Result<(), Error> {
-        let environment_string = environment
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<String>>()
-            .join("\n");
+    fn update_environment(&mut self, environment: &Environment) -> Result<(), Error> {
+        let mut environment_string = encode_environment(environment);
+        // Terminates the string in case a longer environment was written before.
+        if environment_string.len() < oort_api::MAX_ENVIRONMENT_SIZE {
+            environment_string.push('\0');
+        }
         if environment_string.len() > oort_api::MAX_ENVIRONMENT_SIZE {
             return Err(Error {
                 msg: "environment too large".to_string(),
             });
         }
-        let store = self.store_mut();
-        let view = self.memory.view(&store);
+        let view = self.memory.view(&self.store);
         let slice = self
             .environment_ptr
             .slice(&view, environment_string.bytes().len() as u32)
@@ -403,6 +461,38 @@ impl WasmVm {
     }
 }
 
+fn encode_environment(environment: &Environment) -> String {
+    environment
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Length in bytes of the environment as written to the guest, not counting
+/// the terminator.
+pub fn encoded_environment_len(environment: &Environment) -> usize {
+    encode_environment(environment).len()
+}
+
+fn emit_crash_particles(sim: &mut Simulation, handle: ShipHandle) {
+    let mut rng = new_rng(sim.tick());
+    if rng.gen_range(0.0..1.0) < 0.2 {
+        let color = vector![0.5, 0.5, 0.9, rng.gen_range(0.5..1.0)];
+        let rot = Rotation2::new(rng.gen_range(0.0..TAU));
+        let speed = 300.0 * rng.gen_range(0.0..1.0);
+        let p = sim.ship(handle).position().vector;
+        let v = sim.ship(handle).body().linvel() + rot.transform_vector(&vector![speed, 0.0]);
+        let offset = v * rng.gen_range(0.0..PHYSICS_TICK_LENGTH);
+        sim.events.particles.push(Particle {
+            position: p + offset,
+            velocity: v,
+            color,
+            lifetime: 1.0,
+        });
+    }
+}
+
 struct LocalSystemState {
     pub state: [u64; SystemState::Size as usize],
 }
@@ -436,6 +526,25 @@ impl LocalSystemState {
     }
 }
 
+/// Returns the number of (ally, enemy) ships for the given team, excluding asteroids.
+fn count_ships(sim: &Simulation, team: i32) -> (usize, usize) {
+    let mut ally_count = 0;
+    let mut enemy_count = 0;
+    for &handle in sim.ships.iter() {
+        let ship = sim.ship(handle);
+        let data = ship.data();
+        if let ShipClass::Asteroid { .. } = data.class {
+            continue;
+        }
+        if data.team == team {
+            ally_count += 1;
+        } else {
+            enemy_count += 1;
+        }
+    }
+    (ally_count, enemy_count)
+}
+
 fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut LocalSystemState) {
     state.set(
         SystemState::Class,
@@ -456,14 +565,31 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
         sim.ship(handle).angular_velocity(),
     );
 
+    for (i, auto_target) in [
+        SystemState::AutoTarget0,
+        SystemState::AutoTarget1,
+        SystemState::AutoTarget2,
+        SystemState::AutoTarget3,
+    ]
+    .iter()
+    .enumerate()
+    {
+        let class = sim.ship(handle).auto_target(i);
+        state.set(*auto_target, class.map_or(0.0, |c| c as u32 as f64 + 1.0));
+    }
+
     if let Some(radar) = sim.ship_mut(handle).data_mut().radar.as_mut() {
         state.set(SystemState::RadarHeading, radar.get_heading());
         state.set(SystemState::RadarWidth, radar.get_width());
         state.set(SystemState::RadarMinDistance, radar.get_min_distance());
         state.set(SystemState::RadarMaxDistance, radar.get_max_distance());
 
-        if let Some(contact) = radar.scan() {
-            state.set(SystemState::RadarContactFound, 1.0);
+        state.set(
+            SystemState::RadarContactFound,
+            if radar.scan().is_some() { 1.0 } else { 0.0 },
+        );
+        // The contact slots keep the last contact until a new one is seen.
+        if let Some((contact, age)) = radar.last_contact() {
             state.set(SystemState::RadarContactPositionX, contact.position.x);
             state.set(SystemState::RadarContactPositionY, contact.position.y);
             state.set(SystemState::RadarContactVelocityX, contact.velocity.x);
@@ -474,11 +600,13 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
             );
             state.set(SystemState::RadarContactRssi, contact.rssi);
             state.set(SystemState::RadarContactSnr, contact.snr);
+            state.set(SystemState::RadarContactAge, age as f64);
         } else {
-            state.set(SystemState::RadarContactFound, 0.0);
+            state.set(SystemState::RadarContactAge, -1.0);
         }
     } else if let Some(target) = sim.ship(handle).data().target.as_ref() {
         state.set(SystemState::RadarContactFound, 1.0);
+        state.set(SystemState::RadarContactAge, 0.0);
         state.set(SystemState::RadarContactPositionX, target.position.x);
         state.set(SystemState::RadarContactPositionY, target.position.y);
         state.set(SystemState::RadarContactVelocityX, target.velocity.x);
@@ -487,6 +615,19 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
             SystemState::RadarContactClass,
             oort_api::Class::Fighter as u32 as f64,
         );
+    } else if let Some(marker) = sim.marker(TARGET_MARKER) {
+        state.set(SystemState::RadarContactFound, 1.0);
+        state.set(SystemState::RadarContactAge, 0.0);
+        state.set(SystemState::RadarContactPositionX, marker.position.x);
+        state.set(SystemState::RadarContactPositionY, marker.position.y);
+        state.set(SystemState::RadarContactVelocityX, 0.0);
+        state.set(SystemState::RadarContactVelocityY, 0.0);
+        state.set(
+            SystemState::RadarContactClass,
+            oort_api::Class::Fighter as u32 as f64,
+        );
+    } else {
+        state.set(SystemState::RadarContactAge, -1.0);
     }
 
     {
@@ -510,6 +651,7 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
         );
         state.set(SystemState::Health, data.health);
         state.set(SystemState::Fuel, data.fuel.unwrap_or(f64::INFINITY));
+        state.set(SystemState::Shield, ship.shield());
     }
 
     for (i, radio) in sim.ship(handle).data().radios.iter().enumerate() {
@@ -528,6 +670,7 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
     }
 
     state.set(SystemState::CurrentTick, sim.tick() as f64);
+    state.set(SystemState::ScriptTickLength, sim.script_tick_length());
 
     for (i, idx) in [
         SystemState::ReloadTicks0,
@@ -540,6 +683,60 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
     {
         state.set(*idx, sim.ship(handle).get_reload_ticks(i) as f64)
     }
+
+    for (i, idx) in [
+        SystemState::MuzzleSpeed0,
+        SystemState::MuzzleSpeed1,
+        SystemState::MuzzleSpeed2,
+        SystemState::MuzzleSpeed3,
+    ]
+    .iter()
+    .enumerate()
+    {
+        state.set(*idx, sim.ship(handle).get_muzzle_speed(i))
+    }
+
+    state.set(
+        SystemState::RadarCrossSection,
+        sim.ship(handle).radar_cross_section(),
+    );
+
+    for (i, idx) in [
+        SystemState::Inaccuracy0,
+        SystemState::Inaccuracy1,
+        SystemState::Inaccuracy2,
+        SystemState::Inaccuracy3,
+    ]
+    .iter()
+    .enumerate()
+    {
+        state.set(*idx, sim.ship(handle).get_inaccuracy(i))
+    }
+
+    for (i, idx) in [
+        SystemState::Spread0,
+        SystemState::Spread1,
+        SystemState::Spread2,
+        SystemState::Spread3,
+    ]
+    .iter()
+    .enumerate()
+    {
+        state.set(*idx, sim.ship(handle).get_spread(i))
+    }
+
+    for (i, idx) in [
+        SystemState::Ammo0,
+        SystemState::Ammo1,
+        SystemState::Ammo2,
+        SystemState::Ammo3,
+    ]
+    .iter()
+    .enumerate()
+    {
+        let ammo = sim.ship(handle).get_ammo(i);
+        state.set(*idx, ammo.map_or(f64::INFINITY, |ammo| ammo.max(0) as f64))
+    }
 }
 
 fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut LocalSystemState) {
@@ -553,6 +750,9 @@ fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut Loca
     sim.ship_mut(handle).torque(state.get(SystemState::Torque));
     state.set(SystemState::Torque, 0.0);
 
+    sim.ship_mut(handle)
+        .set_radar_cross_section(state.get(SystemState::RadarCrossSection));
+
     for (i, (aim, fire)) in [
         (SystemState::Aim0, SystemState::Fire0),
         (SystemState::Aim1, SystemState::Fire1),
@@ -569,6 +769,20 @@ fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut Loca
         }
     }
 
+    for (i, auto_target) in [
+        SystemState::AutoTarget0,
+        SystemState::AutoTarget1,
+        SystemState::AutoTarget2,
+        SystemState::AutoTarget3,
+    ]
+    .iter()
+    .enumerate()
+    {
+        let value = state.get(*auto_target);
+        let class = (value > 0.0).then(|| Class::from_f64(value - 1.0));
+        sim.ship_mut(handle).set_auto_target(i, class);
+    }
+
     if let Some(radar) = sim.ship_mut(handle).data_mut().radar.as_mut() {
         radar.set_heading(state.get(SystemState::RadarHeading));
         radar.set_width(state.get(SystemState::RadarWidth));
@@ -590,6 +804,11 @@ fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut Loca
         }
     }
 
+    if state.get(SystemState::ActivateShieldBoost) > 0.0 {
+        sim.ship_mut(handle).activate_shield_boost();
+        state.set(SystemState::ActivateShieldBoost, 0.0);
+    }
+
     if state.get(SystemState::Explode) > 0.0 {
         sim.ship_mut(handle).explode();
         state.set(SystemState::Explode, 0.0);
@@ -616,7 +835,7 @@ fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut Loca
     }
 }
 
-fn translate_class(class: ShipClass) -> Class {
+pub(crate) fn translate_class(class: ShipClass) -> Class {
     match class {
         ShipClass::Fighter => Class::Fighter,
         ShipClass::Frigate => Class::Frigate,
@@ -54,6 +54,132 @@ fn test_world_size() {
     );
 }
 
+#[test]
+fn test_scenario_var() {
+    use simulation::ScenarioVar;
+    let mut sim =
+        simulation::Simulation::new("test", 0, &[Code::Builtin("test".to_string()), Code::None]);
+    let mut env = BTreeMap::new();
+    env.insert("TESTCASE".to_string(), "scenario_var".to_string());
+    sim.update_environment(0, env);
+    sim.write_var(0, "position", ScenarioVar::Vec2(vector![1.5, -2.0]));
+    sim.write_var(0, "speed", ScenarioVar::F64(250.0));
+    sim.write_var(0, "enabled", ScenarioVar::Bool(true));
+    sim.write_var(0, "target", ScenarioVar::Vec2(vector![10.0, 20.0]));
+    // Other teams don't see them.
+    sim.write_var(1, "speed", ScenarioVar::F64(1.0));
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    sim.step();
+    let output = sim
+        .events()
+        .debug_text
+        .get(&ship0.into())
+        .expect("Missing debug text");
+    assert!(
+        output.contains("Vars: Some((1.5, -2.0)) Some(250.0) Some(true) false Target: 10 20"),
+        "output: {:?}",
+        output
+    );
+}
+
+#[test]
+fn test_current_tick() {
+    let start = || {
+        let mut sim = simulation::Simulation::new(
+            "test",
+            0,
+            &[Code::Builtin("test".to_string()), Code::None],
+        );
+        let mut env = BTreeMap::new();
+        env.insert("TESTCASE".to_string(), "current_tick".to_string());
+        sim.update_environment(0, env);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(0),
+        );
+        (sim, ship0)
+    };
+    // Returns (current_tick(), current_time() / TICK_LENGTH).
+    let read = |sim: &simulation::Simulation, ship0: ShipHandle| -> (u32, f64) {
+        let output = sim
+            .events()
+            .debug_text
+            .get(&ship0.into())
+            .expect("Missing debug text");
+        let words: Vec<&str> = output.split_whitespace().collect();
+        assert_eq!(words[0], "Tick:", "output: {:?}", output);
+        (words[1].parse().unwrap(), words[3].parse().unwrap())
+    };
+
+    let (mut sim, ship0) = start();
+    sim.step();
+    let (first_tick, first_time) = read(&sim, ship0);
+    assert!((first_time - first_tick as f64).abs() < 1e-6);
+    for _ in 0..10 {
+        sim.step();
+    }
+    let (tick, time) = read(&sim, ship0);
+    assert_eq!(tick, first_tick + 10);
+    assert!((time - tick as f64).abs() < 1e-6);
+
+    // A restarted scenario starts counting again.
+    let (mut sim, ship0) = start();
+    sim.step();
+    assert_eq!(read(&sim, ship0).0, first_tick);
+}
+
+#[test]
+fn test_nearest_wall() {
+    let run = |scenario_name: &str, position| {
+        let mut sim = simulation::Simulation::new(
+            scenario_name,
+            0,
+            &[Code::Builtin("test".to_string()), Code::None],
+        );
+        let mut env = BTreeMap::new();
+        env.insert("TESTCASE".to_string(), "nearest_wall".to_string());
+        sim.update_environment(0, env);
+        let ship0 = ship::create(&mut sim, position, vector![0.0, 0.0], 0.0, fighter(0));
+        sim.step();
+        sim.events()
+            .debug_text
+            .get(&ship0.into())
+            .expect("Missing debug text")
+            .clone()
+    };
+
+    // The test scenario is walled and 200 km across.
+    let output = run("test", vector![99000.0, 500.0]);
+    assert!(
+        output.contains("Nearest wall: Some((1000.0, 0.0)) Distance: 1000"),
+        "output: {:?}",
+        output
+    );
+    let output = run("test", vector![-500.0, -99500.0]);
+    assert!(
+        output.contains("Nearest wall: Some((0.0, -500.0)) Distance: 500"),
+        "output: {:?}",
+        output
+    );
+
+    // Ships in a wrapping world never hit a wall.
+    let output = run("wrapping_test", vector![4900.0, 0.0]);
+    assert!(
+        output.contains("Nearest wall: None Distance: inf"),
+        "output: {:?}",
+        output
+    );
+}
+
 #[test]
 fn test_id() {
     let mut sim = simulation::Simulation::new(
@@ -99,3 +225,242 @@ fn test_id() {
     check(ship_handles[1], 2);
     check(ship_handles[2], 1);
 }
+
+#[test]
+fn test_team() {
+    let mut sim = simulation::Simulation::new(
+        "test",
+        0,
+        &[
+            Code::Builtin("test".to_string()),
+            Code::Builtin("test".to_string()),
+        ],
+    );
+    let mut env = BTreeMap::new();
+    env.insert("TESTCASE".to_string(), "team".to_string());
+    sim.update_environment(0, env.clone());
+    sim.update_environment(1, env);
+    let ship_handles = [0, 0, 1]
+        .iter()
+        .copied()
+        .map(|team| {
+            ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                fighter(team),
+            )
+        })
+        .collect::<Vec<_>>();
+    sim.step();
+
+    let check = |ship_handle: ShipHandle, expected: &str| {
+        let output = sim
+            .events()
+            .debug_text
+            .get(&ship_handle.into())
+            .expect("Missing debug text");
+        assert!(output.contains(expected), "output: {:?}", output);
+    };
+    check(ship_handles[0], "Team: 0 Allies: 2 Enemies: 1");
+    check(ship_handles[1], "Team: 0 Allies: 2 Enemies: 1");
+    check(ship_handles[2], "Team: 1 Allies: 1 Enemies: 2");
+}
+
+#[test]
+fn test_accelerate_local() {
+    let mut sim =
+        simulation::Simulation::new("test", 0, &[Code::Builtin("test".to_string()), Code::None]);
+    let mut env = BTreeMap::new();
+    env.insert("TESTCASE".to_string(), "accelerate_local".to_string());
+    sim.update_environment(0, env);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        std::f64::consts::FRAC_PI_2,
+        fighter(0),
+    );
+    sim.step();
+    sim.step();
+    let v = sim.ship(ship0).velocity();
+    assert!(v.y > 0.0, "velocity: {:?}", v);
+    assert!(v.x.abs() < 1e-6, "velocity: {:?}", v);
+}
+
+#[test]
+fn test_accelerate_world() {
+    let mut sim =
+        simulation::Simulation::new("test", 0, &[Code::Builtin("test".to_string()), Code::None]);
+    let mut env = BTreeMap::new();
+    env.insert("TESTCASE".to_string(), "accelerate_world".to_string());
+    sim.update_environment(0, env);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        std::f64::consts::FRAC_PI_2,
+        fighter(0),
+    );
+    sim.step();
+    sim.step();
+    let v = sim.ship(ship0).velocity();
+    assert!(v.y > 0.0, "velocity: {:?}", v);
+    assert!(v.x.abs() < 1e-6, "velocity: {:?}", v);
+}
+
+#[test]
+fn test_api_v1_compatibility() {
+    let mut sim = simulation::Simulation::new(
+        "test",
+        0,
+        &[Code::Builtin("api_v1_test".to_string()), Code::None],
+    );
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    for _ in 0..10 {
+        sim.step();
+    }
+    assert!(sim.events().errors.is_empty());
+    assert!(!sim.bullets.is_empty());
+    // The warning sticks around after the tick it was first printed.
+    let output = sim
+        .events()
+        .debug_text
+        .get(&ship0.into())
+        .expect("Missing debug text");
+    assert!(
+        output.contains("aim_gun is deprecated, use aim instead"),
+        "output: {:?}",
+        output
+    );
+}
+
+#[test]
+fn test_normalize_angle() {
+    use oort_api::prelude::{normalize_angle, PI, TAU};
+    assert_eq!(normalize_angle(0.0), 0.0);
+    assert_eq!(normalize_angle(1.0), 1.0);
+    assert_eq!(normalize_angle(-1.0), -1.0);
+    assert_eq!(normalize_angle(PI), PI);
+    assert_eq!(normalize_angle(-PI), PI);
+    assert!((normalize_angle(TAU + 1.0) - 1.0).abs() < 1e-9);
+    assert!((normalize_angle(-TAU - 1.0) + 1.0).abs() < 1e-9);
+    assert!((normalize_angle(3.0 * PI / 2.0) + PI / 2.0).abs() < 1e-9);
+    assert!((normalize_angle(100.0 * TAU + 0.5) - 0.5).abs() < 1e-9);
+    for i in -1000..1000 {
+        let a = normalize_angle(i as f64 * 0.01);
+        assert!(a > -PI && a <= PI, "{} -> {}", i as f64 * 0.01, a);
+    }
+}
+
+#[test]
+fn test_clamp_and_lerp() {
+    use oort_api::prelude::{clamp, lerp};
+    assert_eq!(clamp(5.0, 0.0, 10.0), 5.0);
+    assert_eq!(clamp(-5.0, 0.0, 10.0), 0.0);
+    assert_eq!(clamp(15.0, 0.0, 10.0), 10.0);
+    assert_eq!(clamp(5.0, 10.0, 0.0), 0.0);
+
+    assert_eq!(lerp(2.0, 4.0, 0.0), 2.0);
+    assert_eq!(lerp(2.0, 4.0, 1.0), 4.0);
+    assert_eq!(lerp(2.0, 4.0, 0.5), 3.0);
+    assert_eq!(lerp(2.0, 4.0, 2.0), 6.0);
+}
+
+#[test]
+fn test_cpa() {
+    use oort_api::prelude::{cpa_distance, cpa_time, vec2};
+    // Passing by.
+    assert_eq!(cpa_time(vec2(-100.0, 10.0), vec2(10.0, 0.0)), 10.0);
+    assert_eq!(cpa_distance(vec2(-100.0, 10.0), vec2(10.0, 0.0)), 10.0);
+    // Head on.
+    assert_eq!(cpa_time(vec2(100.0, 0.0), vec2(-20.0, 0.0)), 5.0);
+    assert_eq!(cpa_distance(vec2(100.0, 0.0), vec2(-20.0, 0.0)), 0.0);
+    // Moving apart.
+    assert_eq!(cpa_time(vec2(100.0, 0.0), vec2(10.0, 5.0)), 0.0);
+    assert_eq!(cpa_distance(vec2(100.0, 0.0), vec2(10.0, 5.0)), 100.0);
+    // No relative velocity.
+    assert_eq!(cpa_time(vec2(30.0, 40.0), vec2(0.0, 0.0)), 0.0);
+    assert_eq!(cpa_distance(vec2(30.0, 40.0), vec2(0.0, 0.0)), 50.0);
+    // Same position.
+    assert_eq!(cpa_time(vec2(0.0, 0.0), vec2(10.0, 0.0)), 0.0);
+    assert_eq!(cpa_distance(vec2(0.0, 0.0), vec2(0.0, 0.0)), 0.0);
+}
+
+#[test]
+fn test_intercept_time() {
+    use oort_api::prelude::{intercept_time, vec2, Vec2, Vec2Extras};
+
+    fn check(rel_pos: Vec2, rel_vel: Vec2, bullet_speed: f64, expected: Option<f64>) {
+        let t = intercept_time(rel_pos, rel_vel, bullet_speed);
+        match (t, expected) {
+            (Some(t), Some(expected)) => {
+                assert!((t - expected).abs() < 1e-9, "{t} != {expected}");
+                // The bullet and target end up in the same place.
+                let miss = (rel_pos + rel_vel * t).length() - bullet_speed * t;
+                assert!(miss.abs() < 1e-6, "missed by {miss}");
+            }
+            _ => assert_eq!(t, expected),
+        }
+    }
+
+    // Stationary target.
+    check(vec2(1000.0, 0.0), vec2(0.0, 0.0), 1000.0, Some(1.0));
+    check(vec2(0.0, -500.0), vec2(0.0, 0.0), 1000.0, Some(0.5));
+    // Approaching and receding along the line of fire.
+    check(vec2(1000.0, 0.0), vec2(-1000.0, 0.0), 1000.0, Some(0.5));
+    check(vec2(1000.0, 0.0), vec2(500.0, 0.0), 1000.0, Some(2.0));
+    // Crossing: 3-4-5 triangle.
+    check(vec2(400.0, 0.0), vec2(0.0, 300.0), 500.0, Some(1.0));
+    // Target as fast as the bullet, coming closer.
+    check(
+        vec2(1000.0, 0.0),
+        vec2(-500.0, 500.0),
+        500.0 * 2f64.sqrt(),
+        Some(1.0),
+    );
+    // Target as fast as the bullet, running away.
+    check(vec2(1000.0, 0.0), vec2(1000.0, 0.0), 1000.0, None);
+    // Target faster than the bullet, running away.
+    check(vec2(1000.0, 0.0), vec2(2000.0, 0.0), 1000.0, None);
+    // Target faster than the bullet but passing through its reach: the
+    // earliest hit is returned.
+    check(
+        vec2(1000.0, 0.0),
+        vec2(-2000.0, 0.0),
+        1000.0,
+        Some(1.0 / 3.0),
+    );
+    // Target faster than the bullet and passing by too far away.
+    check(vec2(1000.0, 1000.0), vec2(-2000.0, 0.0), 100.0, None);
+    // Already on target.
+    check(vec2(0.0, 0.0), vec2(100.0, 0.0), 1000.0, Some(0.0));
+    // No bullet speed and no relative velocity.
+    check(vec2(1000.0, 0.0), vec2(0.0, 0.0), 0.0, None);
+}
+
+#[test]
+fn test_declared_api_version() {
+    use oort_api::declared_api_version;
+    assert_eq!(
+        declared_api_version("// oort-api: 1\nfn main() {}"),
+        Some(1)
+    );
+    assert_eq!(
+        declared_api_version("\n// Fighter AI\n//oort-api:2\nuse oort_api::prelude::*;"),
+        Some(2)
+    );
+    assert_eq!(
+        declared_api_version("use oort_api::prelude::*;\n// oort-api: 1"),
+        None
+    );
+    assert_eq!(declared_api_version("// oort-api: latest"), None);
+    assert_eq!(declared_api_version(""), None);
+}
@@ -1,6 +1,6 @@
 use nalgebra::vector;
 use oort_simulator::ship::{fighter, frigate, target};
-use oort_simulator::simulation::{self, Code};
+use oort_simulator::simulation::{self, Code, SimEvent};
 use oort_simulator::{bullet, ship};
 use test_log::test;
 
@@ -30,6 +30,9 @@ fn test_hit() {
 
     sim.ship_mut(ship0).fire_gun(0);
     assert!(!sim.bullets.iter().len() > 0);
+    let bullet = *sim.bullets.iter().next().unwrap();
+    let muzzle_speed = sim.ship(ship0).get_muzzle_speed(0);
+    assert!((bullet::body(&sim, bullet).linvel().norm() - muzzle_speed).abs() < 1e-6);
 
     for _ in 0..100 {
         sim.step();
@@ -42,6 +45,201 @@ fn test_hit() {
     assert_ne!(sim.ship(ship1).data().health, initial_health);
 }
 
+#[test]
+fn test_hit_while_moving() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+
+    let ship0 = ship::create(
+        &mut sim,
+        vector![-1000.0, 0.0],
+        vector![300.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.1,
+        fighter(1),
+    );
+
+    let initial_health = sim.ship(ship1).data().health;
+
+    sim.ship_mut(ship0).fire_gun(0);
+    let bullet = *sim.bullets.iter().next().unwrap();
+    // The bullet inherits the ship's velocity.
+    let relative_velocity = bullet::body(&sim, bullet).linvel() - vector![300.0, 0.0];
+    let muzzle_speed = sim.ship(ship0).get_muzzle_speed(0);
+    assert!((relative_velocity.norm() - muzzle_speed).abs() < 1e-6);
+
+    for _ in 0..200 {
+        sim.step();
+    }
+
+    assert!(sim.ships.contains(ship1));
+    assert_ne!(sim.ship(ship1).data().health, initial_health);
+}
+
+#[test]
+fn test_zero_inaccuracy() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut data = fighter(0);
+    data.guns[0].inaccuracy = 0.0;
+    let heading = 0.5;
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        heading,
+        data,
+    );
+    assert_eq!(sim.ship(ship0).get_inaccuracy(0), 0.0);
+
+    for _ in 0..10 {
+        sim.ship_mut(ship0).fire_gun(0);
+        sim.step();
+    }
+
+    assert!(!sim.bullets.is_empty());
+    for &bullet in sim.bullets.iter() {
+        let v = bullet::body(&sim, bullet).linvel();
+        assert!((v.y.atan2(v.x) - heading).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_inaccuracy_is_deterministic() {
+    let fire = || {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(0),
+        );
+        let inaccuracy = sim.ship(ship0).get_inaccuracy(0);
+        assert!(inaccuracy > 0.0);
+        sim.ship_mut(ship0).fire_gun(0);
+        let bullet = *sim.bullets.iter().next().unwrap();
+        let v = *bullet::body(&sim, bullet).linvel();
+        assert!(v.y.atan2(v.x).abs() <= inaccuracy);
+        v
+    };
+    assert_eq!(fire(), fire());
+}
+
+#[test]
+fn test_spread_distribution() {
+    let spread = 0.01;
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut data = fighter(0);
+    data.guns[0].inaccuracy = 0.0;
+    data.guns[0].spread = spread;
+    data.guns[0].reload_ticks = 0;
+    data.guns[0].magazine_size = 10000;
+    // Bullets expire on the next step, leaving only the newest.
+    data.guns[0].ttl = 0.001;
+    let ship0 = ship::create(&mut sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, data);
+    assert_eq!(sim.ship(ship0).get_spread(0), spread);
+
+    let n = 1000;
+    let mut angles = vec![];
+    for _ in 0..n {
+        sim.ship_mut(ship0).fire_gun(0);
+        assert_eq!(sim.bullets.len(), 1);
+        let bullet = *sim.bullets.iter().next().unwrap();
+        let v = bullet::body(&sim, bullet).linvel();
+        angles.push(v.y.atan2(v.x));
+        sim.step();
+    }
+
+    let mean = angles.iter().sum::<f64>() / n as f64;
+    let stddev = (angles.iter().map(|a| (a - mean).powi(2)).sum::<f64>() / n as f64).sqrt();
+    let within_one_sigma = angles.iter().filter(|a| a.abs() < spread).count() as f64 / n as f64;
+    assert!(mean.abs() < 3.0 * spread / (n as f64).sqrt(), "mean={mean}");
+    assert!((stddev - spread).abs() < 0.1 * spread, "stddev={stddev}");
+    assert!(
+        (within_one_sigma - 0.683).abs() < 0.05,
+        "within_one_sigma={within_one_sigma}"
+    );
+}
+
+#[test]
+fn test_spread_is_deterministic() {
+    let run = |seed| {
+        let mut sim = simulation::Simulation::new("test", seed, &[Code::None, Code::None]);
+        let mut data = fighter(0);
+        data.guns[0].spread = 0.05;
+        data.guns[0].recoil_impulse = 1000.0;
+        let ship0 = ship::create(&mut sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, data);
+        for _ in 0..50 {
+            sim.ship_mut(ship0).fire_gun(0);
+            sim.step();
+        }
+        sim.snapshot(0)
+            .bullets
+            .iter()
+            .map(|b| (b.position, b.velocity))
+            .collect::<Vec<_>>()
+    };
+    let bullets = run(1234);
+    assert!(bullets.len() > 1);
+    assert_eq!(bullets, run(1234));
+    // Replays need the seed, but other seeds don't reuse the same spread.
+    assert_ne!(bullets, run(1235));
+}
+
+#[test]
+fn test_recoil() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut data = fighter(0);
+    data.guns[0].recoil_impulse = 1000.0;
+    let ship0 = ship::create(&mut sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, data);
+    let mass = sim.ship(ship0).body().mass();
+
+    sim.ship_mut(ship0).fire_gun(0);
+    sim.step();
+    let v = sim.ship(ship0).velocity();
+    assert!((v.x + 1000.0 / mass).abs() < 1e-6, "{v:?}");
+    assert!(v.y.abs() < 1e-6, "{v:?}");
+}
+
+#[test]
+fn test_ammo() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut data = fighter(0);
+    data.guns[0].ammo = Some(5);
+    data.guns[0].burst_size = 2;
+    data.guns[0].reload_ticks = 0;
+    data.guns[0].magazine_size = 1000;
+    data.missile_launchers[0].ammo = Some(1);
+    let ship0 = ship::create(&mut sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, data);
+    assert_eq!(sim.ship(ship0).get_ammo(0), Some(5));
+    assert_eq!(sim.ship(ship0).get_ammo(1), Some(1));
+    assert_eq!(sim.ship(ship0).get_ammo(2), Some(0));
+
+    // The last burst only has one bullet left.
+    for _ in 0..10 {
+        sim.ship_mut(ship0).fire(0);
+        sim.step();
+    }
+    assert_eq!(sim.bullets.len(), 5);
+    assert_eq!(sim.ship(ship0).get_ammo(0), Some(0));
+
+    // Missiles are created as soon as they're fired.
+    let mut launched = 0;
+    for _ in 0..1000 {
+        let num_ships = sim.ships.len();
+        sim.ship_mut(ship0).fire(1);
+        launched += sim.ships.len() - num_ships;
+        sim.step();
+    }
+    assert_eq!(launched, 1);
+    assert_eq!(sim.ship(ship0).get_ammo(1), Some(0));
+}
+
 #[test]
 fn test_destroyed() {
     let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
@@ -113,3 +311,80 @@ fn test_penetration() {
     assert_ne!(bullet::data(&sim, bullet).mass, initial_bullet_mass);
     assert_ne!(*bullet::body(&sim, bullet).linvel(), initial_velocity);
 }
+
+#[test]
+fn test_events() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+
+    let ship0 = ship::create(
+        &mut sim,
+        vector![-100.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![100.0, 0.0],
+        vector![0.0, 0.0],
+        0.1,
+        target(1),
+    );
+
+    let mut events = vec![];
+    for _ in 0..1000 {
+        sim.ship_mut(ship0).fire_gun(0);
+        sim.step();
+        events.extend(sim.snapshot(0).events);
+        if !sim.ships.contains(ship1) {
+            break;
+        }
+    }
+
+    assert!(!sim.ships.contains(ship1));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, SimEvent::BulletHit { .. })));
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| matches!(e, SimEvent::Explosion { .. }))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_fire_multiple_weapons() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let burst_size = sim.ship(ship0).data().guns[0].burst_size as usize;
+
+    // Gun and missile launcher in the same tick.
+    sim.ship_mut(ship0).fire(0);
+    sim.ship_mut(ship0).fire(1);
+    assert_eq!(sim.bullets.len(), burst_size);
+    assert_eq!(sim.ships.len(), 2);
+
+    // The gun is reloading, so a second shot in the same tick does nothing.
+    sim.ship_mut(ship0).fire(0);
+    assert_eq!(sim.bullets.len(), burst_size);
+
+    // Each weapon reloads independently.
+    let gun_reload_ticks = sim.ship(ship0).data().guns[0].reload_ticks;
+    assert!(gun_reload_ticks < sim.ship(ship0).data().missile_launchers[0].reload_ticks);
+    for _ in 0..gun_reload_ticks {
+        sim.step();
+    }
+    let bullets = sim.bullets.len();
+    sim.ship_mut(ship0).fire(0);
+    sim.ship_mut(ship0).fire(1);
+    assert_eq!(sim.bullets.len(), bullets + burst_size);
+    assert_eq!(sim.ships.len(), 2);
+}
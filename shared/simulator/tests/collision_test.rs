@@ -1,5 +1,5 @@
-use nalgebra::vector;
-use oort_simulator::ship::{fighter, missile};
+use nalgebra::{point, vector};
+use oort_simulator::ship::{asteroid, fighter, missile};
 use oort_simulator::simulation::{self, Code};
 use oort_simulator::{bullet, collision, ship};
 use rand::Rng;
@@ -73,6 +73,125 @@ fn test_head_on_collision() {
     assert!(sim.ship(ship1).velocity().x > 0.0);
 }
 
+#[test]
+fn test_ram_destroys_fighters() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+
+    let ship0 = ship::create(
+        &mut sim,
+        vector![-100.0, 0.0],
+        vector![50.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![100.0, 0.0],
+        vector![-50.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+
+    for _ in 0..300 {
+        sim.step();
+    }
+
+    assert!(!sim.ships.contains(ship0));
+    assert!(!sim.ships.contains(ship1));
+}
+
+#[test]
+fn test_gentle_nudge_does_no_damage() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+
+    let ship0 = ship::create(
+        &mut sim,
+        vector![-30.0, 0.0],
+        vector![1.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![30.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let initial_health = sim.ship(ship0).data().health;
+
+    for _ in 0..(60 * 60) {
+        sim.step();
+    }
+
+    assert!(sim.ship(ship1).velocity().x > 0.0);
+    assert_eq!(sim.ship(ship0).data().health, initial_health);
+    assert_eq!(sim.ship(ship1).data().health, initial_health);
+}
+
+#[test]
+fn test_ram_damage_absorbed_by_shield() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+
+    let shielded = ship::create(
+        &mut sim,
+        vector![-100.0, 0.0],
+        vector![30.0, 0.0],
+        0.0,
+        ship::ShipData {
+            shield: Some(ship::Shield {
+                max: 100.0,
+                ..Default::default()
+            }),
+            ..fighter(0)
+        },
+    );
+    let unshielded = ship::create(
+        &mut sim,
+        vector![100.0, 0.0],
+        vector![-30.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let initial_health = sim.ship(shielded).data().health;
+
+    for _ in 0..300 {
+        sim.step();
+    }
+
+    assert_eq!(sim.ship(shielded).data().health, initial_health);
+    assert!(sim.ship(shielded).data().shield.as_ref().unwrap().value < 100.0);
+    assert!(sim.ship(unshielded).data().health < initial_health);
+    assert!(!sim.ship(unshielded).data().destroyed);
+}
+
+#[test]
+fn test_asteroid_ram() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+
+    let fighter = ship::create(
+        &mut sim,
+        vector![-200.0, 0.0],
+        vector![50.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let asteroid = ship::create(
+        &mut sim,
+        vector![100.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        asteroid(0),
+    );
+
+    for _ in 0..600 {
+        sim.step();
+    }
+
+    assert!(!sim.ships.contains(fighter));
+    assert!(sim.ships.contains(asteroid));
+}
+
 #[test]
 fn test_fighter_bullet_collision_same_team() {
     let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
@@ -304,3 +423,139 @@ fn test_ship_wall_collision() {
 
     assert!(!sim.ship(ship0).exists());
 }
+
+#[test]
+fn test_obstacle_collision() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    collision::add_obstacle(
+        &mut sim,
+        &[
+            point![100.0, -100.0],
+            point![200.0, -100.0],
+            point![200.0, 100.0],
+            point![100.0, 100.0],
+        ],
+    );
+
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![200.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    bullet::create(
+        &mut sim,
+        vector![0.0, 50.0],
+        vector![1000.0, 0.0],
+        bullet::BulletData {
+            mass: 1.0,
+            team: 1,
+            color: BULLET_COLOR,
+            ttl: 10.0,
+        },
+    );
+
+    for _ in 0..60 {
+        sim.step();
+    }
+
+    // The ship bounces off instead of exploding like it would on a wall.
+    assert!(sim.ship(ship0).exists());
+    assert!(sim.ship(ship0).velocity().x < 0.0);
+    assert_eq!(sim.bullets.len(), 0);
+}
+
+#[test]
+fn test_obstacle_blocks_distant_bullet() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    collision::add_obstacle(
+        &mut sim,
+        &[
+            point![3500.0, -100.0],
+            point![3600.0, -100.0],
+            point![3600.0, 100.0],
+            point![3500.0, 100.0],
+        ],
+    );
+
+    // No enemy ship is anywhere near the bullet's path.
+    ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    bullet::create(
+        &mut sim,
+        vector![3000.0, 0.0],
+        vector![1000.0, 0.0],
+        bullet::BulletData {
+            mass: 1.0,
+            team: 0,
+            color: BULLET_COLOR,
+            ttl: 10.0,
+        },
+    );
+
+    for _ in 0..15 {
+        sim.step();
+    }
+    assert_eq!(sim.bullets.len(), 1);
+
+    for _ in 0..45 {
+        sim.step();
+    }
+    assert_eq!(sim.bullets.len(), 0);
+}
+
+#[test]
+fn test_bullet_bullet_collision() {
+    let mut sim = simulation::Simulation::new("projectile_collision_test", 0, &[Code::None]);
+
+    for (team, x, vx) in [(0, -1000.0, 1000.0), (1, 1000.0, -1000.0)] {
+        bullet::create(
+            &mut sim,
+            vector![x, 0.5 * team as f64],
+            vector![vx, 0.0],
+            bullet::BulletData {
+                mass: 0.1,
+                team,
+                color: BULLET_COLOR,
+                ttl: 5.0,
+            },
+        );
+    }
+
+    for _ in 0..120 {
+        sim.step();
+    }
+
+    assert_eq!(sim.bullets.len(), 0);
+}
+
+#[test]
+fn test_bullet_bullet_collision_disabled() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+
+    for (team, x, vx) in [(0, -1000.0, 1000.0), (1, 1000.0, -1000.0)] {
+        bullet::create(
+            &mut sim,
+            vector![x, 0.5 * team as f64],
+            vector![vx, 0.0],
+            bullet::BulletData {
+                mass: 0.1,
+                team,
+                color: BULLET_COLOR,
+                ttl: 5.0,
+            },
+        );
+    }
+
+    for _ in 0..120 {
+        sim.step();
+    }
+
+    assert_eq!(sim.bullets.len(), 2);
+}
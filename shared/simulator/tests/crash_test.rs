@@ -1,6 +1,7 @@
 use nalgebra::vector;
 use oort_simulator::ship::{self, fighter};
 use oort_simulator::simulation::{self, Code};
+use oort_simulator::snapshot::ScriptStatus;
 use serial_test::serial;
 use std::collections::BTreeMap;
 
@@ -25,7 +26,7 @@ fn test_panic() {
 
     assert_eq!(
         sim.events().debug_text.get(&handle.into()).unwrap(),
-        "Crashed: ship panicked at 'Panic!', lib.rs:17:24"
+        "Crashed: ship panicked at 'Panic!', lib.rs:25:24"
     );
 
     testing_logger::validate(|captured_logs| {
@@ -33,7 +34,7 @@ fn test_panic() {
         assert_eq!(captured_logs[0].level, log::Level::Warn);
         assert_eq!(
             &captured_logs[0].body,
-            "ship panicked at 'Panic!', lib.rs:17:24"
+            "ship panicked at 'Panic!', lib.rs:25:24"
         );
     });
 }
@@ -71,3 +72,47 @@ fn test_infinite_loop() {
         );
     });
 }
+
+#[test]
+#[serial]
+fn test_script_status() {
+    let mut sim =
+        simulation::Simulation::new("test", 0, &[Code::Builtin("test".to_string()), Code::None]);
+    let mut env = BTreeMap::new();
+    env.insert("TESTCASE".to_string(), "panic".to_string());
+    sim.update_environment(0, env);
+    let crashed = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let disabled = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+
+    let status = |sim: &simulation::Simulation, handle: ship::ShipHandle| {
+        let id: u64 = handle.into();
+        sim.snapshot(0)
+            .ships
+            .iter()
+            .find(|ship| ship.id == id)
+            .unwrap()
+            .script_status
+            .clone()
+    };
+    assert_eq!(status(&sim, crashed), ScriptStatus::Ok);
+    assert_eq!(status(&sim, disabled), ScriptStatus::Disabled);
+
+    sim.step();
+    assert_eq!(
+        status(&sim, crashed),
+        ScriptStatus::Crashed("ship panicked at 'Panic!', lib.rs:25:24".to_string())
+    );
+    assert_eq!(status(&sim, disabled), ScriptStatus::Disabled);
+}
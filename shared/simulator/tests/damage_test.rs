@@ -63,3 +63,95 @@ fn test_dps() {
     approx::assert_abs_diff_eq!(find_gun_dps(frigate(0), 2), 73.5, epsilon = 1.0);
     approx::assert_abs_diff_eq!(find_gun_dps(cruiser(0), 0), 75.7, epsilon = 1.0);
 }
+
+fn shielded_fighter(team: i32) -> ship::ShipData {
+    ship::ShipData {
+        shield: Some(ship::Shield {
+            max: 100.0,
+            recharge_rate: 50.0,
+            recharge_delay_ticks: 100,
+            ..Default::default()
+        }),
+        ..fighter(team)
+    }
+}
+
+fn setup_range(
+    target_data: ship::ShipData,
+) -> (
+    Box<simulation::Simulation>,
+    ship::ShipHandle,
+    ship::ShipHandle,
+) {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut shooter_data = fighter(0);
+    shooter_data.guns[0].inaccuracy = 0.0;
+    let shooter = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        shooter_data,
+    );
+    let target = ship::create(
+        &mut sim,
+        vector![200.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        target_data,
+    );
+    (sim, shooter, target)
+}
+
+#[test]
+fn test_shield_absorbs_damage() {
+    let burst = |target_data: ship::ShipData| {
+        let (mut sim, shooter, target) = setup_range(target_data);
+        // About 150 damage, more than a fighter's health but less than its
+        // health plus shield.
+        for _ in 0..80 {
+            sim.ship_mut(shooter).fire_gun(0);
+            sim.step();
+        }
+        for _ in 0..20 {
+            sim.step();
+        }
+        sim.ships.contains(target)
+    };
+
+    assert!(!burst(fighter(1)));
+    assert!(burst(shielded_fighter(1)));
+}
+
+#[test]
+fn test_shield_recharge_interrupted_by_hit() {
+    let (mut sim, shooter, target) = setup_range(shielded_fighter(1));
+    let step = |sim: &mut simulation::Simulation, n: usize| {
+        for _ in 0..n {
+            sim.step();
+        }
+    };
+    assert_eq!(sim.ship(target).shield(), 100.0);
+
+    sim.ship_mut(shooter).fire_gun(0);
+    step(&mut sim, 20);
+    let after_first_hit = sim.ship(target).shield();
+    assert!(after_first_hit < 100.0);
+
+    // Still inside the recharge delay.
+    step(&mut sim, 60);
+    assert_eq!(sim.ship(target).shield(), after_first_hit);
+
+    // The second hit restarts the delay, even though the first hit was long
+    // enough ago that recharging would otherwise have started.
+    sim.ship_mut(shooter).fire_gun(0);
+    step(&mut sim, 20);
+    let after_second_hit = sim.ship(target).shield();
+    assert!(after_second_hit < after_first_hit);
+    step(&mut sim, 60);
+    assert_eq!(sim.ship(target).shield(), after_second_hit);
+
+    step(&mut sim, 100);
+    assert!(sim.ship(target).shield() > after_second_hit);
+    assert_eq!(sim.ship(target).data().health, 100.0);
+}
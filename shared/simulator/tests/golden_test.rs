@@ -1,5 +1,7 @@
+use nalgebra::vector;
 use oort_simulator::scenario;
-use oort_simulator::simulation;
+use oort_simulator::ship;
+use oort_simulator::simulation::{self, Code};
 use test_log::test;
 
 fn run(scenario_name: &str) -> u64 {
@@ -17,5 +19,177 @@ fn run(scenario_name: &str) -> u64 {
 
 #[test]
 fn test_frigate_vs_cruiser() {
-    assert_eq!(run("frigate_vs_cruiser"), 14094678654862248462);
+    assert_eq!(run("frigate_vs_cruiser"), 2030452086719448692);
+}
+
+/// Runs a scenario's solution for up to `ticks` ticks and returns the state
+/// hash.
+fn state_hash_after(scenario_name: &str, seed: u32, ticks: u32) -> u64 {
+    state_hash_with_divisor(scenario_name, seed, ticks, 1)
+}
+
+fn state_hash_with_divisor(
+    scenario_name: &str,
+    seed: u32,
+    ticks: u32,
+    script_tick_divisor: u32,
+) -> u64 {
+    let scenario = scenario::load(scenario_name);
+    let codes = scenario.solution_codes();
+    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
+    sim.script_tick_divisor = script_tick_divisor;
+    while sim.status() == scenario::Status::Running && sim.tick() < ticks {
+        sim.step();
+    }
+    sim.state_hash()
+}
+
+#[test]
+fn test_state_hashes() {
+    // Every listed scenario after 300 ticks with seeds 0 and 1. If a change
+    // is meant to alter behavior, update the affected values.
+    let expected: &[(&str, u32, u64)] = &[
+        ("welcome", 0, 16705186056903778611),
+        ("welcome", 1, 2049528751154227371),
+        ("tutorial_guns", 0, 2349863613027582380),
+        ("tutorial_guns", 1, 929130688005604351),
+        ("tutorial_acceleration", 0, 10762935502581667630),
+        ("tutorial_acceleration", 1, 10762935502581667630),
+        ("tutorial_acceleration2", 0, 3787866989569611698),
+        ("tutorial_acceleration2", 1, 5511675873361656296),
+        ("tutorial_rotation", 0, 4893381636522978035),
+        ("tutorial_rotation", 1, 13787131229452959858),
+        ("tutorial_lead", 0, 7080722845931937826),
+        ("tutorial_lead", 1, 3447569045328228217),
+        ("tutorial_deflection", 0, 15605946600024223071),
+        ("tutorial_deflection", 1, 12960591997849613671),
+        ("tutorial_radar", 0, 15834658338823620473),
+        ("tutorial_radar", 1, 10688956517306317292),
+        ("tutorial_search", 0, 3274127072188247092),
+        ("tutorial_search", 1, 13752547339239406263),
+        ("tutorial_radio", 0, 5878766385367599018),
+        ("tutorial_radio", 1, 3403940067592089548),
+        ("tutorial_missiles", 0, 8325389982183961461),
+        ("tutorial_missiles", 1, 486031959846239434),
+        ("tutorial_squadron", 0, 13641073314621914729),
+        ("tutorial_squadron", 1, 14829834092636435200),
+        ("tutorial_formation", 0, 3816191579677319524),
+        ("tutorial_formation", 1, 9211834062565491544),
+        ("tutorial_frigate", 0, 2608327236711258313),
+        ("tutorial_frigate", 1, 16950739236233243392),
+        ("tutorial_cruiser", 0, 14114215448585837175),
+        ("tutorial_cruiser", 1, 3319644300019027831),
+        ("gunnery", 0, 18127893973009209819),
+        ("gunnery", 1, 6306634412634108786),
+        ("planetary_defense", 0, 7436660570496193898),
+        ("planetary_defense", 1, 4872963280519454249),
+        ("duel01", 0, 908109495172480818),
+        ("duel01", 1, 569208541579831334),
+        ("fleet01", 0, 13053609969267300840),
+        ("fleet01", 1, 1923948051255268771),
+        ("missiles01", 0, 8778580607200841937),
+        ("missiles01", 1, 8778580607200841937),
+        ("fighter_duel", 0, 8905477527714278263),
+        ("fighter_duel", 1, 4133701901094636727),
+        ("mini_fleet", 0, 11071966995255167003),
+        ("mini_fleet", 1, 3473168995071706176),
+        ("frigate_duel", 0, 415835495334247923),
+        ("frigate_duel", 1, 1592375353438607762),
+        ("cruiser_duel", 0, 6418815818432566819),
+        ("cruiser_duel", 1, 3695901228251521337),
+        ("asteroid_duel", 0, 6967199899336643245),
+        ("asteroid_duel", 1, 1469383618561123573),
+        ("obstacle_duel", 0, 7871063803078084611),
+        ("obstacle_duel", 1, 8380162637356837835),
+        ("squadrons", 0, 11909944890839783859),
+        ("squadrons", 1, 3451309771094841193),
+        ("fleet", 0, 7306183115995632083),
+        ("fleet", 1, 1461905340663509581),
+        ("belt", 0, 17767879428395959813),
+        ("belt", 1, 6914357492108500034),
+        ("orbit", 0, 13929129425955340474),
+        ("orbit", 1, 6249485511193287449),
+        ("versus01", 0, 6987994745675674467),
+        ("versus01", 1, 3990322567193286847),
+        ("sandbox", 0, 9913247237316303108),
+        ("sandbox", 1, 9913247237316303108),
+    ];
+    let names: Vec<String> = scenario::list()
+        .into_iter()
+        .flat_map(|(_, names)| names)
+        .collect();
+    assert_eq!(expected.len(), names.len() * 2);
+    for &(name, seed, hash) in expected {
+        assert!(names.iter().any(|x| x == name), "{name} is not listed");
+        assert_eq!(
+            state_hash_after(name, seed, 300),
+            hash,
+            "state hash for {name} with seed {seed}"
+        );
+    }
+}
+
+#[test]
+fn test_missile_defense_hashes() {
+    // missiles01 launches its first wave at tick 300, so the 300 tick hashes
+    // above don't depend on the seed. Check again with that wave in flight.
+    let expected: &[(u32, u64)] = &[(0, 13359609434756464853), (1, 5011381204485511726)];
+    for &(seed, hash) in expected {
+        assert_eq!(
+            state_hash_after("missiles01", seed, 900),
+            hash,
+            "state hash for missiles01 with seed {seed}"
+        );
+    }
+}
+
+#[test]
+fn test_script_tick_divisor_hashes() {
+    // fighter_duel after 600 ticks with scripts running every `divisor`
+    // physics ticks. Each divisor must be deterministic and distinct.
+    let expected: &[(u32, u64)] = &[
+        (1, 10495130920064048815),
+        (2, 11931462841455974320),
+        (3, 13517173189850877239),
+        (4, 14486716096887109952),
+        (8, 384553798332454686),
+    ];
+    for &(divisor, hash) in expected {
+        let run = || state_hash_with_divisor("fighter_duel", 0, 600, divisor);
+        let first = run();
+        assert_eq!(first, run(), "divisor {divisor} was not deterministic");
+        assert_eq!(first, hash, "state hash with divisor {divisor}");
+    }
+}
+
+#[test]
+fn test_state_hash_is_reproducible() {
+    for name in scenario::list().into_iter().flat_map(|(_, names)| names) {
+        let run = || {
+            let mut sim = simulation::Simulation::new(&name, 0, &[]);
+            for _ in 0..60 {
+                sim.step();
+            }
+            sim.state_hash()
+        };
+        assert_eq!(run(), run(), "scenario {name} was not deterministic");
+    }
+}
+
+#[test]
+fn test_state_hash_sees_small_changes() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ship::fighter(0),
+    );
+    let initial = sim.state_hash();
+    assert_eq!(sim.state_hash(), initial);
+    sim.ship_mut(ship0)
+        .body()
+        .set_linvel(vector![f64::EPSILON, 0.0], true);
+    assert_ne!(sim.state_hash(), initial);
 }
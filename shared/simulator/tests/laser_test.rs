@@ -0,0 +1,101 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter, frigate, FIRST_LASER_INDEX};
+use oort_simulator::simulation::{self, Code, SimEvent};
+use test_log::test;
+
+fn setup(
+    target_position: f64,
+) -> (
+    Box<simulation::Simulation>,
+    ship::ShipHandle,
+    ship::ShipHandle,
+) {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![target_position, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    // Ray casts see the ships once they've been through a physics step.
+    sim.step();
+    (sim, ship0, ship1)
+}
+
+fn laser_index(sim: &simulation::Simulation, ship: ship::ShipHandle) -> i64 {
+    sim.ship(ship).data().first_laser_index() as i64
+}
+
+#[test]
+fn test_laser_weapon_index() {
+    let fighter = fighter(0);
+    assert_eq!(fighter.lasers.len(), 1);
+    assert_eq!(fighter.first_laser_index(), FIRST_LASER_INDEX);
+    assert_eq!(FIRST_LASER_INDEX, 3);
+
+    // Classes without lasers keep their own weapon at index 3.
+    let frigate = frigate(0);
+    assert!(frigate.lasers.is_empty());
+    assert_eq!(frigate.first_laser_index(), 4);
+}
+
+#[test]
+fn test_unused_weapon_index() {
+    let (mut sim, ship0, ship1) = setup(1000.0);
+    let initial_health = sim.ship(ship1).data().health;
+
+    sim.ship_mut(ship0).fire(2);
+    assert_eq!(sim.ship(ship1).data().health, initial_health);
+    assert_eq!(sim.ship(ship0).get_reload_ticks(2), 0);
+}
+
+#[test]
+fn test_laser_hits_instantly() {
+    let (mut sim, ship0, ship1) = setup(1000.0);
+    let initial_health = sim.ship(ship1).data().health;
+
+    let index = laser_index(&sim, ship0);
+    sim.ship_mut(ship0).fire(index);
+    assert!(sim.ship(ship1).data().health < initial_health);
+    assert!(sim.bullets.is_empty());
+    assert!(sim.events().sim_events.iter().any(|e| matches!(
+        e,
+        SimEvent::LaserFired { end, .. } if end.x > 900.0 && end.x < 1000.0
+    )));
+}
+
+#[test]
+fn test_laser_range() {
+    let (mut sim, ship0, ship1) = setup(3000.0);
+    let initial_health = sim.ship(ship1).data().health;
+
+    let index = laser_index(&sim, ship0);
+    sim.ship_mut(ship0).fire(index);
+    assert_eq!(sim.ship(ship1).data().health, initial_health);
+}
+
+#[test]
+fn test_laser_reload() {
+    let (mut sim, ship0, ship1) = setup(1000.0);
+    let index = laser_index(&sim, ship0);
+    let reload_ticks = sim.ship(ship0).data().lasers[0].reload_ticks;
+
+    sim.ship_mut(ship0).fire(index);
+    let health = sim.ship(ship1).data().health;
+    assert_eq!(
+        sim.ship(ship0).get_reload_ticks(index as usize),
+        reload_ticks
+    );
+
+    sim.step();
+    sim.ship_mut(ship0).fire(index);
+    assert_eq!(sim.ship(ship1).data().health, health);
+}
@@ -0,0 +1,43 @@
+use oort_simulator::matchup::{self, Outcome};
+use oort_simulator::scenario;
+use test_log::test;
+
+#[test]
+fn test_matchup() {
+    let seeds = [0, 1];
+    let results = matchup::run(
+        "fighter_duel",
+        &scenario::reference_ai(),
+        &scenario::empty_ai(),
+        &seeds,
+    );
+    assert_eq!((results.wins, results.losses, results.draws), (2, 0, 0));
+    assert!(!results.games[0].swapped);
+    assert!(results.games[1].swapped);
+    assert!(results
+        .games
+        .iter()
+        .all(|game| game.outcome == Outcome::Win));
+
+    let reversed = matchup::run(
+        "fighter_duel",
+        &scenario::empty_ai(),
+        &scenario::reference_ai(),
+        &seeds,
+    );
+    assert_eq!((reversed.wins, reversed.losses, reversed.draws), (0, 2, 0));
+}
+
+#[test]
+fn test_matchup_deterministic() {
+    let seeds = [3, 4];
+    let run = || {
+        matchup::run(
+            "fighter_duel",
+            &scenario::reference_ai(),
+            &scenario::reference_ai(),
+            &seeds,
+        )
+    };
+    assert_eq!(run(), run());
+}
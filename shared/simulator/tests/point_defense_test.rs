@@ -0,0 +1,58 @@
+use nalgebra::vector;
+use oort_api::Class;
+use oort_simulator::ship::{self, cruiser, missile, ShipClass};
+use oort_simulator::simulation::{Code, Simulation};
+use std::f64::consts::PI;
+use test_log::test;
+
+// The missile volley in "cruiser_point_defense" does about 600 damage to an
+// undefended cruiser and none to one using point defense.
+const CRUISER_HEALTH: f64 = 300.0;
+
+// Returns whether the cruiser in "cruiser_point_defense" survives the missile
+// volley with `CRUISER_HEALTH` left.
+fn survives_volley(point_defense: bool) -> bool {
+    let mut sim = Simulation::new("cruiser_point_defense", 0, &[Code::None, Code::None]);
+    let cruiser = *sim.ships.iter().next().unwrap();
+    assert_eq!(sim.ship(cruiser).data().class, ShipClass::Cruiser);
+    sim.ship_mut(cruiser).data_mut().health = CRUISER_HEALTH;
+    if point_defense {
+        sim.ship_mut(cruiser)
+            .set_auto_target(0, Some(Class::Missile));
+    }
+    for _ in 0..(30 * 60) {
+        sim.step();
+    }
+    sim.ships.contains(cruiser)
+}
+
+#[test]
+fn test_point_defense() {
+    assert!(!survives_volley(false));
+    assert!(survives_volley(true));
+}
+
+#[test]
+fn test_point_defense_ignores_other_classes() {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let cruiser = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        cruiser(0),
+    );
+    sim.ship_mut(cruiser)
+        .set_auto_target(0, Some(Class::Torpedo));
+    ship::create(
+        &mut sim,
+        vector![500.0, 0.0],
+        vector![0.0, 0.0],
+        PI,
+        missile(1),
+    );
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert!(sim.bullets.is_empty());
+}
@@ -0,0 +1,58 @@
+use oort_simulator::rounds::{self, Session};
+use oort_simulator::scenario::{self, Status};
+use test_log::test;
+
+#[test]
+fn test_rounds() {
+    let codes = [scenario::reference_ai(), scenario::empty_ai()];
+    let mut session = Session::with_rounds("fighter_duel", 10, &codes, 3);
+    let mut last_tick = 0;
+    while !session.is_complete() {
+        assert_eq!(session.status(), Status::Running);
+        session.step();
+        let snapshot = session.snapshot(0);
+        assert!(snapshot.tick > last_tick);
+        last_tick = snapshot.tick;
+    }
+
+    // With seed 12 the reference fighter overshoots and is destroyed at the
+    // edge of the world, so the empty AI takes that round.
+    let tally = session.tally();
+    assert_eq!((tally.wins, tally.losses, tally.draws), (2, 1, 0));
+    let seeds: Vec<u32> = tally.rounds.iter().map(|round| round.seed).collect();
+    assert_eq!(seeds, vec![10, 11, 12]);
+    assert_eq!(
+        tally.rounds.last().unwrap().status,
+        Status::Victory { team: 1 }
+    );
+    assert_eq!(session.status(), Status::Victory { team: 0 });
+    assert_eq!(session.snapshot(0).rounds.as_ref(), Some(tally));
+
+    // The score time is the mean over the two rounds that were won.
+    let mean_time = (tally.rounds[0].time + tally.rounds[1].time) / 2.0;
+    assert_eq!(session.score_time(), mean_time);
+    assert_eq!(session.snapshot(0).score_time, mean_time);
+
+    // Stepping a finished session does nothing.
+    session.step();
+    assert_eq!(session.tally().rounds.len(), 3);
+}
+
+#[test]
+fn test_single_round() {
+    let codes = [scenario::reference_ai(), scenario::empty_ai()];
+    let mut session = Session::new("fighter_duel", 0, &codes);
+    while !session.is_complete() {
+        session.step();
+    }
+    assert_eq!(session.tally().total, 1);
+    assert_eq!(session.status(), session.sim().status());
+    assert!(session.snapshot(0).rounds.is_none());
+}
+
+#[test]
+fn test_round_seed() {
+    assert_eq!(rounds::round_seed(5, 0), 5);
+    assert_eq!(rounds::round_seed(u32::MAX, 1), 0);
+    assert_eq!(scenario::load("duel01").rounds(), 5);
+}
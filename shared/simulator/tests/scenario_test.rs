@@ -0,0 +1,391 @@
+use nalgebra::{point, vector};
+use oort_simulator::rng::new_rng;
+use oort_simulator::scenario::{self, MIN_SPAWN_DISTANCE};
+use oort_simulator::simulation::{Code, Marker, Simulation, TARGET_MARKER};
+use test_log::test;
+
+#[test]
+fn test_sample_position() {
+    let mut rng = new_rng(0);
+    let bounds = vector![500.0, 500.0];
+    let avoid = [point![0.0, 0.0], point![150.0, 0.0], point![-300.0, 400.0]];
+    for _ in 0..10000 {
+        let p = scenario::sample_position(&mut rng, bounds, &avoid, MIN_SPAWN_DISTANCE).unwrap();
+        assert!(p.x.abs() <= bounds.x && p.y.abs() <= bounds.y, "{p:?}");
+        for q in avoid.iter() {
+            assert!(
+                (p - q).magnitude() >= MIN_SPAWN_DISTANCE,
+                "{p:?} near {q:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_sample_position_impossible() {
+    let mut rng = new_rng(0);
+    let bounds = vector![100.0, 100.0];
+    assert_eq!(
+        scenario::sample_position(&mut rng, bounds, &[point![0.0, 0.0]], 1000.0),
+        None
+    );
+}
+
+#[test]
+fn test_resolve_name() {
+    assert_eq!(
+        scenario::resolve_name("tutorial_radar"),
+        Some("tutorial_radar".to_string())
+    );
+    assert_eq!(
+        scenario::resolve_name("tutorial06"),
+        Some("tutorial_radar".to_string())
+    );
+    assert_eq!(scenario::resolve_name("nonexistent"), None);
+}
+
+#[test]
+fn test_unknown_scenario() {
+    let err = scenario::load_safe("nonexistent").err().unwrap();
+    assert_eq!(
+        err,
+        scenario::ScenarioError::UnknownScenario("nonexistent".to_string())
+    );
+    assert_eq!(err.to_string(), "Unknown scenario \"nonexistent\"");
+}
+
+#[test]
+fn test_list_with_metadata() {
+    let infos = scenario::list_with_metadata();
+    let expected: Vec<(String, String)> = scenario::list()
+        .into_iter()
+        .flat_map(|(category, names)| names.into_iter().map(move |name| (category.clone(), name)))
+        .collect();
+    assert_eq!(infos.len(), expected.len());
+    for (order, (info, (category, name))) in infos.iter().zip(expected).enumerate() {
+        assert_eq!(info.order, order);
+        assert_eq!(info.category, category);
+        assert_eq!(info.name, name);
+        assert_eq!(info.human_name, scenario::load(&name).human_name());
+    }
+    let guns = infos.iter().find(|x| x.name == "tutorial_guns").unwrap();
+    assert_eq!(guns.human_name, "Tutorial 1: Guns");
+    assert_eq!(guns.category, "Tutorial");
+}
+
+#[test]
+fn test_markers() {
+    let mut sim = Simulation::new("tutorial_acceleration", 0, &[Code::None]);
+    let target = sim.marker(TARGET_MARKER).unwrap().clone();
+    assert_eq!(target.position, vector![250.0, 0.0]);
+    // The target annotation plus a 20 segment circle for the marker.
+    assert_eq!(sim.snapshot(0).scenario_lines.len(), 21);
+
+    sim.set_marker(
+        "waypoint",
+        Marker {
+            position: vector![0.0, 100.0],
+            radius: 10.0,
+            color: vector![1.0, 1.0, 1.0, 1.0],
+        },
+    );
+    assert_eq!(sim.marker("waypoint").unwrap().radius, 10.0);
+    assert_eq!(sim.snapshot(0).scenario_lines.len(), 41);
+}
+
+#[test]
+fn test_markers_overflow_environment() {
+    let mut sim = Simulation::new("test", 0, &[scenario::empty_ai()]);
+    // About 50 bytes each, so 100 of them are several times the 1 KB limit.
+    for i in 0..100 {
+        sim.set_marker(
+            &format!("waypoint{i:03}"),
+            Marker {
+                position: vector![12345.678 + i as f64, -12345.678],
+                radius: 10.0,
+                color: vector![1.0, 1.0, 1.0, 1.0],
+            },
+        );
+    }
+    assert!(sim.marker("waypoint000").is_none());
+    assert!(sim.marker("waypoint099").is_some());
+    let count = (0..100)
+        .filter(|i| sim.marker(&format!("waypoint{i:03}")).is_some())
+        .count();
+    assert!(count > 5 && count < 30, "{count} markers kept");
+
+    sim.step();
+    assert!(sim.events().errors.is_empty());
+}
+
+#[test]
+fn test_practice() {
+    use oort_simulator::ship::{self, fighter};
+
+    let mut sim = Simulation::new("test", 0, &[Code::None, scenario::reference_ai()]);
+    sim.practice = true;
+    let ship0 = ship::create(
+        &mut sim,
+        vector![-500.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![500.0, 0.0],
+        vector![0.0, 0.0],
+        std::f64::consts::PI,
+        fighter(1),
+    );
+    let initial_health = sim.ship(ship0).data().health;
+
+    sim.ship_mut(ship1).fire(0);
+    for _ in 0..300 {
+        sim.step();
+        assert!(sim.bullets.is_empty());
+    }
+    assert_eq!(sim.ship(ship0).data().health, initial_health);
+
+    // The player's own weapons still work.
+    sim.ship_mut(ship0).fire(0);
+    assert!(!sim.bullets.is_empty());
+}
+
+#[test]
+fn test_duel_opponents() {
+    assert_eq!(
+        scenario::load("duel01:sniper").name(),
+        "duel01:sniper".to_string()
+    );
+    assert!(scenario::load_safe("duel01:nonexistent").is_err());
+    assert!(scenario::load_safe("gunnery:sniper").is_err());
+
+    for &opponent in scenario::OPPONENTS {
+        let name = format!("duel01:{opponent}");
+        let codes = scenario::load(&name).initial_code();
+        assert_eq!(codes[1], scenario::opponent_ai(opponent).unwrap());
+        let mut sim = Simulation::new(&name, 0, &[scenario::empty_ai(), codes[1].clone()]);
+        while sim.status() == scenario::Status::Running {
+            sim.step();
+        }
+        assert_eq!(
+            sim.status(),
+            scenario::Status::Failed,
+            "{opponent} failed to beat an empty AI"
+        );
+    }
+}
+
+#[test]
+fn test_team_victory() {
+    use oort_simulator::scenario::sandbox::{self, SandboxCommand, SpawnKind};
+    use oort_simulator::scenario::{check_team_victory, Status};
+
+    let spawn = |sim: &mut Simulation, kind, team, x| {
+        sandbox::apply(
+            sim,
+            &SandboxCommand::Spawn {
+                kind,
+                team,
+                position: point![x, 0.0],
+            },
+        );
+    };
+    let destroy_team = |sim: &mut Simulation, team| {
+        for handle in sim.ships.iter().cloned().collect::<Vec<_>>() {
+            if sim.ship(handle).data().team == team {
+                sim.ship_mut(handle).data_mut().destroyed = true;
+            }
+        }
+        sim.step();
+    };
+    let max_ticks = 100;
+
+    // Victory: allies survive together.
+    let mut sim = Simulation::new("sandbox", 0, &[Code::None]);
+    spawn(&mut sim, SpawnKind::Fighter, 0, 1000.0);
+    spawn(&mut sim, SpawnKind::Fighter, 1, 2000.0);
+    sim.step();
+    assert_eq!(check_team_victory(&sim, 0, max_ticks), Status::Running);
+    destroy_team(&mut sim, 1);
+    assert_eq!(sim.ships.len(), 2);
+    assert_eq!(
+        check_team_victory(&sim, 0, max_ticks),
+        Status::Victory { team: 0 }
+    );
+
+    // Failed: the player is wiped out. A leftover missile doesn't count.
+    let mut sim = Simulation::new("sandbox", 0, &[Code::None]);
+    spawn(&mut sim, SpawnKind::Fighter, 1, 2000.0);
+    sim.step();
+    destroy_team(&mut sim, 0);
+    spawn(&mut sim, SpawnKind::Missile, 0, 3000.0);
+    sim.step();
+    assert_eq!(check_team_victory(&sim, 0, max_ticks), Status::Failed);
+    assert_eq!(
+        check_team_victory(&sim, 1, max_ticks),
+        Status::Victory { team: 1 }
+    );
+
+    // Draw: the tick limit expires with both teams alive.
+    let mut sim = Simulation::new("sandbox", 0, &[Code::None]);
+    spawn(&mut sim, SpawnKind::Fighter, 1, 2000.0);
+    while check_team_victory(&sim, 0, max_ticks) == Status::Running {
+        sim.step();
+    }
+    assert_eq!(check_team_victory(&sim, 0, max_ticks), Status::Draw);
+    assert_eq!(sim.tick(), max_ticks - 1);
+
+    // Draw: everyone is destroyed at once.
+    let mut sim = Simulation::new("sandbox", 0, &[Code::None]);
+    spawn(&mut sim, SpawnKind::Fighter, 1, 2000.0);
+    sim.step();
+    sandbox::apply(&mut sim, &SandboxCommand::Clear);
+    sim.step();
+    assert_eq!(check_team_victory(&sim, 0, max_ticks), Status::Draw);
+}
+
+#[test]
+fn test_seed_determinism() {
+    for name in scenario::list().into_iter().flat_map(|(_, names)| names) {
+        let hash = |seed| Simulation::new(&name, seed, &[]).hash();
+        assert_eq!(hash(7), hash(7), "scenario {name} was not deterministic");
+    }
+
+    let hash = |seed| Simulation::new("belt", seed, &[]).hash();
+    assert_ne!(hash(1), hash(2));
+}
+
+#[test]
+fn test_sandbox() {
+    use oort_simulator::scenario::sandbox::{self, SandboxCommand, SpawnKind};
+
+    let mut sim = Simulation::new("sandbox", 0, &[Code::None]);
+    assert_eq!(sim.ships.len(), 1);
+    for (i, &kind) in SpawnKind::ALL.iter().enumerate() {
+        sandbox::apply(
+            &mut sim,
+            &SandboxCommand::Spawn {
+                kind,
+                team: 1,
+                position: point![1000.0 * (i + 1) as f64, 0.0],
+            },
+        );
+    }
+    sim.step();
+    assert_eq!(sim.ships.len(), 8);
+    assert_eq!(sim.bullets.len(), 1);
+    assert_eq!(sim.status(), scenario::Status::Running);
+    assert_eq!(scenario::load("sandbox").max_ticks(), u32::MAX);
+
+    sandbox::apply(&mut sim, &SandboxCommand::Clear);
+    sim.step();
+    assert!(sim.ships.is_empty());
+    assert!(sim.bullets.is_empty());
+    assert_eq!(sim.status(), scenario::Status::Running);
+}
+
+#[test]
+fn test_versus_spawns_are_symmetric() {
+    for seed in 0..10 {
+        let sim = Simulation::new("versus01", seed, &[]);
+        let ships = |team| {
+            sim.ships
+                .iter()
+                .filter(|&&handle| sim.ship(handle).data().team == team)
+                .map(|&handle| {
+                    let ship = sim.ship(handle);
+                    (ship.position().vector, ship.heading())
+                })
+                .collect::<Vec<_>>()
+        };
+        let (team0, team1) = (ships(0), ships(1));
+        assert_eq!(team0.len(), team1.len());
+        for ((p0, h0), (p1, h1)) in team0.iter().zip(team1.iter()) {
+            assert!(
+                (p0 + p1).magnitude() < 1e-9,
+                "seed {seed}: {p0:?} vs {p1:?}"
+            );
+            assert!(
+                ((h1 - h0).rem_euclid(std::f64::consts::TAU) - std::f64::consts::PI).abs() < 1e-9
+            );
+        }
+    }
+}
+
+#[test]
+fn test_initial_camera_shows_target() {
+    let camera = scenario::load("tutorial_acceleration2")
+        .initial_camera()
+        .unwrap();
+    for seed in 0..100 {
+        let sim = Simulation::new("tutorial_acceleration2", seed, &[Code::None]);
+        let target = sim.marker(TARGET_MARKER).unwrap();
+        let offset = target.position - camera.target.coords;
+        assert!(
+            offset.x.abs() + target.radius <= camera.extent / 2.0
+                && offset.y.abs() + target.radius <= camera.extent / 2.0,
+            "seed {seed}: target at {:?} is off camera",
+            target.position
+        );
+    }
+    assert_eq!(scenario::load("fighter_duel").initial_camera(), None);
+}
+
+#[test]
+fn test_tutorial_labels() {
+    let mut sim = Simulation::new("tutorial_acceleration", 0, &[Code::None]);
+    let labels = sim.snapshot(0).scenario_labels;
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].text, "Fly here");
+
+    // The instructions go away once the target is reached.
+    let ship = *sim.ships.iter().next().unwrap();
+    let target = sim.marker(TARGET_MARKER).unwrap().position;
+    sim.ship_mut(ship).body().set_translation(target, true);
+    sim.step();
+    assert!(sim.snapshot(0).scenario_labels.is_empty());
+}
+
+#[test]
+fn test_missile_defense() {
+    use oort_simulator::scenario::Status;
+
+    let mut sim = Simulation::new("missiles01", 0, &[Code::None, Code::None]);
+    assert_eq!(sim.ships.len(), 1);
+    let frigate = sim.ships.iter().next().cloned().unwrap();
+    sim.ship_mut(frigate).data_mut().health = 1e9;
+    while sim.time() < 6.0 {
+        sim.step();
+    }
+    assert_eq!(sim.ships.len(), 3);
+    assert_eq!(sim.status(), Status::Running);
+    while sim.status() == Status::Running {
+        sim.step();
+    }
+    assert_eq!(sim.status(), Status::Victory { team: 0 });
+    assert!(sim.time() >= 90.0);
+    // Missiles that detonated against the frigate weren't intercepted.
+    assert_eq!(sim.score_time(), sim.time());
+
+    let mut sim = Simulation::new("missiles01", 0, &[Code::None, Code::None]);
+    sim.step();
+    let frigate = sim.ships.iter().next().cloned().unwrap();
+    sim.ship_mut(frigate).data_mut().destroyed = true;
+    sim.step();
+    assert_eq!(sim.status(), Status::Failed);
+}
+
+#[test]
+fn test_missile_defense_intercepts() {
+    use oort_simulator::scenario::Status;
+
+    let scenario = scenario::load("missiles01");
+    let mut sim = Simulation::new("missiles01", 0, &scenario.solution_codes());
+    while sim.status() == Status::Running {
+        sim.step();
+    }
+    assert_eq!(sim.status(), Status::Victory { team: 0 });
+    assert!(sim.score_time() < sim.time());
+}
@@ -0,0 +1,86 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, ShipClass};
+use oort_simulator::simulation::{Code, Simulation};
+use std::f64::consts::TAU;
+use test_log::test;
+
+#[test]
+fn test_builtin_classes() {
+    let fighter = ship::fighter(1);
+    assert_eq!(fighter.class, ShipClass::Fighter);
+    assert_eq!(fighter.team, 1);
+    assert_eq!(fighter.mass, 15000.0);
+    assert_eq!(fighter.max_angular_acceleration, TAU);
+    assert_eq!(fighter.guns.len(), 1);
+    assert_eq!(fighter.radios.len(), 2);
+    assert_eq!(fighter.radar.unwrap().min_width, TAU / 720.0);
+    assert_eq!(fighter.guns[0].ammo, Some(1200));
+    assert_eq!(fighter.missile_launchers[0].ammo, Some(10));
+
+    let cruiser = ship::cruiser(0);
+    assert_eq!(
+        cruiser.radar_cross_section,
+        ship::CRUISER_RADAR_CROSS_SECTION
+    );
+    assert_eq!(cruiser.missile_launchers[1].angle, -TAU / 4.0);
+    assert_eq!(cruiser.missile_launchers[2].class, ShipClass::Torpedo);
+    assert_eq!(cruiser.guns[0].ammo, Some(600));
+    assert_eq!(cruiser.missile_launchers[2].ammo, Some(8));
+
+    let asteroid = ship::asteroid(3);
+    assert_eq!(asteroid.class, ShipClass::Asteroid { variant: 3 });
+    assert_eq!(asteroid.team, 9);
+
+    let torpedo = ship::torpedo(0);
+    assert_eq!(torpedo.ttl, Some(60 * 60));
+    assert_eq!(torpedo.warhead.count, 50);
+}
+
+#[test]
+fn test_parse_ship_classes() {
+    let classes = ship::parse_ship_classes(
+        r#"{
+            "drone": {
+                "class": "Target",
+                "mass": 5.0,
+                "guns": [{ "offset": [1.0, 2.0], "reload_ticks": 7 }]
+            }
+        }"#,
+    )
+    .unwrap();
+    let drone = &classes["drone"];
+    assert_eq!(drone.class, ShipClass::Target);
+    assert_eq!(drone.mass, 5.0);
+    assert_eq!(drone.guns[0].offset.y, 2.0);
+    assert_eq!(drone.guns[0].reload_ticks, 7);
+    // Unspecified fields take their defaults.
+    assert_eq!(drone.health, 100.0);
+    assert_eq!(drone.guns[0].magazine_size, 10);
+}
+
+#[test]
+fn test_parse_ship_classes_errors() {
+    assert!(ship::parse_ship_classes("{").is_err());
+    assert!(ship::parse_ship_classes(r#"{"x": {"class": "Dreadnought"}}"#).is_err());
+    assert!(ship::parse_ship_classes(r#"{"x": {"class": "Fighter", "mass": 0.0}}"#).is_err());
+    assert!(ship::parse_ship_classes(r#"{"x": {"class": "Fighter", "mass": -1.0}}"#).is_err());
+}
+
+#[test]
+fn test_asteroids_are_unpowered() {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let handle = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ship::asteroid(0),
+    );
+    sim.ship_mut(handle).accelerate(vector![100.0, 100.0]);
+    sim.ship_mut(handle).torque(1.0);
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert_eq!(sim.ship(handle).velocity(), vector![0.0, 0.0]);
+    assert_eq!(sim.ship(handle).angular_velocity(), 0.0);
+}
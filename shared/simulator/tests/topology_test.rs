@@ -0,0 +1,105 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter};
+use oort_simulator::simulation::{Code, Simulation};
+use oort_simulator::topology::{self, world_distance, WorldTopology};
+use test_log::test;
+
+#[test]
+fn test_world_distance() {
+    let a = vector![4500.0, 0.0];
+    let b = vector![-4500.0, 0.0];
+    assert_eq!(world_distance(a, b, 10e3, WorldTopology::Walled), 9000.0);
+    assert_eq!(world_distance(a, b, 10e3, WorldTopology::Open), 9000.0);
+    assert_eq!(world_distance(a, b, 10e3, WorldTopology::Wrapping), 1000.0);
+
+    // Across a corner.
+    let a = vector![4500.0, 4500.0];
+    let b = vector![-4500.0, -4500.0];
+    assert_eq!(
+        topology::world_offset(a, b, 10e3, WorldTopology::Wrapping),
+        vector![1000.0, 1000.0]
+    );
+
+    // Points that don't need to cross the edge are unaffected.
+    let a = vector![-1000.0, 0.0];
+    let b = vector![1000.0, 0.0];
+    assert_eq!(world_distance(a, b, 10e3, WorldTopology::Wrapping), 2000.0);
+}
+
+#[test]
+fn test_wrap_position() {
+    assert_eq!(
+        topology::wrap_position(vector![5100.0, -5200.0], 10e3),
+        vector![-4900.0, 4800.0]
+    );
+    assert_eq!(
+        topology::wrap_position(vector![100.0, 200.0], 10e3),
+        vector![100.0, 200.0]
+    );
+}
+
+#[test]
+fn test_seam_offsets() {
+    assert!(topology::seam_offsets(nalgebra::point![0.0, 0.0], 10e3, 500.0).is_empty());
+    assert_eq!(
+        topology::seam_offsets(nalgebra::point![4800.0, 0.0], 10e3, 500.0),
+        vec![vector![-10e3, 0.0]]
+    );
+    assert_eq!(
+        topology::seam_offsets(nalgebra::point![4800.0, -4800.0], 10e3, 500.0).len(),
+        3
+    );
+}
+
+#[test]
+fn test_wraparound() {
+    let mut sim = Simulation::new("wrapping_test", 0, &[Code::None, Code::None]);
+    assert_eq!(sim.topology(), WorldTopology::Wrapping);
+    let handle = ship::create(
+        &mut sim,
+        vector![4990.0, 0.0],
+        vector![600.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+
+    for _ in 0..10 {
+        sim.step();
+    }
+
+    let position = sim.ship(handle).position().vector;
+    assert!(position.x < -4900.0, "position={position:?}");
+    assert!((sim.ship(handle).velocity().x - 600.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_radar_across_seam() {
+    let mut sim = Simulation::new("wrapping_test", 0, &[Code::None, Code::None]);
+    let handle = ship::create(
+        &mut sim,
+        vector![4500.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    ship::create(
+        &mut sim,
+        vector![-4500.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+
+    sim.step();
+
+    let contact = sim
+        .ship(handle)
+        .data()
+        .radar
+        .as_ref()
+        .unwrap()
+        .scan()
+        .expect("no radar contact");
+    // The contact is reported at the nearest copy, just past the edge.
+    assert!((contact.position.x - 5500.0).abs() < 100.0, "{contact:?}");
+}
@@ -24,6 +24,14 @@ fn check_solution(scenario_name: &str) {
                 scenario::Status::Victory { team: 0 },
                 "tutorial {scenario_name} did not succeed with seed {seed}"
             );
+            if let Some(par_ticks) = scenario.par_ticks() {
+                assert_eq!(
+                    scenario::stars(par_ticks, sim.tick()),
+                    3,
+                    "tutorial {scenario_name} solution took {} ticks with seed {seed}",
+                    sim.tick()
+                );
+            }
             sim.hash()
         };
         let hashes: Vec<u64> = (0..2usize)
@@ -57,6 +65,39 @@ fn test_tutorials() {
         .for_each(|x| check_solution(&x));
 }
 
+#[test]
+fn test_tutorial_prerequisites() {
+    let categories = scenario::list();
+    let scenario_names: &Vec<String> = &categories
+        .iter()
+        .find(|(category, _)| category == "Tutorial")
+        .unwrap()
+        .1;
+    assert_eq!(scenario::load(&scenario_names[0]).prerequisite(), None);
+    for pair in scenario_names.windows(2) {
+        assert_eq!(
+            scenario::load(&pair[1]).prerequisite(),
+            Some(pair[0].clone()),
+            "wrong prerequisite for {}",
+            pair[1]
+        );
+        assert_eq!(
+            scenario::load(&pair[0]).next_scenario(),
+            Some(pair[1].clone())
+        );
+    }
+}
+
+#[test]
+fn test_stars() {
+    let par_ticks = (100, 200, 300);
+    assert_eq!(scenario::stars(par_ticks, 0), 3);
+    assert_eq!(scenario::stars(par_ticks, 100), 3);
+    assert_eq!(scenario::stars(par_ticks, 101), 2);
+    assert_eq!(scenario::stars(par_ticks, 300), 1);
+    assert_eq!(scenario::stars(par_ticks, 301), 0);
+}
+
 #[test]
 fn test_gunnery() {
     check_solution("gunnery");
@@ -67,6 +108,17 @@ fn test_missiles() {
     check_solution("missile_test");
 }
 
+#[test]
+fn test_fleet01() {
+    // check_solution's 10000 tick cap is also this scenario's time limit.
+    check_solution("fleet01");
+}
+
+#[test]
+fn test_missiles01() {
+    check_solution("missiles01");
+}
+
 #[test]
 fn test_welcome() {
     let scenario_name = "welcome";
@@ -0,0 +1,120 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter};
+use oort_simulator::simulation::{self, Code};
+use oort_simulator::vm::builtin;
+use test_log::test;
+
+#[test]
+fn test_upload_wasm() {
+    let code = builtin::load_compiled("tutorial/tutorial_deflection_enemy").unwrap();
+    assert!(matches!(code, Code::Wasm(_)));
+
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    sim.upload_code(0, &code).unwrap();
+    assert!(sim.events().errors.is_empty());
+
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert!(sim.events().errors.is_empty());
+    assert!(sim.ship(ship0).velocity().magnitude() > 10.0);
+    assert!(sim.ship(ship0).position().vector.magnitude() > 1.0);
+}
+
+#[test]
+fn test_upload_source() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    assert!(sim
+        .upload_code(0, &Code::Rust("fn main() {}".to_string()))
+        .is_err());
+    assert_eq!(sim.events().errors.len(), 1);
+}
+
+#[test]
+fn test_upload_keeps_previous_code_on_error() {
+    let code = builtin::load_compiled("tutorial/tutorial_deflection_enemy").unwrap();
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    sim.upload_code(0, &code).unwrap();
+    sim.step();
+
+    assert!(sim
+        .upload_code(0, &Code::Rust("fn main() {}".to_string()))
+        .is_err());
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert!(sim.events().errors.is_empty());
+    assert!(sim.ship(ship0).velocity().magnitude() > 10.0);
+}
+
+#[test]
+fn test_upload_replaces_running_code() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    sim.upload_code(0, &builtin::load_compiled("empty").unwrap())
+        .unwrap();
+    sim.step();
+    assert!(sim.ship(ship0).velocity().magnitude() < 1e-6);
+
+    // Ships that already exist are handed to the new code.
+    sim.upload_code(
+        0,
+        &builtin::load_compiled("tutorial/tutorial_deflection_enemy").unwrap(),
+    )
+    .unwrap();
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert!(sim.events().errors.is_empty());
+    assert!(sim.ship(ship0).velocity().magnitude() > 10.0);
+}
+
+#[test]
+fn test_script_tick_divisor() {
+    let code = builtin::load_compiled("tutorial/tutorial_deflection_enemy").unwrap();
+    let run = |divisor| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        sim.script_tick_divisor = divisor;
+        assert_eq!(
+            sim.script_tick_length(),
+            simulation::PHYSICS_TICK_LENGTH * divisor as f64
+        );
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(0),
+        );
+        sim.upload_code(0, &code).unwrap();
+        for _ in 0..60 {
+            sim.step();
+        }
+        assert!(sim.events().errors.is_empty());
+        // Acceleration persists between script ticks.
+        assert!(sim.ship(ship0).velocity().magnitude() > 10.0);
+        sim.hash()
+    };
+    assert_eq!(run(4), run(4));
+    assert_ne!(run(1), run(4));
+}
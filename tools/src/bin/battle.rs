@@ -1,6 +1,8 @@
 use clap::Parser;
+use oort_simulator::rounds::{Round, Session};
+use oort_simulator::scenario;
 use oort_simulator::simulation::Code;
-use oort_simulator::{scenario, simulation};
+use oort_tools::AI;
 use rayon::prelude::*;
 use std::default::Default;
 use std::path::PathBuf;
@@ -19,6 +21,10 @@ struct Arguments {
 
     #[clap(long, default_value = "/tmp/oort-wasm-cache")]
     wasm_cache: Option<PathBuf>,
+
+    /// Additional opponents, e.g. "builtin:sniper".
+    #[clap(long)]
+    enemy: Vec<String>,
 }
 
 #[tokio::main]
@@ -28,19 +34,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     let args = Arguments::parse();
     scenario::load_safe(&args.scenario).expect("Unknown scenario");
-    if args.shortcodes.len() < 2 {
+    if args.shortcodes.len() + args.enemy.len() < 2 {
         panic!("Expected at least two shortcodes");
     }
 
     log::info!("Compiling AIs");
     let http = reqwest::Client::new();
-    let ais = oort_tools::fetch_and_compile_multiple(
+    let mut ais = oort_tools::fetch_and_compile_multiple(
         &http,
         &args.shortcodes,
         args.dev,
         args.wasm_cache.as_deref(),
     )
     .await?;
+    for enemy in args.enemy.iter() {
+        ais.push(builtin_enemy(enemy)?);
+    }
 
     log::info!("Running simulations");
     let player0 = &ais[0];
@@ -89,6 +98,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     Ok(())
 }
 
+fn builtin_enemy(spec: &str) -> anyhow::Result<AI> {
+    let name = match spec.strip_prefix("builtin:") {
+        Some(name) => name,
+        None => anyhow::bail!("Expected an enemy like \"builtin:NAME\", got {spec:?}"),
+    };
+    let compiled_code = match scenario::opponent_ai(name) {
+        Some(code) => code,
+        None => anyhow::bail!(
+            "Unknown builtin enemy {name:?}, expected one of {:?}",
+            scenario::OPPONENTS
+        ),
+    };
+    Ok(AI {
+        name: spec.to_string(),
+        source_code: String::new(),
+        compiled_code,
+    })
+}
+
 #[derive(Default, Debug)]
 struct Results {
     team0_wins: Vec<u32>,
@@ -98,28 +126,26 @@ struct Results {
 }
 
 fn run_simulations(scenario_name: &str, codes: Vec<Code>, rounds: u32) -> Results {
-    let seed_statuses: Vec<(u32, (scenario::Status, f64))> = (0..rounds)
+    // Each round is its own session so they can run in parallel. The seeds
+    // match the rounds of a single session with base seed 0.
+    let rounds: Vec<Round> = (0..rounds)
         .into_par_iter()
-        .map(|seed| (seed, run_simulation(scenario_name, seed, codes.clone())))
+        .map(|seed| {
+            let mut session = Session::with_rounds(scenario_name, seed, &codes, 1);
+            session.run();
+            session.tally().rounds[0].clone()
+        })
         .collect();
     let mut results: Results = Default::default();
-    for (seed, (status, time)) in seed_statuses {
-        match status {
-            scenario::Status::Victory { team: 0 } => results.team0_wins.push(seed),
-            scenario::Status::Victory { team: 1 } => results.team1_wins.push(seed),
-            scenario::Status::Draw => results.draws.push(seed),
-            scenario::Status::Failed => results.team1_wins.push(seed),
+    for round in rounds.iter() {
+        match round.status {
+            scenario::Status::Victory { team: 0 } => results.team0_wins.push(round.seed),
+            scenario::Status::Victory { team: 1 } => results.team1_wins.push(round.seed),
+            scenario::Status::Draw => results.draws.push(round.seed),
+            scenario::Status::Failed => results.team1_wins.push(round.seed),
             _ => unreachable!(),
         }
-        results.times.push(time);
+        results.times.push(round.time);
     }
     results
 }
-
-fn run_simulation(scenario_name: &str, seed: u32, codes: Vec<Code>) -> (scenario::Status, f64) {
-    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
-    while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
-        sim.step();
-    }
-    (sim.status(), sim.score_time())
-}
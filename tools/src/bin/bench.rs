@@ -0,0 +1,62 @@
+use clap::Parser;
+use oort_simulator::snapshot::Timing;
+use oort_simulator::{scenario, simulation};
+use std::time::{Duration, Instant};
+
+#[derive(Parser, Debug)]
+#[clap()]
+struct Arguments {
+    #[clap(default_value = "bullet-stress")]
+    scenario: String,
+
+    #[clap(short, long, default_value = "1000", value_parser = clap::value_parser!(u32).range(1..))]
+    ticks: u32,
+
+    #[clap(short, long, default_value = "0")]
+    seed: u32,
+}
+
+fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("bench=info"))
+        .init();
+
+    let args = Arguments::parse();
+    let scenario = scenario::load_safe(&args.scenario).expect("Unknown scenario");
+    let codes = scenario.solution_codes();
+    let mut sim = simulation::Simulation::new(&args.scenario, args.seed, &codes);
+
+    let mut step_times: Vec<Duration> = Vec::with_capacity(args.ticks as usize);
+    let mut timing = Timing::default();
+    let start_time = Instant::now();
+    while step_times.len() < args.ticks as usize {
+        let step_start = Instant::now();
+        sim.step();
+        step_times.push(step_start.elapsed());
+        timing += sim.timing().clone();
+        if sim.status() != scenario::Status::Running {
+            log::info!("Scenario ended at tick {}", sim.tick());
+            break;
+        }
+    }
+    let elapsed = start_time.elapsed();
+
+    let n = step_times.len();
+    let mean = elapsed / n as u32;
+    step_times.sort();
+    let percentile = |p: f64| step_times[((n - 1) as f64 * p).round() as usize];
+
+    println!("Scenario: {} (seed {})", args.scenario, args.seed);
+    println!("Ticks: {} in {:?}", n, elapsed);
+    println!("Steps/sec: {:.1}", n as f64 / elapsed.as_secs_f64());
+    println!(
+        "Step time: mean {:?} p50 {:?} p90 {:?} p99 {:?} max {:?}",
+        mean,
+        percentile(0.5),
+        percentile(0.9),
+        percentile(0.99),
+        step_times[n - 1]
+    );
+    println!("Mean step breakdown (ms): {:?}", timing * (1e3 / n as f64));
+    println!("Ships: {}", sim.ships.len());
+    println!("Bullets: {}", sim.bullets.len());
+}
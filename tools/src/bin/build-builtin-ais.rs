@@ -4,8 +4,9 @@ use glob::glob;
 use libflate::gzip::{EncodeOptions, Encoder, HeaderBuilder};
 use rayon::prelude::*;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tar::Header;
 
 thread_local! {
@@ -30,13 +31,14 @@ fn main() -> Result<()> {
     let paths: Vec<_> = glob(&format!("{}/**/*.rs", args.input))?
         .map(|x| x.unwrap())
         .filter(|x| !["lib.rs", "mod.rs"].contains(&x.file_name().unwrap().to_str().unwrap()))
+        .filter(|x| defines_ship(x))
         .collect();
 
     let results: Vec<_> = paths
         .par_iter()
         .map(
             |path| -> Result<(PathBuf, /*rust*/ String, /*wasm*/ Vec<u8>)> {
-                let source_code = std::fs::read_to_string(path).unwrap();
+                let source_code = join_modules(path)?;
                 let wasm = COMPILERS
                     .with(|compiler_cell| compiler_cell.borrow_mut().compile(&source_code))?;
                 let optimized_wasm = wasm_opt(&wasm)?;
@@ -86,6 +88,25 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Files without a Ship are helper modules for the AIs next to them.
+fn defines_ship(path: &Path) -> bool {
+    let re = regex::Regex::new(r"pub (struct|enum) Ship\b").unwrap();
+    re.is_match(&std::fs::read_to_string(path).unwrap())
+}
+
+/// Inlines the modules the AI declares from files in its directory, so it
+/// compiles as a single file like user code.
+fn join_modules(path: &Path) -> Result<String> {
+    let mut files = HashMap::new();
+    for sibling in glob(&format!("{}/*.rs", path.parent().unwrap().display()))? {
+        let sibling = sibling?;
+        let name = sibling.file_name().unwrap().to_str().unwrap().to_string();
+        files.insert(name, std::fs::read_to_string(&sibling)?);
+    }
+    files.insert("lib.rs".to_string(), std::fs::read_to_string(path)?);
+    oort_multifile::join(files)
+}
+
 fn wasm_opt(wasm: &[u8]) -> Result<Vec<u8>> {
     let mut child = std::process::Command::new("wasm-opt")
         .args(["-Oz", "-o", "-"])
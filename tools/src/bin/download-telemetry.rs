@@ -100,6 +100,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         (),
     )?;
 
+    transaction.execute(
+        "CREATE TABLE IF NOT EXISTS ScenarioComplete (
+            id INTEGER PRIMARY KEY,
+            timestamp TEXT,
+            userid TEXT,
+            username TEXT,
+            build TEXT,
+            scenario_name TEXT,
+            ticks INTEGER,
+            seed INTEGER
+        )",
+        (),
+    )?;
+
+    transaction.execute(
+        "CREATE TABLE IF NOT EXISTS Compile (
+            id INTEGER PRIMARY KEY,
+            timestamp TEXT,
+            userid TEXT,
+            username TEXT,
+            build TEXT,
+            success INTEGER
+        )",
+        (),
+    )?;
+
     let last_sync_timestamp = {
         let mut last_sync_timestamp_text = "1970-01-01T00:00:00Z".to_string();
         let mut stmt = transaction
@@ -181,6 +207,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                             "INSERT INTO Feedback (timestamp, userid, username, build, text) VALUES (?1, ?2, ?3, ?4, ?5)",
                             (&msg.timestamp.to_rfc3339(), &msg.userid, &msg.username, &msg.build, text))?;
                 }
+                Telemetry::ScenarioComplete {
+                    scenario_name,
+                    ticks,
+                    seed,
+                } => {
+                    transaction.execute(
+                            "INSERT INTO ScenarioComplete (timestamp, userid, username, build, scenario_name, ticks, seed) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                            (&msg.timestamp.to_rfc3339(), &msg.userid, &msg.username, &msg.build, scenario_name, ticks, seed))?;
+                }
+                Telemetry::Compile { success } => {
+                    transaction.execute(
+                            "INSERT INTO Compile (timestamp, userid, username, build, success) VALUES (?1, ?2, ?3, ?4, ?5)",
+                            (&msg.timestamp.to_rfc3339(), &msg.userid, &msg.username, &msg.build, success))?;
+                }
             }
         } else {
             log::error!("Failed to deserialize doc {}", doc.name);
@@ -40,6 +40,8 @@ async fn run(args: &Arguments) -> Result<(), Box<dyn std::error::Error + Send +
                 Telemetry::Crash { .. } => {}
                 Telemetry::SubmitToTournament { .. } => {}
                 Telemetry::Feedback { .. } => {}
+                Telemetry::ScenarioComplete { .. } => {}
+                Telemetry::Compile { .. } => {}
             }
             if msg != original_msg {
                 let docid = doc.name.rsplit_once('/').unwrap().1.to_string();
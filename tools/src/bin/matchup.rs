@@ -0,0 +1,78 @@
+use clap::Parser;
+use oort_simulator::matchup::{self, Outcome};
+use oort_simulator::scenario;
+use rayon::prelude::*;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[clap()]
+struct Arguments {
+    scenario: String,
+    shortcode0: String,
+    shortcode1: String,
+
+    #[clap(short, long, default_value = "10")]
+    rounds: u32,
+
+    #[clap(short, long, default_value = "0")]
+    first_seed: u32,
+
+    #[clap(short, long)]
+    dev: bool,
+
+    #[clap(long, default_value = "/tmp/oort-wasm-cache")]
+    wasm_cache: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("matchup=info"))
+        .init();
+
+    let args = Arguments::parse();
+    scenario::load_safe(&args.scenario).expect("Unknown scenario");
+
+    log::info!("Compiling AIs");
+    let http = reqwest::Client::new();
+    let ais = oort_tools::fetch_and_compile_multiple(
+        &http,
+        &[args.shortcode0.clone(), args.shortcode1.clone()],
+        args.dev,
+        args.wasm_cache.as_deref(),
+    )
+    .await?;
+
+    log::info!("Running simulations");
+    let seeds: Vec<u32> = (args.first_seed..(args.first_seed + args.rounds)).collect();
+    let games = seeds
+        .par_iter()
+        .enumerate()
+        .map(|(i, &seed)| {
+            matchup::run_game(
+                &args.scenario,
+                &ais[0].compiled_code,
+                &ais[1].compiled_code,
+                seed,
+                matchup::swapped(i),
+            )
+        })
+        .collect();
+    let results = matchup::Results::from_games(games);
+
+    println!("{} vs {}:", ais[0].name, ais[1].name);
+    println!(
+        "  Wins: {} Losses: {} Draws: {}",
+        results.wins, results.losses, results.draws
+    );
+    for outcome in [Outcome::Win, Outcome::Loss, Outcome::Draw] {
+        let seeds: Vec<_> = results
+            .games
+            .iter()
+            .filter(|game| game.outcome == outcome)
+            .map(|game| game.seed)
+            .collect();
+        println!("  {:?} seeds: {:?}", outcome, seeds);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use clap::Parser;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use oort_simulator::scenario;
+use rayon::prelude::*;
+
+#[derive(Parser, Debug)]
+#[clap()]
+struct Arguments {
+    /// Scenarios to calibrate. Defaults to all tutorials.
+    scenarios: Vec<String>,
+
+    #[clap(short, long, default_value = "10")]
+    rounds: u32,
+}
+
+fn run(scenario_name: &str, seed: u32) -> Option<u32> {
+    match oort_tools::run_solution(scenario_name, seed) {
+        (scenario::Status::Victory { team: 0 }, ticks) => Some(ticks),
+        _ => None,
+    }
+}
+
+fn round_up(ticks: u32) -> u32 {
+    (ticks + 59) / 60 * 60
+}
+
+fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("par_times=info"))
+        .init();
+
+    let args = Arguments::parse();
+    let scenario_names = if args.scenarios.is_empty() {
+        scenario::list()
+            .into_iter()
+            .find(|(category, _)| category == "Tutorial")
+            .map(|(_, names)| names)
+            .unwrap_or_default()
+    } else {
+        args.scenarios.clone()
+    };
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        "Scenario",
+        "Failures",
+        "Worst",
+        "Current",
+        "Suggested",
+    ]);
+
+    for scenario_name in scenario_names.iter() {
+        let scenario = scenario::load_safe(scenario_name).expect("Unknown scenario");
+        let results: Vec<Option<u32>> = (0..args.rounds)
+            .into_par_iter()
+            .map(|seed| run(scenario_name, seed))
+            .collect();
+        let failures = results.iter().filter(|x| x.is_none()).count();
+        let worst = results.iter().flatten().copied().max().unwrap_or(0);
+        // The solution should always earn three stars.
+        let three = round_up(worst + worst / 4);
+        let suggested = (three, three * 3 / 2, three * 2);
+        table.add_row(vec![
+            scenario_name.clone(),
+            failures.to_string(),
+            worst.to_string(),
+            format!("{:?}", scenario.par_ticks()),
+            format!("{:?}", suggested),
+        ]);
+    }
+
+    println!("{table}");
+}
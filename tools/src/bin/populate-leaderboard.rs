@@ -110,6 +110,9 @@ async fn run(
                 code: code.clone(),
                 code_size: *code_size,
                 time: time.unwrap(),
+                code_hash: String::new(),
+                seed: None,
+                token: String::new(),
             });
         }
     }
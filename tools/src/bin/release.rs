@@ -9,6 +9,8 @@ use tokio_retry::strategy::ExponentialBackoff;
 use tokio_retry::Retry;
 
 const REGION: &str = "us-west1";
+const SECRETS_PATH: &str = ".secrets/secrets.toml";
+const PLACEHOLDER_SECRET: &str = "placeholder";
 const WORKSPACES: &[&str] = &[".", "frontend"];
 static PROGRESS: Lazy<MultiProgress> = Lazy::new(MultiProgress::new);
 
@@ -46,7 +48,8 @@ struct Arguments {
     skip_version_bump: bool,
 
     #[clap(short = 'n')]
-    /// Skip pushing.
+    /// Build locally without bumping the version, loading secrets or
+    /// deploying anything.
     dry_run: bool,
 
     #[clap(long)]
@@ -78,6 +81,26 @@ struct Secrets {
     discord_leaderboard_webhook: Option<String>,
 }
 
+impl Secrets {
+    fn load() -> Result<Self> {
+        if std::fs::metadata(SECRETS_PATH).is_err() {
+            bail!("Missing {SECRETS_PATH}, required for deploying (use -n for a local dry run)");
+        }
+        Ok(toml::from_str(&std::fs::read_to_string(SECRETS_PATH)?)?)
+    }
+
+    fn placeholder() -> Self {
+        let placeholder = Some(PLACEHOLDER_SECRET.to_string());
+        Self {
+            oort_envelope_secret: placeholder.clone(),
+            oort_code_encryption_secret: placeholder,
+            discord_changelog_webhook: None,
+            discord_telemetry_webhook: None,
+            discord_leaderboard_webhook: None,
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("release=info"))
@@ -88,13 +111,16 @@ async fn main() -> anyhow::Result<()> {
     let args = Arguments::parse();
     let dry_run = args.dry_run;
 
-    let mut secrets: Secrets = Secrets::default();
-    if !args.no_secrets && std::fs::metadata(".secrets/secrets.toml").is_ok() {
-        secrets = toml::from_str(&std::fs::read_to_string(".secrets/secrets.toml")?)?;
-        std::env::set_var(
-            "OORT_ENVELOPE_SECRET",
-            &secrets.oort_envelope_secret.clone().unwrap_or_default(),
-        );
+    let secrets = if dry_run {
+        log::info!("Dry run, using placeholder secrets");
+        Secrets::placeholder()
+    } else if args.no_secrets {
+        Secrets::default()
+    } else {
+        Secrets::load()?
+    };
+    if let Some(secret) = &secrets.oort_envelope_secret {
+        std::env::set_var("OORT_ENVELOPE_SECRET", secret);
     }
 
     std::env::set_var("DOCKER_BUILDKIT", "1");
@@ -111,7 +137,11 @@ async fn main() -> anyhow::Result<()> {
 
     let mut version = "unknown".to_string();
     let mut changelog = "unknown".to_string();
-    let bump_version = !args.skip_version_bump;
+    // A dry run must not leave a version bump, commit or tag behind.
+    let bump_version = !args.skip_version_bump && !dry_run;
+    if dry_run && !args.skip_version_bump {
+        log::info!("Dry run, skipping version bump");
+    }
     if bump_version {
         if args.components != ALL_COMPONENTS && !args.skip_components_check {
             bail!("Attempted to bump version without pushing all components");
@@ -221,32 +251,41 @@ async fn main() -> anyhow::Result<()> {
         sync_cmd_ok(&["git", "tag", &format!("v{version}")]).await?;
     }
 
-    let backend_url = sync_cmd_ok(&[
-        "gcloud",
-        "--project",
-        &args.project,
-        "run",
-        "services",
-        "describe",
-        "oort-backend-service",
-        "--format=value(status.url)",
-    ])
-    .await?
-    .stdout_string();
-    std::env::set_var("BACKEND_URL", &backend_url);
+    let (backend_url, compiler_url) = if dry_run {
+        (
+            "http://localhost:8082".to_string(),
+            "http://localhost:8081".to_string(),
+        )
+    } else {
+        let backend_url = sync_cmd_ok(&[
+            "gcloud",
+            "--project",
+            &args.project,
+            "run",
+            "services",
+            "describe",
+            "oort-backend-service",
+            "--format=value(status.url)",
+        ])
+        .await?
+        .stdout_string();
+
+        let compiler_url = sync_cmd_ok(&[
+            "gcloud",
+            "--project",
+            &args.project,
+            "run",
+            "services",
+            "describe",
+            "oort-compiler-service",
+            "--format=value(status.url)",
+        ])
+        .await?
+        .stdout_string();
 
-    let compiler_url = sync_cmd_ok(&[
-        "gcloud",
-        "--project",
-        &args.project,
-        "run",
-        "services",
-        "describe",
-        "oort-compiler-service",
-        "--format=value(status.url)",
-    ])
-    .await?
-    .stdout_string();
+        (backend_url, compiler_url)
+    };
+    std::env::set_var("BACKEND_URL", &backend_url);
 
     let mut tasks = tokio::task::JoinSet::new();
 
@@ -388,7 +427,13 @@ async fn main() -> anyhow::Result<()> {
                         "--timeout=20s",
                         "--concurrency=1",
                         "--max-instances=10",
+                        // Compile jobs are stored in memory and run after the
+                        // response has been sent.
+                        "--session-affinity",
+                        "--no-cpu-throttling",
                         &format!("--service-account=oort-compiler-service@{project}.iam.gserviceaccount.com"),
+                        // Used to allow the project's Firebase Hosting origins.
+                        &format!("--set-env-vars=PROJECT_ID={project}"),
                     ])
                     .await?;
                 }
@@ -484,6 +529,7 @@ async fn main() -> anyhow::Result<()> {
                         "--max-instances=1",
                         &format!("--service-account=oort-backend-service@{project}.iam.gserviceaccount.com"),
                         &format!("--set-env-vars=PROJECT_ID={project}"),
+                        "--set-secrets=LEADERBOARD_TOKEN_SECRET=leaderboard-token-secret:latest",
                     ])
                     .await?;
 
@@ -565,7 +611,7 @@ async fn main() -> anyhow::Result<()> {
         log::info!("Building docs");
         sync_cmd_ok(&["cargo", "doc", "-p", "oort_api"]).await?;
 
-        if !dry_run && bump_version {
+        if bump_version {
             log::info!("Publishing docs");
             sync_cmd_ok(&["cargo", "publish", "-p", "oort_api"]).await?;
         }
@@ -576,7 +622,7 @@ async fn main() -> anyhow::Result<()> {
         sync_cmd_ok(&["git", "push"]).await?;
     }
 
-    if bump_version && !dry_run && !args.skip_discord {
+    if bump_version && !args.skip_discord {
         log::info!("Sending Discord message");
         let mut map = std::collections::HashMap::new();
         map.insert(
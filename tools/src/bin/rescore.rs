@@ -4,6 +4,7 @@ use comfy_table::Table;
 use firestore::*;
 use gcloud_sdk::google::firestore::v1::Document;
 use oort_proto::LeaderboardSubmission;
+use oort_simulator::rounds::Session;
 use oort_simulator::{scenario, simulation};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -187,12 +188,10 @@ fn run_simulation(scenario_name: &str, seed: u32, wasm: Vec<u8>) -> Option<f64>
     let scenario = scenario::load(scenario_name);
     let mut codes = scenario.initial_code();
     codes[0] = simulation::Code::Wasm(wasm);
-    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
-    while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
-        sim.step();
-    }
-    match sim.status() {
-        scenario::Status::Victory { team: 0 } => Some(sim.score_time()),
+    let mut session = Session::new(scenario_name, seed, &codes);
+    session.run();
+    match session.status() {
+        scenario::Status::Victory { team: 0 } => Some(session.score_time()),
         _ => None,
     }
 }
@@ -104,6 +104,16 @@ async fn cmd_list(
                     println!("{prefix} SubmitToTournament user={user} scenario={scenario_name}")
                 }
                 Telemetry::Feedback { .. } => println!("{prefix} Feedback user={user}"),
+                Telemetry::ScenarioComplete {
+                    scenario_name,
+                    ticks,
+                    seed,
+                } => {
+                    println!("{prefix} ScenarioComplete user={user} scenario={scenario_name} ticks={ticks} seed={seed}")
+                }
+                Telemetry::Compile { success } => {
+                    println!("{prefix} Compile user={user} success={success}")
+                }
             }
         } else {
             log::error!("Failed to deserialize doc {}", doc.name);
@@ -163,6 +173,12 @@ async fn cmd_get(
                 println!("// Build: {}", msg.build);
                 println!("{}", text.trim());
             }
+            Telemetry::ScenarioComplete {
+                scenario_name,
+                ticks,
+                seed,
+            } => println!("ScenarioComplete: {scenario_name} ticks={ticks} seed={seed}"),
+            Telemetry::Compile { success } => println!("Compile: success={success}"),
         }
     } else {
         let doc = db.get_doc(COLLECTION_NAME, &docid, None).await?;
@@ -0,0 +1,92 @@
+use clap::Parser;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use oort_simulator::scenario::{self, Status};
+use oort_simulator::simulation::Code;
+use rayon::prelude::*;
+
+// Tournament and introduction solutions play against equally strong opponents,
+// so they aren't expected to always win.
+const CATEGORIES: &[&str] = &["Tutorial", "Challenge"];
+
+/// Runs every scenario's solution to completion and exits with an error if any
+/// of them fail to win.
+#[derive(Parser, Debug)]
+#[clap()]
+struct Arguments {
+    /// Only validate these scenarios, from any category. May be repeated.
+    #[clap(long)]
+    only: Vec<String>,
+
+    /// Number of seeds to run for each scenario.
+    #[clap(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
+    seed_sweep: u32,
+}
+
+fn main() {
+    env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or("validate_scenarios=info"),
+    )
+    .init();
+
+    let args = Arguments::parse();
+    let scenario_names: Vec<String> = if args.only.is_empty() {
+        scenario::list()
+            .into_iter()
+            .filter(|(category, _)| CATEGORIES.contains(&category.as_str()))
+            .flat_map(|(_, names)| names)
+            .filter(|name| scenario::load(name).solution() != Code::None)
+            .collect()
+    } else {
+        args.only
+            .iter()
+            .map(|name| scenario::resolve_name(name).expect("Unknown scenario"))
+            .collect()
+    };
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        "Scenario",
+        "Failed seeds",
+        "Min ticks",
+        "Max ticks",
+    ]);
+
+    let mut num_failed = 0;
+    for scenario_name in scenario_names.iter() {
+        let results: Vec<(u32, Status, u32)> = (0..args.seed_sweep)
+            .into_par_iter()
+            .map(|seed| {
+                let (status, ticks) = oort_tools::run_solution(scenario_name, seed);
+                (seed, status, ticks)
+            })
+            .collect();
+        let failed_seeds: Vec<u32> = results
+            .iter()
+            .filter(|(_, status, _)| *status != Status::Victory { team: 0 })
+            .map(|(seed, _, _)| *seed)
+            .collect();
+        let ticks = results.iter().map(|(_, _, ticks)| *ticks);
+        if !failed_seeds.is_empty() {
+            log::error!("{} failed with seeds {:?}", scenario_name, failed_seeds);
+            num_failed += 1;
+        }
+        table.add_row(vec![
+            scenario_name.clone(),
+            format!("{:?}", failed_seeds),
+            ticks.clone().min().unwrap_or(0).to_string(),
+            ticks.max().unwrap_or(0).to_string(),
+        ]);
+    }
+
+    println!("{table}");
+
+    if num_failed > 0 {
+        log::error!(
+            "{} of {} scenarios failed",
+            num_failed,
+            scenario_names.len()
+        );
+        std::process::exit(1);
+    }
+}
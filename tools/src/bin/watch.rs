@@ -0,0 +1,84 @@
+use clap::Parser;
+use oort_simulator::rounds::Session;
+use oort_simulator::scenario;
+use oort_simulator::simulation::Code;
+use rayon::prelude::*;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Recompiles a local file and reruns a scenario every time it changes.
+#[derive(Parser, Debug)]
+#[clap()]
+struct Arguments {
+    scenario: String,
+    path: PathBuf,
+
+    #[clap(short, long, default_value = "1")]
+    rounds: u32,
+
+    #[clap(short, long, default_value = "0")]
+    first_seed: u32,
+
+    #[clap(short, long)]
+    dev: bool,
+
+    #[clap(long, default_value = "500")]
+    poll_ms: u64,
+}
+
+fn run(scenario_name: &str, codes: &[Code], seed: u32) -> (scenario::Status, f64) {
+    let mut session = Session::new(scenario_name, seed, codes);
+    let mut reported_error = false;
+    while !session.is_complete() {
+        session.step();
+        let sim = session.sim();
+        if let Some(e) = sim.events().errors.first() {
+            if !reported_error {
+                log::error!("Seed {} tick {}: {}", sim.seed(), sim.tick(), e.msg);
+                reported_error = true;
+            }
+        }
+    }
+    (session.status(), session.score_time())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("watch=info"))
+        .init();
+
+    let args = Arguments::parse();
+    let scenario = scenario::load_safe(&args.scenario).expect("Unknown scenario");
+    let path = args.path.to_str().expect("Non-UTF8 path").to_string();
+    let http = reqwest::Client::new();
+    let mut last_modified: Option<SystemTime> = None;
+
+    log::info!("Watching {:?}", args.path);
+    loop {
+        let modified = std::fs::metadata(&args.path)
+            .and_then(|m| m.modified())
+            .ok();
+        if modified.is_some() && modified != last_modified {
+            last_modified = modified;
+            // The file just changed, so the wasm cache would always miss.
+            match oort_tools::fetch_and_compile(&http, &path, args.dev, None).await {
+                Ok(ai) => {
+                    let mut codes = scenario.initial_code();
+                    codes[0] = ai.compiled_code;
+                    let seeds: Vec<u32> =
+                        (args.first_seed..(args.first_seed + args.rounds)).collect();
+                    let results: Vec<_> = seeds
+                        .par_iter()
+                        .map(|&seed| (seed, run(&args.scenario, &codes, seed)))
+                        .collect();
+                    for (seed, (status, time)) in results {
+                        println!("Seed {}: {:?} at {:.2}s", seed, status, time);
+                    }
+                }
+                Err(e) => log::error!("{}", e),
+            }
+            log::info!("Waiting for changes");
+        }
+        tokio::time::sleep(Duration::from_millis(args.poll_ms)).await;
+    }
+}
@@ -1,6 +1,7 @@
 use std::{fs, path::Path, path::PathBuf};
 
-use oort_simulator::simulation::Code;
+use oort_simulator::scenario::{self, Status};
+use oort_simulator::simulation::{Code, Simulation};
 
 pub struct AI {
     pub name: String,
@@ -86,6 +87,19 @@ pub async fn fetch_and_compile_multiple(
     results.into_iter().collect()
 }
 
+/// Runs a scenario with its solution against the initial code for the other
+/// teams, stopping when the scenario finishes or at the tick limit.
+///
+/// Returns the final status and tick count.
+pub fn run_solution(scenario_name: &str, seed: u32) -> (Status, u32) {
+    let scenario = scenario::load(scenario_name);
+    let mut sim = Simulation::new(scenario_name, seed, &scenario.solution_codes());
+    while sim.status() == Status::Running && sim.tick() < scenario::MAX_TICKS {
+        sim.step();
+    }
+    (sim.status(), sim.tick())
+}
+
 pub struct WasmCache {
     path: PathBuf,
 }